@@ -0,0 +1,24 @@
+use internal::{
+    Radius,
+    Width,
+};
+
+/// Implemented by contexts that can add border.
+pub trait AddBorder<'a, T> {
+    /// Adds a border radius, which is half the border width.
+    fn border_radius(&'a self, radius: Radius) -> T;
+
+    /// Adds a border width.
+    #[inline(always)]
+    fn border_width(&'a self, width: Width) -> T {
+        self.border_radius(0.5 * width)
+    }
+
+    /// Adds a border with a width.
+    ///
+    /// This is the same as `border_width`.
+    #[inline(always)]
+    fn border(&'a self, width: Width) -> T {
+        self.border_width(width)
+    }
+}
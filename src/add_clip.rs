@@ -0,0 +1,10 @@
+use internal::{Scalar};
+
+/// Implemented by contexts that can restrict drawing to a rectangle.
+pub trait AddClip<'a, T> {
+    /// Restricts drawing to a rectangle in the current coordinates.
+    ///
+    /// Nested clipping rectangles restrict drawing to their overlap.
+    /// A rotated rectangle clips to its bounding rectangle after transformation.
+    fn clip_rect(&'a self, x: Scalar, y: Scalar, w: Scalar, h: Scalar) -> T;
+}
@@ -0,0 +1,26 @@
+use {
+    Dash,
+};
+use internal::{
+    Scalar,
+};
+
+/// Implemented by contexts that can stroke dashed lines.
+pub trait AddDash<'a, T> {
+    /// Sets the dash pattern.
+    fn dash_pattern(&'a self, dash: Dash) -> T;
+
+    /// Adds dashes with alternating drawn and skipped lengths.
+    ///
+    /// The phase is the distance into the pattern at the start of the line.
+    #[inline(always)]
+    fn dash(&'a self, lengths: &[Scalar], phase: Scalar) -> T {
+        self.dash_pattern(Dash::new(lengths, phase))
+    }
+
+    /// Adds dashes that move forward one period as the tween factor goes from 0 to 1.
+    #[inline(always)]
+    fn dash_lerp(&'a self, lengths: &[Scalar], tween_factor: Scalar) -> T {
+        self.dash_pattern(Dash::lerp(lengths, tween_factor))
+    }
+}
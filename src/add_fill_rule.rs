@@ -0,0 +1,23 @@
+use {
+    EvenOdd,
+    FillRule,
+    NonZero,
+};
+
+/// Implemented by contexts that can set fill rule.
+pub trait AddFillRule<'a, T> {
+    /// Sets the fill rule.
+    fn fill_rule(&'a self, fill_rule: FillRule) -> T;
+
+    /// Fills areas with odd winding number.
+    #[inline(always)]
+    fn even_odd(&'a self) -> T {
+        self.fill_rule(EvenOdd)
+    }
+
+    /// Fills areas with winding number other than zero.
+    #[inline(always)]
+    fn non_zero(&'a self) -> T {
+        self.fill_rule(NonZero)
+    }
+}
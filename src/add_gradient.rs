@@ -0,0 +1,26 @@
+use {
+    Gradient,
+    GradientStop,
+};
+use internal::{
+    Radius,
+    Vec2d,
+};
+
+/// Implemented by contexts that can fill with a gradient.
+pub trait AddGradient<'a, T> {
+    /// Sets the gradient.
+    fn gradient(&'a self, gradient: Gradient) -> T;
+
+    /// Adds a gradient changing color along the line from `p0` to `p1`.
+    #[inline(always)]
+    fn linear_gradient(&'a self, p0: Vec2d, p1: Vec2d, stops: &[GradientStop]) -> T {
+        self.gradient(Gradient::linear(p0, p1, stops))
+    }
+
+    /// Adds a gradient changing color from the center out to the radius.
+    #[inline(always)]
+    fn radial_gradient(&'a self, center: Vec2d, radius: Radius, stops: &[GradientStop]) -> T {
+        self.gradient(Gradient::radial(center, radius, stops))
+    }
+}
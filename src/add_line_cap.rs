@@ -0,0 +1,15 @@
+use {
+    LineCap,
+};
+
+/// Implemented by contexts that can set the ends of stroked lines.
+pub trait AddLineCap<'a, T> {
+    /// Sets the cap at the start of the line.
+    fn start_cap(&'a self, cap: LineCap) -> T;
+
+    /// Sets the cap at the end of the line.
+    fn end_cap(&'a self, cap: LineCap) -> T;
+
+    /// Sets the same cap at both ends of the line.
+    fn cap(&'a self, cap: LineCap) -> T;
+}
@@ -0,0 +1,17 @@
+use {
+    LineJoin,
+};
+use internal::{
+    Scalar,
+};
+
+/// Implemented by contexts that can set how stroked lines are joined.
+pub trait AddLineJoin<'a, T> {
+    /// Sets the line join.
+    fn join(&'a self, join: LineJoin) -> T;
+
+    /// Sets the miter limit.
+    ///
+    /// This is the maximum ratio between the length of a miter join and the border width.
+    fn miter_limit(&'a self, miter_limit: Scalar) -> T;
+}
@@ -0,0 +1,19 @@
+use internal::{
+    Scalar,
+};
+
+/// Implemented by contexts that can draw images as nine slices.
+pub trait AddNineSlice<'a, T> {
+    /// Splits the image into a 3x3 grid with borders of fixed size.
+    ///
+    /// The borders are measured in pixels of the source rectangle
+    /// and drawn with the same size in the destination rectangle,
+    /// shrinking them if the rectangle is too small.
+    fn nine_slice(
+        &'a self,
+        left: Scalar,
+        top: Scalar,
+        right: Scalar,
+        bottom: Scalar
+    ) -> T;
+}
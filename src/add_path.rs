@@ -0,0 +1,7 @@
+use {Path};
+
+/// Implemented by contexts who can add path.
+pub trait AddPath<'a, T> {
+    /// Add path.
+    fn path(&'a self, path: &'a Path) -> T;
+}
@@ -0,0 +1,16 @@
+use internal::{
+    Polyline,
+};
+
+/// Implemented by contexts that can add polyline.
+pub trait AddPolyline<'a, T> {
+    /// Creates a polyline context.
+    ///
+    /// The points are not connected from the last to the first.
+    fn polyline(&'a self, polyline: Polyline<'a>) -> T;
+
+    /// Creates a polyline context where the last point connects to the first.
+    ///
+    /// Unlike a polygon, the line caps still apply to the ends of dashes.
+    fn closed_polyline(&'a self, polyline: Polyline<'a>) -> T;
+}
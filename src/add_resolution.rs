@@ -0,0 +1,27 @@
+use {
+    Resolution,
+    Segments,
+    Tolerance,
+};
+use internal::{Scalar};
+
+/// Implemented by contexts that approximate curves with segments.
+pub trait AddResolution<'a, T> {
+    /// Sets how many segments are used for curves.
+    fn resolution(&'a self, resolution: Resolution) -> T;
+
+    /// Picks the number of segments from the size on screen.
+    ///
+    /// The tolerance is the maximum distance between the curve and the segments,
+    /// measured after transformation.
+    #[inline(always)]
+    fn tolerance(&'a self, tolerance: Scalar) -> T {
+        self.resolution(Tolerance(tolerance))
+    }
+
+    /// Uses a fixed number of segments.
+    #[inline(always)]
+    fn segments(&'a self, segments: uint) -> T {
+        self.resolution(Segments(segments))
+    }
+}
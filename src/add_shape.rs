@@ -0,0 +1,9 @@
+use internal::{
+    Shape,
+};
+
+/// Implemented by contexts who can add shape.
+pub trait AddShape<'a, T> {
+    /// Add shape made of one or more polygons.
+    fn shape(&'a self, shape: Shape<'a>) -> T;
+}
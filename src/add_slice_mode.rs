@@ -0,0 +1,23 @@
+use {
+    SliceMode,
+    Stretch,
+    Tile,
+};
+
+/// Implemented by contexts that can set slice mode.
+pub trait AddSliceMode<'a, T> {
+    /// Sets how the image fills its area.
+    fn slice_mode(&'a self, mode: SliceMode) -> T;
+
+    /// Stretches the image.
+    #[inline(always)]
+    fn stretch(&'a self) -> T {
+        self.slice_mode(Stretch)
+    }
+
+    /// Repeats the image.
+    #[inline(always)]
+    fn tile(&'a self) -> T {
+        self.slice_mode(Tile)
+    }
+}
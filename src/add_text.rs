@@ -0,0 +1,10 @@
+use {Font};
+use internal::{
+    Scalar,
+};
+
+/// Implemented by contexts that can add text.
+pub trait AddText<'a, T> {
+    /// Adds text with a font and the height of the em square.
+    fn text(&'a self, font: &'a Font, size: Scalar, text: &'a str) -> T;
+}
@@ -0,0 +1,36 @@
+use {
+    AlignCenter,
+    AlignLeft,
+    AlignRight,
+    TextAlign,
+};
+use internal::{
+    Scalar,
+};
+
+/// Implemented by contexts that can lay out text.
+pub trait AddTextLayout<'a, T> {
+    /// Sets the horizontal alignment of lines.
+    fn align(&'a self, align: TextAlign) -> T;
+
+    /// Breaks lines between words that do not fit within a width.
+    fn max_width(&'a self, width: Scalar) -> T;
+
+    /// Aligns lines at the left side.
+    #[inline(always)]
+    fn align_left(&'a self) -> T {
+        self.align(AlignLeft)
+    }
+
+    /// Centers lines.
+    #[inline(always)]
+    fn align_center(&'a self) -> T {
+        self.align(AlignCenter)
+    }
+
+    /// Aligns lines at the right side.
+    #[inline(always)]
+    fn align_right(&'a self) -> T {
+        self.align(AlignRight)
+    }
+}
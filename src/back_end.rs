@@ -9,6 +9,55 @@
 /// Alpha blending is assumed to be expensive and turned off when not needed.
 /// For cases when alpha blending is explicitly not wanted there will be own methods,
 /// in case the back-end needs to restore to its own default afterwards.
+///
+/// ## Scissor
+/// A back-end that supports scissor restricts drawing to a rectangle.
+/// The rectangle is in the same coordinates as the vertices.
+/// When scissor is not supported, the triangles are clipped before they are sent.
+///
+/// ## Stencil
+/// A back-end that supports stencil can restrict drawing to the shape of a mask.
+/// While writing to the stencil, triangles mark the stencil instead of changing colors.
+/// While testing the stencil, only the marked parts are drawn.
+/// Masks do not nest, so there is at most one mask at a time.
+///
+/// ## Texture upload
+/// A back-end that supports texture upload creates textures from RGBA pixels,
+/// stored row by row starting at the top with 4 bytes per pixel.
+/// The returned id is used like any other texture id, for example in `Image`.
+/// Use `Textures` to delete textures when they are no longer used.
+///
+/// ## Indexed triangles
+/// A back-end that supports indexed triangle lists takes each vertex once,
+/// with 3 indices into the vertices per triangle.
+/// Shapes sharing vertices between triangles then send less data.
+/// Wrappers that change triangles, such as clipping without scissor,
+/// do not support indexed triangle lists.
+///
+/// There are variants with `u32` and with `u16` indices.
+/// A back-end limited to `u16` indices, such as OpenGL ES 2 without extensions,
+/// supports only the `u16` variants.
+/// The contexts send `u16` indices to such a back-end when a shape has at most 65536 vertices,
+/// and `BatchBackEnd` sends its batch before it grows past that.
+///
+/// ## Triangle strips and fans
+/// In a triangle strip every vertex after the second
+/// makes a triangle with the two previous vertices.
+/// In a triangle fan every vertex after the second
+/// makes a triangle with the first and the previous vertex.
+/// Ellipses are drawn as fans and borders as strips when supported.
+///
+/// ## Vertex precision
+/// A back-end that supports f64 vertices gets the transformed vertices without rounding.
+/// Shapes without texture prefer f64 vertices over the other triangle methods,
+/// which avoids jitter when zooming far into large coordinates.
+///
+/// ## Fallbacks
+/// A back-end needs to support only one format for colored triangles:
+/// f32 vertices, f64 vertices, indexed triangles or a uniform color.
+/// The contexts convert the triangles to the first supported format in that order.
+/// A back-end that supports only a uniform color can not render gradients.
+/// Contexts return a `RenderError` when the back-end can not render them.
 pub trait BackEnd {
     /// Returns true if feature is supported.
     #[inline(always)]
@@ -23,6 +72,35 @@ pub trait BackEnd {
     /// Turns off alpha blending.
     fn disable_alpha_blend(&mut self) {}
 
+    /// Returns true if scissor is supported.
+    #[inline(always)]
+    fn supports_scissor(&self) -> bool { false }
+
+    /// Restricts drawing to a rectangle.
+    ///
+    /// Clearing the background is restricted too.
+    /// Drawing with a clipping rectangle sets the scissor and clears it afterwards,
+    /// so a scissor set directly on the back-end does not last through such a draw.
+    fn set_scissor(&mut self, _x: f32, _y: f32, _w: f32, _h: f32) {}
+
+    /// Turns off the scissor, such that drawing is no longer restricted.
+    fn clear_scissor(&mut self) {}
+
+    /// Returns true if stencil is supported.
+    #[inline(always)]
+    fn supports_stencil(&self) -> bool { false }
+
+    /// Clears the stencil and makes the following triangles mark it.
+    ///
+    /// The colors are not changed while writing to the stencil.
+    fn begin_stencil_write(&mut self) {}
+
+    /// Restricts the following triangles to the marked parts of the stencil.
+    fn begin_stencil_test(&mut self) {}
+
+    /// Turns off the stencil, such that drawing is no longer restricted.
+    fn end_stencil(&mut self) {}
+
     /// Returns true if texture feature is supported.
     #[inline(always)]
     fn supports_single_texture(&self) -> bool { false }
@@ -42,6 +120,32 @@ pub trait BackEnd {
     #[inline(always)]
     fn has_texture_alpha(&self, _texture_id: uint) -> bool { false }
 
+    /// Returns true if textures can be created from pixels.
+    #[inline(always)]
+    fn supports_texture_upload(&self) -> bool { false }
+
+    /// Creates a texture from RGBA pixels and returns its id.
+    ///
+    /// Returns `None` if the texture could not be created.
+    fn create_texture(&mut self, _width: u32, _height: u32, _pixels: &[u8]) -> Option<uint> {
+        None
+    }
+
+    /// Replaces the pixels of a rectangle within a texture.
+    ///
+    /// The pixels contain only the rectangle, row by row starting at the top.
+    fn update_texture(
+        &mut self,
+        _texture_id: uint,
+        _x: u32, _y: u32, _w: u32, _h: u32,
+        _pixels: &[u8]
+    ) {}
+
+    /// Deletes a texture.
+    ///
+    /// The texture id might be reused by textures created later.
+    fn delete_texture(&mut self, _texture_id: uint) {}
+
     /// Returns true if feature is supported.
     #[inline(always)]
     fn supports_tri_list_xy_f64_rgba_f32(&self) -> bool { false }
@@ -78,5 +182,105 @@ pub trait BackEnd {
         _colors: &[f32],
         _texture_coords: &[f32]
     ) {}
-}
 
+    /// Returns true if feature is supported.
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32(&self) -> bool { false }
+
+    /// Renders list of 2d triangles picked from vertices by indices.
+    ///
+    /// A color is assigned per vertex.
+    /// Every 3 indices make a triangle.
+    fn tri_list_indexed_xy_f32_rgba_f32(
+        &mut self,
+        _vertices: &[f32],
+        _colors: &[f32],
+        _indices: &[u32]
+    ) {}
+
+    /// Returns true if feature is supported.
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32(&self) -> bool { false }
+
+    /// Renders list of 2d triangles picked from vertices by indices.
+    ///
+    /// A color and a texture coordinate is assigned per vertex.
+    /// The texture coordinates refers to the current single-texture.
+    /// Every 3 indices make a triangle.
+    fn tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+        &mut self,
+        _vertices: &[f32],
+        _colors: &[f32],
+        _texture_coords: &[f32],
+        _indices: &[u32]
+    ) {}
+
+    /// Returns true if feature is supported.
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_u16(&self) -> bool { false }
+
+    /// Renders list of 2d triangles picked from vertices by 16 bit indices.
+    ///
+    /// A color is assigned per vertex.
+    /// Every 3 indices make a triangle.
+    fn tri_list_indexed_xy_f32_rgba_f32_u16(
+        &mut self,
+        _vertices: &[f32],
+        _colors: &[f32],
+        _indices: &[u16]
+    ) {}
+
+    /// Returns true if feature is supported.
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(&self) -> bool { false }
+
+    /// Renders list of 2d triangles picked from vertices by 16 bit indices.
+    ///
+    /// A color and a texture coordinate is assigned per vertex.
+    /// The texture coordinates refers to the current single-texture.
+    /// Every 3 indices make a triangle.
+    fn tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(
+        &mut self,
+        _vertices: &[f32],
+        _colors: &[f32],
+        _texture_coords: &[f32],
+        _indices: &[u16]
+    ) {}
+
+    /// Returns true if feature is supported.
+    #[inline(always)]
+    fn supports_tri_strip_xy_f32_rgba_f32(&self) -> bool { false }
+
+    /// Renders a strip of 2d triangles.
+    ///
+    /// A color is assigned per vertex.
+    fn tri_strip_xy_f32_rgba_f32(
+        &mut self,
+        _vertices: &[f32],
+        _colors: &[f32]
+    ) {}
+
+    /// Returns true if feature is supported.
+    #[inline(always)]
+    fn supports_tri_fan_xy_f32_rgba_f32(&self) -> bool { false }
+
+    /// Renders a fan of 2d triangles.
+    ///
+    /// A color is assigned per vertex.
+    fn tri_fan_xy_f32_rgba_f32(
+        &mut self,
+        _vertices: &[f32],
+        _colors: &[f32]
+    ) {}
+
+    /// Returns true if feature is supported.
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_uniform_rgba_f32(&self) -> bool { false }
+
+    /// Renders list of 2d triangles with the same color for all vertices.
+    fn tri_list_xy_f32_uniform_rgba_f32(
+        &mut self,
+        _vertices: &[f32],
+        _r: f32, _g: f32, _b: f32, _a: f32
+    ) {}
+}
@@ -0,0 +1,760 @@
+//! A back-end wrapper that merges triangle lists into fewer calls.
+
+use std::u16;
+use {BackEnd};
+use triangulation::{expand_indexed_f32, tri_fan_indices, tri_strip_indices};
+
+/// Counts the calls made through a `BatchBackEnd`.
+pub struct BatchStats {
+    /// The number of triangle list calls received.
+    pub calls: uint,
+    /// The number of triangle list calls sent to the back-end.
+    pub batches: uint,
+    /// The number of triangles sent to the back-end.
+    pub triangles: uint,
+}
+
+impl Clone for BatchStats {
+    #[inline(always)]
+    fn clone(&self) -> BatchStats {
+        BatchStats {
+            calls: self.calls,
+            batches: self.batches,
+            triangles: self.triangles,
+        }
+    }
+}
+
+/// Merges consecutive triangle lists drawn with the same state.
+///
+/// Alpha blending and texture changes are delayed until the next triangles,
+/// so shapes that turn a state on and off again are still merged.
+/// The triangles are sent when the state changes, on `flush`
+/// or when the batching back-end is dropped.
+/// Scissor, stencil, clear and texture upload flush immediately.
+///
+/// Supports indexed triangle lists when the back-end does.
+/// A batch that received indexed triangles is sent as one indexed triangle list.
+/// When the back-end supports only `u16` indices,
+/// the batch is sent before it grows past the vertices those can pick.
+/// Triangles with f64 vertices are merged separately from f32 vertices.
+/// Triangle strips and fans are not merged.
+pub struct BatchBackEnd<'b, B> {
+    back_end: &'b mut B,
+    vertices: Vec<f32>,
+    colors: Vec<f32>,
+    texture_coords: Vec<f32>,
+    indices: Vec<u32>,
+    // Whether any of the triangles waiting were indexed.
+    indexed: bool,
+    // Used instead of `vertices` when the triangles waiting have f64 vertices.
+    vertices_f64: Vec<f64>,
+    // The state of the triangles waiting to be sent.
+    batch_alpha_blend: bool,
+    batch_texture: Option<uint>,
+    // The state set by the caller.
+    alpha_blend: bool,
+    texture: Option<uint>,
+    // The state of the back-end.
+    back_end_alpha_blend: bool,
+    back_end_texture: Option<uint>,
+    stats: BatchStats,
+}
+
+impl<'b, B: BackEnd> BatchBackEnd<'b, B> {
+    /// Creates a new batching wrapper around a back-end.
+    ///
+    /// Assumes alpha blending and texture are turned off.
+    pub fn new(back_end: &'b mut B) -> BatchBackEnd<'b, B> {
+        BatchBackEnd {
+            back_end: back_end,
+            vertices: Vec::new(),
+            colors: Vec::new(),
+            texture_coords: Vec::new(),
+            indices: Vec::new(),
+            indexed: false,
+            vertices_f64: Vec::new(),
+            batch_alpha_blend: false,
+            batch_texture: None,
+            alpha_blend: false,
+            texture: None,
+            back_end_alpha_blend: false,
+            back_end_texture: None,
+            stats: BatchStats { calls: 0, batches: 0, triangles: 0 },
+        }
+    }
+
+    /// Calls a closure with a batching back-end and flushes it afterwards.
+    ///
+    /// Returns the statistics of the batching.
+    pub fn with_batch(back_end: &mut B, f: |back_end: &mut BatchBackEnd<B>|) -> BatchStats {
+        let mut batch = BatchBackEnd::new(back_end);
+        f(&mut batch);
+        batch.flush();
+        batch.stats()
+    }
+
+    /// Returns the statistics since creation or `reset_stats`.
+    #[inline(always)]
+    pub fn stats(&self) -> BatchStats {
+        self.stats.clone()
+    }
+
+    /// Sets the statistics to zero.
+    pub fn reset_stats(&mut self) {
+        self.stats = BatchStats { calls: 0, batches: 0, triangles: 0 };
+    }
+
+    /// Sends the triangles waiting and brings the back-end to the current state.
+    pub fn flush(&mut self) {
+        self.send();
+        self.apply_state();
+    }
+
+    /// Sends the triangles waiting, if any.
+    fn send(&mut self) {
+        if self.vertices_f64.len() > 0 {
+            self.back_end.tri_list_xy_f64_rgba_f32(
+                self.vertices_f64.as_slice(),
+                self.colors.as_slice()
+            );
+            self.stats.batches += 1;
+            self.stats.triangles += self.vertices_f64.len() / 6;
+            self.vertices_f64.clear();
+            self.colors.clear();
+            return;
+        }
+        if self.vertices.len() == 0 { return; }
+        let u16_only = self.u16_only(self.batch_texture.is_some());
+        if self.indexed && u16_only {
+            let indices: Vec<u16> = self.indices.iter().map(|&i| i as u16).collect();
+            if self.batch_texture.is_some() {
+                self.back_end.tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(
+                    self.vertices.as_slice(),
+                    self.colors.as_slice(),
+                    self.texture_coords.as_slice(),
+                    indices.as_slice()
+                );
+            } else {
+                self.back_end.tri_list_indexed_xy_f32_rgba_f32_u16(
+                    self.vertices.as_slice(),
+                    self.colors.as_slice(),
+                    indices.as_slice()
+                );
+            }
+        } else if self.indexed && self.batch_texture.is_some() {
+            self.back_end.tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+                self.vertices.as_slice(),
+                self.colors.as_slice(),
+                self.texture_coords.as_slice(),
+                self.indices.as_slice()
+            );
+        } else if self.indexed {
+            self.back_end.tri_list_indexed_xy_f32_rgba_f32(
+                self.vertices.as_slice(),
+                self.colors.as_slice(),
+                self.indices.as_slice()
+            );
+        } else if self.batch_texture.is_some() {
+            self.back_end.tri_list_xy_f32_rgba_f32_uv_f32(
+                self.vertices.as_slice(),
+                self.colors.as_slice(),
+                self.texture_coords.as_slice()
+            );
+        } else {
+            self.back_end.tri_list_xy_f32_rgba_f32(
+                self.vertices.as_slice(),
+                self.colors.as_slice()
+            );
+        }
+        self.stats.batches += 1;
+        self.stats.triangles += if self.indexed { self.indices.len() / 3 }
+            else { self.vertices.len() / 6 };
+        self.vertices.clear();
+        self.colors.clear();
+        self.texture_coords.clear();
+        self.indices.clear();
+        self.indexed = false;
+    }
+
+    /// Returns true if the back-end supports indexed triangles with `u16` indices only.
+    fn u16_only(&self, textured: bool) -> bool {
+        if textured {
+            !self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32()
+        } else {
+            !self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32()
+        }
+    }
+
+    /// Sends the triangles waiting if `n` more vertices
+    /// could not be picked by the `u16` indices of an indexed batch.
+    fn reserve_indices(&mut self, n: uint, indexed: bool) {
+        if !(indexed || self.indexed) || !self.u16_only(self.batch_texture.is_some()) {
+            return;
+        }
+        if self.vertices.len() / 2 + n > u16::MAX as uint + 1 {
+            self.send();
+        }
+    }
+
+    /// Adds indices for triangles without shared vertices,
+    /// once an indexed triangle list joined the batch.
+    fn push_sequential_indices(&mut self, n: uint) {
+        if !self.indexed { return; }
+        let offset = self.vertices.len() / 2;
+        for i in range(offset, offset + n) {
+            self.indices.push(i as u32);
+        }
+    }
+
+    /// Adds indices offset by the vertices waiting.
+    ///
+    /// The triangles without indices waiting get sequential indices first,
+    /// such that batches without indexed triangles build no indices.
+    fn push_indices(&mut self, indices: &[u32]) {
+        let offset = (self.vertices.len() / 2) as u32;
+        if !self.indexed {
+            for i in range(0, offset) {
+                self.indices.push(i);
+            }
+        }
+        for &i in indices.iter() {
+            self.indices.push(offset + i);
+        }
+        self.indexed = true;
+    }
+
+    /// Sets the delayed alpha blending and texture on the back-end.
+    fn apply_state(&mut self) {
+        if self.alpha_blend != self.back_end_alpha_blend {
+            if self.alpha_blend { self.back_end.enable_alpha_blend(); }
+            else { self.back_end.disable_alpha_blend(); }
+            self.back_end_alpha_blend = self.alpha_blend;
+        }
+        if self.texture != self.back_end_texture {
+            match self.texture {
+                Some(texture_id) => self.back_end.enable_single_texture(texture_id),
+                None => self.back_end.disable_single_texture(),
+            }
+            self.back_end_texture = self.texture;
+        }
+    }
+
+    /// Prepares for adding triangles with the current state.
+    fn begin_triangles(&mut self, textured: bool, f64_vertices: bool) {
+        let texture = if textured { self.texture } else { None };
+        let waiting_f64 = self.vertices_f64.len() > 0;
+        if (self.vertices.len() > 0 || waiting_f64)
+        && (self.batch_alpha_blend != self.alpha_blend || self.batch_texture != texture
+            || waiting_f64 != f64_vertices) {
+            self.send();
+        }
+        self.apply_state();
+        self.batch_alpha_blend = self.alpha_blend;
+        self.batch_texture = texture;
+        self.stats.calls += 1;
+    }
+}
+
+#[unsafe_destructor]
+impl<'b, B: BackEnd> Drop for BatchBackEnd<'b, B> {
+    fn drop(&mut self) {
+        // Triangles waiting would otherwise be lost.
+        self.flush();
+    }
+}
+
+impl<'b, B: BackEnd> BackEnd for BatchBackEnd<'b, B> {
+    #[inline(always)]
+    fn supports_clear_rgba(&self) -> bool {
+        self.back_end.supports_clear_rgba()
+    }
+
+    fn clear_rgba(&mut self, r: f32, g: f32, b: f32, a: f32) {
+        self.flush();
+        self.back_end.clear_rgba(r, g, b, a);
+    }
+
+    fn enable_alpha_blend(&mut self) {
+        self.alpha_blend = true;
+    }
+
+    fn disable_alpha_blend(&mut self) {
+        self.alpha_blend = false;
+    }
+
+    #[inline(always)]
+    fn supports_scissor(&self) -> bool {
+        self.back_end.supports_scissor()
+    }
+
+    fn set_scissor(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        self.flush();
+        self.back_end.set_scissor(x, y, w, h);
+    }
+
+    fn clear_scissor(&mut self) {
+        self.flush();
+        self.back_end.clear_scissor();
+    }
+
+    #[inline(always)]
+    fn supports_stencil(&self) -> bool {
+        self.back_end.supports_stencil()
+    }
+
+    fn begin_stencil_write(&mut self) {
+        self.flush();
+        self.back_end.begin_stencil_write();
+    }
+
+    fn begin_stencil_test(&mut self) {
+        self.flush();
+        self.back_end.begin_stencil_test();
+    }
+
+    fn end_stencil(&mut self) {
+        self.flush();
+        self.back_end.end_stencil();
+    }
+
+    #[inline(always)]
+    fn supports_single_texture(&self) -> bool {
+        self.back_end.supports_single_texture()
+    }
+
+    fn enable_single_texture(&mut self, texture_id: uint) {
+        self.texture = Some(texture_id);
+    }
+
+    fn disable_single_texture(&mut self) {
+        self.texture = None;
+    }
+
+    #[inline(always)]
+    fn has_texture_alpha(&self, texture_id: uint) -> bool {
+        self.back_end.has_texture_alpha(texture_id)
+    }
+
+    #[inline(always)]
+    fn supports_texture_upload(&self) -> bool {
+        self.back_end.supports_texture_upload()
+    }
+
+    fn create_texture(&mut self, width: u32, height: u32, pixels: &[u8]) -> Option<uint> {
+        self.back_end.create_texture(width, height, pixels)
+    }
+
+    fn update_texture(
+        &mut self,
+        texture_id: uint,
+        x: u32, y: u32, w: u32, h: u32,
+        pixels: &[u8]
+    ) {
+        // The triangles waiting might use the old pixels.
+        self.send();
+        self.back_end.update_texture(texture_id, x, y, w, h, pixels);
+    }
+
+    fn delete_texture(&mut self, texture_id: uint) {
+        self.send();
+        self.back_end.delete_texture(texture_id);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f64_rgba_f32(&self) -> bool {
+        self.back_end.supports_tri_list_xy_f64_rgba_f32()
+    }
+
+    fn tri_list_xy_f64_rgba_f32(
+        &mut self,
+        vertices: &[f64],
+        colors: &[f32]
+    ) {
+        self.begin_triangles(false, true);
+        self.vertices_f64.push_all(vertices);
+        self.colors.push_all(colors);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_rgba_f32(&self) -> bool {
+        self.back_end.supports_tri_list_xy_f32_rgba_f32()
+    }
+
+    fn tri_list_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        self.begin_triangles(false, false);
+        self.reserve_indices(vertices.len() / 2, false);
+        self.push_sequential_indices(vertices.len() / 2);
+        self.vertices.push_all(vertices);
+        self.colors.push_all(colors);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_rgba_f32_uv_f32(&self) -> bool {
+        self.back_end.supports_tri_list_xy_f32_rgba_f32_uv_f32()
+    }
+
+    fn tri_list_xy_f32_rgba_f32_uv_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32]
+    ) {
+        self.begin_triangles(true, false);
+        self.reserve_indices(vertices.len() / 2, false);
+        self.push_sequential_indices(vertices.len() / 2);
+        self.vertices.push_all(vertices);
+        self.colors.push_all(colors);
+        self.texture_coords.push_all(texture_coords);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32(&self) -> bool {
+        self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32()
+        || self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_u16()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        indices: &[u32]
+    ) {
+        let n = vertices.len() / 2;
+        if !self.supports_tri_list_indexed_xy_f32_rgba_f32()
+        || (self.u16_only(false) && n > u16::MAX as uint + 1) {
+            self.tri_list_xy_f32_rgba_f32(
+                expand_indexed_f32(vertices, 2, indices).as_slice(),
+                expand_indexed_f32(colors, 4, indices).as_slice()
+            );
+            return;
+        }
+        self.begin_triangles(false, false);
+        self.reserve_indices(n, true);
+        self.push_indices(indices);
+        self.vertices.push_all(vertices);
+        self.colors.push_all(colors);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32(&self) -> bool {
+        self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32()
+        || self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32],
+        indices: &[u32]
+    ) {
+        let n = vertices.len() / 2;
+        if !self.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32()
+        || (self.u16_only(true) && n > u16::MAX as uint + 1) {
+            self.tri_list_xy_f32_rgba_f32_uv_f32(
+                expand_indexed_f32(vertices, 2, indices).as_slice(),
+                expand_indexed_f32(colors, 4, indices).as_slice(),
+                expand_indexed_f32(texture_coords, 2, indices).as_slice()
+            );
+            return;
+        }
+        self.begin_triangles(true, false);
+        self.reserve_indices(n, true);
+        self.push_indices(indices);
+        self.vertices.push_all(vertices);
+        self.colors.push_all(colors);
+        self.texture_coords.push_all(texture_coords);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_u16(&self) -> bool {
+        self.supports_tri_list_indexed_xy_f32_rgba_f32()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_u16(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        indices: &[u16]
+    ) {
+        let indices: Vec<u32> = indices.iter().map(|&i| i as u32).collect();
+        self.tri_list_indexed_xy_f32_rgba_f32(vertices, colors, indices.as_slice());
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(&self) -> bool {
+        self.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32],
+        indices: &[u16]
+    ) {
+        let indices: Vec<u32> = indices.iter().map(|&i| i as u32).collect();
+        self.tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+            vertices, colors, texture_coords, indices.as_slice());
+    }
+
+    #[inline(always)]
+    fn supports_tri_strip_xy_f32_rgba_f32(&self) -> bool {
+        self.back_end.supports_tri_strip_xy_f32_rgba_f32()
+    }
+
+    fn tri_strip_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        let n = vertices.len() / 2;
+        if !self.back_end.supports_tri_strip_xy_f32_rgba_f32() {
+            let indices = tri_strip_indices(n);
+            self.tri_list_indexed_xy_f32_rgba_f32(vertices, colors, indices.as_slice());
+            return;
+        }
+        self.send();
+        self.begin_triangles(false, false);
+        self.back_end.tri_strip_xy_f32_rgba_f32(vertices, colors);
+        self.stats.batches += 1;
+        if n > 2 { self.stats.triangles += n - 2; }
+    }
+
+    #[inline(always)]
+    fn supports_tri_fan_xy_f32_rgba_f32(&self) -> bool {
+        self.back_end.supports_tri_fan_xy_f32_rgba_f32()
+    }
+
+    fn tri_fan_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        let n = vertices.len() / 2;
+        if !self.back_end.supports_tri_fan_xy_f32_rgba_f32() {
+            let indices = tri_fan_indices(n);
+            self.tri_list_indexed_xy_f32_rgba_f32(vertices, colors, indices.as_slice());
+            return;
+        }
+        self.send();
+        self.begin_triangles(false, false);
+        self.back_end.tri_fan_xy_f32_rgba_f32(vertices, colors);
+        self.stats.batches += 1;
+        if n > 2 { self.stats.triangles += n - 2; }
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_uniform_rgba_f32(&self) -> bool {
+        self.back_end.supports_tri_list_xy_f32_uniform_rgba_f32()
+    }
+
+    fn tri_list_xy_f32_uniform_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        r: f32, g: f32, b: f32, a: f32
+    ) {
+        // Triangles with a uniform color are not merged.
+        self.send();
+        self.begin_triangles(false, false);
+        self.back_end.tri_list_xy_f32_uniform_rgba_f32(vertices, r, g, b, a);
+        self.stats.batches += 1;
+        self.stats.triangles += vertices.len() / 6;
+    }
+}
+
+#[test]
+fn test_batch() {
+    use {AddColor, AddRectangle, Context, Fill, RecordingBackEnd};
+    use {DisableAlphaBlend, EnableAlphaBlend, TriListXyF32RgbaF32};
+
+    let mut recording = RecordingBackEnd::new();
+    let c = Context::new();
+    let stats = BatchBackEnd::with_batch(&mut recording, |back_end| {
+        for i in range(0u, 10) {
+            let x = i as f64 * 0.1;
+            c.rect(x, 0.0, 0.1, 0.1).rgba(1.0, 0.0, 0.0, 0.5).fill(back_end).unwrap();
+        }
+        c.rect(0.0, 0.5, 0.1, 0.1).rgba(1.0, 0.0, 0.0, 1.0).fill(back_end).unwrap();
+    });
+    assert_eq!(stats.calls, 11);
+    assert_eq!(stats.batches, 2);
+    assert_eq!(stats.triangles, 22);
+    // Alpha blending is turned on for the transparent rectangles only.
+    assert_eq!(recording.commands.len(), 4);
+    assert_eq!(*recording.commands.get(0), EnableAlphaBlend);
+    match *recording.commands.get(1) {
+        TriListXyF32RgbaF32(ref vertices, ref colors) => {
+            assert_eq!(vertices.len(), 10 * 12);
+            assert_eq!(colors.len(), 10 * 24);
+        },
+        _ => fail!("Expected triangle list"),
+    }
+    assert_eq!(*recording.commands.get(2), DisableAlphaBlend);
+}
+
+#[test]
+fn test_batch_texture() {
+    use {AddImage, AddRectangle, Context, Draw, Image, RecordingBackEnd};
+    use {TriListXyF32RgbaF32UvF32};
+
+    let mut recording = RecordingBackEnd::new();
+    let c = Context::new();
+    let image = |texture_id| Image {
+        texture_id: texture_id,
+        texture_width: 1,
+        texture_height: 1,
+        source_rect: [0, 0, 1, 1],
+    };
+    let stats = {
+        let mut back_end = BatchBackEnd::new(&mut recording);
+        c.rect(0.0, 0.0, 0.5, 0.5).image(image(1)).draw(&mut back_end).unwrap();
+        c.rect(0.5, 0.0, 0.5, 0.5).image(image(1)).draw(&mut back_end).unwrap();
+        c.rect(0.0, 0.5, 0.5, 0.5).image(image(2)).draw(&mut back_end).unwrap();
+        back_end.flush();
+        back_end.stats()
+    };
+    assert_eq!(stats.calls, 3);
+    assert_eq!(stats.batches, 2);
+    let textured = recording.commands.iter().filter(|command| match **command {
+        TriListXyF32RgbaF32UvF32(..) => true,
+        _ => false,
+    }).count();
+    assert_eq!(textured, 2);
+}
+
+#[test]
+fn test_batch_indexed() {
+    use {AddColor, AddEllipse, AddRectangle, Context, Fill, RecordingBackEnd};
+    use {TriListIndexedXyF32RgbaF32};
+
+    let mut recording = RecordingBackEnd::new();
+    recording.set_indexed(true);
+    let c = Context::new();
+    let stats = BatchBackEnd::with_batch(&mut recording, |back_end| {
+        c.rect(0.0, 0.0, 0.1, 0.1).rgba(1.0, 0.0, 0.0, 1.0).fill(back_end).unwrap();
+        c.rect(0.5, 0.0, 0.1, 0.1).rgba(1.0, 0.0, 0.0, 1.0).fill(back_end).unwrap();
+        c.ellipse(0.0, 0.5, 0.2, 0.2).rgba(1.0, 0.0, 0.0, 1.0).fill(back_end).unwrap();
+    });
+    assert_eq!(stats.calls, 3);
+    assert_eq!(stats.batches, 1);
+    assert_eq!(recording.commands.len(), 1);
+    match *recording.commands.get(0) {
+        TriListIndexedXyF32RgbaF32(ref vertices, _, ref indices) => {
+            // The second rectangle refers to its own vertices.
+            assert_eq!(indices.slice(6, 12), &[4, 5, 6, 5, 7, 6]);
+            assert_eq!(stats.triangles, indices.len() / 3);
+            assert!(indices.iter().all(|&i| (i as uint) < vertices.len() / 2));
+        },
+        _ => fail!("Expected indexed triangle list"),
+    }
+}
+
+#[test]
+fn test_batch_f64() {
+    use {AddColor, AddEllipse, AddRectangle, Context, Fill, RecordingBackEnd};
+    use {TriListXyF64RgbaF32};
+
+    let mut recording = RecordingBackEnd::new();
+    recording.set_f64_vertices(true);
+    let c = Context::new();
+    let stats = BatchBackEnd::with_batch(&mut recording, |back_end| {
+        c.rect(0.0, 0.0, 0.1, 0.1).rgba(1.0, 0.0, 0.0, 1.0).fill(back_end).unwrap();
+        c.ellipse(0.0, 0.5, 0.2, 0.2).rgba(1.0, 0.0, 0.0, 1.0).fill(back_end).unwrap();
+    });
+    assert_eq!(stats.calls, 2);
+    assert_eq!(stats.batches, 1);
+    assert_eq!(recording.commands.len(), 1);
+    match *recording.commands.get(0) {
+        TriListXyF64RgbaF32(ref vertices, ref colors) => {
+            assert_eq!(vertices.len() * 2, colors.len());
+            assert_eq!(stats.triangles, vertices.len() / 6);
+        },
+        _ => fail!("Expected triangle list with f64 vertices"),
+    }
+}
+
+#[test]
+fn test_batch_drop() {
+    use {AddColor, AddRectangle, Context, Fill, RecordingBackEnd};
+
+    let mut recording = RecordingBackEnd::new();
+    let c = Context::new();
+    {
+        let mut back_end = BatchBackEnd::new(&mut recording);
+        c.rect(0.0, 0.0, 0.1, 0.1).rgba(1.0, 0.0, 0.0, 1.0).fill(&mut back_end).unwrap();
+    }
+    assert_eq!(recording.commands.len(), 1);
+}
+
+#[test]
+fn test_batch_sequential_indices() {
+    use {RecordingBackEnd};
+    use {TriListIndexedXyF32RgbaF32};
+
+    let mut recording = RecordingBackEnd::new();
+    recording.set_indexed(true);
+    let triangle = [0.0f32, 0.0, 1.0, 0.0, 0.0, 1.0];
+    let colors = [1.0f32, ..12];
+    let stats = BatchBackEnd::with_batch(&mut recording, |back_end| {
+        back_end.tri_list_xy_f32_rgba_f32(triangle, colors);
+        back_end.tri_list_indexed_xy_f32_rgba_f32(triangle, colors, [2, 1, 0]);
+        back_end.tri_list_xy_f32_rgba_f32(triangle, colors);
+    });
+    assert_eq!(stats.batches, 1);
+    assert_eq!(stats.triangles, 3);
+    match *recording.commands.get(0) {
+        TriListIndexedXyF32RgbaF32(_, _, ref indices) => {
+            assert_eq!(indices.as_slice(), &[0, 1, 2, 5, 4, 3, 6, 7, 8]);
+        },
+        _ => fail!("Expected indexed triangle list"),
+    }
+}
+
+#[test]
+fn test_batch_u16() {
+    use {AddColor, AddRectangle, Context, Fill, RecordingBackEnd};
+    use {TriListIndexedXyF32RgbaF32U16};
+
+    let mut recording = RecordingBackEnd::new();
+    recording.set_indexed_u16(true);
+    let c = Context::new();
+    let stats = BatchBackEnd::with_batch(&mut recording, |back_end| {
+        c.rect(0.0, 0.0, 0.1, 0.1).rgba(1.0, 0.0, 0.0, 1.0).fill(back_end).unwrap();
+        c.rect(0.5, 0.0, 0.1, 0.1).rgba(1.0, 0.0, 0.0, 1.0).fill(back_end).unwrap();
+    });
+    assert_eq!(stats.batches, 1);
+    match *recording.commands.get(0) {
+        TriListIndexedXyF32RgbaF32U16(_, _, ref indices) => {
+            assert_eq!(indices.as_slice(), &[0, 1, 2, 1, 3, 2, 4, 5, 6, 5, 7, 6]);
+        },
+        _ => fail!("Expected indexed triangle list with u16 indices"),
+    }
+
+    // A batch is sent before its vertices can not be picked by u16 indices.
+    recording.clear_commands();
+    let n = 40000u;
+    let vertices = Vec::from_elem(n * 2, 0.0f32);
+    let colors = Vec::from_elem(n * 4, 1.0f32);
+    let indices = Vec::from_fn(n / 4 * 3, |i| i as u32);
+    let stats = BatchBackEnd::with_batch(&mut recording, |back_end| {
+        back_end.tri_list_indexed_xy_f32_rgba_f32(
+            vertices.as_slice(), colors.as_slice(), indices.as_slice());
+        back_end.tri_list_indexed_xy_f32_rgba_f32(
+            vertices.as_slice(), colors.as_slice(), indices.as_slice());
+    });
+    assert_eq!(stats.batches, 2);
+    for command in recording.commands.iter() {
+        match *command {
+            TriListIndexedXyF32RgbaF32U16(ref vertices, _, _) => {
+                assert_eq!(vertices.len(), n * 2);
+            },
+            _ => fail!("Expected indexed triangle list with u16 indices"),
+        }
+    }
+}
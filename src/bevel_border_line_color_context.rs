@@ -1,14 +1,23 @@
 
 use {
+    AddDash,
     BackEnd,
     Borrowed,
     Clear,
+    ClipBackEnd,
+    Dash,
+    FallbackBackEnd,
     Field,
+    RenderResult,
     Stroke,
+    TriList,
+    TriangularCap,
     Value,
 };
 use triangulation::{
-    with_round_border_line_tri_list_xy_f32_rgba_f32
+    Coordinate,
+    DEFAULT_TOLERANCE,
+    with_line_border_xy_rgba_f32,
 };
 use internal::{
     CanColor,
@@ -21,6 +30,7 @@ use internal::{
     Line,
     Matrix2d,
     Radius,
+    Rectangle,
 };
 
 /// A line context with bevel border information.
@@ -29,12 +39,16 @@ pub struct BevelBorderLineColorContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transform.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current line.
     pub line: Field<'a, Line>,
     /// Current color.
     pub color: Field<'a, Color>,
     /// Current bevel border.
     pub bevel_border_radius: Field<'a, Radius>,
+    /// Current dash pattern.
+    pub dash: Field<'a, Option<Dash>>,
 }
 
 impl<'a> Clone for BevelBorderLineColorContext<'a> {
@@ -43,9 +57,11 @@ impl<'a> Clone for BevelBorderLineColorContext<'a> {
         BevelBorderLineColorContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             line: Value(*self.line.get()),
             color: Value(*self.color.get()),
             bevel_border_radius: Value(*self.bevel_border_radius.get()),
+            dash: Value(*self.dash.get()),
         }
     }
 }
@@ -63,9 +79,11 @@ impl<'a> CanTransform<'a, BevelBorderLineColorContext<'a>, Matrix2d> for BevelBo
         BevelBorderLineColorContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             color: Borrowed(self.color.get()),
             bevel_border_radius: Borrowed(self.bevel_border_radius.get()),
+            dash: Borrowed(self.dash.get()),
         }
     }
 }
@@ -84,9 +102,11 @@ for BevelBorderLineColorContext<'a> {
         BevelBorderLineColorContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             color: Borrowed(self.color.get()),
             bevel_border_radius: Borrowed(self.bevel_border_radius.get()),
+            dash: Borrowed(self.dash.get()),
         }
     }
 }
@@ -104,49 +124,78 @@ impl<'a> CanColor<'a, BevelBorderLineColorContext<'a>, Color> for BevelBorderLin
         BevelBorderLineColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             color: Value(value),
             bevel_border_radius: Borrowed(self.bevel_border_radius.get()),
+            dash: Borrowed(self.dash.get()),
         }
     }
 }
 
+impl<'a> AddDash<'a, BevelBorderLineColorContext<'a>> for BevelBorderLineColorContext<'a> {
+    #[inline(always)]
+    fn dash_pattern(&'a self, dash: Dash) -> BevelBorderLineColorContext<'a> {
+        BevelBorderLineColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            line: Borrowed(self.line.get()),
+            color: Borrowed(self.color.get()),
+            bevel_border_radius: Borrowed(self.bevel_border_radius.get()),
+            dash: Value(Some(dash)),
+        }
+    }
+}
+
+impl<'a> BevelBorderLineColorContext<'a> {
+    /// Streams the line border with f32 or f64 vertices.
+    fn stream_tri_list<V: Coordinate>(&self, f: |vertices: &[V], colors: &[f32]|) {
+        with_line_border_xy_rgba_f32(
+            TriList,
+            DEFAULT_TOLERANCE,
+            *self.transform.get(),
+            *self.line.get(),
+            *self.bevel_border_radius.get(),
+            TriangularCap,
+            TriangularCap,
+            *self.dash.get(),
+            *self.color.get(),
+            f
+        );
+    }
+}
+
 impl<'a> Stroke<'a> for BevelBorderLineColorContext<'a> {
     #[inline(always)]
-    fn stroke<B: BackEnd>(&'a self, back_end: &mut B) {
-        if back_end.supports_tri_list_xy_f32_rgba_f32() {
-            let line = self.line.get();
-            let bevel_border_radius = self.bevel_border_radius.get();
-            let color = self.color.get();
-            // Complete transparency does not need to be rendered.
-            if color[3] == 0.0 { return; }
-            // Turn on alpha blending if not completely opaque.
-            let needs_alpha = color[3] != 1.0;
-            if needs_alpha { back_end.enable_alpha_blend(); }
-            with_round_border_line_tri_list_xy_f32_rgba_f32(
-                3,
-                *self.transform.get(),
-                *line,
-                *bevel_border_radius,
-                *color,
-                |vertices, colors| {
-                    back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+    fn stroke<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let color = self.color.get();
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                // Turn on alpha blending if not completely opaque.
+                let needs_alpha = color[3] != 1.0;
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                if back_end.supports_tri_list_xy_f64_rgba_f32() {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f64_rgba_f32(vertices, colors)
+                    });
+                } else {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+                    });
                 }
-            );
-            if needs_alpha { back_end.disable_alpha_blend(); }
-        } else {
-            unimplemented!();
-        }
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
     }
 }
 
 impl<'a> Clear for BevelBorderLineColorContext<'a> {
     #[inline(always)]
-    fn clear<B: BackEnd>(&self, back_end: &mut B) {
-        if back_end.supports_clear_rgba() {
-            let color = self.color.get();
-            back_end.clear_rgba(color[0], color[1], color[2], color[3]);
-        }
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
     }
 }
 
@@ -15,6 +15,7 @@ use internal::{
     Line,
     Matrix2d,
     Radius,
+    Rectangle,
 };
 
 /// A line context with bevel border information.
@@ -23,6 +24,8 @@ pub struct BevelBorderLineContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transform.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current line.
     pub line: Field<'a, Line>,
     /// Current bevel border.
@@ -35,6 +38,7 @@ impl<'a> Clone for BevelBorderLineContext<'a> {
         BevelBorderLineContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             line: Value(*self.line.get()),
             bevel_border_radius: Value(*self.bevel_border_radius.get()),
         }
@@ -54,6 +58,7 @@ impl<'a> CanTransform<'a, BevelBorderLineContext<'a>, Matrix2d> for BevelBorderL
         BevelBorderLineContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             bevel_border_radius: Borrowed(self.bevel_border_radius.get()),
         }
@@ -73,6 +78,7 @@ impl<'a> CanViewTransform<'a, BevelBorderLineContext<'a>, Matrix2d> for BevelBor
         BevelBorderLineContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             bevel_border_radius: Borrowed(self.bevel_border_radius.get()),
         }
@@ -91,9 +97,11 @@ impl<'a> AddColor<'a, BevelBorderLineColorContext<'a>> for BevelBorderLineContex
         BevelBorderLineColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             color: Value([r, g, b, a]),
             bevel_border_radius: Borrowed(self.bevel_border_radius.get()),
+            dash: Value(None),
         }
     }
 }
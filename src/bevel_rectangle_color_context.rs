@@ -1,13 +1,25 @@
 use {
+    AddBorder,
+    AddGradient,
     BackEnd,
+    BorderBevelRectangleColorContext,
     Borrowed,
     Clear,
+    ClipBackEnd,
+    FallbackBackEnd,
     Field,
     Fill,
+    Gradient,
+    MiterJoin,
+    RenderResult,
+    TriList,
     Value,
 };
 use triangulation::{
-    with_round_rectangle_tri_list_xy_f32_rgba_f32
+    Coordinate,
+    DEFAULT_MITER_LIMIT,
+    with_round_rectangle_gradient_tri_list_xy_rgba_f32,
+    with_round_rectangle_xy_rgba_f32,
 };
 use internal::{
     CanColor,
@@ -30,12 +42,16 @@ pub struct BevelRectangleColorContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transformation.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current rectangle.
     pub rect: Field<'a, Rectangle>,
     /// Current bevel radius.
     pub bevel_radius: Field<'a, Radius>,
     /// Current color.
     pub color: Field<'a, Color>,
+    /// Current gradient.
+    pub gradient: Field<'a, Option<Gradient>>,
 }
 
 impl<'a> Clone for BevelRectangleColorContext<'a> {
@@ -44,9 +60,11 @@ impl<'a> Clone for BevelRectangleColorContext<'a> {
         BevelRectangleColorContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             rect: Value(*self.rect.get()),
             bevel_radius: Value(*self.bevel_radius.get()),
             color: Value(*self.color.get()),
+            gradient: Value(*self.gradient.get()),
         }
     }
 }
@@ -64,9 +82,11 @@ impl<'a> CanTransform<'a, BevelRectangleColorContext<'a>, Matrix2d> for BevelRec
         BevelRectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             bevel_radius: Borrowed(self.bevel_radius.get()),
             color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
         }
     }
 }
@@ -85,9 +105,11 @@ for BevelRectangleColorContext<'a> {
         BevelRectangleColorContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             bevel_radius: Borrowed(self.bevel_radius.get()),
             color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
         }
     }
 }
@@ -105,7 +127,9 @@ impl<'a> CanColor<'a, BevelRectangleColorContext<'a>, Color> for BevelRectangleC
         BevelRectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Value(value),
+            gradient: Borrowed(self.gradient.get()),
             rect: Borrowed(self.rect.get()),
             bevel_radius: Borrowed(self.bevel_radius.get()),
         }
@@ -125,51 +149,103 @@ impl<'a> CanRectangle<'a, BevelRectangleColorContext<'a>, Rectangle> for BevelRe
         BevelRectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Value(rect),
             bevel_radius: Borrowed(self.bevel_radius.get()),
             color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
         }
     }
 }
 
 impl<'a> Clear for BevelRectangleColorContext<'a> {
     #[inline(always)]
-    fn clear<B: BackEnd>(&self, back_end: &mut B) {
-        if back_end.supports_clear_rgba() {
-            let color = self.color.get();
-            back_end.clear_rgba(color[0], color[1], color[2], color[3]);
-        } else {
-            unimplemented!();
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
+
+impl<'a> BevelRectangleColorContext<'a> {
+    /// Streams the filled bevel rectangle with f32 or f64 vertices.
+    fn stream_tri_list<V: Coordinate>(&self, f: |vertices: &[V], colors: &[f32]|) {
+        let m = *self.transform.get();
+        let (rect, bevel_radius) = (*self.rect.get(), *self.bevel_radius.get());
+        match *self.gradient.get() {
+            Some(ref gradient) => with_round_rectangle_gradient_tri_list_xy_rgba_f32(
+                2, m, rect, bevel_radius, gradient, f),
+            None => {
+                with_round_rectangle_xy_rgba_f32(
+                    TriList, 2, m, rect, bevel_radius, *self.color.get(), f);
+            },
         }
     }
 }
 
 impl<'a> Fill<'a> for BevelRectangleColorContext<'a> {
     #[inline(always)]
-    fn fill<B: BackEnd>(&'a self, back_end: &mut B) {
-        if back_end.supports_tri_list_xy_f32_rgba_f32() {
-            let rect = self.rect.get();
-            let bevel_radius = self.bevel_radius.get();
-            let color = self.color.get();
-            // Complete transparency does not need to be rendered.
-            if color[3] == 0.0 { return; }
-            // Turn on alpha blending if not completely opaque.
-            let needs_alpha = color[3] != 1.0;
-            if needs_alpha { back_end.enable_alpha_blend(); }
-            with_round_rectangle_tri_list_xy_f32_rgba_f32(
-                2,
-                *self.transform.get(),
-                *rect,
-                *bevel_radius,
-                *color,
-                |vertices, colors| {
-                    back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+    fn fill<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let needs_alpha = match *self.gradient.get() {
+                    Some(ref gradient) => {
+                        // Complete transparency does not need to be rendered.
+                        if gradient.is_invisible() { return; }
+                        // Turn on alpha blending if any stop is not completely opaque.
+                        gradient.has_alpha()
+                    },
+                    None => {
+                        let color = self.color.get();
+                        // Complete transparency does not need to be rendered.
+                        if color[3] == 0.0 { return; }
+                        // Turn on alpha blending if not completely opaque.
+                        color[3] != 1.0
+                    },
+                };
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                if back_end.supports_tri_list_xy_f64_rgba_f32() {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f64_rgba_f32(vertices, colors)
+                    });
+                } else {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+                    });
                 }
-            );
-            if needs_alpha { back_end.disable_alpha_blend(); }
-        } else {
-            unimplemented!();
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+impl<'a> AddGradient<'a, BevelRectangleColorContext<'a>> for BevelRectangleColorContext<'a> {
+    #[inline(always)]
+    fn gradient(&'a self, gradient: Gradient) -> BevelRectangleColorContext<'a> {
+        BevelRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            bevel_radius: Borrowed(self.bevel_radius.get()),
+            color: Borrowed(self.color.get()),
+            gradient: Value(Some(gradient)),
         }
     }
 }
 
+impl<'a> AddBorder<'a, BorderBevelRectangleColorContext<'a>> for BevelRectangleColorContext<'a> {
+    #[inline(always)]
+    fn border_radius(&'a self, radius: Radius) -> BorderBevelRectangleColorContext<'a> {
+        BorderBevelRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            bevel_radius: Borrowed(self.bevel_radius.get()),
+            border_radius: Value(radius),
+            join: Value(MiterJoin),
+            miter_limit: Value(DEFAULT_MITER_LIMIT),
+            dash: Value(None),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
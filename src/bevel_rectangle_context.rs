@@ -1,8 +1,10 @@
 use {
     AddColor,
+    AddGradient,
     Borrowed,
     Field,
     BevelRectangleColorContext,
+    Gradient,
     Value,
 };
 use internal::{
@@ -24,6 +26,8 @@ pub struct BevelRectangleContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transform.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current rectangle.
     pub rect: Field<'a, Rectangle>,
     /// Current bevel radius.
@@ -36,6 +40,7 @@ impl<'a> Clone for BevelRectangleContext<'a> {
         BevelRectangleContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             rect: Value(*self.rect.get()),
             bevel_radius: Value(*self.bevel_radius.get()),
         }
@@ -55,6 +60,7 @@ impl<'a> CanTransform<'a, BevelRectangleContext<'a>, Matrix2d> for BevelRectangl
         BevelRectangleContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             bevel_radius: Borrowed(self.bevel_radius.get()),
         }
@@ -74,6 +80,7 @@ impl<'a> CanViewTransform<'a, BevelRectangleContext<'a>, Matrix2d> for BevelRect
         BevelRectangleContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             bevel_radius: Borrowed(self.bevel_radius.get()),
         }
@@ -93,6 +100,7 @@ impl<'a> CanRectangle<'a, BevelRectangleContext<'a>, Rectangle> for BevelRectang
         BevelRectangleContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Value(rect),
             bevel_radius: Borrowed(self.bevel_radius.get()),
         }
@@ -112,7 +120,24 @@ impl<'a> AddColor<'a, BevelRectangleColorContext<'a>> for BevelRectangleContext<
         BevelRectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Value([r, g, b, a]),
+            gradient: Value(None),
+            rect: Borrowed(self.rect.get()),
+            bevel_radius: Borrowed(self.bevel_radius.get()),
+        }
+    }
+}
+
+impl<'a> AddGradient<'a, BevelRectangleColorContext<'a>> for BevelRectangleContext<'a> {
+    #[inline(always)]
+    fn gradient(&'a self, gradient: Gradient) -> BevelRectangleColorContext<'a> {
+        BevelRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            color: Value(gradient.stops()[0].val1()),
+            gradient: Value(Some(gradient)),
             rect: Borrowed(self.rect.get()),
             bevel_radius: Borrowed(self.bevel_radius.get()),
         }
@@ -0,0 +1,281 @@
+use {
+    AddDash,
+    AddLineJoin,
+    BackEnd,
+    Borrowed,
+    Clear,
+    ClipBackEnd,
+    Dash,
+    FallbackBackEnd,
+    Field,
+    LineJoin,
+    RenderResult,
+    Stroke,
+    TriList,
+    Value,
+};
+use triangulation::{
+    Coordinate,
+    with_round_rectangle_border_xy_rgba_f32,
+};
+use internal::{
+    CanColor,
+    CanRectangle,
+    CanTransform,
+    CanViewTransform,
+    Color,
+    HasColor,
+    HasRectangle,
+    HasTransform,
+    HasViewTransform,
+    Matrix2d,
+    Radius,
+    Rectangle,
+    Scalar,
+};
+
+/// A bevel rectangle color context with border information.
+pub struct BorderBevelRectangleColorContext<'a> {
+    /// Base/original transformation.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transformation.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current rectangle.
+    pub rect: Field<'a, Rectangle>,
+    /// Current bevel radius.
+    pub bevel_radius: Field<'a, Radius>,
+    /// Current border radius.
+    pub border_radius: Field<'a, Radius>,
+    /// Current line join.
+    pub join: Field<'a, LineJoin>,
+    /// Current miter limit.
+    pub miter_limit: Field<'a, Scalar>,
+    /// Current dash pattern.
+    pub dash: Field<'a, Option<Dash>>,
+    /// Current color.
+    pub color: Field<'a, Color>,
+}
+
+impl<'a> Clone for BorderBevelRectangleColorContext<'a> {
+    #[inline(always)]
+    fn clone(&self) -> BorderBevelRectangleColorContext<'static> {
+        BorderBevelRectangleColorContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            rect: Value(*self.rect.get()),
+            bevel_radius: Value(*self.bevel_radius.get()),
+            border_radius: Value(*self.border_radius.get()),
+            join: Value(*self.join.get()),
+            miter_limit: Value(*self.miter_limit.get()),
+            dash: Value(*self.dash.get()),
+            color: Value(*self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasTransform<'a, Matrix2d> for BorderBevelRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a> CanTransform<'a, BorderBevelRectangleColorContext<'a>, Matrix2d> for BorderBevelRectangleColorContext<'a> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> BorderBevelRectangleColorContext<'a> {
+        BorderBevelRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            bevel_radius: Borrowed(self.bevel_radius.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasViewTransform<'a, Matrix2d> for BorderBevelRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a> CanViewTransform<'a, BorderBevelRectangleColorContext<'a>, Matrix2d> for BorderBevelRectangleColorContext<'a> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> BorderBevelRectangleColorContext<'a> {
+        BorderBevelRectangleColorContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            bevel_radius: Borrowed(self.bevel_radius.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasColor<'a, Color> for BorderBevelRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_color(&'a self) -> &'a Color {
+        self.color.get()
+    }
+}
+
+impl<'a> CanColor<'a, BorderBevelRectangleColorContext<'a>, Color> for BorderBevelRectangleColorContext<'a> {
+    #[inline(always)]
+    fn color(&'a self, value: Color) -> BorderBevelRectangleColorContext<'a> {
+        BorderBevelRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            bevel_radius: Borrowed(self.bevel_radius.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Value(value),
+        }
+    }
+}
+
+impl<'a> HasRectangle<'a, Rectangle> for BorderBevelRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_rectangle(&'a self) -> &'a Rectangle {
+        self.rect.get()
+    }
+}
+
+impl<'a> CanRectangle<'a, BorderBevelRectangleColorContext<'a>, Rectangle> for BorderBevelRectangleColorContext<'a> {
+    #[inline(always)]
+    fn rectangle(&'a self, value: Rectangle) -> BorderBevelRectangleColorContext<'a> {
+        BorderBevelRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Value(value),
+            bevel_radius: Borrowed(self.bevel_radius.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> AddLineJoin<'a, BorderBevelRectangleColorContext<'a>> for BorderBevelRectangleColorContext<'a> {
+    #[inline(always)]
+    fn join(&'a self, join: LineJoin) -> BorderBevelRectangleColorContext<'a> {
+        BorderBevelRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            bevel_radius: Borrowed(self.bevel_radius.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Value(join),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+
+    #[inline(always)]
+    fn miter_limit(&'a self, miter_limit: Scalar) -> BorderBevelRectangleColorContext<'a> {
+        BorderBevelRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            bevel_radius: Borrowed(self.bevel_radius.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Value(miter_limit),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> AddDash<'a, BorderBevelRectangleColorContext<'a>> for BorderBevelRectangleColorContext<'a> {
+    #[inline(always)]
+    fn dash_pattern(&'a self, dash: Dash) -> BorderBevelRectangleColorContext<'a> {
+        BorderBevelRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            bevel_radius: Borrowed(self.bevel_radius.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Value(Some(dash)),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> BorderBevelRectangleColorContext<'a> {
+    /// Streams the bevel rectangle border with f32 or f64 vertices.
+    fn stream_tri_list<V: Coordinate>(&self, f: |vertices: &[V], colors: &[f32]|) {
+        with_round_rectangle_border_xy_rgba_f32(
+            TriList,
+            2,
+            *self.transform.get(),
+            *self.rect.get(),
+            *self.bevel_radius.get(),
+            *self.border_radius.get(),
+            *self.join.get(),
+            *self.miter_limit.get(),
+            *self.dash.get(),
+            *self.color.get(),
+            f
+        );
+    }
+}
+
+impl<'a> Stroke<'a> for BorderBevelRectangleColorContext<'a> {
+    #[inline(always)]
+    fn stroke<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let color = self.color.get();
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                // Turn on alpha blending if not completely opaque.
+                let needs_alpha = color[3] != 1.0;
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                if back_end.supports_tri_list_xy_f64_rgba_f32() {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f64_rgba_f32(vertices, colors)
+                    });
+                } else {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+                    });
+                }
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+impl<'a> Clear for BorderBevelRectangleColorContext<'a> {
+    #[inline(always)]
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
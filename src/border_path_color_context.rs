@@ -0,0 +1,337 @@
+use {
+    AddDash,
+    AddLineCap,
+    AddLineJoin,
+    BackEnd,
+    Borrowed,
+    Clear,
+    ClipBackEnd,
+    Dash,
+    FallbackBackEnd,
+    Field,
+    LineCap,
+    LineJoin,
+    Path,
+    RenderResult,
+    Stroke,
+    Value,
+};
+use triangulation::{
+    Coordinate,
+    DEFAULT_TOLERANCE,
+    with_path_border_tri_list_xy_rgba_f32
+};
+use internal::{
+    CanColor,
+    CanTransform,
+    CanViewTransform,
+    Color,
+    HasColor,
+    HasTransform,
+    HasViewTransform,
+    Matrix2d,
+    Radius,
+    Rectangle,
+    Scalar,
+};
+
+/// A path context with border information.
+pub struct BorderPathColorContext<'a, 'b> {
+    /// Base/original transformation.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transformation.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current path.
+    pub path: Field<'a, &'b Path>,
+    /// Current border radius.
+    pub border_radius: Field<'a, Radius>,
+    /// Current line join.
+    pub join: Field<'a, LineJoin>,
+    /// Current miter limit.
+    pub miter_limit: Field<'a, Scalar>,
+    /// Current cap at the start of the line.
+    pub start_cap: Field<'a, LineCap>,
+    /// Current cap at the end of the line.
+    pub end_cap: Field<'a, LineCap>,
+    /// Current dash pattern.
+    pub dash: Field<'a, Option<Dash>>,
+    /// Current color.
+    pub color: Field<'a, Color>,
+}
+
+impl<'a, 'b> Clone for BorderPathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn clone(&self) -> BorderPathColorContext<'static, 'b> {
+        BorderPathColorContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            path: Value(*self.path.get()),
+            border_radius: Value(*self.border_radius.get()),
+            join: Value(*self.join.get()),
+            miter_limit: Value(*self.miter_limit.get()),
+            start_cap: Value(*self.start_cap.get()),
+            end_cap: Value(*self.end_cap.get()),
+            dash: Value(*self.dash.get()),
+            color: Value(*self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasTransform<'a, Matrix2d> for BorderPathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a, 'b> CanTransform<'a, BorderPathColorContext<'a, 'b>, Matrix2d> for BorderPathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> BorderPathColorContext<'a, 'b> {
+        BorderPathColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            start_cap: Borrowed(self.start_cap.get()),
+            end_cap: Borrowed(self.end_cap.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasViewTransform<'a, Matrix2d> for BorderPathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a, 'b> CanViewTransform<'a, BorderPathColorContext<'a, 'b>, Matrix2d> for BorderPathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> BorderPathColorContext<'a, 'b> {
+        BorderPathColorContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            start_cap: Borrowed(self.start_cap.get()),
+            end_cap: Borrowed(self.end_cap.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasColor<'a, Color> for BorderPathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_color(&'a self) -> &'a Color {
+        self.color.get()
+    }
+}
+
+impl<'a, 'b> CanColor<'a, BorderPathColorContext<'a, 'b>, Color> for BorderPathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn color(&'a self, value: Color) -> BorderPathColorContext<'a, 'b> {
+        BorderPathColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            start_cap: Borrowed(self.start_cap.get()),
+            end_cap: Borrowed(self.end_cap.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Value(value),
+        }
+    }
+}
+
+impl<'a, 'b> AddLineJoin<'a, BorderPathColorContext<'a, 'b>> for BorderPathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn join(&'a self, join: LineJoin) -> BorderPathColorContext<'a, 'b> {
+        BorderPathColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Value(join),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            start_cap: Borrowed(self.start_cap.get()),
+            end_cap: Borrowed(self.end_cap.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+
+    #[inline(always)]
+    fn miter_limit(&'a self, miter_limit: Scalar) -> BorderPathColorContext<'a, 'b> {
+        BorderPathColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Value(miter_limit),
+            start_cap: Borrowed(self.start_cap.get()),
+            end_cap: Borrowed(self.end_cap.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> AddLineCap<'a, BorderPathColorContext<'a, 'b>> for BorderPathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn start_cap(&'a self, cap: LineCap) -> BorderPathColorContext<'a, 'b> {
+        BorderPathColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            start_cap: Value(cap),
+            end_cap: Borrowed(self.end_cap.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+
+    #[inline(always)]
+    fn end_cap(&'a self, cap: LineCap) -> BorderPathColorContext<'a, 'b> {
+        BorderPathColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            start_cap: Borrowed(self.start_cap.get()),
+            end_cap: Value(cap),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+
+    #[inline(always)]
+    fn cap(&'a self, cap: LineCap) -> BorderPathColorContext<'a, 'b> {
+        BorderPathColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            start_cap: Value(cap),
+            end_cap: Value(cap),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> AddDash<'a, BorderPathColorContext<'a, 'b>> for BorderPathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn dash_pattern(&'a self, dash: Dash) -> BorderPathColorContext<'a, 'b> {
+        BorderPathColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            start_cap: Borrowed(self.start_cap.get()),
+            end_cap: Borrowed(self.end_cap.get()),
+            dash: Value(Some(dash)),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> BorderPathColorContext<'a, 'b> {
+    /// Streams the path border with f32 or f64 vertices.
+    fn stream_tri_list<V: Coordinate>(&self, f: |vertices: &[V], colors: &[f32]|) {
+        with_path_border_tri_list_xy_rgba_f32(
+            DEFAULT_TOLERANCE,
+            *self.transform.get(),
+            *self.path.get(),
+            *self.border_radius.get(),
+            *self.join.get(),
+            *self.miter_limit.get(),
+            *self.start_cap.get(),
+            *self.end_cap.get(),
+            *self.dash.get(),
+            *self.color.get(),
+            f
+        );
+    }
+}
+
+impl<'a, 'b> Stroke<'a> for BorderPathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn stroke<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let color = self.color.get();
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                // Turn on alpha blending if not completely opaque.
+                let needs_alpha = color[3] != 1.0;
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                if back_end.supports_tri_list_xy_f64_rgba_f32() {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f64_rgba_f32(vertices, colors)
+                    });
+                } else {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+                    });
+                }
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+impl<'a, 'b> Clear for BorderPathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
+
+#[test]
+fn test_stroke_path() {
+    use {AddBorder, AddColor, AddPath, Context, SoftwareBackEnd};
+
+    let path = Path::new()
+        .move_to(-0.5, -0.5)
+        .line_to(0.5, -0.5)
+        .line_to(0.5, 0.5);
+    let mut back_end = SoftwareBackEnd::new(8, 8);
+    let c = Context::new();
+    c.path(&path).rgba(1.0, 1.0, 1.0, 1.0).border_width(0.25).stroke(&mut back_end).unwrap();
+    assert!(back_end.pixel(2, 6) == [255, 255, 255, 255]);
+    assert!(back_end.pixel(6, 2) == [255, 255, 255, 255]);
+    // The path is not closed.
+    assert!(back_end.pixel(1, 1) == [0, 0, 0, 0]);
+    assert!(back_end.pixel(4, 4) == [0, 0, 0, 0]);
+}
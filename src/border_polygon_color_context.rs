@@ -0,0 +1,257 @@
+use {
+    AddDash,
+    AddLineJoin,
+    BackEnd,
+    Borrowed,
+    Clear,
+    ClipBackEnd,
+    Dash,
+    FallbackBackEnd,
+    Field,
+    LineJoin,
+    RenderResult,
+    Stroke,
+    TriList,
+    TriStrip,
+    TriangleKind,
+    Value,
+};
+use triangulation::{
+    Coordinate,
+    with_polygon_border_xy_rgba_f32,
+};
+use internal::{
+    CanColor,
+    CanTransform,
+    CanViewTransform,
+    Color,
+    HasColor,
+    HasTransform,
+    HasViewTransform,
+    Matrix2d,
+    Polygon,
+    Radius,
+    Rectangle,
+    Scalar,
+};
+
+/// A polygon color context with border information.
+pub struct BorderPolygonColorContext<'a, 'b> {
+    /// Base/original transformation.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transformation.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current polygon.
+    pub polygon: Field<'a, Polygon<'b>>,
+    /// Current border radius.
+    pub border_radius: Field<'a, Radius>,
+    /// Current line join.
+    pub join: Field<'a, LineJoin>,
+    /// Current miter limit.
+    pub miter_limit: Field<'a, Scalar>,
+    /// Current dash pattern.
+    pub dash: Field<'a, Option<Dash>>,
+    /// Current color.
+    pub color: Field<'a, Color>,
+}
+
+impl<'a, 'b> Clone for BorderPolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn clone(&self) -> BorderPolygonColorContext<'static, 'b> {
+        BorderPolygonColorContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            polygon: Value(*self.polygon.get()),
+            border_radius: Value(*self.border_radius.get()),
+            join: Value(*self.join.get()),
+            miter_limit: Value(*self.miter_limit.get()),
+            dash: Value(*self.dash.get()),
+            color: Value(*self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasTransform<'a, Matrix2d> for BorderPolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a, 'b> CanTransform<'a, BorderPolygonColorContext<'a, 'b>, Matrix2d> for BorderPolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> BorderPolygonColorContext<'a, 'b> {
+        BorderPolygonColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasViewTransform<'a, Matrix2d> for BorderPolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a, 'b> CanViewTransform<'a, BorderPolygonColorContext<'a, 'b>, Matrix2d> for BorderPolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> BorderPolygonColorContext<'a, 'b> {
+        BorderPolygonColorContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasColor<'a, Color> for BorderPolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_color(&'a self) -> &'a Color {
+        self.color.get()
+    }
+}
+
+impl<'a, 'b> CanColor<'a, BorderPolygonColorContext<'a, 'b>, Color> for BorderPolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn color(&'a self, value: Color) -> BorderPolygonColorContext<'a, 'b> {
+        BorderPolygonColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Value(value),
+        }
+    }
+}
+
+impl<'a, 'b> AddLineJoin<'a, BorderPolygonColorContext<'a, 'b>> for BorderPolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn join(&'a self, join: LineJoin) -> BorderPolygonColorContext<'a, 'b> {
+        BorderPolygonColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Value(join),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+
+    #[inline(always)]
+    fn miter_limit(&'a self, miter_limit: Scalar) -> BorderPolygonColorContext<'a, 'b> {
+        BorderPolygonColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Value(miter_limit),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> AddDash<'a, BorderPolygonColorContext<'a, 'b>> for BorderPolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn dash_pattern(&'a self, dash: Dash) -> BorderPolygonColorContext<'a, 'b> {
+        BorderPolygonColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Value(Some(dash)),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> BorderPolygonColorContext<'a, 'b> {
+    /// Streams the polygon border with f32 or f64 vertices as the given kind of triangles.
+    fn stream<V: Coordinate>(
+        &self,
+        kind: TriangleKind,
+        f: |vertices: &[V], colors: &[f32]|
+    ) -> bool {
+        with_polygon_border_xy_rgba_f32(
+            kind,
+            *self.transform.get(),
+            *self.polygon.get(),
+            *self.border_radius.get(),
+            *self.join.get(),
+            *self.miter_limit.get(),
+            *self.dash.get(),
+            *self.color.get(),
+            f
+        )
+    }
+}
+
+impl<'a, 'b> Stroke<'a> for BorderPolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn stroke<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let color = self.color.get();
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                // Turn on alpha blending if not completely opaque.
+                let needs_alpha = color[3] != 1.0;
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                let strip = !back_end.supports_tri_list_xy_f64_rgba_f32()
+                    && back_end.supports_tri_strip_xy_f32_rgba_f32()
+                    && self.stream(TriStrip, |vertices, colors| {
+                        back_end.tri_strip_xy_f32_rgba_f32(vertices, colors)
+                    });
+                if !strip {
+                    if back_end.supports_tri_list_xy_f64_rgba_f32() {
+                        self.stream(TriList, |vertices, colors| {
+                            back_end.tri_list_xy_f64_rgba_f32(vertices, colors)
+                        });
+                    } else {
+                        self.stream(TriList, |vertices, colors| {
+                            back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+                        });
+                    }
+                }
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+impl<'a, 'b> Clear for BorderPolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
@@ -0,0 +1,418 @@
+use {
+    AddDash,
+    AddLineCap,
+    AddLineJoin,
+    BackEnd,
+    Borrowed,
+    Clear,
+    ClipBackEnd,
+    Dash,
+    FallbackBackEnd,
+    Field,
+    LineCap,
+    LineJoin,
+    RenderResult,
+    Stroke,
+    TriList,
+    TriStrip,
+    TriangleKind,
+    Value,
+};
+use triangulation::{
+    Coordinate,
+    DEFAULT_TOLERANCE,
+    with_polyline_border_xy_rgba_f32,
+};
+use internal::{
+    CanColor,
+    CanTransform,
+    CanViewTransform,
+    Color,
+    HasColor,
+    HasTransform,
+    HasViewTransform,
+    Matrix2d,
+    Polyline,
+    Radius,
+    Rectangle,
+    Scalar,
+};
+
+/// A polyline context with border information.
+pub struct BorderPolylineColorContext<'a, 'b> {
+    /// Base/original transformation.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transformation.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current polyline.
+    pub polyline: Field<'a, Polyline<'b>>,
+    /// Whether the last point connects to the first.
+    pub closed: Field<'a, bool>,
+    /// Current border radius.
+    pub border_radius: Field<'a, Radius>,
+    /// Current line join.
+    pub join: Field<'a, LineJoin>,
+    /// Current miter limit.
+    pub miter_limit: Field<'a, Scalar>,
+    /// Current cap at the start of the line.
+    pub start_cap: Field<'a, LineCap>,
+    /// Current cap at the end of the line.
+    pub end_cap: Field<'a, LineCap>,
+    /// Current dash pattern.
+    pub dash: Field<'a, Option<Dash>>,
+    /// Current color.
+    pub color: Field<'a, Color>,
+}
+
+impl<'a, 'b> Clone for BorderPolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn clone(&self) -> BorderPolylineColorContext<'static, 'b> {
+        BorderPolylineColorContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            polyline: Value(*self.polyline.get()),
+            closed: Value(*self.closed.get()),
+            border_radius: Value(*self.border_radius.get()),
+            join: Value(*self.join.get()),
+            miter_limit: Value(*self.miter_limit.get()),
+            start_cap: Value(*self.start_cap.get()),
+            end_cap: Value(*self.end_cap.get()),
+            dash: Value(*self.dash.get()),
+            color: Value(*self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasTransform<'a, Matrix2d> for BorderPolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a, 'b> CanTransform<'a, BorderPolylineColorContext<'a, 'b>, Matrix2d> for BorderPolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> BorderPolylineColorContext<'a, 'b> {
+        BorderPolylineColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            polyline: Borrowed(self.polyline.get()),
+            closed: Borrowed(self.closed.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            start_cap: Borrowed(self.start_cap.get()),
+            end_cap: Borrowed(self.end_cap.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasViewTransform<'a, Matrix2d> for BorderPolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a, 'b> CanViewTransform<'a, BorderPolylineColorContext<'a, 'b>, Matrix2d> for BorderPolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> BorderPolylineColorContext<'a, 'b> {
+        BorderPolylineColorContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polyline: Borrowed(self.polyline.get()),
+            closed: Borrowed(self.closed.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            start_cap: Borrowed(self.start_cap.get()),
+            end_cap: Borrowed(self.end_cap.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasColor<'a, Color> for BorderPolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_color(&'a self) -> &'a Color {
+        self.color.get()
+    }
+}
+
+impl<'a, 'b> CanColor<'a, BorderPolylineColorContext<'a, 'b>, Color> for BorderPolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn color(&'a self, value: Color) -> BorderPolylineColorContext<'a, 'b> {
+        BorderPolylineColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polyline: Borrowed(self.polyline.get()),
+            closed: Borrowed(self.closed.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            start_cap: Borrowed(self.start_cap.get()),
+            end_cap: Borrowed(self.end_cap.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Value(value),
+        }
+    }
+}
+
+impl<'a, 'b> AddLineJoin<'a, BorderPolylineColorContext<'a, 'b>> for BorderPolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn join(&'a self, join: LineJoin) -> BorderPolylineColorContext<'a, 'b> {
+        BorderPolylineColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polyline: Borrowed(self.polyline.get()),
+            closed: Borrowed(self.closed.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Value(join),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            start_cap: Borrowed(self.start_cap.get()),
+            end_cap: Borrowed(self.end_cap.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+
+    #[inline(always)]
+    fn miter_limit(&'a self, miter_limit: Scalar) -> BorderPolylineColorContext<'a, 'b> {
+        BorderPolylineColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polyline: Borrowed(self.polyline.get()),
+            closed: Borrowed(self.closed.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Value(miter_limit),
+            start_cap: Borrowed(self.start_cap.get()),
+            end_cap: Borrowed(self.end_cap.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> AddLineCap<'a, BorderPolylineColorContext<'a, 'b>> for BorderPolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn start_cap(&'a self, cap: LineCap) -> BorderPolylineColorContext<'a, 'b> {
+        BorderPolylineColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polyline: Borrowed(self.polyline.get()),
+            closed: Borrowed(self.closed.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            start_cap: Value(cap),
+            end_cap: Borrowed(self.end_cap.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+
+    #[inline(always)]
+    fn end_cap(&'a self, cap: LineCap) -> BorderPolylineColorContext<'a, 'b> {
+        BorderPolylineColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polyline: Borrowed(self.polyline.get()),
+            closed: Borrowed(self.closed.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            start_cap: Borrowed(self.start_cap.get()),
+            end_cap: Value(cap),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+
+    #[inline(always)]
+    fn cap(&'a self, cap: LineCap) -> BorderPolylineColorContext<'a, 'b> {
+        BorderPolylineColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polyline: Borrowed(self.polyline.get()),
+            closed: Borrowed(self.closed.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            start_cap: Value(cap),
+            end_cap: Value(cap),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> AddDash<'a, BorderPolylineColorContext<'a, 'b>> for BorderPolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn dash_pattern(&'a self, dash: Dash) -> BorderPolylineColorContext<'a, 'b> {
+        BorderPolylineColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polyline: Borrowed(self.polyline.get()),
+            closed: Borrowed(self.closed.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            start_cap: Borrowed(self.start_cap.get()),
+            end_cap: Borrowed(self.end_cap.get()),
+            dash: Value(Some(dash)),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> BorderPolylineColorContext<'a, 'b> {
+    /// Streams the polyline border with f32 or f64 vertices as the given kind of triangles.
+    fn stream<V: Coordinate>(
+        &self,
+        kind: TriangleKind,
+        f: |vertices: &[V], colors: &[f32]|
+    ) -> bool {
+        with_polyline_border_xy_rgba_f32(
+            kind,
+            DEFAULT_TOLERANCE,
+            *self.transform.get(),
+            *self.polyline.get(),
+            *self.closed.get(),
+            *self.border_radius.get(),
+            *self.join.get(),
+            *self.miter_limit.get(),
+            *self.start_cap.get(),
+            *self.end_cap.get(),
+            *self.dash.get(),
+            *self.color.get(),
+            f
+        )
+    }
+}
+
+impl<'a, 'b> Stroke<'a> for BorderPolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn stroke<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let color = self.color.get();
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                // Turn on alpha blending if not completely opaque.
+                let needs_alpha = color[3] != 1.0;
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                let strip = !back_end.supports_tri_list_xy_f64_rgba_f32()
+                    && back_end.supports_tri_strip_xy_f32_rgba_f32()
+                    && self.stream(TriStrip, |vertices, colors| {
+                        back_end.tri_strip_xy_f32_rgba_f32(vertices, colors)
+                    });
+                if !strip {
+                    if back_end.supports_tri_list_xy_f64_rgba_f32() {
+                        self.stream(TriList, |vertices, colors| {
+                            back_end.tri_list_xy_f64_rgba_f32(vertices, colors)
+                        });
+                    } else {
+                        self.stream(TriList, |vertices, colors| {
+                            back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+                        });
+                    }
+                }
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+impl<'a, 'b> Clear for BorderPolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
+
+#[test]
+fn test_stroke_polyline_joins() {
+    use {AddBorder, AddColor, AddPolyline, BevelJoin, Context, MiterJoin, RoundJoin, SoftwareBackEnd};
+
+    let stroke = |join: LineJoin, miter_limit: Scalar| -> SoftwareBackEnd {
+        let polyline = [-0.5, -0.5, 0.5, -0.5, 0.5, 0.5];
+        let mut back_end = SoftwareBackEnd::new(16, 16);
+        let c = Context::new();
+        c.polyline(polyline).rgba(1.0, 1.0, 1.0, 1.0).border_width(0.5)
+            .join(join).miter_limit(miter_limit).stroke(&mut back_end).unwrap();
+        back_end
+    };
+    let miter = stroke(MiterJoin, 4.0);
+    let round = stroke(RoundJoin, 4.0);
+    let bevel = stroke(BevelJoin, 4.0);
+    let limited = stroke(MiterJoin, 1.0);
+
+    // Near the corner all joins are filled.
+    for back_end in [&miter, &round, &bevel, &limited].iter() {
+        assert!(back_end.pixel(12, 12) == [255, 255, 255, 255]);
+    }
+    // Only the miter reaches the sharp corner.
+    assert!(miter.pixel(13, 13) == [255, 255, 255, 255]);
+    assert!(round.pixel(13, 13) == [0, 0, 0, 0]);
+    assert!(bevel.pixel(13, 13) == [0, 0, 0, 0]);
+    assert!(limited.pixel(13, 13) == [0, 0, 0, 0]);
+}
+
+#[test]
+fn test_stroke_polyline_dash() {
+    use {AddBorder, AddColor, AddDash, AddPolyline, Context, SoftwareBackEnd};
+
+    let stroke = |tween_factor: Scalar| -> SoftwareBackEnd {
+        let polyline = [-1.0, 0.0, 1.0, 0.0];
+        let mut back_end = SoftwareBackEnd::new(16, 2);
+        let c = Context::new();
+        c.polyline(polyline).rgba(1.0, 1.0, 1.0, 1.0).border_width(2.0)
+            .dash_lerp([0.25, 0.25], tween_factor).stroke(&mut back_end).unwrap();
+        back_end
+    };
+    // Each dash covers two pixels.
+    let start = stroke(0.0);
+    assert!(start.pixel(1, 0) == [255, 255, 255, 255]);
+    assert!(start.pixel(2, 0) == [0, 0, 0, 0]);
+    assert!(start.pixel(5, 0) == [255, 255, 255, 255]);
+    // Half a period later the dashes have moved forward two pixels.
+    let moved = stroke(0.5);
+    assert!(moved.pixel(1, 0) == [0, 0, 0, 0]);
+    assert!(moved.pixel(2, 0) == [255, 255, 255, 255]);
+    assert!(moved.pixel(5, 0) == [0, 0, 0, 0]);
+}
+
+#[test]
+fn test_stroke_closed_polyline() {
+    use {AddBorder, AddColor, AddPolyline, Context, SoftwareBackEnd};
+
+    let polyline = [-0.5, -0.5, 0.5, -0.5, 0.5, 0.5, -0.5, 0.5];
+    let mut open = SoftwareBackEnd::new(16, 16);
+    let mut closed = SoftwareBackEnd::new(16, 16);
+    let c = Context::new();
+    c.polyline(polyline).rgba(1.0, 1.0, 1.0, 1.0).border_width(0.25)
+        .stroke(&mut open).unwrap();
+    c.closed_polyline(polyline).rgba(1.0, 1.0, 1.0, 1.0).border_width(0.25)
+        .stroke(&mut closed).unwrap();
+    // Only the closed polyline connects the last point to the first.
+    assert!(open.pixel(12, 8) == [255, 255, 255, 255]);
+    assert!(closed.pixel(12, 8) == [255, 255, 255, 255]);
+    assert!(open.pixel(3, 8) == [0, 0, 0, 0]);
+    assert!(closed.pixel(3, 8) == [255, 255, 255, 255]);
+}
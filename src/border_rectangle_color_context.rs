@@ -0,0 +1,320 @@
+use {
+    AddDash,
+    AddLineJoin,
+    BackEnd,
+    Borrowed,
+    Clear,
+    ClipBackEnd,
+    Dash,
+    FallbackBackEnd,
+    Field,
+    LineJoin,
+    RenderResult,
+    Stroke,
+    TriList,
+    TriStrip,
+    TriangleKind,
+    Value,
+};
+use triangulation::{
+    Coordinate,
+    with_rectangle_border_xy_rgba_f32,
+};
+use internal::{
+    CanColor,
+    CanRectangle,
+    CanTransform,
+    CanViewTransform,
+    Color,
+    HasColor,
+    HasRectangle,
+    HasTransform,
+    HasViewTransform,
+    Matrix2d,
+    Radius,
+    Rectangle,
+    Scalar,
+};
+
+/// A rectangle color context with border information.
+pub struct BorderRectangleColorContext<'a> {
+    /// Base/original transformation.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transformation.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current rectangle.
+    pub rect: Field<'a, Rectangle>,
+    /// Current border radius.
+    pub border_radius: Field<'a, Radius>,
+    /// Current line join.
+    pub join: Field<'a, LineJoin>,
+    /// Current miter limit.
+    pub miter_limit: Field<'a, Scalar>,
+    /// Current dash pattern.
+    pub dash: Field<'a, Option<Dash>>,
+    /// Current color.
+    pub color: Field<'a, Color>,
+}
+
+impl<'a> Clone for BorderRectangleColorContext<'a> {
+    #[inline(always)]
+    fn clone(&self) -> BorderRectangleColorContext<'static> {
+        BorderRectangleColorContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            rect: Value(*self.rect.get()),
+            border_radius: Value(*self.border_radius.get()),
+            join: Value(*self.join.get()),
+            miter_limit: Value(*self.miter_limit.get()),
+            dash: Value(*self.dash.get()),
+            color: Value(*self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasTransform<'a, Matrix2d> for BorderRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a> CanTransform<'a, BorderRectangleColorContext<'a>, Matrix2d> for BorderRectangleColorContext<'a> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> BorderRectangleColorContext<'a> {
+        BorderRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasViewTransform<'a, Matrix2d> for BorderRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a> CanViewTransform<'a, BorderRectangleColorContext<'a>, Matrix2d> for BorderRectangleColorContext<'a> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> BorderRectangleColorContext<'a> {
+        BorderRectangleColorContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasColor<'a, Color> for BorderRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_color(&'a self) -> &'a Color {
+        self.color.get()
+    }
+}
+
+impl<'a> CanColor<'a, BorderRectangleColorContext<'a>, Color> for BorderRectangleColorContext<'a> {
+    #[inline(always)]
+    fn color(&'a self, value: Color) -> BorderRectangleColorContext<'a> {
+        BorderRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Value(value),
+        }
+    }
+}
+
+impl<'a> HasRectangle<'a, Rectangle> for BorderRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_rectangle(&'a self) -> &'a Rectangle {
+        self.rect.get()
+    }
+}
+
+impl<'a> CanRectangle<'a, BorderRectangleColorContext<'a>, Rectangle> for BorderRectangleColorContext<'a> {
+    #[inline(always)]
+    fn rectangle(&'a self, value: Rectangle) -> BorderRectangleColorContext<'a> {
+        BorderRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Value(value),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> AddLineJoin<'a, BorderRectangleColorContext<'a>> for BorderRectangleColorContext<'a> {
+    #[inline(always)]
+    fn join(&'a self, join: LineJoin) -> BorderRectangleColorContext<'a> {
+        BorderRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Value(join),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+
+    #[inline(always)]
+    fn miter_limit(&'a self, miter_limit: Scalar) -> BorderRectangleColorContext<'a> {
+        BorderRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Value(miter_limit),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> AddDash<'a, BorderRectangleColorContext<'a>> for BorderRectangleColorContext<'a> {
+    #[inline(always)]
+    fn dash_pattern(&'a self, dash: Dash) -> BorderRectangleColorContext<'a> {
+        BorderRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Value(Some(dash)),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> BorderRectangleColorContext<'a> {
+    /// Streams the rectangle border with f32 or f64 vertices as the given kind of triangles.
+    fn stream<V: Coordinate>(
+        &self,
+        kind: TriangleKind,
+        f: |vertices: &[V], colors: &[f32]|
+    ) -> bool {
+        with_rectangle_border_xy_rgba_f32(
+            kind,
+            *self.transform.get(),
+            *self.rect.get(),
+            *self.border_radius.get(),
+            *self.join.get(),
+            *self.miter_limit.get(),
+            *self.dash.get(),
+            *self.color.get(),
+            f
+        )
+    }
+}
+
+impl<'a> Stroke<'a> for BorderRectangleColorContext<'a> {
+    #[inline(always)]
+    fn stroke<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let color = self.color.get();
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                // Turn on alpha blending if not completely opaque.
+                let needs_alpha = color[3] != 1.0;
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                let strip = !back_end.supports_tri_list_xy_f64_rgba_f32()
+                    && back_end.supports_tri_strip_xy_f32_rgba_f32()
+                    && self.stream(TriStrip, |vertices, colors| {
+                        back_end.tri_strip_xy_f32_rgba_f32(vertices, colors)
+                    });
+                if !strip {
+                    if back_end.supports_tri_list_xy_f64_rgba_f32() {
+                        self.stream(TriList, |vertices, colors| {
+                            back_end.tri_list_xy_f64_rgba_f32(vertices, colors)
+                        });
+                    } else {
+                        self.stream(TriList, |vertices, colors| {
+                            back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+                        });
+                    }
+                }
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+impl<'a> Clear for BorderRectangleColorContext<'a> {
+    #[inline(always)]
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
+
+#[test]
+fn test_stroke_rectangle() {
+    use {AddBorder, AddColor, AddRectangle, Context, SoftwareBackEnd};
+
+    let mut back_end = SoftwareBackEnd::new(16, 16);
+    let c = Context::new();
+    c.rect(-0.5, -0.5, 1.0, 1.0).rgba(1.0, 1.0, 1.0, 0.5).border(0.25).stroke(&mut back_end).unwrap();
+    // The corners are blended once like the sides.
+    let side = back_end.pixel(3, 8);
+    assert!(side[3] > 0);
+    for &(x, y) in [(3u32, 3u32), (4, 4), (12, 12), (11, 4), (8, 12)].iter() {
+        assert!(back_end.pixel(x, y) == side);
+    }
+    assert!(back_end.pixel(8, 8) == [0, 0, 0, 0]);
+    assert!(back_end.pixel(1, 1) == [0, 0, 0, 0]);
+}
+
+#[test]
+fn test_stroke_rectangle_join() {
+    use {AddBorder, AddColor, AddRectangle, BevelJoin, Context, MiterJoin, SoftwareBackEnd};
+
+    let stroke = |join: LineJoin| -> SoftwareBackEnd {
+        let mut back_end = SoftwareBackEnd::new(16, 16);
+        let c = Context::new();
+        c.rect(-0.5, -0.5, 1.0, 1.0).rgba(1.0, 1.0, 1.0, 1.0).border_width(0.5)
+            .join(join).stroke(&mut back_end).unwrap();
+        back_end
+    };
+    let miter = stroke(MiterJoin);
+    let bevel = stroke(BevelJoin);
+    for back_end in [&miter, &bevel].iter() {
+        assert!(back_end.pixel(3, 3) == [255, 255, 255, 255]);
+    }
+    // Only the miter reaches the outer corner.
+    assert!(miter.pixel(2, 2) == [255, 255, 255, 255]);
+    assert!(bevel.pixel(2, 2) == [0, 0, 0, 0]);
+}
@@ -0,0 +1,329 @@
+use {
+    AddDash,
+    AddLineJoin,
+    AddResolution,
+    BackEnd,
+    Borrowed,
+    Clear,
+    ClipBackEnd,
+    Dash,
+    FallbackBackEnd,
+    Field,
+    LineJoin,
+    RenderResult,
+    Resolution,
+    Stroke,
+    TriList,
+    TriStrip,
+    TriangleKind,
+    Value,
+};
+use triangulation::{
+    Coordinate,
+    round_rectangle_resolution,
+    with_round_rectangle_border_xy_rgba_f32,
+};
+use internal::{
+    CanColor,
+    CanRectangle,
+    CanTransform,
+    CanViewTransform,
+    Color,
+    HasColor,
+    HasRectangle,
+    HasTransform,
+    HasViewTransform,
+    Matrix2d,
+    Radius,
+    Rectangle,
+    Scalar,
+};
+
+/// A round rectangle color context with border information.
+pub struct BorderRoundRectangleColorContext<'a> {
+    /// Base/original transformation.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transformation.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current rectangle.
+    pub rect: Field<'a, Rectangle>,
+    /// Current roundness radius.
+    pub round_radius: Field<'a, Radius>,
+    /// Current resolution of the curves.
+    pub resolution: Field<'a, Resolution>,
+    /// Current border radius.
+    pub border_radius: Field<'a, Radius>,
+    /// Current line join.
+    pub join: Field<'a, LineJoin>,
+    /// Current miter limit.
+    pub miter_limit: Field<'a, Scalar>,
+    /// Current dash pattern.
+    pub dash: Field<'a, Option<Dash>>,
+    /// Current color.
+    pub color: Field<'a, Color>,
+}
+
+impl<'a> Clone for BorderRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn clone(&self) -> BorderRoundRectangleColorContext<'static> {
+        BorderRoundRectangleColorContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            rect: Value(*self.rect.get()),
+            round_radius: Value(*self.round_radius.get()),
+            resolution: Value(*self.resolution.get()),
+            border_radius: Value(*self.border_radius.get()),
+            join: Value(*self.join.get()),
+            miter_limit: Value(*self.miter_limit.get()),
+            dash: Value(*self.dash.get()),
+            color: Value(*self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasTransform<'a, Matrix2d> for BorderRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a> CanTransform<'a, BorderRoundRectangleColorContext<'a>, Matrix2d> for BorderRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> BorderRoundRectangleColorContext<'a> {
+        BorderRoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasViewTransform<'a, Matrix2d> for BorderRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a> CanViewTransform<'a, BorderRoundRectangleColorContext<'a>, Matrix2d> for BorderRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> BorderRoundRectangleColorContext<'a> {
+        BorderRoundRectangleColorContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasColor<'a, Color> for BorderRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_color(&'a self) -> &'a Color {
+        self.color.get()
+    }
+}
+
+impl<'a> CanColor<'a, BorderRoundRectangleColorContext<'a>, Color> for BorderRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn color(&'a self, value: Color) -> BorderRoundRectangleColorContext<'a> {
+        BorderRoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Value(value),
+        }
+    }
+}
+
+impl<'a> HasRectangle<'a, Rectangle> for BorderRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_rectangle(&'a self) -> &'a Rectangle {
+        self.rect.get()
+    }
+}
+
+impl<'a> CanRectangle<'a, BorderRoundRectangleColorContext<'a>, Rectangle> for BorderRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn rectangle(&'a self, value: Rectangle) -> BorderRoundRectangleColorContext<'a> {
+        BorderRoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Value(value),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> AddLineJoin<'a, BorderRoundRectangleColorContext<'a>> for BorderRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn join(&'a self, join: LineJoin) -> BorderRoundRectangleColorContext<'a> {
+        BorderRoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Value(join),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+
+    #[inline(always)]
+    fn miter_limit(&'a self, miter_limit: Scalar) -> BorderRoundRectangleColorContext<'a> {
+        BorderRoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Value(miter_limit),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> AddDash<'a, BorderRoundRectangleColorContext<'a>> for BorderRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn dash_pattern(&'a self, dash: Dash) -> BorderRoundRectangleColorContext<'a> {
+        BorderRoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Value(Some(dash)),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> BorderRoundRectangleColorContext<'a> {
+    /// Streams the round rectangle border with f32 or f64 vertices as the given kind of triangles.
+    fn stream<V: Coordinate>(
+        &self,
+        resolution: uint,
+        kind: TriangleKind,
+        f: |vertices: &[V], colors: &[f32]|
+    ) -> bool {
+        with_round_rectangle_border_xy_rgba_f32(
+            kind,
+            resolution,
+            *self.transform.get(),
+            *self.rect.get(),
+            *self.round_radius.get(),
+            *self.border_radius.get(),
+            *self.join.get(),
+            *self.miter_limit.get(),
+            *self.dash.get(),
+            *self.color.get(),
+            f
+        )
+    }
+}
+
+impl<'a> Stroke<'a> for BorderRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn stroke<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let round_radius = self.round_radius.get();
+                let color = self.color.get();
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                // Turn on alpha blending if not completely opaque.
+                let needs_alpha = color[3] != 1.0;
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                let resolution = round_rectangle_resolution(*self.transform.get(), *round_radius, *self.resolution.get());
+                let strip = !back_end.supports_tri_list_xy_f64_rgba_f32()
+                    && back_end.supports_tri_strip_xy_f32_rgba_f32()
+                    && self.stream(resolution, TriStrip, |vertices, colors| {
+                        back_end.tri_strip_xy_f32_rgba_f32(vertices, colors)
+                    });
+                if !strip {
+                    if back_end.supports_tri_list_xy_f64_rgba_f32() {
+                        self.stream(resolution, TriList, |vertices, colors| {
+                            back_end.tri_list_xy_f64_rgba_f32(vertices, colors)
+                        });
+                    } else {
+                        self.stream(resolution, TriList, |vertices, colors| {
+                            back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+                        });
+                    }
+                }
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+impl<'a> AddResolution<'a, BorderRoundRectangleColorContext<'a>> for BorderRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn resolution(&'a self, resolution: Resolution) -> BorderRoundRectangleColorContext<'a> {
+        BorderRoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Value(resolution),
+            border_radius: Borrowed(self.border_radius.get()),
+            join: Borrowed(self.join.get()),
+            miter_limit: Borrowed(self.miter_limit.get()),
+            dash: Borrowed(self.dash.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> Clear for BorderRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
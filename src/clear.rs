@@ -1,8 +1,10 @@
-use {BackEnd};
+use {BackEnd, RenderResult};
 
 /// Implemented by contexts that can clear the background.
 pub trait Clear {
     /// Clears the background.
-    fn clear<B: BackEnd>(&self, back_end: &mut B);
+    ///
+    /// Returns an error if the back-end can not render it.
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult;
 }
 
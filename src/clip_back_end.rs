@@ -0,0 +1,404 @@
+//! A back-end wrapper that restricts drawing to a clipping rectangle.
+
+use {
+    BackEnd,
+    FallbackBackEnd,
+    RenderResult,
+    UnsupportedClear,
+};
+use internal::{Color, Rectangle};
+use triangulation::{
+    clip_tri_list_xy_f32_rgba_f32,
+    clip_tri_list_xy_f32_rgba_f32_uv_f32,
+    rect_tri_list_rgba_f32,
+    rect_tri_list_xy_f32,
+};
+
+/// Restricts drawing of another back-end to a clipping rectangle.
+///
+/// Uses the scissor of the back-end when it is supported.
+/// Otherwise the triangles are clipped before they are sent.
+pub struct ClipBackEnd<'b, B> {
+    back_end: &'b mut B,
+    clip: Option<Rectangle>,
+    /// Whether alpha blending is enabled through this back-end.
+    alpha_blend: bool,
+}
+
+impl<'b, B: BackEnd> ClipBackEnd<'b, B> {
+    /// Calls a closure with a back-end that is clipped to a rectangle.
+    ///
+    /// The rectangle is in the same coordinates as the vertices.
+    /// Nothing is drawn if the rectangle is empty.
+    /// When the scissor is used it is cleared afterwards, see `BackEnd::set_scissor`.
+    pub fn with_clip(
+        clip: Option<Rectangle>,
+        back_end: &mut B,
+        f: |back_end: &mut ClipBackEnd<B>|
+    ) {
+        match clip {
+            None => f(&mut ClipBackEnd { back_end: back_end, clip: None, alpha_blend: false }),
+            Some(rect) => {
+                if rect[2] <= 0.0 || rect[3] <= 0.0 { return; }
+                if back_end.supports_scissor() {
+                    back_end.set_scissor(rect[0] as f32, rect[1] as f32,
+                        rect[2] as f32, rect[3] as f32);
+                    f(&mut ClipBackEnd {
+                        back_end: &mut *back_end,
+                        clip: None,
+                        alpha_blend: false
+                    });
+                    back_end.clear_scissor();
+                } else {
+                    f(&mut ClipBackEnd {
+                        back_end: back_end,
+                        clip: Some(rect),
+                        alpha_blend: false
+                    });
+                }
+            }
+        }
+    }
+
+    /// Clears the background with a color, restricted to a clipping rectangle.
+    ///
+    /// Without scissor the rectangle is drawn as triangles,
+    /// in any format the back-end supports.
+    /// Returns an error if the back-end can not clear the rectangle.
+    pub fn clear(clip: Option<Rectangle>, back_end: &mut B, color: Color) -> RenderResult {
+        if !back_end.supports_clear_rgba() { return Err(UnsupportedClear); }
+        if clip.is_some() && !back_end.supports_scissor() {
+            // The clipped area is drawn as triangles.
+            FallbackBackEnd::with_fallback(back_end, |back_end| {
+                ClipBackEnd::with_clip(clip, back_end, |back_end| {
+                    back_end.clear_rgba(color[0], color[1], color[2], color[3]);
+                })
+            })
+        } else {
+            ClipBackEnd::with_clip(clip, back_end, |back_end| {
+                back_end.clear_rgba(color[0], color[1], color[2], color[3]);
+            });
+            Ok(())
+        }
+    }
+}
+
+impl<'b, B: BackEnd> BackEnd for ClipBackEnd<'b, B> {
+    #[inline(always)]
+    fn supports_clear_rgba(&self) -> bool {
+        self.back_end.supports_clear_rgba()
+    }
+
+    fn clear_rgba(&mut self, r: f32, g: f32, b: f32, a: f32) {
+        match self.clip {
+            // Back-ends without f32 triangles are wrapped in `FallbackBackEnd` by `clear`.
+            Some(_) if !self.back_end.supports_tri_list_xy_f32_rgba_f32() => {},
+            Some(rect) => {
+                // Alpha blending is turned off, so the color replaces the background.
+                self.back_end.disable_alpha_blend();
+                self.back_end.tri_list_xy_f32_rgba_f32(
+                    rect_tri_list_xy_f32([1.0, 0.0, 0.0, 0.0, 1.0, 0.0], rect),
+                    rect_tri_list_rgba_f32([r, g, b, a])
+                );
+                if self.alpha_blend { self.back_end.enable_alpha_blend(); }
+            },
+            _ => self.back_end.clear_rgba(r, g, b, a),
+        }
+    }
+
+    fn enable_alpha_blend(&mut self) {
+        self.alpha_blend = true;
+        self.back_end.enable_alpha_blend();
+    }
+
+    fn disable_alpha_blend(&mut self) {
+        self.alpha_blend = false;
+        self.back_end.disable_alpha_blend();
+    }
+
+    #[inline(always)]
+    fn supports_scissor(&self) -> bool {
+        self.back_end.supports_scissor()
+    }
+
+    fn set_scissor(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        self.back_end.set_scissor(x, y, w, h);
+    }
+
+    fn clear_scissor(&mut self) {
+        self.back_end.clear_scissor();
+    }
+
+    #[inline(always)]
+    fn supports_stencil(&self) -> bool {
+        self.back_end.supports_stencil()
+    }
+
+    fn begin_stencil_write(&mut self) {
+        self.back_end.begin_stencil_write();
+    }
+
+    fn begin_stencil_test(&mut self) {
+        self.back_end.begin_stencil_test();
+    }
+
+    fn end_stencil(&mut self) {
+        self.back_end.end_stencil();
+    }
+
+    #[inline(always)]
+    fn supports_single_texture(&self) -> bool {
+        self.back_end.supports_single_texture()
+    }
+
+    fn enable_single_texture(&mut self, texture_id: uint) {
+        self.back_end.enable_single_texture(texture_id);
+    }
+
+    fn disable_single_texture(&mut self) {
+        self.back_end.disable_single_texture();
+    }
+
+    #[inline(always)]
+    fn has_texture_alpha(&self, texture_id: uint) -> bool {
+        self.back_end.has_texture_alpha(texture_id)
+    }
+
+    #[inline(always)]
+    fn supports_texture_upload(&self) -> bool {
+        self.back_end.supports_texture_upload()
+    }
+
+    fn create_texture(&mut self, width: u32, height: u32, pixels: &[u8]) -> Option<uint> {
+        self.back_end.create_texture(width, height, pixels)
+    }
+
+    fn update_texture(
+        &mut self,
+        texture_id: uint,
+        x: u32, y: u32, w: u32, h: u32,
+        pixels: &[u8]
+    ) {
+        self.back_end.update_texture(texture_id, x, y, w, h, pixels);
+    }
+
+    fn delete_texture(&mut self, texture_id: uint) {
+        self.back_end.delete_texture(texture_id);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f64_rgba_f32(&self) -> bool {
+        // Only triangles with f32 vertices are clipped.
+        self.clip.is_none() && self.back_end.supports_tri_list_xy_f64_rgba_f32()
+    }
+
+    fn tri_list_xy_f64_rgba_f32(
+        &mut self,
+        vertices: &[f64],
+        colors: &[f32]
+    ) {
+        self.back_end.tri_list_xy_f64_rgba_f32(vertices, colors);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_rgba_f32(&self) -> bool {
+        self.back_end.supports_tri_list_xy_f32_rgba_f32()
+    }
+
+    fn tri_list_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        match self.clip {
+            None => self.back_end.tri_list_xy_f32_rgba_f32(vertices, colors),
+            Some(rect) => {
+                let back_end = &mut self.back_end;
+                clip_tri_list_xy_f32_rgba_f32(rect, vertices, colors,
+                    |vertices, colors| back_end.tri_list_xy_f32_rgba_f32(vertices, colors));
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_rgba_f32_uv_f32(&self) -> bool {
+        self.back_end.supports_tri_list_xy_f32_rgba_f32_uv_f32()
+    }
+
+    fn tri_list_xy_f32_rgba_f32_uv_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32]
+    ) {
+        match self.clip {
+            None => self.back_end.tri_list_xy_f32_rgba_f32_uv_f32(
+                vertices, colors, texture_coords),
+            Some(rect) => {
+                let back_end = &mut self.back_end;
+                clip_tri_list_xy_f32_rgba_f32_uv_f32(rect, vertices, colors, texture_coords,
+                    |vertices, colors, texture_coords| {
+                        back_end.tri_list_xy_f32_rgba_f32_uv_f32(
+                            vertices, colors, texture_coords)
+                    });
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32(&self) -> bool {
+        // Only triangle lists without indices are clipped.
+        self.clip.is_none() && self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        indices: &[u32]
+    ) {
+        self.back_end.tri_list_indexed_xy_f32_rgba_f32(vertices, colors, indices);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32(&self) -> bool {
+        self.clip.is_none() && self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32],
+        indices: &[u32]
+    ) {
+        self.back_end.tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+            vertices, colors, texture_coords, indices);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_u16(&self) -> bool {
+        self.clip.is_none() && self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_u16()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_u16(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        indices: &[u16]
+    ) {
+        self.back_end.tri_list_indexed_xy_f32_rgba_f32_u16(vertices, colors, indices);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(&self) -> bool {
+        self.clip.is_none() && self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32],
+        indices: &[u16]
+    ) {
+        self.back_end.tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(
+            vertices, colors, texture_coords, indices);
+    }
+
+    #[inline(always)]
+    fn supports_tri_strip_xy_f32_rgba_f32(&self) -> bool {
+        self.clip.is_none() && self.back_end.supports_tri_strip_xy_f32_rgba_f32()
+    }
+
+    fn tri_strip_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        self.back_end.tri_strip_xy_f32_rgba_f32(vertices, colors);
+    }
+
+    #[inline(always)]
+    fn supports_tri_fan_xy_f32_rgba_f32(&self) -> bool {
+        self.clip.is_none() && self.back_end.supports_tri_fan_xy_f32_rgba_f32()
+    }
+
+    fn tri_fan_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        self.back_end.tri_fan_xy_f32_rgba_f32(vertices, colors);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_uniform_rgba_f32(&self) -> bool {
+        self.clip.is_none() && self.back_end.supports_tri_list_xy_f32_uniform_rgba_f32()
+    }
+
+    fn tri_list_xy_f32_uniform_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        r: f32, g: f32, b: f32, a: f32
+    ) {
+        self.back_end.tri_list_xy_f32_uniform_rgba_f32(vertices, r, g, b, a);
+    }
+}
+
+#[test]
+fn test_clip_rect() {
+    use {AddClip, AddColor, AddEllipse, AddRectangle, Clear, Context, Fill, SoftwareBackEnd};
+    use software_back_end::{MinimalBackEnd};
+
+    let draw = |back_end: &mut MinimalBackEnd, scissor: bool| {
+        let c = Context::new();
+        let d = c.clip_rect(-0.5, -0.5, 1.5, 1.5);
+        // Nested clipping rectangles restrict drawing to the overlap.
+        let e = d.clip_rect(-1.0, -1.0, 1.0, 2.0);
+        if scissor {
+            let software = &mut back_end.software;
+            e.rgba(0.0, 0.0, 1.0, 1.0).clear(software).unwrap();
+            d.ellipse(-1.0, -1.0, 2.0, 2.0).rgba(1.0, 0.0, 0.0, 0.5).fill(software).unwrap();
+        } else {
+            e.rgba(0.0, 0.0, 1.0, 1.0).clear(back_end).unwrap();
+            d.ellipse(-1.0, -1.0, 2.0, 2.0).rgba(1.0, 0.0, 0.0, 0.5).fill(back_end).unwrap();
+        }
+        c.clip_rect(0.0, 0.0, 0.0, 1.0).rect(-1.0, -1.0, 2.0, 2.0)
+            .rgba(1.0, 1.0, 1.0, 1.0).fill(back_end).unwrap();
+    };
+    let mut with_scissor = MinimalBackEnd { software: SoftwareBackEnd::new(8, 8) };
+    draw(&mut with_scissor, true);
+    let mut without_scissor = MinimalBackEnd { software: SoftwareBackEnd::new(8, 8) };
+    draw(&mut without_scissor, false);
+    // The colors are interpolated differently across the cut triangles.
+    let near = |a: &[u8], b: &[u8]| {
+        a.iter().zip(b.iter()).all(|(&a, &b)| (a as int - b as int).abs() <= 1)
+    };
+    assert!(near(with_scissor.software.pixels(), without_scissor.software.pixels()));
+
+    let pixels = &with_scissor.software;
+    // Outside the clipping rectangles.
+    assert!(pixels.pixel(1, 6) == [0, 0, 0, 0]);
+    assert!(pixels.pixel(7, 7) == [0, 0, 0, 0]);
+    // Inside both, where the background is cleared.
+    assert!(near(pixels.pixel(2, 4), [128, 0, 128, 255]));
+    // Inside the outer only.
+    assert!(near(pixels.pixel(5, 3), [128, 0, 0, 128]));
+}
+
+#[test]
+fn test_clip_clear_alpha_blend() {
+    use {AddClip, AddColor, Clear, Context, SoftwareBackEnd};
+    use software_back_end::{MinimalBackEnd};
+
+    let mut back_end = MinimalBackEnd { software: SoftwareBackEnd::new(8, 8) };
+    let c = Context::new();
+    c.rgba(1.0, 0.0, 0.0, 1.0).clear(&mut back_end).unwrap();
+    back_end.enable_alpha_blend();
+    let d = c.clip_rect(-1.0, -1.0, 1.0, 2.0);
+    d.rgba(0.0, 0.0, 1.0, 0.5).clear(&mut back_end).unwrap();
+    // The clear color replaces the background even when blending was on.
+    assert!(back_end.software.pixel(2, 4) == [0, 0, 255, 128]);
+    assert!(back_end.software.pixel(6, 4) == [255, 0, 0, 255]);
+}
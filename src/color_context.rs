@@ -1,24 +1,49 @@
 
 use {
+    AddClip,
     AddEllipse,
     AddImage,
     AddLine,
+    AddPath,
     AddPolygon,
+    AddPolyline,
     AddRectangle,
+    AddShape,
+    AddText,
     AddTween,
+    AlignLeft,
     BackEnd,
     Borrowed,
     Clear,
+    ClipBackEnd,
     EllipseColorContext,
     Field,
+    Font,
     Image,
     ImageRectangleColorContext,
     LineColorContext,
+    NonZero,
+    Path,
+    PathColorContext,
     PolygonColorContext,
+    PolylineColorContext,
     RectangleColorContext,
+    RenderResult,
+    ShapeColorContext,
+    Stretch,
+    TextColorContext,
+    Tolerance,
     TweenColorContext,
     Value,
 };
+use triangulation::{
+    DEFAULT_TOLERANCE,
+};
+use vecmath::{
+    identity,
+    intersect_rectangles,
+    transform_rectangle_bounds,
+};
 use internal::{
     CanColor,
     CanTransform,
@@ -29,7 +54,10 @@ use internal::{
     HasViewTransform,
     Matrix2d,
     Polygon,
+    Polyline,
+    Rectangle,
     Scalar,
+    Shape,
 };
 
 /// A context with color information.
@@ -38,6 +66,8 @@ pub struct ColorContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transformation.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current color.
     pub color: Field<'a, Color>,
 }
@@ -48,6 +78,7 @@ impl<'a> Clone for ColorContext<'a> {
         ColorContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             color: Value(*self.color.get()),
         }
     }
@@ -66,6 +97,7 @@ impl<'a> CanTransform<'a, ColorContext<'a>, Matrix2d> for ColorContext<'a> {
         ColorContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             color: Borrowed(self.color.get()),
         }
     }
@@ -84,6 +116,24 @@ impl<'a> CanViewTransform<'a, ColorContext<'a>, Matrix2d> for ColorContext<'a> {
         ColorContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> AddClip<'a, ColorContext<'a>> for ColorContext<'a> {
+    #[inline(always)]
+    fn clip_rect(&'a self, x: Scalar, y: Scalar, w: Scalar, h: Scalar) -> ColorContext<'a> {
+        let rect = transform_rectangle_bounds(*self.transform.get(), [x, y, w, h]);
+        let clip = match *self.clip.get() {
+            Some(parent) => intersect_rectangles(parent, rect),
+            None => rect,
+        };
+        ColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Value(Some(clip)),
             color: Borrowed(self.color.get()),
         }
     }
@@ -102,6 +152,7 @@ impl<'a> CanColor<'a, ColorContext<'a>, Color> for ColorContext<'a> {
         ColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Value(value),
         }
     }
@@ -113,7 +164,9 @@ impl<'a> AddRectangle<'a, RectangleColorContext<'a>> for ColorContext<'a> {
         RectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Borrowed(self.color.get()),
+            gradient: Value(None),
             rect: Value([x, y, w, h]),
         }
     }
@@ -136,8 +189,11 @@ impl<'a> AddEllipse<'a, EllipseColorContext<'a>> for ColorContext<'a> {
         EllipseColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Borrowed(self.color.get()),
+            gradient: Value(None),
             rect: Value([x, y, w, h]),
+            resolution: Value(Tolerance(DEFAULT_TOLERANCE)),
         }
     }
 }
@@ -148,18 +204,75 @@ impl<'a, 'b> AddPolygon<'a, PolygonColorContext<'a, 'b>> for ColorContext<'a> {
         PolygonColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Borrowed(self.color.get()),
+            gradient: Value(None),
             polygon: Value(polygon),
         }
     }
 }
 
+impl<'a, 'b> AddPolyline<'a, PolylineColorContext<'a, 'b>> for ColorContext<'a> {
+    #[inline(always)]
+    fn polyline(&'a self, polyline: Polyline<'b>) -> PolylineColorContext<'a, 'b> {
+        PolylineColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            color: Borrowed(self.color.get()),
+            polyline: Value(polyline),
+            closed: Value(false),
+        }
+    }
+
+    #[inline(always)]
+    fn closed_polyline(&'a self, polyline: Polyline<'b>) -> PolylineColorContext<'a, 'b> {
+        PolylineColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            color: Borrowed(self.color.get()),
+            polyline: Value(polyline),
+            closed: Value(true),
+        }
+    }
+}
+
+impl<'a, 'b> AddPath<'a, PathColorContext<'a, 'b>> for ColorContext<'a> {
+    #[inline(always)]
+    fn path(&'a self, path: &'b Path) -> PathColorContext<'a, 'b> {
+        PathColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            color: Borrowed(self.color.get()),
+            path: Value(path),
+            fill_rule: Value(NonZero),
+        }
+    }
+}
+
+impl<'a, 'b> AddShape<'a, ShapeColorContext<'a, 'b>> for ColorContext<'a> {
+    #[inline(always)]
+    fn shape(&'a self, shape: Shape<'b>) -> ShapeColorContext<'a, 'b> {
+        ShapeColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            color: Borrowed(self.color.get()),
+            shape: Value(shape),
+            fill_rule: Value(NonZero),
+        }
+    }
+}
+
 impl<'a> AddTween<'a, TweenColorContext<'a>> for ColorContext<'a> {
     #[inline(always)]
     fn lerp(&'a self, tween_factor: Scalar) -> TweenColorContext<'a> {
         TweenColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Borrowed(self.color.get()),
             tween_factor: Value(tween_factor),
         }
@@ -167,11 +280,8 @@ impl<'a> AddTween<'a, TweenColorContext<'a>> for ColorContext<'a> {
 }
 
 impl<'a> Clear for ColorContext<'a> {
-    fn clear<B: BackEnd>(&self, back_end: &mut B) {
-        if back_end.supports_clear_rgba() {
-            let color = self.color.get();
-            back_end.clear_rgba(color[0], color[1], color[2], color[3]);
-        }
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
     }
 }
 
@@ -181,10 +291,13 @@ impl<'a> AddImage<'a, ImageRectangleColorContext<'a>> for ColorContext<'a> {
         ImageRectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Value(
                 [0.0, 0.0, image.source_rect[2] as f64, image.source_rect[3] as f64]
             ),
             image: Value(image),
+            uv_transform: Value(identity()),
+            slice_mode: Value(Stretch),
             color: Borrowed(self.color.get()),
         }
     }
@@ -196,9 +309,26 @@ impl<'a> AddLine<'a, LineColorContext<'a>> for ColorContext<'a> {
         LineColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Value([x1, y1, x2, y2]),
             color: Borrowed(self.color.get()),
         }
     }
 }
 
+impl<'a, 'b> AddText<'a, TextColorContext<'a, 'b>> for ColorContext<'a> {
+    #[inline(always)]
+    fn text(&'a self, font: &'b Font, size: Scalar, text: &'b str) -> TextColorContext<'a, 'b> {
+        TextColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            font: Value(font),
+            size: Value(size),
+            text: Value(text),
+            max_width: Value(None),
+            align: Value(AlignLeft),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
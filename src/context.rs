@@ -1,24 +1,46 @@
 
 use {
+    AddClip,
     AddColor,
     AddEllipse,
     AddImage,
     AddLine,
+    AddPath,
     AddPolygon,
+    AddPolyline,
     AddRectangle,
+    AddShape,
+    AddText,
     AddTween,
+    AlignLeft,
+    BackEnd,
     Borrowed,
     ColorContext,
     EllipseContext,
     Field,
+    Font,
     Image,
     ImageRectangleContext,
     LineContext,
+    Mask,
+    MaskBackEnd,
+    NonZero,
+    Path,
+    PathContext,
     PolygonContext,
+    PolylineContext,
     RectangleContext,
+    ShapeContext,
+    Stretch,
+    TextContext,
     TweenContext,
     Value,
 };
+use vecmath::{
+    identity,
+    intersect_rectangles,
+    transform_rectangle_bounds,
+};
 use internal::{
     CanTransform,
     CanViewTransform,
@@ -27,7 +49,10 @@ use internal::{
     HasViewTransform,
     Matrix2d,
     Polygon,
+    Polyline,
+    Rectangle,
     Scalar,
+    Shape,
 };
 
 /// Drawing 2d context.
@@ -36,6 +61,8 @@ pub struct Context<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transformation.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
 }
 
 impl<'a> Clone for Context<'a> {
@@ -44,6 +71,7 @@ impl<'a> Clone for Context<'a> {
         Context {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
         }
     }
 }
@@ -61,6 +89,7 @@ impl<'a> CanTransform<'a, Context<'a>, Matrix2d> for Context<'a> {
         Context {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
         }
     }
 }
@@ -78,6 +107,23 @@ impl<'a> CanViewTransform<'a, Context<'a>, Matrix2d> for Context<'a> {
         Context {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+        }
+    }
+}
+
+impl<'a> AddClip<'a, Context<'a>> for Context<'a> {
+    #[inline(always)]
+    fn clip_rect(&'a self, x: Scalar, y: Scalar, w: Scalar, h: Scalar) -> Context<'a> {
+        let rect = transform_rectangle_bounds(*self.transform.get(), [x, y, w, h]);
+        let clip = match *self.clip.get() {
+            Some(parent) => intersect_rectangles(parent, rect),
+            None => rect,
+        };
+        Context {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Value(Some(clip)),
         }
     }
 }
@@ -94,8 +140,29 @@ impl<'a> Context<'a> {
                 [1.0, 0.0, 0.0,
                  0.0, 1.0, 0.0]
             ),
+            clip: Value(None),
         }
     }
+
+    /// Creates a mask from the shapes drawn by a closure.
+    ///
+    /// Call `draw_inside` on the result to draw restricted to the mask:
+    ///
+    /// ```
+    /// c.mask(&mut back_end, |c, mask| {
+    ///     c.ellipse(x, y, w, h).rgb(1.0, 1.0, 1.0).fill(mask).unwrap();
+    /// }).draw_inside(|c, back_end| {
+    ///     c.rect(x, y, w, h).rgb(1.0, 0.0, 0.0).fill(back_end).unwrap();
+    /// });
+    /// ```
+    #[inline(always)]
+    pub fn mask<'b, B: BackEnd>(
+        &'a self,
+        back_end: &'b mut B,
+        f: |c: &'a Context<'a>, mask: &mut MaskBackEnd<B>|
+    ) -> Mask<'a, 'b, B> {
+        Mask::new(self, back_end, f)
+    }
 }
 
 #[test]
@@ -138,6 +205,7 @@ impl<'a> AddRectangle<'a, RectangleContext<'a>> for Context<'a> {
         RectangleContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Value([x, y, w, h]),
         }
     }
@@ -163,6 +231,7 @@ impl<'a> AddColor<'a, ColorContext<'a>> for Context<'a> {
         ColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Value([r, g, b, a]),
         }
     }
@@ -182,6 +251,7 @@ impl<'a> AddEllipse<'a, EllipseContext<'a>> for Context<'a> {
         EllipseContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Value([x, y, w, h]),
         }
     }
@@ -201,17 +271,69 @@ impl<'a, 'b> AddPolygon<'a, PolygonContext<'a, 'b>> for Context<'a> {
         PolygonContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             polygon: Value(polygon),
         }
     }
 }
 
+impl<'a, 'b> AddPolyline<'a, PolylineContext<'a, 'b>> for Context<'a> {
+    #[inline(always)]
+    fn polyline(&'a self, polyline: Polyline<'b>) -> PolylineContext<'a, 'b> {
+        PolylineContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polyline: Value(polyline),
+            closed: Value(false),
+        }
+    }
+
+    #[inline(always)]
+    fn closed_polyline(&'a self, polyline: Polyline<'b>) -> PolylineContext<'a, 'b> {
+        PolylineContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polyline: Value(polyline),
+            closed: Value(true),
+        }
+    }
+}
+
+impl<'a, 'b> AddPath<'a, PathContext<'a, 'b>> for Context<'a> {
+    #[inline(always)]
+    fn path(&'a self, path: &'b Path) -> PathContext<'a, 'b> {
+        PathContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            path: Value(path),
+            fill_rule: Value(NonZero),
+        }
+    }
+}
+
+impl<'a, 'b> AddShape<'a, ShapeContext<'a, 'b>> for Context<'a> {
+    #[inline(always)]
+    fn shape(&'a self, shape: Shape<'b>) -> ShapeContext<'a, 'b> {
+        ShapeContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            shape: Value(shape),
+            fill_rule: Value(NonZero),
+        }
+    }
+}
+
 impl<'a> AddImage<'a, ImageRectangleContext<'a>> for Context<'a> {
     #[inline(always)]
     fn image(&'a self, image: Image) -> ImageRectangleContext<'a> {
         ImageRectangleContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Value([
                 0.0, 
                 0.0, 
@@ -219,6 +341,8 @@ impl<'a> AddImage<'a, ImageRectangleContext<'a>> for Context<'a> {
                 image.source_rect[3] as f64
             ]),
             image: Value(image),
+            uv_transform: Value(identity()),
+            slice_mode: Value(Stretch),
         }
     }
 }
@@ -229,6 +353,7 @@ impl<'a> AddTween<'a, TweenContext<'a>> for Context<'a> {
         TweenContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             tween_factor: Value(tween_factor),
         }
     }
@@ -240,8 +365,24 @@ impl<'a> AddLine<'a, LineContext<'a>> for Context<'a> {
         LineContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Value([x1, y1, x2, y2]),
         }
     }
 }
 
+impl<'a, 'b> AddText<'a, TextContext<'a, 'b>> for Context<'a> {
+    #[inline(always)]
+    fn text(&'a self, font: &'b Font, size: Scalar, text: &'b str) -> TextContext<'a, 'b> {
+        TextContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            font: Value(font),
+            size: Value(size),
+            text: Value(text),
+            max_width: Value(None),
+            align: Value(AlignLeft),
+        }
+    }
+}
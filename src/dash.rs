@@ -0,0 +1,85 @@
+use std::cmp::min;
+use internal::{
+    Scalar,
+};
+
+/// The maximum number of lengths in a dash pattern.
+pub static MAX_DASH_LENGTHS: uint = 8;
+
+/// The maximum number of dashes along a stroked line.
+///
+/// Every dash takes its own triangles,
+/// so a pattern too short for the length of the line draws it without dashes.
+pub static MAX_DASHES: uint = 10000;
+
+/// A pattern of dashes along a stroked line.
+///
+/// The lengths alternate between drawn and skipped parts of the line,
+/// starting with a drawn part, and the pattern repeats along the line.
+/// With an odd number of lengths, every other repetition starts with a skipped part.
+/// A drawn part with zero length becomes a dot when the line has caps.
+pub struct Dash {
+    lengths: [Scalar, ..MAX_DASH_LENGTHS],
+    count: uint,
+    /// The distance into the pattern at the start of the line.
+    pub phase: Scalar,
+}
+
+impl Dash {
+    /// Creates a dash pattern from non-negative lengths.
+    ///
+    /// Only the first `MAX_DASH_LENGTHS` lengths are used.
+    pub fn new(lengths: &[Scalar], phase: Scalar) -> Dash {
+        let lengths = lengths.slice_to(min(lengths.len(), MAX_DASH_LENGTHS));
+        let mut dash = Dash {
+            lengths: [0.0, ..MAX_DASH_LENGTHS],
+            count: lengths.len(),
+            phase: phase,
+        };
+        for (a, &b) in dash.lengths.mut_iter().zip(lengths.iter()) {
+            *a = b.max(0.0);
+        }
+        dash
+    }
+
+    /// Creates a dash pattern that moves along the line when animated.
+    ///
+    /// When the animation inbetweening factor goes from 0 to 1,
+    /// the dashes move forward one period, which repeats seamlessly.
+    pub fn lerp(lengths: &[Scalar], tween_factor: Scalar) -> Dash {
+        let mut dash = Dash::new(lengths, 0.0);
+        dash.phase = -(tween_factor % 1.0) * dash.period();
+        dash
+    }
+
+    /// Returns the lengths of the pattern.
+    #[inline(always)]
+    pub fn lengths<'a>(&'a self) -> &'a [Scalar] {
+        self.lengths.slice_to(self.count)
+    }
+
+    /// Returns the distance along the line before the pattern repeats.
+    pub fn period(&self) -> Scalar {
+        let sum = self.lengths().iter().fold(0.0, |a, &b| a + b);
+        if self.count % 2 == 1 { 2.0 * sum } else { sum }
+    }
+}
+
+#[test]
+fn test_dash_period() {
+    assert_eq!(Dash::new([2.0, 1.0], 0.0).period(), 3.0);
+    // An odd number of lengths swaps drawn and skipped parts every other time.
+    assert_eq!(Dash::new([2.0, 1.0, 1.0], 0.0).period(), 8.0);
+    assert_eq!(Dash::lerp([2.0, 1.0], 0.5).phase, -1.5);
+    assert_eq!(Dash::lerp([2.0, 1.0], 1.5).phase, -1.5);
+}
+
+#[test]
+fn test_dash_too_many_lengths() {
+    // Lengths beyond the maximum are ignored.
+    let mut lengths = [1.0, ..MAX_DASH_LENGTHS + 1];
+    lengths[MAX_DASH_LENGTHS] = 5.0;
+    let dash = Dash::new(lengths, 0.0);
+    assert_eq!(dash.lengths().len(), MAX_DASH_LENGTHS);
+    assert_eq!(dash.period(), MAX_DASH_LENGTHS as Scalar);
+}
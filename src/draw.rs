@@ -1,8 +1,10 @@
-use {BackEnd};
+use {BackEnd, RenderResult};
 
 /// Implemented by contexts that can draws something using a back-end.
 pub trait Draw<'a> {
     /// Draw using back-end.
-    fn draw<B: BackEnd>(&'a self, back_end: &mut B);
+    ///
+    /// Returns an error if the back-end can not render it.
+    fn draw<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult;
 }
 
@@ -1,14 +1,37 @@
 
 use {
+    AddBorder,
+    AddGradient,
+    AddImage,
+    AddResolution,
     BackEnd,
-    Clear,
+    BorderEllipseColorContext,
     Borrowed,
+    Clear,
+    ClipBackEnd,
+    FallbackBackEnd,
     Field,
     Fill,
+    Gradient,
+    Image,
+    ImageEllipseColorContext,
+    MiterJoin,
+    RenderResult,
+    Resolution,
+    TriFan,
+    TriList,
     Value,
 };
 use triangulation::{
-    with_ellipse_tri_list_xy_f32_rgba_f32
+    Coordinate,
+    DEFAULT_MITER_LIMIT,
+    ellipse_resolution,
+    ellipse_tri_list_indexed_xy_f32_rgba_f32,
+    with_ellipse_gradient_tri_list_xy_rgba_f32,
+    with_ellipse_xy_rgba_f32,
+};
+use vecmath::{
+    identity,
 };
 use internal::{
     CanColor,
@@ -21,6 +44,7 @@ use internal::{
     HasTransform,
     HasViewTransform,
     Matrix2d,
+    Radius,
     Rectangle,
 };
 
@@ -30,10 +54,16 @@ pub struct EllipseColorContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transformation.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current rectangle.
     pub rect: Field<'a, Rectangle>,
+    /// Current resolution of the curves.
+    pub resolution: Field<'a, Resolution>,
     /// Current color.
     pub color: Field<'a, Color>,
+    /// Current gradient.
+    pub gradient: Field<'a, Option<Gradient>>,
 }
 
 impl<'a> Clone for EllipseColorContext<'a> {
@@ -42,8 +72,11 @@ impl<'a> Clone for EllipseColorContext<'a> {
         EllipseColorContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             rect: Value(*self.rect.get()),
+            resolution: Value(*self.resolution.get()),
             color: Value(*self.color.get()),
+            gradient: Value(*self.gradient.get()),
         }
     }
 }
@@ -61,8 +94,11 @@ impl<'a> CanTransform<'a, EllipseColorContext<'a>, Matrix2d> for EllipseColorCon
         EllipseColorContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
+            resolution: Borrowed(self.resolution.get()),
             color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
         }
     }
 }
@@ -81,8 +117,11 @@ for EllipseColorContext<'a> {
         EllipseColorContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
+            resolution: Borrowed(self.resolution.get()),
             color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
         }
     }
 }
@@ -100,8 +139,11 @@ impl<'a> CanColor<'a, EllipseColorContext<'a>, Color> for EllipseColorContext<'a
         EllipseColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Value(value),
+            gradient: Borrowed(self.gradient.get()),
             rect: Borrowed(self.rect.get()),
+            resolution: Borrowed(self.resolution.get()),
         }
     }
 }
@@ -119,48 +161,170 @@ impl<'a> CanRectangle<'a, EllipseColorContext<'a>, Rectangle> for EllipseColorCo
         EllipseColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Value(rect),
+            resolution: Borrowed(self.resolution.get()),
             color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
+        }
+    }
+}
+
+impl<'a> EllipseColorContext<'a> {
+    /// Streams the filled ellipse with f32 or f64 vertices.
+    fn stream_tri_list<V: Coordinate>(
+        &self,
+        resolution: uint,
+        f: |vertices: &[V], colors: &[f32]|
+    ) {
+        match *self.gradient.get() {
+            Some(ref gradient) => with_ellipse_gradient_tri_list_xy_rgba_f32(
+                resolution, *self.transform.get(), *self.rect.get(), gradient, f),
+            None => {
+                with_ellipse_xy_rgba_f32(TriList,
+                    resolution, *self.transform.get(), *self.rect.get(), *self.color.get(), f);
+            },
         }
     }
 }
 
 impl<'a> Fill<'a> for EllipseColorContext<'a> {
     #[inline(always)]
-    fn fill<B: BackEnd>(&'a self, back_end: &mut B) {
-        if back_end.supports_tri_list_xy_f32_rgba_f32() {
-            let rect = self.rect.get();
-            let color = self.color.get();
-            // Complete transparency does not need to be rendered.
-            if color[3] == 0.0 { return; }
-            // Turn on alpha blending if not completely opaque.
-            let needs_alpha = color[3] != 1.0;
-            if needs_alpha { back_end.enable_alpha_blend(); }
-            with_ellipse_tri_list_xy_f32_rgba_f32(
-                128,
-                *self.transform.get(),
-                *rect,
-                *color,
-                |vertices, colors| {
-                    back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+    fn fill<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let rect = self.rect.get();
+                let color = self.color.get();
+                let needs_alpha = match *self.gradient.get() {
+                    Some(ref gradient) => {
+                        // Complete transparency does not need to be rendered.
+                        if gradient.is_invisible() { return; }
+                        // Turn on alpha blending if any stop is not completely opaque.
+                        gradient.has_alpha()
+                    },
+                    None => {
+                        // Complete transparency does not need to be rendered.
+                        if color[3] == 0.0 { return; }
+                        // Turn on alpha blending if not completely opaque.
+                        color[3] != 1.0
+                    },
+                };
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                let resolution = ellipse_resolution(*self.transform.get(), *rect, *self.resolution.get());
+                // Gradients are only streamed as triangle lists.
+                let solid = self.gradient.get().is_none();
+                if back_end.supports_tri_list_xy_f64_rgba_f32() {
+                    self.stream_tri_list(resolution, |vertices, colors| {
+                        back_end.tri_list_xy_f64_rgba_f32(vertices, colors)
+                    });
+                } else if solid && back_end.supports_tri_fan_xy_f32_rgba_f32() {
+                    with_ellipse_xy_rgba_f32(
+                        TriFan,
+                        resolution,
+                        *self.transform.get(),
+                        *rect,
+                        *color,
+                        |vertices, colors| {
+                            back_end.tri_fan_xy_f32_rgba_f32(vertices, colors)
+                        }
+                    );
+                } else if solid && back_end.supports_tri_list_indexed_xy_f32_rgba_f32() {
+                    let (vertices, colors, indices) = ellipse_tri_list_indexed_xy_f32_rgba_f32(
+                        resolution, *self.transform.get(), *rect, *color);
+                    back_end.tri_list_indexed_xy_f32_rgba_f32(
+                        vertices.as_slice(), colors.as_slice(), indices.as_slice());
+                } else {
+                    self.stream_tri_list(resolution, |vertices, colors| {
+                        back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+                    });
                 }
-            );
-            if needs_alpha { back_end.disable_alpha_blend(); }
-        } else {
-            unimplemented!();
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+impl<'a> AddResolution<'a, EllipseColorContext<'a>> for EllipseColorContext<'a> {
+    #[inline(always)]
+    fn resolution(&'a self, resolution: Resolution) -> EllipseColorContext<'a> {
+        EllipseColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            resolution: Value(resolution),
+            color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
+        }
+    }
+}
+
+impl<'a> AddGradient<'a, EllipseColorContext<'a>> for EllipseColorContext<'a> {
+    #[inline(always)]
+    fn gradient(&'a self, gradient: Gradient) -> EllipseColorContext<'a> {
+        EllipseColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            resolution: Borrowed(self.resolution.get()),
+            color: Borrowed(self.color.get()),
+            gradient: Value(Some(gradient)),
         }
     }
 }
 
 impl<'a> Clear for EllipseColorContext<'a> {
     #[inline(always)]
-    fn clear<B: BackEnd>(&self, back_end: &mut B) {
-        if back_end.supports_clear_rgba() {
-            let color = self.color.get();
-            back_end.clear_rgba(color[0], color[1], color[2], color[3]);
-        } else {
-            unimplemented!();
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
+
+impl<'a> AddBorder<'a, BorderEllipseColorContext<'a>> for EllipseColorContext<'a> {
+    #[inline(always)]
+    fn border_radius(&'a self, radius: Radius) -> BorderEllipseColorContext<'a> {
+        BorderEllipseColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            resolution: Borrowed(self.resolution.get()),
+            border_radius: Value(radius),
+            join: Value(MiterJoin),
+            miter_limit: Value(DEFAULT_MITER_LIMIT),
+            dash: Value(None),
+            color: Borrowed(self.color.get()),
         }
     }
 }
 
+impl<'a> AddImage<'a, ImageEllipseColorContext<'a>> for EllipseColorContext<'a> {
+    #[inline(always)]
+    fn image(&'a self, image: Image) -> ImageEllipseColorContext<'a> {
+        ImageEllipseColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            resolution: Borrowed(self.resolution.get()),
+            image: Value(image),
+            uv_transform: Value(identity()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+#[test]
+fn test_fill_segments() {
+    use {AddColor, AddEllipse, AddResolution, Context, RecordingBackEnd, TriListXyF32RgbaF32};
+
+    let mut back_end = RecordingBackEnd::new();
+    let c = Context::new();
+    c.ellipse(0.0, 0.0, 1.0, 1.0).rgba(1.0, 0.0, 0.0, 1.0).segments(16).fill(&mut back_end).unwrap();
+    match *back_end.commands.get(0) {
+        // Each segment of the outline becomes one triangle of the fan.
+        TriListXyF32RgbaF32(ref vertices, _) => assert_eq!(vertices.len(), 14 * 6),
+        _ => fail!("Expected triangle list"),
+    }
+}
@@ -1,10 +1,22 @@
 use {
     AddColor,
+    AddGradient,
+    AddImage,
     Borrowed,
     EllipseColorContext,
     Field,
+    Gradient,
+    Image,
+    ImageEllipseContext,
+    Tolerance,
     Value,
 };
+use triangulation::{
+    DEFAULT_TOLERANCE,
+};
+use vecmath::{
+    identity,
+};
 use internal::{
     CanRectangle,
     CanTransform,
@@ -23,6 +35,8 @@ pub struct EllipseContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transformation.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current rectangle enclosing the ellipse.
     pub rect: Field<'a, Rectangle>,
 }
@@ -33,6 +47,7 @@ impl<'a> Clone for EllipseContext<'a> {
         EllipseContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             rect: Value(*self.rect.get()),
         }
     }
@@ -51,6 +66,7 @@ impl<'a> CanTransform<'a, EllipseContext<'a>, Matrix2d> for EllipseContext<'a> {
         EllipseContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
         }
     }
@@ -70,6 +86,7 @@ for EllipseContext<'a> {
         EllipseContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
         }
     }
@@ -87,8 +104,26 @@ impl<'a> AddColor<'a, EllipseColorContext<'a>> for EllipseContext<'a> {
         EllipseColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
+            resolution: Value(Tolerance(DEFAULT_TOLERANCE)),
             color: Value([r, g, b, a]),
+            gradient: Value(None),
+        }
+    }
+}
+
+impl<'a> AddGradient<'a, EllipseColorContext<'a>> for EllipseContext<'a> {
+    #[inline(always)]
+    fn gradient(&'a self, gradient: Gradient) -> EllipseColorContext<'a> {
+        EllipseColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            resolution: Value(Tolerance(DEFAULT_TOLERANCE)),
+            color: Value(gradient.stops()[0].val1()),
+            gradient: Value(Some(gradient)),
         }
     }
 }
@@ -106,8 +141,23 @@ impl<'a> CanRectangle<'a, EllipseContext<'a>, Rectangle> for EllipseContext<'a>
         EllipseContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Value(rect),
         }
     }
 }
 
+impl<'a> AddImage<'a, ImageEllipseContext<'a>> for EllipseContext<'a> {
+    #[inline(always)]
+    fn image(&'a self, image: Image) -> ImageEllipseContext<'a> {
+        ImageEllipseContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            resolution: Value(Tolerance(DEFAULT_TOLERANCE)),
+            image: Value(image),
+            uv_transform: Value(identity()),
+        }
+    }
+}
@@ -0,0 +1,635 @@
+//! A back-end wrapper that converts triangles to a format the back-end supports.
+
+use std::cmp::{min};
+use std::u16;
+use {
+    BackEnd,
+    RenderError,
+    RenderResult,
+    UnsupportedTexture,
+    UnsupportedTriangles,
+    UnsupportedVertexColors,
+};
+use triangulation::{expand_indexed_f32};
+
+/// Converts triangles to the first format supported by another back-end.
+///
+/// Triangle lists with f32 vertices are sent as they are if supported.
+/// Otherwise they are converted to f64 vertices, to indexed triangles
+/// or to triangles with a uniform color, in that order.
+/// Indexed triangles are converted to `u16` indices
+/// when the back-end supports only those and the vertices fit,
+/// or else to triangle lists.
+/// The first triangles that can not be converted are remembered as an error.
+pub struct FallbackBackEnd<'b, B> {
+    back_end: &'b mut B,
+    error: Option<RenderError>,
+}
+
+impl<'b, B: BackEnd> FallbackBackEnd<'b, B> {
+    /// Calls a closure with a back-end that supports triangle lists with f32 vertices.
+    ///
+    /// Returns an error without calling the closure
+    /// if the back-end supports no format for colored triangles.
+    /// Returns an error if some triangles could not be converted.
+    pub fn with_fallback(
+        back_end: &mut B,
+        f: |back_end: &mut FallbackBackEnd<B>|
+    ) -> RenderResult {
+        if !(back_end.supports_tri_list_xy_f32_rgba_f32()
+        || back_end.supports_tri_list_xy_f64_rgba_f32()
+        || back_end.supports_tri_list_indexed_xy_f32_rgba_f32()
+        || back_end.supports_tri_list_indexed_xy_f32_rgba_f32_u16()
+        || back_end.supports_tri_list_xy_f32_uniform_rgba_f32()) {
+            return Err(UnsupportedTriangles);
+        }
+        FallbackBackEnd::call(back_end, f)
+    }
+
+    /// Calls a closure with a back-end that supports textured triangle lists with f32 vertices.
+    ///
+    /// Returns an error without calling the closure
+    /// if the back-end supports no format for textured triangles.
+    pub fn with_texture_fallback(
+        back_end: &mut B,
+        f: |back_end: &mut FallbackBackEnd<B>|
+    ) -> RenderResult {
+        if !(back_end.supports_single_texture()
+        && (back_end.supports_tri_list_xy_f32_rgba_f32_uv_f32()
+        || back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32()
+        || back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16())) {
+            return Err(UnsupportedTexture);
+        }
+        FallbackBackEnd::call(back_end, f)
+    }
+
+    /// Calls a closure and returns the first error.
+    fn call(
+        back_end: &mut B,
+        f: |back_end: &mut FallbackBackEnd<B>|
+    ) -> RenderResult {
+        let mut fallback = FallbackBackEnd { back_end: back_end, error: None };
+        f(&mut fallback);
+        match fallback.error {
+            None => Ok(()),
+            Some(error) => Err(error),
+        }
+    }
+
+    /// Remembers the first error.
+    fn fail(&mut self, error: RenderError) {
+        if self.error.is_none() { self.error = Some(error); }
+    }
+}
+
+/// Converts indices to `u16` if all vertices can be picked by them.
+fn indices_u16(vertex_count: uint, indices: &[u32]) -> Option<Vec<u16>> {
+    if vertex_count > u16::MAX as uint + 1 { return None; }
+    Some(indices.iter().map(|&i| i as u16).collect())
+}
+
+/// Returns the color if all vertices have the same color.
+fn uniform_color(colors: &[f32]) -> Option<[f32, ..4]> {
+    if colors.len() < 4 { return None; }
+    let color = [colors[0], colors[1], colors[2], colors[3]];
+    if colors.iter().enumerate().all(|(i, &c)| c == color[i % 4]) {
+        Some(color)
+    } else {
+        None
+    }
+}
+
+impl<'b, B: BackEnd> BackEnd for FallbackBackEnd<'b, B> {
+    #[inline(always)]
+    fn supports_clear_rgba(&self) -> bool {
+        self.back_end.supports_clear_rgba()
+    }
+
+    fn clear_rgba(&mut self, r: f32, g: f32, b: f32, a: f32) {
+        self.back_end.clear_rgba(r, g, b, a);
+    }
+
+    fn enable_alpha_blend(&mut self) {
+        self.back_end.enable_alpha_blend();
+    }
+
+    fn disable_alpha_blend(&mut self) {
+        self.back_end.disable_alpha_blend();
+    }
+
+    #[inline(always)]
+    fn supports_scissor(&self) -> bool {
+        self.back_end.supports_scissor()
+    }
+
+    fn set_scissor(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        self.back_end.set_scissor(x, y, w, h);
+    }
+
+    fn clear_scissor(&mut self) {
+        self.back_end.clear_scissor();
+    }
+
+    #[inline(always)]
+    fn supports_stencil(&self) -> bool {
+        self.back_end.supports_stencil()
+    }
+
+    fn begin_stencil_write(&mut self) {
+        self.back_end.begin_stencil_write();
+    }
+
+    fn begin_stencil_test(&mut self) {
+        self.back_end.begin_stencil_test();
+    }
+
+    fn end_stencil(&mut self) {
+        self.back_end.end_stencil();
+    }
+
+    #[inline(always)]
+    fn supports_single_texture(&self) -> bool {
+        self.back_end.supports_single_texture()
+    }
+
+    fn enable_single_texture(&mut self, texture_id: uint) {
+        self.back_end.enable_single_texture(texture_id);
+    }
+
+    fn disable_single_texture(&mut self) {
+        self.back_end.disable_single_texture();
+    }
+
+    #[inline(always)]
+    fn has_texture_alpha(&self, texture_id: uint) -> bool {
+        self.back_end.has_texture_alpha(texture_id)
+    }
+
+    #[inline(always)]
+    fn supports_texture_upload(&self) -> bool {
+        self.back_end.supports_texture_upload()
+    }
+
+    fn create_texture(&mut self, width: u32, height: u32, pixels: &[u8]) -> Option<uint> {
+        self.back_end.create_texture(width, height, pixels)
+    }
+
+    fn update_texture(
+        &mut self,
+        texture_id: uint,
+        x: u32, y: u32, w: u32, h: u32,
+        pixels: &[u8]
+    ) {
+        self.back_end.update_texture(texture_id, x, y, w, h, pixels);
+    }
+
+    fn delete_texture(&mut self, texture_id: uint) {
+        self.back_end.delete_texture(texture_id);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f64_rgba_f32(&self) -> bool {
+        self.back_end.supports_tri_list_xy_f64_rgba_f32()
+    }
+
+    fn tri_list_xy_f64_rgba_f32(
+        &mut self,
+        vertices: &[f64],
+        colors: &[f32]
+    ) {
+        self.back_end.tri_list_xy_f64_rgba_f32(vertices, colors);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_rgba_f32(&self) -> bool {
+        // Checked by `with_fallback`, but wrappers ask again.
+        self.back_end.supports_tri_list_xy_f32_rgba_f32()
+        || self.back_end.supports_tri_list_xy_f64_rgba_f32()
+        || self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32()
+        || self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_u16()
+        || self.back_end.supports_tri_list_xy_f32_uniform_rgba_f32()
+    }
+
+    fn tri_list_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        if self.back_end.supports_tri_list_xy_f32_rgba_f32() {
+            self.back_end.tri_list_xy_f32_rgba_f32(vertices, colors);
+        } else if self.back_end.supports_tri_list_xy_f64_rgba_f32() {
+            let vertices: Vec<f64> = vertices.iter().map(|&x| x as f64).collect();
+            self.back_end.tri_list_xy_f64_rgba_f32(vertices.as_slice(), colors);
+        } else if self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32() {
+            let indices = Vec::from_fn(vertices.len() / 2, |i| i as u32);
+            self.back_end.tri_list_indexed_xy_f32_rgba_f32(
+                vertices, colors, indices.as_slice());
+        } else if self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_u16() {
+            // Every chunk of whole triangles is picked by `u16` indices.
+            let chunk = (u16::MAX as uint + 1) / 3 * 3;
+            let indices = Vec::from_fn(chunk, |i| i as u16);
+            let n = vertices.len() / 2;
+            let mut start = 0;
+            while start < n {
+                let end = min(start + chunk, n);
+                self.back_end.tri_list_indexed_xy_f32_rgba_f32_u16(
+                    vertices.slice(start * 2, end * 2),
+                    colors.slice(start * 4, end * 4),
+                    indices.slice_to(end - start));
+                start = end;
+            }
+        } else if self.back_end.supports_tri_list_xy_f32_uniform_rgba_f32() {
+            match uniform_color(colors) {
+                Some(c) => self.back_end.tri_list_xy_f32_uniform_rgba_f32(
+                    vertices, c[0], c[1], c[2], c[3]),
+                None if colors.len() == 0 => {},
+                None => self.fail(UnsupportedVertexColors),
+            }
+        } else {
+            self.fail(UnsupportedTriangles);
+        }
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_rgba_f32_uv_f32(&self) -> bool {
+        self.back_end.supports_tri_list_xy_f32_rgba_f32_uv_f32()
+        || self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32()
+        || self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16()
+    }
+
+    fn tri_list_xy_f32_rgba_f32_uv_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32]
+    ) {
+        if self.back_end.supports_tri_list_xy_f32_rgba_f32_uv_f32() {
+            self.back_end.tri_list_xy_f32_rgba_f32_uv_f32(vertices, colors, texture_coords);
+        } else if self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32() {
+            let indices = Vec::from_fn(vertices.len() / 2, |i| i as u32);
+            self.back_end.tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+                vertices, colors, texture_coords, indices.as_slice());
+        } else if self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16() {
+            let chunk = (u16::MAX as uint + 1) / 3 * 3;
+            let indices = Vec::from_fn(chunk, |i| i as u16);
+            let n = vertices.len() / 2;
+            let mut start = 0;
+            while start < n {
+                let end = min(start + chunk, n);
+                self.back_end.tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(
+                    vertices.slice(start * 2, end * 2),
+                    colors.slice(start * 4, end * 4),
+                    texture_coords.slice(start * 2, end * 2),
+                    indices.slice_to(end - start));
+                start = end;
+            }
+        } else {
+            self.fail(UnsupportedTexture);
+        }
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32(&self) -> bool {
+        self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32()
+        || self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_u16()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        indices: &[u32]
+    ) {
+        if self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32() {
+            self.back_end.tri_list_indexed_xy_f32_rgba_f32(vertices, colors, indices);
+            return;
+        }
+        match indices_u16(vertices.len() / 2, indices) {
+            Some(ref indices) if self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_u16() => {
+                self.back_end.tri_list_indexed_xy_f32_rgba_f32_u16(
+                    vertices, colors, indices.as_slice());
+            },
+            _ => self.tri_list_xy_f32_rgba_f32(
+                expand_indexed_f32(vertices, 2, indices).as_slice(),
+                expand_indexed_f32(colors, 4, indices).as_slice()
+            ),
+        }
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32(&self) -> bool {
+        self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32()
+        || self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32],
+        indices: &[u32]
+    ) {
+        if self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32() {
+            self.back_end.tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+                vertices, colors, texture_coords, indices);
+            return;
+        }
+        match indices_u16(vertices.len() / 2, indices) {
+            Some(ref indices)
+            if self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16() => {
+                self.back_end.tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(
+                    vertices, colors, texture_coords, indices.as_slice());
+            },
+            _ => self.tri_list_xy_f32_rgba_f32_uv_f32(
+                expand_indexed_f32(vertices, 2, indices).as_slice(),
+                expand_indexed_f32(colors, 4, indices).as_slice(),
+                expand_indexed_f32(texture_coords, 2, indices).as_slice()
+            ),
+        }
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_u16(&self) -> bool {
+        self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_u16()
+        || self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_u16(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        indices: &[u16]
+    ) {
+        if self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_u16() {
+            self.back_end.tri_list_indexed_xy_f32_rgba_f32_u16(vertices, colors, indices);
+        } else {
+            let indices: Vec<u32> = indices.iter().map(|&i| i as u32).collect();
+            self.back_end.tri_list_indexed_xy_f32_rgba_f32(vertices, colors, indices.as_slice());
+        }
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(&self) -> bool {
+        self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16()
+        || self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32],
+        indices: &[u16]
+    ) {
+        if self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16() {
+            self.back_end.tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(
+                vertices, colors, texture_coords, indices);
+        } else {
+            let indices: Vec<u32> = indices.iter().map(|&i| i as u32).collect();
+            self.back_end.tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+                vertices, colors, texture_coords, indices.as_slice());
+        }
+    }
+
+    #[inline(always)]
+    fn supports_tri_strip_xy_f32_rgba_f32(&self) -> bool {
+        self.back_end.supports_tri_strip_xy_f32_rgba_f32()
+    }
+
+    fn tri_strip_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        self.back_end.tri_strip_xy_f32_rgba_f32(vertices, colors);
+    }
+
+    #[inline(always)]
+    fn supports_tri_fan_xy_f32_rgba_f32(&self) -> bool {
+        self.back_end.supports_tri_fan_xy_f32_rgba_f32()
+    }
+
+    fn tri_fan_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        self.back_end.tri_fan_xy_f32_rgba_f32(vertices, colors);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_uniform_rgba_f32(&self) -> bool {
+        self.back_end.supports_tri_list_xy_f32_uniform_rgba_f32()
+    }
+
+    fn tri_list_xy_f32_uniform_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        r: f32, g: f32, b: f32, a: f32
+    ) {
+        self.back_end.tri_list_xy_f32_uniform_rgba_f32(vertices, r, g, b, a);
+    }
+}
+
+/// Supports only some formats for colored triangles.
+#[cfg(test)]
+struct LimitedBackEnd {
+    recording: ::RecordingBackEnd,
+    f64_vertices: bool,
+    indexed: bool,
+    indexed_u16: bool,
+}
+
+#[cfg(test)]
+impl LimitedBackEnd {
+    fn new(f64_vertices: bool, indexed: bool) -> LimitedBackEnd {
+        LimitedBackEnd {
+            recording: ::RecordingBackEnd::new(),
+            f64_vertices: f64_vertices,
+            indexed: indexed,
+            indexed_u16: false,
+        }
+    }
+}
+
+#[cfg(test)]
+impl BackEnd for LimitedBackEnd {
+    fn supports_clear_rgba(&self) -> bool { true }
+
+    fn clear_rgba(&mut self, r: f32, g: f32, b: f32, a: f32) {
+        self.recording.clear_rgba(r, g, b, a);
+    }
+
+    fn supports_tri_list_xy_f64_rgba_f32(&self) -> bool { self.f64_vertices }
+
+    fn tri_list_xy_f64_rgba_f32(&mut self, vertices: &[f64], colors: &[f32]) {
+        self.recording.tri_list_xy_f64_rgba_f32(vertices, colors);
+    }
+
+    fn supports_tri_list_indexed_xy_f32_rgba_f32(&self) -> bool { self.indexed }
+
+    fn tri_list_indexed_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        indices: &[u32]
+    ) {
+        self.recording.tri_list_indexed_xy_f32_rgba_f32(vertices, colors, indices);
+    }
+
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_u16(&self) -> bool { self.indexed_u16 }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_u16(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        indices: &[u16]
+    ) {
+        self.recording.tri_list_indexed_xy_f32_rgba_f32_u16(vertices, colors, indices);
+    }
+
+    fn supports_tri_list_xy_f32_uniform_rgba_f32(&self) -> bool { true }
+
+    fn tri_list_xy_f32_uniform_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        r: f32, g: f32, b: f32, a: f32
+    ) {
+        self.recording.tri_list_xy_f32_uniform_rgba_f32(vertices, r, g, b, a);
+    }
+}
+
+/// Supports nothing.
+#[cfg(test)]
+struct NullBackEnd;
+
+#[cfg(test)]
+impl BackEnd for NullBackEnd {}
+
+#[test]
+fn test_uniform_fallback() {
+    use {AddColor, AddEllipse, AddGradient, AddRectangle, Context, Fill};
+    use {TriListXyF32UniformRgbaF32};
+
+    let mut back_end = LimitedBackEnd::new(false, false);
+    let c = Context::new();
+    assert_eq!(c.rect(-1.0, -1.0, 1.0, 1.0).rgba(1.0, 0.0, 0.0, 1.0).fill(&mut back_end), Ok(()));
+    assert_eq!(c.ellipse(-1.0, -1.0, 1.0, 1.0).rgba(0.0, 1.0, 0.0, 1.0).fill(&mut back_end), Ok(()));
+    assert_eq!(back_end.recording.commands.len(), 2);
+    match *back_end.recording.commands.get(0) {
+        TriListXyF32UniformRgbaF32(ref vertices, r, g, b, a) => {
+            assert_eq!(vertices.len(), 12);
+            assert_eq!((r, g, b, a), (1.0, 0.0, 0.0, 1.0));
+        },
+        _ => fail!("Expected triangle list with uniform color"),
+    }
+    // Gradients need a color per vertex.
+    let (black, white) = ([0.0, 0.0, 0.0, 1.0], [1.0, 1.0, 1.0, 1.0]);
+    let result = c.rect(-1.0, -1.0, 2.0, 2.0)
+        .linear_gradient([-1.0, 0.0], [1.0, 0.0], [(0.0, black), (1.0, white)])
+        .fill(&mut back_end);
+    assert_eq!(result, Err(UnsupportedVertexColors));
+}
+
+#[test]
+fn test_fallback_order() {
+    use {TriListIndexedXyF32RgbaF32, TriListXyF64RgbaF32};
+
+    let draw = |back_end: &mut LimitedBackEnd| {
+        FallbackBackEnd::with_fallback(back_end, |back_end| {
+            back_end.tri_list_xy_f32_rgba_f32([0.0, 0.0, 1.0, 0.0, 0.0, 1.0],
+                [1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0]);
+        })
+    };
+    // f64 vertices are preferred over indexed triangles.
+    let mut back_end = LimitedBackEnd::new(true, true);
+    assert_eq!(draw(&mut back_end), Ok(()));
+    match *back_end.recording.commands.get(0) {
+        TriListXyF64RgbaF32(ref vertices, _) => assert_eq!(vertices.len(), 6),
+        _ => fail!("Expected triangle list with f64 vertices"),
+    }
+    // Indexed triangles are preferred over a uniform color.
+    let mut back_end = LimitedBackEnd::new(false, true);
+    assert_eq!(draw(&mut back_end), Ok(()));
+    match *back_end.recording.commands.get(0) {
+        TriListIndexedXyF32RgbaF32(_, _, ref indices) => {
+            assert_eq!(indices.as_slice(), &[0, 1, 2]);
+        },
+        _ => fail!("Expected indexed triangle list"),
+    }
+    // The colors differ, so a uniform color can not be used.
+    let mut back_end = LimitedBackEnd::new(false, false);
+    assert_eq!(draw(&mut back_end), Err(UnsupportedVertexColors));
+    assert_eq!(back_end.recording.commands.len(), 0);
+}
+
+#[test]
+fn test_u16_fallback() {
+    use {AddColor, AddRectangle, Context, Fill};
+    use {TriListIndexedXyF32RgbaF32U16};
+
+    let mut back_end = LimitedBackEnd::new(false, false);
+    back_end.indexed_u16 = true;
+    let c = Context::new();
+    assert_eq!(c.rect(-1.0, -1.0, 1.0, 1.0).rgba(1.0, 0.0, 0.0, 1.0).fill(&mut back_end), Ok(()));
+    let triangle = [0.0f32, 0.0, 1.0, 0.0, 0.0, 1.0];
+    let colors = [1.0f32, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0];
+    assert_eq!(FallbackBackEnd::with_fallback(&mut back_end, |back_end| {
+        back_end.tri_list_xy_f32_rgba_f32(triangle, colors);
+    }), Ok(()));
+    assert_eq!(back_end.recording.commands.len(), 2);
+    match *back_end.recording.commands.get(0) {
+        TriListIndexedXyF32RgbaF32U16(_, _, ref indices) => {
+            assert_eq!(indices.as_slice(), &[0, 1, 2, 1, 3, 2]);
+        },
+        _ => fail!("Expected indexed triangle list with u16 indices"),
+    }
+    match *back_end.recording.commands.get(1) {
+        TriListIndexedXyF32RgbaF32U16(_, _, ref indices) => {
+            assert_eq!(indices.as_slice(), &[0, 1, 2]);
+        },
+        _ => fail!("Expected indexed triangle list with u16 indices"),
+    }
+}
+
+#[test]
+fn test_unsupported() {
+    use {AddColor, AddImage, AddRectangle, Clear, Context, Draw, Fill, Image};
+    use {UnsupportedClear};
+
+    let c = Context::new();
+    let image = Image {
+        texture_id: 0,
+        texture_width: 1,
+        texture_height: 1,
+        source_rect: [0, 0, 1, 1],
+    };
+    let rect = c.rect(-1.0, -1.0, 2.0, 2.0);
+    assert_eq!(rect.rgba(1.0, 1.0, 1.0, 1.0).fill(&mut NullBackEnd), Err(UnsupportedTriangles));
+    assert_eq!(c.rgba(1.0, 1.0, 1.0, 1.0).clear(&mut NullBackEnd), Err(UnsupportedClear));
+    assert_eq!(rect.image(image).draw(&mut NullBackEnd), Err(UnsupportedTexture));
+}
+
+#[test]
+fn test_clip_clear_fallback() {
+    use {AddClip, AddColor, Clear, Context};
+    use {ClearRgba, TriListXyF64RgbaF32};
+
+    let c = Context::new();
+    let d = c.clip_rect(-1.0, -1.0, 1.0, 2.0);
+    // Without scissor and f32 triangles the clipped area is drawn with f64 triangles.
+    let mut back_end = LimitedBackEnd::new(true, false);
+    d.rgba(1.0, 1.0, 1.0, 1.0).clear(&mut back_end).unwrap();
+    assert!(back_end.recording.commands.iter().all(|command| match *command {
+        ClearRgba(..) => false,
+        _ => true,
+    }));
+    assert!(back_end.recording.commands.iter().any(|command| match *command {
+        TriListXyF64RgbaF32(..) => true,
+        _ => false,
+    }));
+    // Without clipping the whole background is cleared.
+    let mut back_end = LimitedBackEnd::new(true, false);
+    c.rgba(1.0, 1.0, 1.0, 1.0).clear(&mut back_end).unwrap();
+    assert!(*back_end.recording.commands.get(0) == ClearRgba(1.0, 1.0, 1.0, 1.0));
+}
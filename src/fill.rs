@@ -1,7 +1,9 @@
-use {BackEnd};
+use {BackEnd, RenderResult};
 
 /// Fills a shape using a back-end.
 pub trait Fill<'a> {
     /// Fill shape using back-end.
-    fn fill<B: BackEnd>(&'a self, back_end: &mut B);
+    ///
+    /// Returns an error if the back-end can not render it.
+    fn fill<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult;
 }
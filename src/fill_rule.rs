@@ -0,0 +1,11 @@
+
+/// Decides which areas of a shape that are inside.
+///
+/// The winding number of a point counts how many times
+/// the rings of a shape go around the point.
+pub enum FillRule {
+    /// A point is inside if the winding number is odd.
+    EvenOdd,
+    /// A point is inside if the winding number is not zero.
+    NonZero,
+}
@@ -0,0 +1,862 @@
+//! Loads TrueType fonts.
+
+use std::cell::{Cell, Ref, RefCell};
+use std::io::{File, IoError};
+use {
+    AlignCenter,
+    AlignLeft,
+    AlignRight,
+    AtlasGlyph,
+    BackEnd,
+    GlyphAtlas,
+    Path,
+    PositionedGlyph,
+    TextAlign,
+    Texture,
+    Textures,
+};
+use internal::{
+    Matrix2d,
+    Scalar,
+    Vec2d,
+};
+use vecmath::{
+    identity,
+    multiply,
+};
+
+/// The size of a new glyph atlas in pixels.
+static ATLAS_SIZE: u32 = 256;
+
+/// The maximum depth of glyphs built from other glyphs.
+static MAX_COMPONENT_DEPTH: uint = 8;
+
+/// An error that occured while loading a font.
+#[deriving(Show)]
+pub enum FontError {
+    /// The font file could not be read.
+    FontIoError(IoError),
+    /// A table required to draw text is missing.
+    FontMissingTable(&'static str),
+    /// The data is not a TrueType font.
+    FontMalformed,
+    /// The font uses outlines that are not supported, such as CFF.
+    FontUnsupported,
+}
+
+/// A TrueType font.
+///
+/// Glyphs are rasterized into a glyph atlas the first time they are drawn.
+/// When the back-end supports texture upload, the atlas is uploaded automatically.
+/// Otherwise the atlas must be uploaded as a texture with alpha channel,
+/// and the texture id set with `set_texture_id`:
+///
+/// ```
+/// if font.cache_text(size, "hello") {
+///     let texture_id = {
+///         let atlas = font.atlas();
+///         upload(atlas.width(), atlas.height(), atlas.pixels())
+///     };
+///     font.set_texture_id(texture_id);
+/// }
+/// c.text(&font, size, "hello").rgba(0.0, 0.0, 0.0, 1.0).draw(&mut back_end).unwrap();
+/// ```
+///
+/// Kerning is read from the legacy `kern` table only.
+/// Pair adjustments in the `GPOS` table are ignored,
+/// so most modern OpenType fonts are drawn without kerning.
+/// Fonts with CFF outlines are not supported.
+pub struct Font {
+    data: Vec<u8>,
+    units_per_em: u16,
+    num_glyphs: u16,
+    long_loca: bool,
+    loca: uint,
+    glyf: uint,
+    hmtx: uint,
+    num_h_metrics: u16,
+    cmap: uint,
+    cmap_format: u16,
+    ascent: i16,
+    descent: i16,
+    line_gap: i16,
+    kerning: Vec<(u32, i16)>,
+    atlas: RefCell<GlyphAtlas>,
+    textures: Textures,
+    texture: RefCell<Option<Texture>>,
+    texture_id: Cell<Option<uint>>,
+}
+
+impl Font {
+    /// Loads a font from the bytes of a TrueType file.
+    ///
+    /// For font collections the first font is used.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Font, FontError> {
+        let start = if u32_at(data.as_slice(), 0) == 0x74746366 {
+            // 'ttcf'
+            u32_at(data.as_slice(), 12) as uint
+        } else {
+            0
+        };
+        match u32_at(data.as_slice(), start) {
+            // Version 1.0 or 'true'.
+            0x00010000 | 0x74727565 => {},
+            // 'OTTO'
+            0x4F54544F => return Err(FontUnsupported),
+            _ => return Err(FontMalformed),
+        }
+
+        let (head, maxp, hhea, hmtx, loca, glyf, cmap) = {
+            let d = data.as_slice();
+            let table = |tag: &'static str| -> Result<uint, FontError> {
+                match find_table(d, start, tag) {
+                    Some((offset, _)) => Ok(offset),
+                    None => Err(FontMissingTable(tag)),
+                }
+            };
+            (
+                try!(table("head")),
+                try!(table("maxp")),
+                try!(table("hhea")),
+                try!(table("hmtx")),
+                try!(table("loca")),
+                try!(table("glyf")),
+                try!(table("cmap"))
+            )
+        };
+        let (cmap, cmap_format) = match find_cmap(data.as_slice(), cmap) {
+            Some(subtable) => subtable,
+            None => return Err(FontUnsupported),
+        };
+        let units_per_em = u16_at(data.as_slice(), head + 18);
+        if units_per_em == 0 { return Err(FontMalformed); }
+        let kerning = match find_table(data.as_slice(), start, "kern") {
+            Some((kern, _)) => read_kerning(data.as_slice(), kern),
+            None => Vec::new(),
+        };
+        Ok(Font {
+            units_per_em: units_per_em,
+            num_glyphs: u16_at(data.as_slice(), maxp + 4),
+            long_loca: u16_at(data.as_slice(), head + 50) != 0,
+            loca: loca,
+            glyf: glyf,
+            hmtx: hmtx,
+            num_h_metrics: u16_at(data.as_slice(), hhea + 34),
+            cmap: cmap,
+            cmap_format: cmap_format,
+            ascent: u16_at(data.as_slice(), hhea + 4) as i16,
+            descent: u16_at(data.as_slice(), hhea + 6) as i16,
+            line_gap: u16_at(data.as_slice(), hhea + 8) as i16,
+            kerning: kerning,
+            data: data,
+            atlas: RefCell::new(GlyphAtlas::new(ATLAS_SIZE, ATLAS_SIZE)),
+            textures: Textures::new(),
+            texture: RefCell::new(None),
+            texture_id: Cell::new(None),
+        })
+    }
+
+    /// Loads a font from a TrueType file.
+    pub fn from_file(path: &::std::path::Path) -> Result<Font, FontError> {
+        match File::open(path).read_to_end() {
+            Ok(data) => Font::from_bytes(data),
+            Err(err) => Err(FontIoError(err)),
+        }
+    }
+
+    /// Returns the number of font units per em square.
+    #[inline(always)]
+    pub fn units_per_em(&self) -> u16 { self.units_per_em }
+
+    /// Returns the number of glyphs in the font.
+    #[inline(always)]
+    pub fn num_glyphs(&self) -> u16 { self.num_glyphs }
+
+    /// Returns the distance from the baseline to the top of the line.
+    #[inline(always)]
+    pub fn ascent(&self, size: Scalar) -> Scalar {
+        self.ascent as Scalar * self.scale(size)
+    }
+
+    /// Returns the distance from the baseline to the bottom of the line.
+    ///
+    /// This is usually positive.
+    #[inline(always)]
+    pub fn descent(&self, size: Scalar) -> Scalar {
+        -self.descent as Scalar * self.scale(size)
+    }
+
+    /// Returns the distance between the baselines of two lines.
+    #[inline(always)]
+    pub fn line_height(&self, size: Scalar) -> Scalar {
+        (self.ascent as Scalar - self.descent as Scalar + self.line_gap as Scalar)
+            * self.scale(size)
+    }
+
+    /// Returns the glyph of a character.
+    ///
+    /// Characters not in the font return glyph 0, the missing glyph.
+    pub fn glyph_index(&self, c: char) -> u16 {
+        let d = self.data.as_slice();
+        let code = c as u32;
+        let cmap = self.cmap;
+        match self.cmap_format {
+            4 => {
+                if code > 0xFFFF { return 0; }
+                let seg_count = (u16_at(d, cmap + 6) / 2) as uint;
+                let ends = cmap + 14;
+                let starts = ends + seg_count * 2 + 2;
+                let deltas = starts + seg_count * 2;
+                let ranges = deltas + seg_count * 2;
+                for i in range(0, seg_count) {
+                    if (u16_at(d, ends + i * 2) as u32) < code { continue; }
+                    let start = u16_at(d, starts + i * 2) as u32;
+                    if start > code { return 0; }
+                    let delta = u16_at(d, deltas + i * 2) as u32;
+                    let range_offset = u16_at(d, ranges + i * 2) as uint;
+                    if range_offset == 0 { return ((code + delta) & 0xFFFF) as u16; }
+                    let glyph = u16_at(d, ranges + i * 2 + range_offset
+                        + (code - start) as uint * 2) as u32;
+                    return if glyph == 0 { 0 } else { ((glyph + delta) & 0xFFFF) as u16 };
+                }
+                0
+            },
+            12 => {
+                let groups = u32_at(d, cmap + 12) as uint;
+                for i in range(0, groups) {
+                    let group = cmap + 16 + i * 12;
+                    let (start, end) = (u32_at(d, group), u32_at(d, group + 4));
+                    if code >= start && code <= end {
+                        return (u32_at(d, group + 8) + code - start) as u16;
+                    }
+                }
+                0
+            },
+            _ => 0,
+        }
+    }
+
+    /// Returns how far the pen moves after a glyph.
+    pub fn advance_width(&self, glyph: u16, size: Scalar) -> Scalar {
+        if self.num_h_metrics == 0 { return 0.0; }
+        let i = if glyph < self.num_h_metrics { glyph } else { self.num_h_metrics - 1 };
+        u16_at(self.data.as_slice(), self.hmtx + i as uint * 4) as Scalar * self.scale(size)
+    }
+
+    /// Returns the adjustment of the distance between two glyphs.
+    pub fn kerning(&self, left: u16, right: u16, size: Scalar) -> Scalar {
+        let key = (left as u32 << 16) | right as u32;
+        let (mut lo, mut hi) = (0u, self.kerning.len());
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            let (mid_key, value) = *self.kerning.get(mid);
+            if mid_key == key { return value as Scalar * self.scale(size); }
+            if mid_key < key { lo = mid + 1; } else { hi = mid; }
+        }
+        0.0
+    }
+
+    /// Returns the outline of a glyph as a path.
+    ///
+    /// The path is in pixels with the origin at the baseline and the y axis pointing down.
+    pub fn glyph_path(&self, glyph: u16, size: Scalar) -> Path {
+        let mut contours = Vec::new();
+        self.glyph_contours(glyph, identity(), 0, &mut contours);
+        let s = self.scale(size);
+        let mut path = Path::new();
+        for contour in contours.iter() {
+            let n = contour.len();
+            if n < 2 { continue; }
+            let point = |i: uint| -> Vec2d {
+                let (p, _) = *contour.get(i);
+                [p[0] * s, -p[1] * s]
+            };
+            let on_curve = |i: uint| -> bool {
+                let (_, on) = *contour.get(i);
+                on
+            };
+            // Start at an on-curve point, or between two control points.
+            let start = if on_curve(0) {
+                point(0)
+            } else if on_curve(n - 1) {
+                point(n - 1)
+            } else {
+                mid(point(0), point(n - 1))
+            };
+            let begin = if on_curve(0) { 1 } else { 0 };
+            let end = if !on_curve(0) && on_curve(n - 1) { n - 1 } else { n };
+            path = path.move_to(start[0], start[1]);
+            let mut control: Option<Vec2d> = None;
+            for i in range(begin, end) {
+                let p = point(i);
+                path = match (control, on_curve(i)) {
+                    (Some(c), true) => path.quad_to(c[0], c[1], p[0], p[1]),
+                    (None, true) => path.line_to(p[0], p[1]),
+                    (Some(c), false) => {
+                        // Two control points in a row have an implied point between.
+                        let m = mid(c, p);
+                        path.quad_to(c[0], c[1], m[0], m[1])
+                    },
+                    (None, false) => path,
+                };
+                control = if on_curve(i) { None } else { Some(p) };
+            }
+            path = match control {
+                Some(c) => path.quad_to(c[0], c[1], start[0], start[1]),
+                None => path,
+            };
+            path = path.close();
+        }
+        path
+    }
+
+    /// Returns the width of a single line of text, including kerning.
+    pub fn text_width(&self, size: Scalar, text: &str) -> Scalar {
+        let glyphs: Vec<u16> = text.chars().map(|c| self.glyph_index(c)).collect();
+        self.glyphs_width(size, glyphs.as_slice())
+    }
+
+    /// Places the glyphs of a text in lines.
+    ///
+    /// The first line starts at the origin with its top at `y = 0`
+    /// and the following lines are placed below.
+    /// Lines are broken at new lines and, with a maximum width,
+    /// between words that would not fit.
+    /// A word wider than the maximum width gets a line of its own.
+    ///
+    /// Lines are aligned within the maximum width,
+    /// or within the widest line if there is none.
+    pub fn layout(
+        &self,
+        size: Scalar,
+        text: &str,
+        max_width: Option<Scalar>,
+        align: TextAlign
+    ) -> Vec<PositionedGlyph> {
+        let space = self.glyph_index(' ');
+        let mut lines: Vec<Vec<u16>> = Vec::new();
+        for paragraph in text.split('\n') {
+            let mut line: Vec<u16> = Vec::new();
+            for word in paragraph.split(' ') {
+                let word: Vec<u16> = word.chars()
+                    .filter(|&c| c != '\r')
+                    .map(|c| self.glyph_index(c))
+                    .collect();
+                let mut candidate = line.clone();
+                if line.len() > 0 || word.len() == 0 { candidate.push(space); }
+                candidate.push_all(word.as_slice());
+                let too_wide = match max_width {
+                    Some(max_width) => self.glyphs_width(size, candidate.as_slice()) > max_width,
+                    None => false,
+                };
+                if too_wide && line.len() > 0 && word.len() > 0 {
+                    lines.push(line);
+                    line = word;
+                } else {
+                    line = candidate;
+                }
+            }
+            lines.push(line);
+        }
+
+        let widths: Vec<Scalar> = lines.iter()
+            .map(|line| self.glyphs_width(size, line.as_slice())).collect();
+        let box_width = match max_width {
+            Some(max_width) => max_width,
+            None => widths.iter().fold(0.0_f64, |a, &b| a.max(b)),
+        };
+        let factor = match align {
+            AlignLeft => 0.0,
+            AlignCenter => 0.5,
+            AlignRight => 1.0,
+        };
+        let ascent = self.ascent(size);
+        let line_height = self.line_height(size);
+        let mut glyphs = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            let mut x = (box_width - *widths.get(i)) * factor;
+            let y = ascent + i as Scalar * line_height;
+            let mut previous: Option<u16> = None;
+            for &glyph in line.iter() {
+                match previous {
+                    Some(left) => x += self.kerning(left, glyph, size),
+                    None => {},
+                }
+                glyphs.push(PositionedGlyph { glyph: glyph, x: x, y: y });
+                x += self.advance_width(glyph, size);
+                previous = Some(glyph);
+            }
+        }
+        glyphs
+    }
+
+    /// Returns the glyph atlas.
+    #[inline(always)]
+    pub fn atlas<'a>(&'a self) -> Ref<'a, GlyphAtlas> {
+        self.atlas.borrow()
+    }
+
+    /// Rasterizes a glyph into the atlas if it is not there already.
+    ///
+    /// Returns `None` if the glyph is larger than the largest atlas.
+    #[inline(always)]
+    pub fn cache_glyph(&self, glyph: u16, size: Scalar) -> Option<AtlasGlyph> {
+        self.atlas.borrow_mut().glyph(self, glyph, size)
+    }
+
+    /// Rasterizes glyphs into the atlas, such that all of them are there at the same time.
+    ///
+    /// Returns `None` if the glyphs do not fit in the atlas together.
+    pub fn cache_glyphs(&self, glyphs: &[u16], size: Scalar) -> Option<Vec<AtlasGlyph>> {
+        // When the atlas starts over, the glyphs before are removed,
+        // so they are added once more.
+        for _ in range(0u, 2) {
+            let generation = self.atlas.borrow().generation();
+            let mut atlas_glyphs = Vec::with_capacity(glyphs.len());
+            for &glyph in glyphs.iter() {
+                match self.cache_glyph(glyph, size) {
+                    Some(atlas_glyph) => atlas_glyphs.push(atlas_glyph),
+                    None => return None,
+                }
+            }
+            if self.atlas.borrow().generation() == generation {
+                return Some(atlas_glyphs);
+            }
+        }
+        None
+    }
+
+    /// Rasterizes the glyphs of a text into the atlas.
+    ///
+    /// Returns true if the atlas has changed since the texture was set.
+    pub fn cache_text(&self, size: Scalar, text: &str) -> bool {
+        for c in text.chars() {
+            self.cache_glyph(self.glyph_index(c), size);
+        }
+        self.atlas.borrow().is_dirty()
+    }
+
+    /// Returns the texture containing the glyph atlas.
+    #[inline(always)]
+    pub fn texture_id(&self) -> Option<uint> {
+        match *self.texture.borrow() {
+            Some(ref texture) => Some(texture.id()),
+            None => self.texture_id.get(),
+        }
+    }
+
+    /// Sets the texture containing the current glyph atlas.
+    ///
+    /// A texture uploaded by the font is released.
+    pub fn set_texture_id(&self, texture_id: uint) {
+        *self.texture.borrow_mut() = None;
+        self.texture_id.set(Some(texture_id));
+        self.atlas.borrow_mut().mark_clean();
+    }
+
+    /// Uploads the glyph atlas if it has changed.
+    ///
+    /// Returns false if the back-end does not support texture upload.
+    pub fn upload<B: BackEnd>(&self, back_end: &mut B) -> bool {
+        if !back_end.supports_texture_upload() { return false; }
+        let mut atlas = self.atlas.borrow_mut();
+        let mut texture = self.texture.borrow_mut();
+        let (w, h) = (atlas.width(), atlas.height());
+        let fits = match *texture {
+            Some(ref texture) => texture.width() == w && texture.height() == h,
+            None => false,
+        };
+        if fits {
+            if atlas.is_dirty() {
+                texture.get_ref().update(back_end, 0, 0, w, h, atlas.pixels());
+            }
+        } else {
+            // The atlas has grown, so the old texture is replaced.
+            *texture = self.textures.create(back_end, w, h, atlas.pixels());
+            self.textures.delete_unused(back_end);
+        }
+        atlas.mark_clean();
+        texture.is_some()
+    }
+
+    /// Returns the factor from font units to pixels.
+    #[inline(always)]
+    fn scale(&self, size: Scalar) -> Scalar {
+        size / self.units_per_em as Scalar
+    }
+
+    /// Returns the width of glyphs placed after each other.
+    fn glyphs_width(&self, size: Scalar, glyphs: &[u16]) -> Scalar {
+        let mut width = 0.0;
+        for (i, &glyph) in glyphs.iter().enumerate() {
+            if i > 0 { width += self.kerning(glyphs[i - 1], glyph, size); }
+            width += self.advance_width(glyph, size);
+        }
+        width
+    }
+
+    /// Reads the contours of a glyph in font units.
+    fn glyph_contours(
+        &self,
+        glyph: u16,
+        m: Matrix2d,
+        depth: uint,
+        contours: &mut Vec<Vec<(Vec2d, bool)>>
+    ) {
+        if glyph >= self.num_glyphs || depth > MAX_COMPONENT_DEPTH { return; }
+        let d = self.data.as_slice();
+        let (start, end) = if self.long_loca {
+            let i = self.loca + glyph as uint * 4;
+            (u32_at(d, i) as uint, u32_at(d, i + 4) as uint)
+        } else {
+            let i = self.loca + glyph as uint * 2;
+            (u16_at(d, i) as uint * 2, u16_at(d, i + 2) as uint * 2)
+        };
+        if end <= start { return; }
+        let g = self.glyf + start;
+        let num_contours = u16_at(d, g) as i16;
+        if num_contours >= 0 {
+            let num_contours = num_contours as uint;
+            if num_contours == 0 { return; }
+            let ends = g + 10;
+            let num_points = u16_at(d, ends + (num_contours - 1) * 2) as uint + 1;
+            let mut pos = ends + num_contours * 2;
+            pos += 2 + u16_at(d, pos) as uint;
+
+            let mut flags: Vec<u8> = Vec::with_capacity(num_points);
+            while flags.len() < num_points {
+                let flag = u8_at(d, pos);
+                pos += 1;
+                flags.push(flag);
+                if flag & 8 != 0 {
+                    let repeat = u8_at(d, pos);
+                    pos += 1;
+                    for _ in range(0, repeat) { flags.push(flag); }
+                }
+            }
+            flags.truncate(num_points);
+
+            let read_coords = |pos: &mut uint, short: u8, same_or_positive: u8| -> Vec<Scalar> {
+                let mut value = 0i32;
+                flags.iter().map(|&flag| {
+                    if flag & short != 0 {
+                        let delta = u8_at(d, *pos) as i32;
+                        *pos += 1;
+                        value += if flag & same_or_positive != 0 { delta } else { -delta };
+                    } else if flag & same_or_positive == 0 {
+                        value += u16_at(d, *pos) as i16 as i32;
+                        *pos += 2;
+                    }
+                    value as Scalar
+                }).collect()
+            };
+            let xs = read_coords(&mut pos, 2, 16);
+            let ys = read_coords(&mut pos, 4, 32);
+
+            let mut first = 0u;
+            for i in range(0, num_contours) {
+                let last = u16_at(d, ends + i * 2) as uint;
+                if last < first || last >= num_points { break; }
+                contours.push(range(first, last + 1).map(|j| {
+                    let (x, y) = (*xs.get(j), *ys.get(j));
+                    ([m[0] * x + m[1] * y + m[2], m[3] * x + m[4] * y + m[5]],
+                     *flags.get(j) & 1 != 0)
+                }).collect());
+                first = last + 1;
+            }
+        } else {
+            // The glyph is built from transformed copies of other glyphs.
+            let mut pos = g + 10;
+            loop {
+                let flags = u16_at(d, pos);
+                let component = u16_at(d, pos + 2);
+                pos += 4;
+                let (dx, dy) = if flags & 1 != 0 {
+                    pos += 4;
+                    (u16_at(d, pos - 4) as i16 as Scalar, u16_at(d, pos - 2) as i16 as Scalar)
+                } else {
+                    pos += 2;
+                    (u8_at(d, pos - 2) as i8 as Scalar, u8_at(d, pos - 1) as i8 as Scalar)
+                };
+                // Matching points instead of offsets is not supported.
+                let (dx, dy) = if flags & 2 != 0 { (dx, dy) } else { (0.0, 0.0) };
+                let f2dot14 = |i: uint| u16_at(d, i) as i16 as Scalar / 16384.0;
+                let (a, b, c, e) = if flags & 8 != 0 {
+                    pos += 2;
+                    let s = f2dot14(pos - 2);
+                    (s, 0.0, 0.0, s)
+                } else if flags & 0x40 != 0 {
+                    pos += 4;
+                    (f2dot14(pos - 4), 0.0, 0.0, f2dot14(pos - 2))
+                } else if flags & 0x80 != 0 {
+                    pos += 8;
+                    (f2dot14(pos - 8), f2dot14(pos - 6), f2dot14(pos - 4), f2dot14(pos - 2))
+                } else {
+                    (1.0, 0.0, 0.0, 1.0)
+                };
+                let component_m = multiply(m, [a, c, dx, b, e, dy]);
+                self.glyph_contours(component, component_m, depth + 1, contours);
+                if flags & 0x20 == 0 { break; }
+            }
+        }
+    }
+}
+
+/// Returns the point between two points.
+#[inline(always)]
+fn mid(a: Vec2d, b: Vec2d) -> Vec2d {
+    [0.5 * (a[0] + b[0]), 0.5 * (a[1] + b[1])]
+}
+
+/// Reads a byte, or 0 outside the data.
+#[inline(always)]
+fn u8_at(data: &[u8], i: uint) -> u8 {
+    if i < data.len() { data[i] } else { 0 }
+}
+
+/// Reads a big-endian 16 bit number, or 0 outside the data.
+#[inline(always)]
+fn u16_at(data: &[u8], i: uint) -> u16 {
+    (u8_at(data, i) as u16 << 8) | u8_at(data, i + 1) as u16
+}
+
+/// Reads a big-endian 32 bit number, or 0 outside the data.
+#[inline(always)]
+fn u32_at(data: &[u8], i: uint) -> u32 {
+    (u16_at(data, i) as u32 << 16) | u16_at(data, i + 2) as u32
+}
+
+/// Finds the offset and length of a table.
+fn find_table(data: &[u8], start: uint, tag: &str) -> Option<(uint, uint)> {
+    let num_tables = u16_at(data, start + 4) as uint;
+    for i in range(0, num_tables) {
+        let record = start + 12 + i * 16;
+        if record + 16 > data.len() { break; }
+        if data.slice(record, record + 4) != tag.as_bytes() { continue; }
+        let offset = u32_at(data, record + 8) as uint;
+        let length = u32_at(data, record + 12) as uint;
+        return if offset + length <= data.len() { Some((offset, length)) } else { None };
+    }
+    None
+}
+
+/// Finds a Unicode character map with a supported format.
+///
+/// Returns the offset and format of the subtable.
+fn find_cmap(data: &[u8], cmap: uint) -> Option<(uint, u16)> {
+    let mut best: Option<(uint, u16)> = None;
+    for i in range(0, u16_at(data, cmap + 2) as uint) {
+        let record = cmap + 4 + i * 8;
+        let platform = u16_at(data, record);
+        let encoding = u16_at(data, record + 2);
+        let unicode = platform == 0 || (platform == 3 && (encoding == 1 || encoding == 10));
+        if !unicode { continue; }
+        let subtable = cmap + u32_at(data, record + 4) as uint;
+        match u16_at(data, subtable) {
+            // Prefer the full Unicode range.
+            12 => return Some((subtable, 12)),
+            4 => best = Some((subtable, 4)),
+            _ => {},
+        }
+    }
+    best
+}
+
+/// Reads the horizontal kerning pairs sorted by glyphs.
+fn read_kerning(data: &[u8], kern: uint) -> Vec<(u32, i16)> {
+    let mut pairs = Vec::new();
+    if u16_at(data, kern) != 0 { return pairs; }
+    let mut pos = kern + 4;
+    for _ in range(0, u16_at(data, kern + 2)) {
+        let length = u16_at(data, pos + 2) as uint;
+        let coverage = u16_at(data, pos + 4);
+        // Format 0 with horizontal kerning.
+        if coverage >> 8 == 0 && coverage & 0x7 == 1 {
+            for j in range(0, u16_at(data, pos + 6) as uint) {
+                let pair = pos + 14 + j * 6;
+                pairs.push((u32_at(data, pair), u16_at(data, pair + 4) as i16));
+            }
+        }
+        if length == 0 { break; }
+        pos += length;
+    }
+    pairs.sort_by(|&(a, _), &(b, _)| a.cmp(&b));
+    pairs
+}
+
+/// Builds a small font for testing.
+///
+/// The font has 1000 units per em, an ascent of 800 and a descent of 200.
+/// It contains a square 'A', a triangle 'V' with a curved side and a space.
+/// The pair 'A' 'V' is kerned by -100.
+#[cfg(test)]
+pub fn test_font_data() -> Vec<u8> {
+    fn push_u16(v: &mut Vec<u8>, x: u16) {
+        v.push((x >> 8) as u8);
+        v.push(x as u8);
+    }
+    fn push_u32(v: &mut Vec<u8>, x: u32) {
+        push_u16(v, (x >> 16) as u16);
+        push_u16(v, x as u16);
+    }
+    // Writes a simple glyph from on-curve flags and absolute coordinates.
+    fn push_glyph(v: &mut Vec<u8>, points: &[(i16, i16, bool)]) {
+        push_u16(v, 1);
+        for _ in range(0u, 4) { push_u16(v, 0); }
+        push_u16(v, points.len() as u16 - 1);
+        push_u16(v, 0);
+        for &(_, _, on) in points.iter() { v.push(if on { 1 } else { 0 }); }
+        let mut last = 0;
+        for &(x, _, _) in points.iter() {
+            push_u16(v, (x - last) as u16);
+            last = x;
+        }
+        last = 0;
+        for &(_, y, _) in points.iter() {
+            push_u16(v, (y - last) as u16);
+            last = y;
+        }
+    }
+
+    let mut head = Vec::from_elem(54, 0u8);
+    *head.get_mut(18) = (1000u16 >> 8) as u8;
+    *head.get_mut(19) = 1000u16 as u8;
+    // Long offsets in 'loca'.
+    *head.get_mut(51) = 1;
+    let mut maxp = Vec::new();
+    push_u32(&mut maxp, 0x00005000);
+    push_u16(&mut maxp, 4);
+    let mut hhea = Vec::from_elem(36, 0u8);
+    *hhea.get_mut(4) = (800u16 >> 8) as u8;
+    *hhea.get_mut(5) = 800u16 as u8;
+    *hhea.get_mut(6) = (-200i16 as u16 >> 8) as u8;
+    *hhea.get_mut(7) = -200i16 as u16 as u8;
+    *hhea.get_mut(35) = 4;
+    let mut hmtx = Vec::new();
+    for &advance in [500u16, 1000, 1000, 250].iter() {
+        push_u16(&mut hmtx, advance);
+        push_u16(&mut hmtx, 0);
+    }
+    let mut glyf = Vec::new();
+    let mut loca = Vec::new();
+    push_u32(&mut loca, 0);
+    push_u32(&mut loca, 0);
+    push_glyph(&mut glyf, [(100, 0, true), (900, 0, true), (900, 800, true), (100, 800, true)]);
+    push_u32(&mut loca, glyf.len() as u32);
+    push_glyph(&mut glyf, [(100, 800, true), (500, -400, false), (900, 800, true)]);
+    push_u32(&mut loca, glyf.len() as u32);
+    push_u32(&mut loca, glyf.len() as u32);
+    let mut cmap = Vec::new();
+    push_u16(&mut cmap, 0);
+    push_u16(&mut cmap, 1);
+    push_u16(&mut cmap, 3);
+    push_u16(&mut cmap, 1);
+    push_u32(&mut cmap, 12);
+    // Format 4 with segments for ' ', 'A', 'V' and the end marker.
+    let segments = [(32u16, 3u16), (65, 1), (86, 2), (0xFFFF, 0)];
+    push_u16(&mut cmap, 4);
+    push_u16(&mut cmap, 16 + 8 * 4);
+    push_u16(&mut cmap, 0);
+    push_u16(&mut cmap, 8);
+    for _ in range(0u, 3) { push_u16(&mut cmap, 0); }
+    for &(code, _) in segments.iter() { push_u16(&mut cmap, code); }
+    push_u16(&mut cmap, 0);
+    for &(code, _) in segments.iter() { push_u16(&mut cmap, code); }
+    for &(code, glyph) in segments.iter() { push_u16(&mut cmap, glyph - code); }
+    for _ in segments.iter() { push_u16(&mut cmap, 0); }
+    let mut kern = Vec::new();
+    push_u16(&mut kern, 0);
+    push_u16(&mut kern, 1);
+    push_u16(&mut kern, 0);
+    push_u16(&mut kern, 20);
+    push_u16(&mut kern, 1);
+    push_u16(&mut kern, 1);
+    for _ in range(0u, 3) { push_u16(&mut kern, 0); }
+    push_u16(&mut kern, 1);
+    push_u16(&mut kern, 2);
+    push_u16(&mut kern, -100i16 as u16);
+
+    let tables = [
+        ("cmap", cmap), ("glyf", glyf), ("head", head), ("hhea", hhea),
+        ("hmtx", hmtx), ("kern", kern), ("loca", loca), ("maxp", maxp)
+    ];
+    let mut data = Vec::new();
+    push_u32(&mut data, 0x00010000);
+    push_u16(&mut data, tables.len() as u16);
+    for _ in range(0u, 3) { push_u16(&mut data, 0); }
+    let mut offset = 12 + 16 * tables.len();
+    for &(tag, ref table) in tables.iter() {
+        data.push_all(tag.as_bytes());
+        push_u32(&mut data, 0);
+        push_u32(&mut data, offset as u32);
+        push_u32(&mut data, table.len() as u32);
+        offset += table.len();
+    }
+    for &(_, ref table) in tables.iter() {
+        data.push_all(table.as_slice());
+    }
+    data
+}
+
+#[test]
+fn test_font_metrics() {
+    let font = Font::from_bytes(test_font_data()).unwrap();
+    assert_eq!(font.num_glyphs(), 4);
+    assert_eq!(font.glyph_index('A'), 1);
+    assert_eq!(font.glyph_index('V'), 2);
+    assert_eq!(font.glyph_index(' '), 3);
+    assert_eq!(font.glyph_index('B'), 0);
+    assert_eq!(font.ascent(10.0), 8.0);
+    assert_eq!(font.descent(10.0), 2.0);
+    assert_eq!(font.line_height(10.0), 10.0);
+    assert_eq!(font.advance_width(3, 10.0), 2.5);
+    assert_eq!(font.kerning(1, 2, 10.0), -1.0);
+    assert_eq!(font.kerning(2, 1, 10.0), 0.0);
+    match Font::from_bytes(vec![0u8, 1, 0, 0]) {
+        Err(FontMissingTable(_)) => {},
+        _ => fail!("Expected missing table"),
+    }
+}
+
+#[test]
+fn test_glyph_path() {
+    let font = Font::from_bytes(test_font_data()).unwrap();
+    let mut polylines = Vec::new();
+    font.glyph_path(1, 10.0).flatten(0.01, |polyline, closed| {
+        assert!(closed);
+        polylines.push(Vec::from_slice(polyline));
+    });
+    assert_eq!(polylines.len(), 1);
+    // The square is flipped to have the y axis pointing down.
+    assert_eq!(polylines.get(0).as_slice(), &[1.0, 0.0, 9.0, 0.0, 9.0, -8.0, 1.0, -8.0]);
+    font.glyph_path(2, 10.0).flatten(0.01, |polyline, _| {
+        // The curve bends down to 2 pixels above the baseline.
+        assert!(polyline.len() > 8);
+        assert!(polyline.chunks(2).all(|p| p[1] <= -2.0 + 0.01));
+        assert!(polyline.chunks(2).any(|p| p[1] > -2.1));
+    });
+}
+
+#[test]
+fn test_layout() {
+    use {AlignLeft, AlignRight};
+
+    let font = Font::from_bytes(test_font_data()).unwrap();
+    // 'A' and 'V' are 10 wide, kerned by -1, and space is 2.5 wide.
+    assert_eq!(font.text_width(10.0, "AV"), 19.0);
+    let glyphs = font.layout(10.0, "AV A\nV", None, AlignLeft);
+    let positions: Vec<(u16, Scalar, Scalar)> = glyphs.iter()
+        .map(|g| (g.glyph, g.x, g.y)).collect();
+    assert_eq!(positions, vec![
+        (1, 0.0, 8.0), (2, 9.0, 8.0), (3, 19.0, 8.0), (1, 21.5, 8.0),
+        (2, 0.0, 18.0)
+    ]);
+    // Words move to the next line when they do not fit.
+    let glyphs = font.layout(10.0, "A A V", Some(25.0), AlignRight);
+    let positions: Vec<(u16, Scalar, Scalar)> = glyphs.iter()
+        .map(|g| (g.glyph, g.x, g.y)).collect();
+    assert_eq!(positions, vec![
+        (1, 2.5, 8.0), (3, 12.5, 8.0), (1, 15.0, 8.0),
+        (2, 15.0, 18.0)
+    ]);
+}
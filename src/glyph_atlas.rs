@@ -0,0 +1,333 @@
+//! Rasterizes glyphs into a texture.
+
+use std::collections::HashMap;
+use std::cmp::{max, min};
+use std::f64::{INFINITY, NEG_INFINITY};
+use {Font};
+use internal::{
+    PixelRectangle,
+    Scalar,
+};
+
+/// The maximum width and height of a glyph atlas in pixels.
+static MAX_ATLAS_SIZE: u32 = 4096;
+
+/// The maximum distance in pixels between the glyph outlines and the lines.
+static GLYPH_TOLERANCE: Scalar = 0.1;
+
+/// The location of a glyph in an atlas.
+pub struct AtlasGlyph {
+    /// The pixels of the glyph in the atlas.
+    ///
+    /// Glyphs without outline, such as space, have zero size.
+    pub source_rect: PixelRectangle,
+    /// The offset from the pen position to the left side of the pixels.
+    pub left: Scalar,
+    /// The offset from the baseline to the top of the pixels, with y axis pointing down.
+    pub top: Scalar,
+}
+
+impl Clone for AtlasGlyph {
+    #[inline(always)]
+    fn clone(&self) -> AtlasGlyph {
+        AtlasGlyph {
+            source_rect: self.source_rect,
+            left: self.left,
+            top: self.top,
+        }
+    }
+}
+
+/// Glyphs rasterized into RGBA pixels, ready to be uploaded as a texture.
+///
+/// The glyphs are white, with the coverage stored in the alpha channel.
+/// They are packed in rows and the atlas grows when it is full.
+/// When it can not grow any more, all glyphs are removed to make room.
+pub struct GlyphAtlas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+    glyphs: HashMap<(u16, u32), AtlasGlyph>,
+    x: u32,
+    y: u32,
+    row_height: u32,
+    dirty: bool,
+    generation: uint,
+}
+
+impl GlyphAtlas {
+    /// Creates a new empty atlas.
+    pub fn new(width: u32, height: u32) -> GlyphAtlas {
+        GlyphAtlas {
+            width: width,
+            height: height,
+            pixels: Vec::from_elem((width * height * 4) as uint, 0u8),
+            glyphs: HashMap::new(),
+            x: 0,
+            y: 0,
+            row_height: 0,
+            dirty: false,
+            generation: 0,
+        }
+    }
+
+    /// Returns the width of the atlas in pixels.
+    #[inline(always)]
+    pub fn width(&self) -> u32 { self.width }
+
+    /// Returns the height of the atlas in pixels.
+    #[inline(always)]
+    pub fn height(&self) -> u32 { self.height }
+
+    /// Returns the RGBA pixels stored row by row starting at the top.
+    #[inline(always)]
+    pub fn pixels<'a>(&'a self) -> &'a [u8] {
+        self.pixels.as_slice()
+    }
+
+    /// Returns true if glyphs were added since `mark_clean` was called.
+    #[inline(always)]
+    pub fn is_dirty(&self) -> bool { self.dirty }
+
+    /// Marks the atlas as uploaded.
+    #[inline(always)]
+    pub fn mark_clean(&mut self) { self.dirty = false; }
+
+    /// Returns the number of times the glyphs were removed to make room.
+    ///
+    /// Glyphs returned before the number changed are no longer in the atlas.
+    #[inline(always)]
+    pub fn generation(&self) -> uint { self.generation }
+
+    /// Returns a glyph, rasterizing it first if it is not in the atlas.
+    ///
+    /// The size is the height of the em square in pixels.
+    /// Returns `None` if the glyph is larger than the largest atlas.
+    pub fn glyph(&mut self, font: &Font, glyph: u16, size: Scalar) -> Option<AtlasGlyph> {
+        // Sizes are told apart down to 1/64 pixel.
+        let key = (glyph, (size * 64.0).round() as u32);
+        match self.glyphs.find(&key) {
+            Some(atlas_glyph) => return Some(atlas_glyph.clone()),
+            None => {},
+        }
+
+        let mut polylines: Vec<Vec<Scalar>> = Vec::new();
+        let (mut min_x, mut min_y) = (INFINITY, INFINITY);
+        let (mut max_x, mut max_y) = (NEG_INFINITY, NEG_INFINITY);
+        font.glyph_path(glyph, size).flatten(GLYPH_TOLERANCE, |polyline, _| {
+            for p in polyline.chunks(2) {
+                min_x = min_x.min(p[0]);
+                min_y = min_y.min(p[1]);
+                max_x = max_x.max(p[0]);
+                max_y = max_y.max(p[1]);
+            }
+            polylines.push(Vec::from_slice(polyline));
+        });
+        let atlas_glyph = if polylines.len() == 0 {
+            AtlasGlyph { source_rect: [0, 0, 0, 0], left: 0.0, top: 0.0 }
+        } else {
+            let (left, top) = (min_x.floor(), min_y.floor());
+            let w = (max_x.ceil() - left) as u32;
+            let h = (max_y.ceil() - top) as u32;
+            let (x, y) = match self.allocate(w, h) {
+                Some(pos) => pos,
+                None => {
+                    // Start over with an empty atlas.
+                    self.reset();
+                    match self.allocate(w, h) {
+                        Some(pos) => pos,
+                        None => return None,
+                    }
+                },
+            };
+            let coverage = rasterize(polylines.as_slice(), left, top, w, h);
+            for row in range(0, h) {
+                for col in range(0, w) {
+                    let i = (((y + row) * self.width + x + col) * 4) as uint;
+                    let a = *coverage.get((row * w + col) as uint);
+                    let p = self.pixels.mut_slice(i, i + 4);
+                    p[0] = 255;
+                    p[1] = 255;
+                    p[2] = 255;
+                    p[3] = (a.min(1.0) * 255.0).round() as u8;
+                }
+            }
+            self.dirty = true;
+            AtlasGlyph { source_rect: [x, y, w, h], left: left, top: top }
+        };
+        self.glyphs.insert(key, atlas_glyph.clone());
+        Some(atlas_glyph)
+    }
+
+    /// Finds room for a glyph, growing the atlas if needed.
+    ///
+    /// Leaves one pixel between glyphs so filtering does not bleed.
+    /// Returns `None` if the atlas would become larger than `MAX_ATLAS_SIZE`.
+    fn allocate(&mut self, w: u32, h: u32) -> Option<(u32, u32)> {
+        let (mut x, mut y) = (self.x, self.y);
+        if x + w > self.width {
+            x = 0;
+            y += self.row_height + 1;
+        }
+        let mut width = self.width;
+        while w > width { width *= 2; }
+        let mut height = self.height;
+        while y + h > height { height *= 2; }
+        if width > MAX_ATLAS_SIZE || height > MAX_ATLAS_SIZE { return None; }
+        if width != self.width || height != self.height {
+            self.grow(width, height);
+        }
+        if y != self.y { self.row_height = 0; }
+        self.x = x + w + 1;
+        self.y = y;
+        self.row_height = max(self.row_height, h);
+        Some((x, y))
+    }
+
+    /// Removes all glyphs, keeping the size of the atlas.
+    fn reset(&mut self) {
+        for p in self.pixels.mut_iter() { *p = 0; }
+        self.glyphs.clear();
+        self.x = 0;
+        self.y = 0;
+        self.row_height = 0;
+        self.dirty = true;
+        self.generation += 1;
+    }
+
+    /// Resizes the atlas, keeping the glyphs where they are.
+    fn grow(&mut self, width: u32, height: u32) {
+        let mut pixels = Vec::from_elem((width * height * 4) as uint, 0u8);
+        let row = (self.width * 4) as uint;
+        for y in range(0, self.height as uint) {
+            let i = y * (width * 4) as uint;
+            pixels.mut_slice(i, i + row).copy_from(self.pixels.slice(y * row, y * row + row));
+        }
+        self.pixels = pixels;
+        self.width = width;
+        self.height = height;
+    }
+}
+
+/// Computes the coverage of pixels inside closed polylines.
+///
+/// Accumulates the signed area each line covers in each pixel,
+/// which gives exact coverage for non-overlapping outlines.
+fn rasterize(polylines: &[Vec<Scalar>], left: Scalar, top: Scalar, w: u32, h: u32) -> Vec<Scalar> {
+    let (w, h) = (w as uint, h as uint);
+    // Lines ending at the right side spill into one extra pixel.
+    let mut acc = Vec::from_elem(w * h + 2, 0.0f64);
+    for polyline in polylines.iter() {
+        let n = polyline.len() / 2;
+        for i in range(0, n) {
+            let j = (i + 1) % n;
+            let p0 = [*polyline.get(i * 2) - left, *polyline.get(i * 2 + 1) - top];
+            let p1 = [*polyline.get(j * 2) - left, *polyline.get(j * 2 + 1) - top];
+            accumulate_line(&mut acc, w, h, p0, p1);
+        }
+    }
+    let mut sum = 0.0;
+    range(0, w * h).map(|i| {
+        sum += *acc.get(i);
+        sum.abs()
+    }).collect()
+}
+
+/// Adds the signed area covered by a line to the pixels it crosses.
+fn accumulate_line(acc: &mut Vec<Scalar>, w: uint, h: uint, p0: [Scalar, ..2], p1: [Scalar, ..2]) {
+    if p0[1] == p1[1] { return; }
+    let (dir, p0, p1) = if p0[1] < p1[1] { (1.0, p0, p1) } else { (-1.0, p1, p0) };
+    let dxdy = (p1[0] - p0[0]) / (p1[1] - p0[1]);
+    let mut x = p0[0];
+    let y0 = p0[1].max(0.0);
+    if p0[1] < 0.0 { x -= p0[1] * dxdy; }
+    let y_end = min(p1[1].ceil() as uint, h);
+    for y in range(y0 as uint, y_end) {
+        let row = y * w;
+        let dy = ((y + 1) as Scalar).min(p1[1]) - (y as Scalar).max(p0[1]);
+        let x_next = x + dxdy * dy;
+        let d = dy * dir;
+        let (x0, x1) = if x < x_next { (x, x_next) } else { (x_next, x) };
+        let x0_floor = x0.floor();
+        let x0i = x0_floor.max(0.0) as uint;
+        let x1_ceil = x1.ceil();
+        let x1i = x1_ceil.max(0.0) as uint;
+        if x1i <= x0i + 1 {
+            let xmf = 0.5 * (x + x_next) - x0_floor;
+            *acc.get_mut(row + x0i) += d - d * xmf;
+            *acc.get_mut(row + x0i + 1) += d * xmf;
+        } else {
+            let s = 1.0 / (x1 - x0);
+            let x0f = x0 - x0_floor;
+            let a0 = 0.5 * s * (1.0 - x0f) * (1.0 - x0f);
+            let x1f = x1 - x1_ceil + 1.0;
+            let am = 0.5 * s * x1f * x1f;
+            *acc.get_mut(row + x0i) += d * a0;
+            if x1i == x0i + 2 {
+                *acc.get_mut(row + x0i + 1) += d * (1.0 - a0 - am);
+            } else {
+                let a1 = s * (1.5 - x0f);
+                *acc.get_mut(row + x0i + 1) += d * (a1 - a0);
+                for xi in range(x0i + 2, x1i - 1) {
+                    *acc.get_mut(row + xi) += d * s;
+                }
+                let a2 = a1 + (x1i - x0i - 3) as Scalar * s;
+                *acc.get_mut(row + x1i - 1) += d * (1.0 - a2 - am);
+            }
+            *acc.get_mut(row + x1i) += d * am;
+        }
+        x = x_next;
+    }
+}
+
+#[test]
+fn test_glyph_atlas() {
+    use font::{test_font_data};
+
+    let font = Font::from_bytes(test_font_data()).unwrap();
+    let mut atlas = GlyphAtlas::new(16, 16);
+    let a = atlas.glyph(&font, 1, 10.0).unwrap();
+    assert!(a.source_rect == [0, 0, 8, 8]);
+    assert_eq!(a.left, 1.0);
+    assert_eq!(a.top, -8.0);
+    assert!(atlas.is_dirty());
+    // The square covers every pixel fully.
+    for row in range(0u, 8) {
+        for col in range(0u, 8) {
+            assert_eq!(atlas.pixels()[(row * 16 + col) * 4 + 3], 255);
+        }
+        assert_eq!(atlas.pixels()[(row * 16 + 8) * 4 + 3], 0);
+    }
+    atlas.mark_clean();
+    let again = atlas.glyph(&font, 1, 10.0).unwrap();
+    assert!(again.source_rect == a.source_rect);
+    assert!(!atlas.is_dirty());
+    // A larger glyph grows the atlas.
+    let big = atlas.glyph(&font, 1, 20.0).unwrap();
+    assert!(big.source_rect == [0, 9, 16, 16]);
+    assert_eq!(atlas.height(), 32);
+    assert_eq!(atlas.pixels()[(7 * 16 + 7) * 4 + 3], 255);
+    assert_eq!(atlas.pixels()[(24 * 16 + 15) * 4 + 3], 255);
+    let space = atlas.glyph(&font, 3, 10.0).unwrap();
+    assert_eq!(space.source_rect[2], 0);
+}
+
+#[test]
+fn test_glyph_atlas_full() {
+    use font::{test_font_data};
+
+    let font = Font::from_bytes(test_font_data()).unwrap();
+    let mut atlas = GlyphAtlas::new(16, 16);
+    // Glyphs of many sizes fill the atlas until it starts over.
+    let mut size = 2000.0;
+    while atlas.generation() == 0 {
+        assert!(atlas.glyph(&font, 1, size).is_some());
+        size += 1.0;
+    }
+    assert_eq!(atlas.height(), MAX_ATLAS_SIZE);
+    // The glyph that did not fit is the only one left.
+    let glyph = atlas.glyph(&font, 1, size - 1.0).unwrap();
+    assert!(glyph.source_rect[0] == 0 && glyph.source_rect[1] == 0);
+    // A glyph larger than the largest atlas is refused.
+    assert!(atlas.glyph(&font, 1, 10000.0).is_none());
+}
@@ -0,0 +1,162 @@
+use interpolation::{lerp_4};
+use internal::{
+    Color,
+    Radius,
+    Scalar,
+    Vec2d,
+};
+
+/// The maximum number of color stops in a gradient.
+pub static MAX_GRADIENT_STOPS: uint = 8;
+
+/// A color at an offset along a gradient.
+///
+/// The offset goes from 0 at the start to 1 at the end of the gradient.
+pub type GradientStop = (Scalar, Color);
+
+/// The shape of the lines with equal color in a gradient.
+pub enum GradientKind {
+    /// Changes color along the line from the first to the second point.
+    LinearGradient(Vec2d, Vec2d),
+    /// Changes color from the center out to the radius.
+    RadialGradient(Vec2d, Radius),
+}
+
+/// A smooth transition between colors.
+///
+/// The coordinates are in the same space as the shape it fills.
+/// Outside the start and end of the gradient,
+/// the color of the first and last stop is used.
+pub struct Gradient {
+    /// The shape of the gradient.
+    pub kind: GradientKind,
+    stops: [GradientStop, ..MAX_GRADIENT_STOPS],
+    count: uint,
+}
+
+impl Gradient {
+    /// Creates a gradient from color stops.
+    ///
+    /// The stops are sorted by offset.
+    /// Only the first `MAX_GRADIENT_STOPS` stops are used.
+    /// Without stops the gradient is completely transparent.
+    pub fn new(kind: GradientKind, stops: &[GradientStop]) -> Gradient {
+        let transparent = [(0.0, [0.0, 0.0, 0.0, 0.0])];
+        let stops = match stops.len() {
+            0 => transparent.as_slice(),
+            n if n > MAX_GRADIENT_STOPS => stops.slice_to(MAX_GRADIENT_STOPS),
+            _ => stops,
+        };
+        let mut gradient = Gradient {
+            kind: kind,
+            stops: [(0.0, [0.0, 0.0, 0.0, 0.0]), ..MAX_GRADIENT_STOPS],
+            count: 0,
+        };
+        // Insertion sort keeps stops with equal offset in order,
+        // which gives a sharp edge between them.
+        for &(offset, color) in stops.iter() {
+            let mut i = gradient.count;
+            while i > 0 && gradient.stops[i - 1].val0() > offset {
+                gradient.stops[i] = gradient.stops[i - 1];
+                i -= 1;
+            }
+            gradient.stops[i] = (offset, color);
+            gradient.count += 1;
+        }
+        gradient
+    }
+
+    /// Creates a linear gradient from one point to another.
+    #[inline(always)]
+    pub fn linear(p0: Vec2d, p1: Vec2d, stops: &[GradientStop]) -> Gradient {
+        Gradient::new(LinearGradient(p0, p1), stops)
+    }
+
+    /// Creates a radial gradient from the center out to the radius.
+    #[inline(always)]
+    pub fn radial(center: Vec2d, radius: Radius, stops: &[GradientStop]) -> Gradient {
+        Gradient::new(RadialGradient(center, radius), stops)
+    }
+
+    /// Returns the color stops sorted by offset.
+    #[inline(always)]
+    pub fn stops<'a>(&'a self) -> &'a [GradientStop] {
+        self.stops.slice_to(self.count)
+    }
+
+    /// Returns the offset along the gradient at a point.
+    ///
+    /// The offset is not clamped.
+    pub fn offset_at(&self, p: Vec2d) -> Scalar {
+        match self.kind {
+            LinearGradient(p0, p1) => {
+                let (dx, dy) = (p1[0] - p0[0], p1[1] - p0[1]);
+                let len_sq = dx * dx + dy * dy;
+                if len_sq == 0.0 { return 0.0; }
+                ((p[0] - p0[0]) * dx + (p[1] - p0[1]) * dy) / len_sq
+            },
+            RadialGradient(center, radius) => {
+                if radius == 0.0 { return 1.0; }
+                let (dx, dy) = (p[0] - center[0], p[1] - center[1]);
+                (dx * dx + dy * dy).sqrt() / radius
+            },
+        }
+    }
+
+    /// Returns the color at an offset along the gradient.
+    pub fn color_at(&self, offset: Scalar) -> Color {
+        let stops = self.stops();
+        let (first_offset, first_color) = stops[0];
+        if offset <= first_offset { return first_color; }
+        for i in range(1, stops.len()) {
+            let (a, color_a) = stops[i - 1];
+            let (b, color_b) = stops[i];
+            if offset < b {
+                let t = ((offset - a) / (b - a)) as f32;
+                return lerp_4(&color_a, &color_b, &t);
+            }
+        }
+        stops[stops.len() - 1].val1()
+    }
+
+    /// Returns `true` if any of the stops are not completely opaque.
+    pub fn has_alpha(&self) -> bool {
+        self.stops().iter().any(|&(_, color)| color[3] != 1.0)
+    }
+
+    /// Returns `true` if all stops are completely transparent.
+    pub fn is_invisible(&self) -> bool {
+        self.stops().iter().all(|&(_, color)| color[3] == 0.0)
+    }
+}
+
+#[test]
+fn test_gradient_color() {
+    let white = [1.0, 1.0, 1.0, 1.0];
+    let black = [0.0, 0.0, 0.0, 1.0];
+    let red = [1.0, 0.0, 0.0, 1.0];
+    let gradient = Gradient::linear([0.0, 0.0], [2.0, 0.0], [(1.0, black), (0.0, white), (0.5, red)]);
+    assert_eq!(gradient.offset_at([1.0, 5.0]), 0.5);
+    assert!(gradient.color_at(-1.0) == white);
+    assert!(gradient.color_at(0.25) == [1.0, 0.5, 0.5, 1.0]);
+    assert!(gradient.color_at(0.5) == red);
+    assert!(gradient.color_at(2.0) == black);
+    let gradient = Gradient::radial([1.0, 1.0], 2.0, [(0.0, white), (1.0, black)]);
+    assert_eq!(gradient.offset_at([1.0, 2.0]), 0.5);
+}
+
+#[test]
+fn test_gradient_stop_count() {
+    let white = [1.0, 1.0, 1.0, 1.0];
+    let black = [0.0, 0.0, 0.0, 1.0];
+    // Stops beyond the maximum are ignored.
+    let stops: Vec<GradientStop> = range(0, MAX_GRADIENT_STOPS + 2).map(|i| {
+        (i as Scalar, if i < MAX_GRADIENT_STOPS { white } else { black })
+    }).collect();
+    let gradient = Gradient::linear([0.0, 0.0], [1.0, 0.0], stops.as_slice());
+    assert_eq!(gradient.stops().len(), MAX_GRADIENT_STOPS);
+    assert!(gradient.color_at(100.0) == white);
+    // Without stops nothing is drawn.
+    let gradient = Gradient::linear([0.0, 0.0], [1.0, 0.0], []);
+    assert!(gradient.is_invisible());
+}
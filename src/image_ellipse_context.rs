@@ -0,0 +1,307 @@
+use {
+    AddColor,
+    AddResolution,
+    BackEnd,
+    Borrowed,
+    ClipBackEnd,
+    Draw,
+    FallbackBackEnd,
+    Field,
+    Image,
+    ImageEllipseColorContext,
+    RenderResult,
+    Resolution,
+    Value,
+};
+use triangulation::{
+    ellipse_resolution,
+    with_ellipse_textured_tri_list_xy_f32_rgba_f32_uv_f32,
+};
+use internal::{
+    CanColor,
+    CanRectangle,
+    CanSourceRectangle,
+    CanTransform,
+    CanUvTransform,
+    CanViewTransform,
+    Color,
+    ColorComponent,
+    HasColor,
+    HasRectangle,
+    HasSourceRectangle,
+    HasTransform,
+    HasUvTransform,
+    HasViewTransform,
+    Matrix2d,
+    PixelRectangle,
+    Rectangle,
+};
+
+/// A textured ellipse context.
+///
+/// The texture coordinates are projected from the bounding box of the shape.
+pub struct ImageEllipseContext<'a> {
+    /// Base/origin transform.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transform.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current rectangle enclosing the ellipse.
+    pub rect: Field<'a, Rectangle>,
+    /// Current resolution of the curves.
+    pub resolution: Field<'a, Resolution>,
+    /// Current image.
+    pub image: Field<'a, Image>,
+    /// Current texture coordinate transformation.
+    pub uv_transform: Field<'a, Matrix2d>,
+}
+
+impl<'a> Clone for ImageEllipseContext<'a> {
+    #[inline(always)]
+    fn clone(&self) -> ImageEllipseContext<'static> {
+        ImageEllipseContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            rect: Value(*self.rect.get()),
+            resolution: Value(*self.resolution.get()),
+            image: Value(*self.image.get()),
+            uv_transform: Value(*self.uv_transform.get()),
+        }
+    }
+}
+
+impl<'a> HasTransform<'a, Matrix2d> for ImageEllipseContext<'a> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a> CanTransform<'a, ImageEllipseContext<'a>, Matrix2d> for ImageEllipseContext<'a> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> ImageEllipseContext<'a> {
+        ImageEllipseContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            resolution: Borrowed(self.resolution.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+        }
+    }
+}
+
+impl<'a> HasViewTransform<'a, Matrix2d> for ImageEllipseContext<'a> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a> CanViewTransform<'a, ImageEllipseContext<'a>, Matrix2d> for ImageEllipseContext<'a> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> ImageEllipseContext<'a> {
+        ImageEllipseContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            resolution: Borrowed(self.resolution.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+        }
+    }
+}
+
+static WHITE: &'static Color = &[1.0, ..4];
+
+impl<'a> HasColor<'a, Color> for ImageEllipseContext<'a> {
+    #[inline(always)]
+    fn get_color(&'a self) -> &'a Color {
+        WHITE
+    }
+}
+
+impl<'a> CanColor<'a, ImageEllipseColorContext<'a>, Color> for ImageEllipseContext<'a> {
+    #[inline(always)]
+    fn color(&'a self, value: Color) -> ImageEllipseColorContext<'a> {
+        ImageEllipseColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            resolution: Borrowed(self.resolution.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            color: Value(value),
+        }
+    }
+}
+
+impl<'a> AddColor<'a, ImageEllipseColorContext<'a>> for ImageEllipseContext<'a> {
+    #[inline(always)]
+    fn rgba(
+        &'a self,
+        r: ColorComponent,
+        g: ColorComponent,
+        b: ColorComponent,
+        a: ColorComponent
+    ) -> ImageEllipseColorContext<'a> {
+        ImageEllipseColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            resolution: Borrowed(self.resolution.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            color: Value([r, g, b, a]),
+        }
+    }
+}
+
+impl<'a> HasRectangle<'a, Rectangle> for ImageEllipseContext<'a> {
+    #[inline(always)]
+    fn get_rectangle(&'a self) -> &'a Rectangle {
+        self.rect.get()
+    }
+}
+
+impl<'a> CanRectangle<'a, ImageEllipseContext<'a>, Rectangle> for ImageEllipseContext<'a> {
+    #[inline(always)]
+    fn rectangle(&'a self, rect: Rectangle) -> ImageEllipseContext<'a> {
+        ImageEllipseContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Value(rect),
+            resolution: Borrowed(self.resolution.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+        }
+    }
+}
+
+impl<'a> AddResolution<'a, ImageEllipseContext<'a>> for ImageEllipseContext<'a> {
+    #[inline(always)]
+    fn resolution(&'a self, resolution: Resolution) -> ImageEllipseContext<'a> {
+        ImageEllipseContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            resolution: Value(resolution),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+        }
+    }
+}
+
+impl<'a> HasSourceRectangle<'a, PixelRectangle> for ImageEllipseContext<'a> {
+    #[inline(always)]
+    fn get_source_rectangle(&'a self) -> &'a PixelRectangle {
+        &self.image.get().source_rect
+    }
+}
+
+impl<'a> CanSourceRectangle<'a, ImageEllipseContext<'a>, PixelRectangle> for ImageEllipseContext<'a> {
+    #[inline(always)]
+    fn source_rectangle(&'a self, source_rect: PixelRectangle) -> ImageEllipseContext<'a> {
+        let mut image = *self.image.get();
+        image.source_rect = source_rect;
+        ImageEllipseContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            resolution: Borrowed(self.resolution.get()),
+            image: Value(image),
+            uv_transform: Borrowed(self.uv_transform.get()),
+        }
+    }
+}
+
+impl<'a> HasUvTransform<'a, Matrix2d> for ImageEllipseContext<'a> {
+    #[inline(always)]
+    fn get_uv_transform(&'a self) -> &'a Matrix2d {
+        self.uv_transform.get()
+    }
+}
+
+impl<'a> CanUvTransform<'a, ImageEllipseContext<'a>, Matrix2d> for ImageEllipseContext<'a> {
+    #[inline(always)]
+    fn uv_transform(&'a self, value: Matrix2d) -> ImageEllipseContext<'a> {
+        ImageEllipseContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            resolution: Borrowed(self.resolution.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Value(value),
+        }
+    }
+}
+
+impl<'a> Draw<'a> for ImageEllipseContext<'a> {
+    #[inline(always)]
+    fn draw<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_texture_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let color: [f32, ..4] = [1.0, 1.0, 1.0, 1.0];
+                let texture_id = self.image.get().texture_id;
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                let rect = *self.rect.get();
+                let resolution = ellipse_resolution(*self.transform.get(), rect, *self.resolution.get());
+                // Turn on alpha blending if not completely opaque or if the texture has alpha channel.
+                let needs_alpha = color[3] != 1.0 || back_end.has_texture_alpha(texture_id);
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                back_end.enable_single_texture(texture_id);
+                with_ellipse_textured_tri_list_xy_f32_rgba_f32_uv_f32(
+                    resolution,
+                    *self.transform.get(),
+                    rect,
+                    self.image.get(),
+                    *self.uv_transform.get(),
+                    color,
+                    |vertices, colors, texture_coords| {
+                        back_end.tri_list_xy_f32_rgba_f32_uv_f32(vertices, colors, texture_coords)
+                    }
+                );
+                back_end.disable_single_texture();
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+#[test]
+fn test_textured_circle() {
+    use {AddEllipse, AddImage, Context, RelativeImage, RelativeTransform2d};
+    use {SoftwareBackEnd};
+
+    // A 2x1 texture, red on the left and green on the right.
+    let mut back_end = SoftwareBackEnd::new(8, 8);
+    let texture_id = back_end.add_texture(2, 1, [255, 0, 0, 255, 0, 255, 0, 255]);
+    let image = Image {
+        texture_id: texture_id,
+        texture_width: 2,
+        texture_height: 1,
+        source_rect: [0, 0, 2, 1],
+    };
+    let c = Context::new();
+    let c = c.scale(2.0 / 8.0, -2.0 / 8.0);
+    let d = c.trans(-1.0, 1.0);
+    d.ellipse(0.0, 0.0, 8.0, 8.0).image(image).draw(&mut back_end).unwrap();
+    assert!(back_end.pixel(1, 4) == [255, 0, 0, 255]);
+    assert!(back_end.pixel(6, 4) == [0, 255, 0, 255]);
+    // The corners are outside the circle.
+    assert_eq!(back_end.pixel(0, 0)[3], 0);
+
+    d.ellipse(0.0, 0.0, 8.0, 8.0).image(image).flip_u().draw(&mut back_end).unwrap();
+    assert!(back_end.pixel(1, 4) == [0, 255, 0, 255]);
+}
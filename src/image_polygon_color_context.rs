@@ -0,0 +1,241 @@
+use {
+    BackEnd,
+    Borrowed,
+    Clear,
+    ClipBackEnd,
+    Draw,
+    FallbackBackEnd,
+    Field,
+    Image,
+    RenderResult,
+    Value,
+};
+use triangulation::{
+    with_polygon_textured_tri_list_xy_f32_rgba_f32_uv_f32,
+};
+use internal::{
+    CanColor,
+    CanSourceRectangle,
+    CanTransform,
+    CanUvTransform,
+    CanViewTransform,
+    Color,
+    HasColor,
+    HasSourceRectangle,
+    HasTransform,
+    HasUvTransform,
+    HasViewTransform,
+    Matrix2d,
+    PixelRectangle,
+    Polygon,
+    Rectangle,
+};
+
+/// A textured polygon color context.
+///
+/// The texture coordinates are projected from the bounding box of the shape.
+pub struct ImagePolygonColorContext<'a, 'b> {
+    /// Base/origin transform.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transform.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current polygon.
+    pub polygon: Field<'a, Polygon<'b>>,
+    /// Current image.
+    pub image: Field<'a, Image>,
+    /// Current texture coordinate transformation.
+    pub uv_transform: Field<'a, Matrix2d>,
+    /// Current color.
+    pub color: Field<'a, Color>,
+}
+
+impl<'a, 'b> Clone for ImagePolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn clone(&self) -> ImagePolygonColorContext<'static, 'b> {
+        ImagePolygonColorContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            polygon: Value(*self.polygon.get()),
+            image: Value(*self.image.get()),
+            uv_transform: Value(*self.uv_transform.get()),
+            color: Value(*self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasTransform<'a, Matrix2d> for ImagePolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a, 'b> CanTransform<'a, ImagePolygonColorContext<'a, 'b>, Matrix2d> for ImagePolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> ImagePolygonColorContext<'a, 'b> {
+        ImagePolygonColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasViewTransform<'a, Matrix2d> for ImagePolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a, 'b> CanViewTransform<'a, ImagePolygonColorContext<'a, 'b>, Matrix2d> for ImagePolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> ImagePolygonColorContext<'a, 'b> {
+        ImagePolygonColorContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasColor<'a, Color> for ImagePolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_color(&'a self) -> &'a Color {
+        self.color.get()
+    }
+}
+
+impl<'a, 'b> CanColor<'a, ImagePolygonColorContext<'a, 'b>, Color> for ImagePolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn color(&'a self, value: Color) -> ImagePolygonColorContext<'a, 'b> {
+        ImagePolygonColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            color: Value(value),
+        }
+    }
+}
+
+impl<'a, 'b> HasSourceRectangle<'a, PixelRectangle> for ImagePolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_source_rectangle(&'a self) -> &'a PixelRectangle {
+        &self.image.get().source_rect
+    }
+}
+
+impl<'a, 'b> CanSourceRectangle<'a, ImagePolygonColorContext<'a, 'b>, PixelRectangle> for ImagePolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn source_rectangle(&'a self, source_rect: PixelRectangle) -> ImagePolygonColorContext<'a, 'b> {
+        let mut image = *self.image.get();
+        image.source_rect = source_rect;
+        ImagePolygonColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            image: Value(image),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasUvTransform<'a, Matrix2d> for ImagePolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_uv_transform(&'a self) -> &'a Matrix2d {
+        self.uv_transform.get()
+    }
+}
+
+impl<'a, 'b> CanUvTransform<'a, ImagePolygonColorContext<'a, 'b>, Matrix2d> for ImagePolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn uv_transform(&'a self, value: Matrix2d) -> ImagePolygonColorContext<'a, 'b> {
+        ImagePolygonColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Value(value),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> Draw<'a> for ImagePolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn draw<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_texture_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let color = self.color.get();
+                let texture_id = self.image.get().texture_id;
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                // Turn on alpha blending if not completely opaque or if the texture has alpha channel.
+                let needs_alpha = color[3] != 1.0 || back_end.has_texture_alpha(texture_id);
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                back_end.enable_single_texture(texture_id);
+                with_polygon_textured_tri_list_xy_f32_rgba_f32_uv_f32(
+                    *self.transform.get(),
+                    *self.polygon.get(),
+                    self.image.get(),
+                    *self.uv_transform.get(),
+                    *color,
+                    |vertices, colors, texture_coords| {
+                        back_end.tri_list_xy_f32_rgba_f32_uv_f32(vertices, colors, texture_coords)
+                    }
+                );
+                back_end.disable_single_texture();
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+impl<'a, 'b> Clear for ImagePolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
+
+#[test]
+fn test_textured_polygon() {
+    use {AddColor, AddImage, AddPolygon, Context, RelativeTransform2d};
+    use {SoftwareBackEnd};
+
+    let mut back_end = SoftwareBackEnd::new(8, 8);
+    let texture_id = back_end.add_texture(1, 2, [255, 255, 255, 255, 0, 0, 0, 255]);
+    let image = Image {
+        texture_id: texture_id,
+        texture_width: 1,
+        texture_height: 2,
+        source_rect: [0, 0, 1, 2],
+    };
+    let c = Context::new();
+    let c = c.scale(2.0 / 8.0, -2.0 / 8.0);
+    let d = c.trans(-1.0, 1.0);
+    // A triangle pointing up, tinted red.
+    let triangle = [4.0, 0.0, 8.0, 8.0, 0.0, 8.0];
+    d.polygon(triangle).rgba(1.0, 0.0, 0.0, 1.0).image(image).draw(&mut back_end).unwrap();
+    // The top half of the bounding box shows white, the bottom half black.
+    assert!(back_end.pixel(4, 2) == [255, 0, 0, 255]);
+    assert!(back_end.pixel(4, 6) == [0, 0, 0, 255]);
+    assert_eq!(back_end.pixel(0, 0)[3], 0);
+}
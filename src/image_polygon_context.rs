@@ -0,0 +1,227 @@
+use {
+    AddColor,
+    BackEnd,
+    Borrowed,
+    ClipBackEnd,
+    Draw,
+    FallbackBackEnd,
+    Field,
+    Image,
+    ImagePolygonColorContext,
+    RenderResult,
+    Value,
+};
+use triangulation::{
+    with_polygon_textured_tri_list_xy_f32_rgba_f32_uv_f32,
+};
+use internal::{
+    CanColor,
+    CanSourceRectangle,
+    CanTransform,
+    CanUvTransform,
+    CanViewTransform,
+    Color,
+    ColorComponent,
+    HasColor,
+    HasSourceRectangle,
+    HasTransform,
+    HasUvTransform,
+    HasViewTransform,
+    Matrix2d,
+    PixelRectangle,
+    Polygon,
+    Rectangle,
+};
+
+/// A textured polygon context.
+///
+/// The texture coordinates are projected from the bounding box of the shape.
+pub struct ImagePolygonContext<'a, 'b> {
+    /// Base/origin transform.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transform.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current polygon.
+    pub polygon: Field<'a, Polygon<'b>>,
+    /// Current image.
+    pub image: Field<'a, Image>,
+    /// Current texture coordinate transformation.
+    pub uv_transform: Field<'a, Matrix2d>,
+}
+
+impl<'a, 'b> Clone for ImagePolygonContext<'a, 'b> {
+    #[inline(always)]
+    fn clone(&self) -> ImagePolygonContext<'static, 'b> {
+        ImagePolygonContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            polygon: Value(*self.polygon.get()),
+            image: Value(*self.image.get()),
+            uv_transform: Value(*self.uv_transform.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasTransform<'a, Matrix2d> for ImagePolygonContext<'a, 'b> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a, 'b> CanTransform<'a, ImagePolygonContext<'a, 'b>, Matrix2d> for ImagePolygonContext<'a, 'b> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> ImagePolygonContext<'a, 'b> {
+        ImagePolygonContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasViewTransform<'a, Matrix2d> for ImagePolygonContext<'a, 'b> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a, 'b> CanViewTransform<'a, ImagePolygonContext<'a, 'b>, Matrix2d> for ImagePolygonContext<'a, 'b> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> ImagePolygonContext<'a, 'b> {
+        ImagePolygonContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+        }
+    }
+}
+
+static WHITE: &'static Color = &[1.0, ..4];
+
+impl<'a, 'b> HasColor<'a, Color> for ImagePolygonContext<'a, 'b> {
+    #[inline(always)]
+    fn get_color(&'a self) -> &'a Color {
+        WHITE
+    }
+}
+
+impl<'a, 'b> CanColor<'a, ImagePolygonColorContext<'a, 'b>, Color> for ImagePolygonContext<'a, 'b> {
+    #[inline(always)]
+    fn color(&'a self, value: Color) -> ImagePolygonColorContext<'a, 'b> {
+        ImagePolygonColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            color: Value(value),
+        }
+    }
+}
+
+impl<'a, 'b> AddColor<'a, ImagePolygonColorContext<'a, 'b>> for ImagePolygonContext<'a, 'b> {
+    #[inline(always)]
+    fn rgba(
+        &'a self,
+        r: ColorComponent,
+        g: ColorComponent,
+        b: ColorComponent,
+        a: ColorComponent
+    ) -> ImagePolygonColorContext<'a, 'b> {
+        ImagePolygonColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            color: Value([r, g, b, a]),
+        }
+    }
+}
+
+impl<'a, 'b> HasSourceRectangle<'a, PixelRectangle> for ImagePolygonContext<'a, 'b> {
+    #[inline(always)]
+    fn get_source_rectangle(&'a self) -> &'a PixelRectangle {
+        &self.image.get().source_rect
+    }
+}
+
+impl<'a, 'b> CanSourceRectangle<'a, ImagePolygonContext<'a, 'b>, PixelRectangle> for ImagePolygonContext<'a, 'b> {
+    #[inline(always)]
+    fn source_rectangle(&'a self, source_rect: PixelRectangle) -> ImagePolygonContext<'a, 'b> {
+        let mut image = *self.image.get();
+        image.source_rect = source_rect;
+        ImagePolygonContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            image: Value(image),
+            uv_transform: Borrowed(self.uv_transform.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasUvTransform<'a, Matrix2d> for ImagePolygonContext<'a, 'b> {
+    #[inline(always)]
+    fn get_uv_transform(&'a self) -> &'a Matrix2d {
+        self.uv_transform.get()
+    }
+}
+
+impl<'a, 'b> CanUvTransform<'a, ImagePolygonContext<'a, 'b>, Matrix2d> for ImagePolygonContext<'a, 'b> {
+    #[inline(always)]
+    fn uv_transform(&'a self, value: Matrix2d) -> ImagePolygonContext<'a, 'b> {
+        ImagePolygonContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Value(value),
+        }
+    }
+}
+
+impl<'a, 'b> Draw<'a> for ImagePolygonContext<'a, 'b> {
+    #[inline(always)]
+    fn draw<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_texture_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let color: [f32, ..4] = [1.0, 1.0, 1.0, 1.0];
+                let texture_id = self.image.get().texture_id;
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                // Turn on alpha blending if not completely opaque or if the texture has alpha channel.
+                let needs_alpha = color[3] != 1.0 || back_end.has_texture_alpha(texture_id);
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                back_end.enable_single_texture(texture_id);
+                with_polygon_textured_tri_list_xy_f32_rgba_f32_uv_f32(
+                    *self.transform.get(),
+                    *self.polygon.get(),
+                    self.image.get(),
+                    *self.uv_transform.get(),
+                    color,
+                    |vertices, colors, texture_coords| {
+                        back_end.tri_list_xy_f32_rgba_f32_uv_f32(vertices, colors, texture_coords)
+                    }
+                );
+                back_end.disable_single_texture();
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
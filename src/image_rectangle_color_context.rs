@@ -1,29 +1,40 @@
 use {
+    AddNineSlice,
+    AddSliceMode,
     BackEnd,
     Borrowed,
     Clear,
+    ClipBackEnd,
     Draw,
+    FallbackBackEnd,
     Field,
     Image,
+    NineSliceColorContext,
+    RenderResult,
+    SliceMode,
     Value,
 };
 use triangulation::{
-    rect_tri_list_xy_f32,
-    rect_tri_list_rgba_f32,
-    rect_tri_list_uv_f32,
+    image_tri_list_xy_f32_uv_f32,
 };
 use internal::{
     CanColor,
     CanRectangle,
+    CanSourceRectangle,
     CanTransform,
+    CanUvTransform,
     CanViewTransform,
     Color,
     HasColor,
     HasRectangle,
+    HasSourceRectangle,
     HasTransform,
+    HasUvTransform,
     HasViewTransform,
     Matrix2d,
+    PixelRectangle,
     Rectangle,
+    Scalar,
 };
 
 /// An image rectangle context.
@@ -32,10 +43,16 @@ pub struct ImageRectangleColorContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transformation.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current rectangle.
     pub rect: Field<'a, Rectangle>,
     /// Current image.
     pub image: Field<'a, Image>,
+    /// Current texture coordinate transformation.
+    pub uv_transform: Field<'a, Matrix2d>,
+    /// Current slice mode.
+    pub slice_mode: Field<'a, SliceMode>,
     /// Current color.
     pub color: Field<'a, Color>,
 }
@@ -46,8 +63,11 @@ impl<'a> Clone for ImageRectangleColorContext<'a> {
         ImageRectangleColorContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             rect: Value(*self.rect.get()),
             image: Value(*self.image.get()),
+            uv_transform: Value(*self.uv_transform.get()),
+            slice_mode: Value(*self.slice_mode.get()),
             color: Value(*self.color.get()),
         }
     }
@@ -66,8 +86,11 @@ impl<'a> CanTransform<'a, ImageRectangleColorContext<'a>, Matrix2d> for ImageRec
         ImageRectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
             color: Borrowed(self.color.get()),
         }
     }
@@ -87,8 +110,11 @@ for ImageRectangleColorContext<'a> {
         ImageRectangleColorContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
             color: Borrowed(self.color.get()),
         }
     }
@@ -107,9 +133,12 @@ impl<'a> CanColor<'a, ImageRectangleColorContext<'a>, Color> for ImageRectangleC
         ImageRectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Value(value),
             rect: Borrowed(self.rect.get()),
             image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
         }
     }
 }
@@ -127,8 +156,11 @@ impl<'a> CanRectangle<'a, ImageRectangleColorContext<'a>, Rectangle> for ImageRe
         ImageRectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Value(rect),
             image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
             color: Borrowed(self.color.get()),
         }
     }
@@ -136,38 +168,127 @@ impl<'a> CanRectangle<'a, ImageRectangleColorContext<'a>, Rectangle> for ImageRe
 
 impl<'a> Draw<'a> for ImageRectangleColorContext<'a> {
     #[inline(always)]
-    fn draw<B: BackEnd>(&'a self, back_end: &mut B) {
-        if back_end.supports_single_texture()
-        && back_end.supports_tri_list_xy_f32_rgba_f32_uv_f32() {
-            let rect = self.rect.get();
-            let color = self.color.get();
-            let texture_id = self.image.get().texture_id;
-            // Complete transparency does not need to be rendered.
-            if color[3] == 0.0 { return; }
-            // Turn on alpha blending if not completely opaque or if the texture has alpha channel.
-            let needs_alpha = color[3] != 1.0 || back_end.has_texture_alpha(texture_id);
-            if needs_alpha { back_end.enable_alpha_blend(); }
-            back_end.enable_single_texture(texture_id);
-            back_end.tri_list_xy_f32_rgba_f32_uv_f32(
-                rect_tri_list_xy_f32(*self.transform.get(), *rect),
-                rect_tri_list_rgba_f32(*color),
-                rect_tri_list_uv_f32(self.image.get())
-            );
-            back_end.disable_single_texture();
-            if needs_alpha { back_end.disable_alpha_blend(); }
-        } else {
-            unimplemented!();
-        }
+    fn draw<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_texture_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let rect = self.rect.get();
+                let color = self.color.get();
+                let texture_id = self.image.get().texture_id;
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                // Turn on alpha blending if not completely opaque or if the texture has alpha channel.
+                let needs_alpha = color[3] != 1.0 || back_end.has_texture_alpha(texture_id);
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                back_end.enable_single_texture(texture_id);
+                let (vertices, uvs) = image_tri_list_xy_f32_uv_f32(
+                    *self.transform.get(),
+                    *rect,
+                    self.image.get(),
+                    *self.uv_transform.get(),
+                    *self.slice_mode.get()
+                );
+                let colors = Vec::from_fn(vertices.len() * 2, |i| color[i % 4]);
+                back_end.tri_list_xy_f32_rgba_f32_uv_f32(
+                    vertices.as_slice(),
+                    colors.as_slice(),
+                    uvs.as_slice()
+                );
+                back_end.disable_single_texture();
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
     }
 }
 
 impl<'a> Clear for ImageRectangleColorContext<'a> {
     #[inline(always)]
-    fn clear<B: BackEnd>(&self, back_end: &mut B) {
-        if back_end.supports_clear_rgba() {
-            let color = self.color.get();
-            back_end.clear_rgba(color[0], color[1], color[2], color[3]);
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
+
+impl<'a> AddNineSlice<'a, NineSliceColorContext<'a>> for ImageRectangleColorContext<'a> {
+    #[inline(always)]
+    fn nine_slice(
+        &'a self,
+        left: Scalar,
+        top: Scalar,
+        right: Scalar,
+        bottom: Scalar
+    ) -> NineSliceColorContext<'a> {
+        NineSliceColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            image: Borrowed(self.image.get()),
+            insets: Value([left, top, right, bottom]),
+            slice_mode: Borrowed(self.slice_mode.get()),
+            color: Borrowed(self.color.get()),
         }
     }
 }
 
+impl<'a> HasSourceRectangle<'a, PixelRectangle> for ImageRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_source_rectangle(&'a self) -> &'a PixelRectangle {
+        &self.image.get().source_rect
+    }
+}
+
+impl<'a> CanSourceRectangle<'a, ImageRectangleColorContext<'a>, PixelRectangle> for ImageRectangleColorContext<'a> {
+    #[inline(always)]
+    fn source_rectangle(&'a self, source_rect: PixelRectangle) -> ImageRectangleColorContext<'a> {
+        let mut image = *self.image.get();
+        image.source_rect = source_rect;
+        ImageRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            image: Value(image),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasUvTransform<'a, Matrix2d> for ImageRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_uv_transform(&'a self) -> &'a Matrix2d {
+        self.uv_transform.get()
+    }
+}
+
+impl<'a> CanUvTransform<'a, ImageRectangleColorContext<'a>, Matrix2d> for ImageRectangleColorContext<'a> {
+    #[inline(always)]
+    fn uv_transform(&'a self, value: Matrix2d) -> ImageRectangleColorContext<'a> {
+        ImageRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Value(value),
+            slice_mode: Borrowed(self.slice_mode.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> AddSliceMode<'a, ImageRectangleColorContext<'a>> for ImageRectangleColorContext<'a> {
+    #[inline(always)]
+    fn slice_mode(&'a self, mode: SliceMode) -> ImageRectangleColorContext<'a> {
+        ImageRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            slice_mode: Value(mode),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
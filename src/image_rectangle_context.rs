@@ -1,31 +1,42 @@
 use {
     AddColor,
+    AddNineSlice,
+    AddSliceMode,
     BackEnd,
     Borrowed,
+    ClipBackEnd,
     Draw,
+    FallbackBackEnd,
     Field,
     Image,
     ImageRectangleColorContext,
+    NineSliceContext,
+    RenderResult,
+    SliceMode,
     Value,
 };
 use triangulation::{
-    rect_tri_list_xy_f32,
-    rect_tri_list_rgba_f32,
-    rect_tri_list_uv_f32,
+    image_tri_list_xy_f32_uv_f32,
 };
 use internal::{
     CanColor,
     CanRectangle,
+    CanSourceRectangle,
     CanTransform,
+    CanUvTransform,
     CanViewTransform,
     Color,
     ColorComponent,
     HasColor,
     HasRectangle,
+    HasSourceRectangle,
     HasTransform,
+    HasUvTransform,
     HasViewTransform,
     Matrix2d,
+    PixelRectangle,
     Rectangle,
+    Scalar,
 };
 
 /// An image rectangle context.
@@ -34,10 +45,16 @@ pub struct ImageRectangleContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transformation.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current rectangle.
     pub rect: Field<'a, Rectangle>,
     /// Current image.
     pub image: Field<'a, Image>,
+    /// Current texture coordinate transformation.
+    pub uv_transform: Field<'a, Matrix2d>,
+    /// Current slice mode.
+    pub slice_mode: Field<'a, SliceMode>,
 }
 
 impl<'a> Clone for ImageRectangleContext<'a> {
@@ -46,8 +63,11 @@ impl<'a> Clone for ImageRectangleContext<'a> {
         ImageRectangleContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             rect: Value(*self.rect.get()),
             image: Value(*self.image.get()),
+            uv_transform: Value(*self.uv_transform.get()),
+            slice_mode: Value(*self.slice_mode.get()),
         }
     }
 }
@@ -65,8 +85,11 @@ impl<'a> CanTransform<'a, ImageRectangleContext<'a>, Matrix2d> for ImageRectangl
         ImageRectangleContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
         }
     }
 }
@@ -85,8 +108,11 @@ for ImageRectangleContext<'a> {
         ImageRectangleContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
         }
     }
 }
@@ -106,9 +132,12 @@ impl<'a> CanColor<'a, ImageRectangleColorContext<'a>, Color> for ImageRectangleC
         ImageRectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Value(value),
             rect: Borrowed(self.rect.get()),
             image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
         }
     }
 }
@@ -126,36 +155,46 @@ impl<'a> CanRectangle<'a, ImageRectangleContext<'a>, Rectangle> for ImageRectang
         ImageRectangleContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Value(rect),
             image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
         }
     }
 }
 
 impl<'a> Draw<'a> for ImageRectangleContext<'a> {
     #[inline(always)]
-    fn draw<B: BackEnd>(&'a self, back_end: &mut B) {
-        if back_end.supports_single_texture()
-        && back_end.supports_tri_list_xy_f32_rgba_f32_uv_f32() {
-            let rect = self.rect.get();
-            let color: [f32, ..4] = [1.0, 1.0, 1.0, 1.0];
-            let texture_id = self.image.get().texture_id;
-            // Complete transparency does not need to be rendered.
-            if color[3] == 0.0 { return; }
-            // Turn on alpha blending if not completely opaque or if the texture has alpha channel.
-            let needs_alpha = color[3] != 1.0 || back_end.has_texture_alpha(texture_id);
-            if needs_alpha { back_end.enable_alpha_blend(); }
-            back_end.enable_single_texture(texture_id);
-            back_end.tri_list_xy_f32_rgba_f32_uv_f32(
-                rect_tri_list_xy_f32(*self.transform.get(), *rect),
-                rect_tri_list_rgba_f32(color),
-                rect_tri_list_uv_f32(self.image.get())
-            );
-            back_end.disable_single_texture();
-            if needs_alpha { back_end.disable_alpha_blend(); }
-        } else {
-            unimplemented!();
-        }
+    fn draw<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_texture_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let rect = self.rect.get();
+                let color: [f32, ..4] = [1.0, 1.0, 1.0, 1.0];
+                let texture_id = self.image.get().texture_id;
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                // Turn on alpha blending if not completely opaque or if the texture has alpha channel.
+                let needs_alpha = color[3] != 1.0 || back_end.has_texture_alpha(texture_id);
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                back_end.enable_single_texture(texture_id);
+                let (vertices, uvs) = image_tri_list_xy_f32_uv_f32(
+                    *self.transform.get(),
+                    *rect,
+                    self.image.get(),
+                    *self.uv_transform.get(),
+                    *self.slice_mode.get()
+                );
+                let colors = Vec::from_fn(vertices.len() * 2, |i| color[i % 4]);
+                back_end.tri_list_xy_f32_rgba_f32_uv_f32(
+                    vertices.as_slice(),
+                    colors.as_slice(),
+                    uvs.as_slice()
+                );
+                back_end.disable_single_texture();
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
     }
 }
 
@@ -171,10 +210,140 @@ impl<'a> AddColor<'a, ImageRectangleColorContext<'a>> for ImageRectangleContext<
         ImageRectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
             color: Value([r, g, b, a]),
         }
     }
 }
 
+impl<'a> AddNineSlice<'a, NineSliceContext<'a>> for ImageRectangleContext<'a> {
+    #[inline(always)]
+    fn nine_slice(
+        &'a self,
+        left: Scalar,
+        top: Scalar,
+        right: Scalar,
+        bottom: Scalar
+    ) -> NineSliceContext<'a> {
+        NineSliceContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            image: Borrowed(self.image.get()),
+            insets: Value([left, top, right, bottom]),
+            slice_mode: Borrowed(self.slice_mode.get()),
+        }
+    }
+}
+
+impl<'a> HasSourceRectangle<'a, PixelRectangle> for ImageRectangleContext<'a> {
+    #[inline(always)]
+    fn get_source_rectangle(&'a self) -> &'a PixelRectangle {
+        &self.image.get().source_rect
+    }
+}
+
+impl<'a> CanSourceRectangle<'a, ImageRectangleContext<'a>, PixelRectangle> for ImageRectangleContext<'a> {
+    #[inline(always)]
+    fn source_rectangle(&'a self, source_rect: PixelRectangle) -> ImageRectangleContext<'a> {
+        let mut image = *self.image.get();
+        image.source_rect = source_rect;
+        ImageRectangleContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            image: Value(image),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
+        }
+    }
+}
+
+impl<'a> HasUvTransform<'a, Matrix2d> for ImageRectangleContext<'a> {
+    #[inline(always)]
+    fn get_uv_transform(&'a self) -> &'a Matrix2d {
+        self.uv_transform.get()
+    }
+}
+
+impl<'a> CanUvTransform<'a, ImageRectangleContext<'a>, Matrix2d> for ImageRectangleContext<'a> {
+    #[inline(always)]
+    fn uv_transform(&'a self, value: Matrix2d) -> ImageRectangleContext<'a> {
+        ImageRectangleContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Value(value),
+            slice_mode: Borrowed(self.slice_mode.get()),
+        }
+    }
+}
+
+impl<'a> AddSliceMode<'a, ImageRectangleContext<'a>> for ImageRectangleContext<'a> {
+    #[inline(always)]
+    fn slice_mode(&'a self, mode: SliceMode) -> ImageRectangleContext<'a> {
+        ImageRectangleContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            slice_mode: Value(mode),
+        }
+    }
+}
+
+#[test]
+fn test_image_orientation() {
+    use {AddImage, AddRectangle, Context, RelativeImage, RelativeTransform2d};
+    use {SoftwareBackEnd};
+
+    // A 2x2 texture, red and green on top, blue and white below.
+    let pixels = [255u8, 0, 0, 255,  0, 255, 0, 255,
+                  0, 0, 255, 255,  255, 255, 255, 255];
+    let mut back_end = SoftwareBackEnd::new(4, 4);
+    let texture_id = back_end.add_texture(2, 2, pixels);
+    let image = Image {
+        texture_id: texture_id,
+        texture_width: 2,
+        texture_height: 2,
+        source_rect: [0, 0, 2, 2],
+    };
+    let c = Context::new();
+    let c = c.scale(2.0 / 4.0, -2.0 / 4.0);
+    let d = c.trans(-1.0, 1.0);
+    let red = [255, 0, 0, 255];
+    let green = [0, 255, 0, 255];
+    let blue = [0, 0, 255, 255];
+    let white = [255, 255, 255, 255];
+
+    d.rect(0.0, 0.0, 4.0, 4.0).image(image).flip_u().draw(&mut back_end).unwrap();
+    assert!(back_end.pixel(0, 0) == green);
+    assert!(back_end.pixel(3, 3) == blue);
+
+    // Rotating clockwise moves the bottom left corner to the top left.
+    d.rect(0.0, 0.0, 4.0, 4.0).image(image).rot90().draw(&mut back_end).unwrap();
+    assert!(back_end.pixel(0, 0) == blue);
+    assert!(back_end.pixel(3, 0) == red);
+    assert!(back_end.pixel(3, 3) == green);
+
+    d.rect(0.0, 0.0, 4.0, 4.0).image(image).region(1, 1, 1, 1).draw(&mut back_end).unwrap();
+    assert!(back_end.pixel(0, 0) == white);
+    assert!(back_end.pixel(3, 3) == white);
+
+    // Tiles have the source size, so the texture repeats twice in each direction.
+    d.rect(0.0, 0.0, 4.0, 4.0).image(image).tile().draw(&mut back_end).unwrap();
+    assert!(back_end.pixel(0, 0) == red);
+    assert!(back_end.pixel(2, 0) == red);
+    assert!(back_end.pixel(3, 2) == green);
+    assert!(back_end.pixel(3, 3) == white);
+}
@@ -0,0 +1,284 @@
+use {
+    AddResolution,
+    BackEnd,
+    Borrowed,
+    Clear,
+    ClipBackEnd,
+    Draw,
+    FallbackBackEnd,
+    Field,
+    Image,
+    RenderResult,
+    Resolution,
+    Value,
+};
+use triangulation::{
+    round_rectangle_resolution,
+    with_round_rectangle_textured_tri_list_xy_f32_rgba_f32_uv_f32,
+};
+use internal::{
+    CanColor,
+    CanRectangle,
+    CanSourceRectangle,
+    CanTransform,
+    CanUvTransform,
+    CanViewTransform,
+    Color,
+    HasColor,
+    HasRectangle,
+    HasSourceRectangle,
+    HasTransform,
+    HasUvTransform,
+    HasViewTransform,
+    Matrix2d,
+    PixelRectangle,
+    Radius,
+    Rectangle,
+};
+
+/// A textured round rectangle color context.
+///
+/// The texture coordinates are projected from the bounding box of the shape.
+pub struct ImageRoundRectangleColorContext<'a> {
+    /// Base/origin transform.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transform.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current rectangle.
+    pub rect: Field<'a, Rectangle>,
+    /// Current roundness radius.
+    pub round_radius: Field<'a, Radius>,
+    /// Current resolution of the curves.
+    pub resolution: Field<'a, Resolution>,
+    /// Current image.
+    pub image: Field<'a, Image>,
+    /// Current texture coordinate transformation.
+    pub uv_transform: Field<'a, Matrix2d>,
+    /// Current color.
+    pub color: Field<'a, Color>,
+}
+
+impl<'a> Clone for ImageRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn clone(&self) -> ImageRoundRectangleColorContext<'static> {
+        ImageRoundRectangleColorContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            rect: Value(*self.rect.get()),
+            round_radius: Value(*self.round_radius.get()),
+            resolution: Value(*self.resolution.get()),
+            image: Value(*self.image.get()),
+            uv_transform: Value(*self.uv_transform.get()),
+            color: Value(*self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasTransform<'a, Matrix2d> for ImageRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a> CanTransform<'a, ImageRoundRectangleColorContext<'a>, Matrix2d> for ImageRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> ImageRoundRectangleColorContext<'a> {
+        ImageRoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasViewTransform<'a, Matrix2d> for ImageRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a> CanViewTransform<'a, ImageRoundRectangleColorContext<'a>, Matrix2d> for ImageRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> ImageRoundRectangleColorContext<'a> {
+        ImageRoundRectangleColorContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasColor<'a, Color> for ImageRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_color(&'a self) -> &'a Color {
+        self.color.get()
+    }
+}
+
+impl<'a> CanColor<'a, ImageRoundRectangleColorContext<'a>, Color> for ImageRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn color(&'a self, value: Color) -> ImageRoundRectangleColorContext<'a> {
+        ImageRoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            color: Value(value),
+        }
+    }
+}
+
+impl<'a> HasRectangle<'a, Rectangle> for ImageRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_rectangle(&'a self) -> &'a Rectangle {
+        self.rect.get()
+    }
+}
+
+impl<'a> CanRectangle<'a, ImageRoundRectangleColorContext<'a>, Rectangle> for ImageRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn rectangle(&'a self, rect: Rectangle) -> ImageRoundRectangleColorContext<'a> {
+        ImageRoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Value(rect),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> AddResolution<'a, ImageRoundRectangleColorContext<'a>> for ImageRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn resolution(&'a self, resolution: Resolution) -> ImageRoundRectangleColorContext<'a> {
+        ImageRoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Value(resolution),
+            image: Borrowed(self.image.get()),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasSourceRectangle<'a, PixelRectangle> for ImageRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_source_rectangle(&'a self) -> &'a PixelRectangle {
+        &self.image.get().source_rect
+    }
+}
+
+impl<'a> CanSourceRectangle<'a, ImageRoundRectangleColorContext<'a>, PixelRectangle> for ImageRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn source_rectangle(&'a self, source_rect: PixelRectangle) -> ImageRoundRectangleColorContext<'a> {
+        let mut image = *self.image.get();
+        image.source_rect = source_rect;
+        ImageRoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
+            image: Value(image),
+            uv_transform: Borrowed(self.uv_transform.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasUvTransform<'a, Matrix2d> for ImageRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn get_uv_transform(&'a self) -> &'a Matrix2d {
+        self.uv_transform.get()
+    }
+}
+
+impl<'a> CanUvTransform<'a, ImageRoundRectangleColorContext<'a>, Matrix2d> for ImageRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn uv_transform(&'a self, value: Matrix2d) -> ImageRoundRectangleColorContext<'a> {
+        ImageRoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
+            image: Borrowed(self.image.get()),
+            uv_transform: Value(value),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> Draw<'a> for ImageRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn draw<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_texture_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let color = self.color.get();
+                let texture_id = self.image.get().texture_id;
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                let rect = *self.rect.get();
+                let round_radius = *self.round_radius.get();
+                let resolution = round_rectangle_resolution(
+                    *self.transform.get(), round_radius, *self.resolution.get());
+                // Turn on alpha blending if not completely opaque or if the texture has alpha channel.
+                let needs_alpha = color[3] != 1.0 || back_end.has_texture_alpha(texture_id);
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                back_end.enable_single_texture(texture_id);
+                with_round_rectangle_textured_tri_list_xy_f32_rgba_f32_uv_f32(
+                    resolution,
+                    *self.transform.get(),
+                    rect,
+                    round_radius,
+                    self.image.get(),
+                    *self.uv_transform.get(),
+                    *color,
+                    |vertices, colors, texture_coords| {
+                        back_end.tri_list_xy_f32_rgba_f32_uv_f32(vertices, colors, texture_coords)
+                    }
+                );
+                back_end.disable_single_texture();
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+impl<'a> Clear for ImageRoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
@@ -37,6 +37,11 @@ pub type Polygon<'a> = &'a [Scalar];
 /// A slice of polygons.
 pub type Polygons<'a> = &'a [Polygon<'a>];
 
+/// [x0, y0, x1, y1, ...]
+///
+/// Unlike a polygon, the last point is not connected to the first.
+pub type Polyline<'a> = &'a [Scalar];
+
 /// The type used for radius.
 pub type Radius = Scalar;
 
@@ -49,6 +54,12 @@ pub type Rectangle = [Scalar, ..4];
 /// The type used for scalars.
 pub type Scalar = f64;
 
+/// [polygon0, polygon1, ...]
+///
+/// A shape consists of one or more rings,
+/// for example an outer ring with holes in it.
+pub type Shape<'a> = &'a [Polygon<'a>];
+
 /// [x1, y1, x2, y2, x3, y3]
 pub type Triangle = [Scalar, ..6];
 
@@ -72,6 +83,12 @@ pub trait CanRectangle<'a, T, U> {
     fn rectangle(&'a self, value: U) -> T;
 }
 
+/// Implemented by contexts that can set the source rectangle of an image.
+pub trait CanSourceRectangle<'a, T, U> {
+    /// Create a new context with source rectangle.
+    fn source_rectangle(&'a self, value: U) -> T;
+}
+
 /// Implemented by contexts that can transform.
 ///
 /// The context can transform to type `T` by adding value `U`.
@@ -80,6 +97,14 @@ pub trait CanTransform<'a, T, U> {
     fn transform(&'a self, value: U) -> T;
 }
 
+/// Implemented by contexts that can transform texture coordinates.
+///
+/// The context can transform to type `T` by adding value `U`.
+pub trait CanUvTransform<'a, T, U> {
+    /// Create a new context with texture coordinate transformation.
+    fn uv_transform(&'a self, value: U) -> T;
+}
+
 /// Implemented by contexts that can view transform.
 ///
 /// The context can view transform to type `T` by adding value `U`.
@@ -100,6 +125,12 @@ pub trait HasRectangle<'a, U> {
     fn get_rectangle(&'a self) -> &'a U;
 }
 
+/// Implemented by contexts that uses type `U` as current source rectangle of an image.
+pub trait HasSourceRectangle<'a, U> {
+    /// Returns the current source rectangle.
+    fn get_source_rectangle(&'a self) -> &'a U;
+}
+
 /// Implemented by contexts that uses type `U` as current transform.
 ///
 /// This helps to remove redundant code.
@@ -108,6 +139,15 @@ pub trait HasTransform<'a, U> {
     fn get_transform(&'a self) -> &'a U;
 }
 
+/// Implemented by contexts that uses type `U` as current texture coordinate transform.
+///
+/// The transform maps the destination rectangle, as a unit square,
+/// to the source rectangle, as a unit square.
+pub trait HasUvTransform<'a, U> {
+    /// Returns the current texture coordinate transform.
+    fn get_uv_transform(&'a self) -> &'a U;
+}
+
 /// Implemented by contexts that uses type `U` as current view transform.
 ///
 /// This helps to remove redundant code.
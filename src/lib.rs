@@ -1,5 +1,6 @@
 #![crate_id = "graphics"]
 #![deny(missing_doc)]
+#![feature(unsafe_destructor)]
 
 //! A library for 2D graphics that works with multiple back-ends.
 //!
@@ -25,9 +26,12 @@
 //! For example, assuming we have a back-end called `back_end`:
 //!
 //! ```
-//! Context::new().rect(x, y, w, h).rgba(r, g, b, a).fill(&mut back_end);
+//! Context::new().rect(x, y, w, h).rgba(r, g, b, a).fill(&mut back_end).unwrap();
 //! ```
 //!
+//! Drawing returns a `RenderError` when the back-end lacks the features to render the shape.
+//! Shapes are converted to a format the back-end supports when possible.
+//!
 //! ## Important!
 //!
 //! Because the context is built using borrowed pointers,
@@ -53,96 +57,282 @@ extern crate std;
 
 pub use AddBevel = add_bevel::AddBevel;
 pub use AddBevelBorder = add_bevel_border::AddBevelBorder;
+pub use AddBorder = add_border::AddBorder;
+pub use AddClip = add_clip::AddClip;
 pub use AddColor = add_color::AddColor;
+pub use AddDash = add_dash::AddDash;
 pub use AddEllipse = add_ellipse::AddEllipse;
+pub use AddFillRule = add_fill_rule::AddFillRule;
+pub use AddGradient = add_gradient::AddGradient;
 pub use AddImage = add_image::AddImage;
 pub use AddLine = add_line::AddLine;
+pub use AddLineCap = add_line_cap::AddLineCap;
+pub use AddLineJoin = add_line_join::AddLineJoin;
+pub use AddNineSlice = add_nine_slice::AddNineSlice;
+pub use AddPath = add_path::AddPath;
 pub use AddPolygon = add_polygon::AddPolygon;
 pub use AddPolygons = add_polygons::AddPolygons;
+pub use AddPolyline = add_polyline::AddPolyline;
 pub use AddRectangle = add_rectangle::AddRectangle;
+pub use AddResolution = add_resolution::AddResolution;
 pub use AddRound = add_round::AddRound;
 pub use AddRoundBorder = add_round_border::AddRoundBorder;
+pub use AddShape = add_shape::AddShape;
+pub use AddSliceMode = add_slice_mode::AddSliceMode;
 pub use AddSquareBorder = add_square_border::AddSquareBorder;
+pub use AddText = add_text::AddText;
+pub use AddTextLayout = add_text_layout::AddTextLayout;
 pub use AddTween = add_tween::AddTween;
+pub use AlignCenter = text_layout::AlignCenter;
+pub use AlignLeft = text_layout::AlignLeft;
+pub use AlignRight = text_layout::AlignRight;
+pub use AtlasGlyph = glyph_atlas::AtlasGlyph;
 pub use BackEnd = back_end::BackEnd;
+pub use BatchBackEnd = batch_back_end::BatchBackEnd;
+pub use BatchStats = batch_back_end::BatchStats;
+pub use BeginStencilTest = recording_back_end::BeginStencilTest;
+pub use BeginStencilWrite = recording_back_end::BeginStencilWrite;
 pub use BevelBorderLineColorContext = bevel_border_line_color_context::BevelBorderLineColorContext;
 pub use BevelBorderLineContext = bevel_border_line_context::BevelBorderLineContext;
+pub use BevelJoin = line_join::BevelJoin;
 pub use BevelRectangleColorContext = bevel_rectangle_color_context::BevelRectangleColorContext;
 pub use BevelRectangleContext = bevel_rectangle_context::BevelRectangleContext;
+pub use BorderBevelRectangleColorContext = border_bevel_rectangle_color_context::BorderBevelRectangleColorContext;
+pub use BorderEllipseColorContext = border_ellipse_color_context::BorderEllipseColorContext;
+pub use BorderPathColorContext = border_path_color_context::BorderPathColorContext;
+pub use BorderPolygonColorContext = border_polygon_color_context::BorderPolygonColorContext;
+pub use BorderPolylineColorContext = border_polyline_color_context::BorderPolylineColorContext;
+pub use BorderRectangleColorContext = border_rectangle_color_context::BorderRectangleColorContext;
+pub use BorderRoundRectangleColorContext = border_round_rectangle_color_context::BorderRoundRectangleColorContext;
+pub use ButtCap = line_cap::ButtCap;
 pub use Clear = clear::Clear;
+pub use ClearRgba = recording_back_end::ClearRgba;
+pub use ClearScissor = recording_back_end::ClearScissor;
+pub use ClipBackEnd = clip_back_end::ClipBackEnd;
 pub use ColorContext = color_context::ColorContext;
+pub use Command = recording_back_end::Command;
 pub use Context = context::Context;
+pub use Dash = dash::Dash;
+pub use DisableAlphaBlend = recording_back_end::DisableAlphaBlend;
+pub use DisableSingleTexture = recording_back_end::DisableSingleTexture;
 pub use Draw = draw::Draw;
 pub use EllipseContext = ellipse_context::EllipseContext;
 pub use EllipseColorContext = ellipse_color_context::EllipseColorContext;
+pub use EnableAlphaBlend = recording_back_end::EnableAlphaBlend;
+pub use EnableSingleTexture = recording_back_end::EnableSingleTexture;
+pub use EndStencil = recording_back_end::EndStencil;
+pub use EvenOdd = fill_rule::EvenOdd;
+pub use FallbackBackEnd = fallback_back_end::FallbackBackEnd;
 pub use Fill = fill::Fill;
+pub use FillRule = fill_rule::FillRule;
+pub use Font = font::Font;
+pub use FontError = font::FontError;
+pub use FontIoError = font::FontIoError;
+pub use FontMalformed = font::FontMalformed;
+pub use FontMissingTable = font::FontMissingTable;
+pub use FontUnsupported = font::FontUnsupported;
+pub use GlyphAtlas = glyph_atlas::GlyphAtlas;
+pub use GlyphAtlasFull = render_error::GlyphAtlasFull;
+pub use Gradient = gradient::Gradient;
+pub use GradientKind = gradient::GradientKind;
+pub use GradientStop = gradient::GradientStop;
+pub use ImageEllipseColorContext = image_ellipse_color_context::ImageEllipseColorContext;
+pub use ImageEllipseContext = image_ellipse_context::ImageEllipseContext;
+pub use ImagePolygonColorContext = image_polygon_color_context::ImagePolygonColorContext;
+pub use ImagePolygonContext = image_polygon_context::ImagePolygonContext;
 pub use ImageRectangleContext = image_rectangle_context::ImageRectangleContext;
 pub use ImageRectangleColorContext = image_rectangle_color_context::ImageRectangleColorContext;
+pub use ImageRoundRectangleColorContext = image_round_rectangle_color_context::ImageRoundRectangleColorContext;
+pub use ImageRoundRectangleContext = image_round_rectangle_context::ImageRoundRectangleContext;
+pub use LineCap = line_cap::LineCap;
 pub use LineContext = line_context::LineContext;
 pub use LineColorContext = line_color_context::LineColorContext;
+pub use LineJoin = line_join::LineJoin;
+pub use LinearGradient = gradient::LinearGradient;
+pub use MAX_DASHES = dash::MAX_DASHES;
+pub use MAX_DASH_LENGTHS = dash::MAX_DASH_LENGTHS;
+pub use MAX_GRADIENT_STOPS = gradient::MAX_GRADIENT_STOPS;
+pub use Mask = mask::Mask;
+pub use MaskBackEnd = mask::MaskBackEnd;
+pub use MaskedBackEnd = mask::MaskedBackEnd;
+pub use MiterJoin = line_join::MiterJoin;
+pub use NineSliceColorContext = nine_slice_color_context::NineSliceColorContext;
+pub use NineSliceContext = nine_slice_context::NineSliceContext;
+pub use NonZero = fill_rule::NonZero;
+pub use Path = path::Path;
+pub use PathColorContext = path_color_context::PathColorContext;
+pub use PathContext = path_context::PathContext;
 pub use PolygonContext = polygon_context::PolygonContext;
 pub use PolygonColorContext = polygon_color_context::PolygonColorContext;
+pub use PolylineColorContext = polyline_color_context::PolylineColorContext;
+pub use PolylineContext = polyline_context::PolylineContext;
+pub use PositionedGlyph = text_layout::PositionedGlyph;
+pub use RadialGradient = gradient::RadialGradient;
+pub use RecordingBackEnd = recording_back_end::RecordingBackEnd;
 pub use RectangleContext = rectangle_context::RectangleContext;
 pub use RectangleColorContext = rectangle_color_context::RectangleColorContext;
 pub use RelativeColor = relative_color::RelativeColor;
+pub use RelativeImage = relative_image::RelativeImage;
 pub use RelativeRectangle = relative_rectangle::RelativeRectangle;
 pub use RelativeTransform2d = relative_transform2d::RelativeTransform2d;
+pub use RenderError = render_error::RenderError;
+pub use RenderResult = render_error::RenderResult;
+pub use Resolution = resolution::Resolution;
 pub use RoundBorderLineContext = round_border_line_context::RoundBorderLineContext;
 pub use RoundBorderLineColorContext = round_border_line_color_context::RoundBorderLineColorContext;
+pub use RoundCap = line_cap::RoundCap;
+pub use RoundJoin = line_join::RoundJoin;
 pub use RoundRectangleContext = round_rectangle_context::RoundRectangleContext;
 pub use RoundRectangleColorContext = round_rectangle_color_context::RoundRectangleColorContext;
+pub use Segments = resolution::Segments;
+pub use SetScissor = recording_back_end::SetScissor;
+pub use ShapeColorContext = shape_color_context::ShapeColorContext;
+pub use ShapeContext = shape_context::ShapeContext;
+pub use SliceMode = slice_mode::SliceMode;
+pub use SoftwareBackEnd = software_back_end::SoftwareBackEnd;
+pub use SpriteSheet = sprite_sheet::SpriteSheet;
+pub use SpriteSheetBuilder = sprite_sheet::SpriteSheetBuilder;
+pub use Sprites = sprite_sheet::Sprites;
+pub use SquareCap = line_cap::SquareCap;
+pub use Stretch = slice_mode::Stretch;
 pub use Stroke = stroke::Stroke;
 pub use SquareBorderLineColorContext = square_border_line_color_context::SquareBorderLineColorContext;
 pub use SquareBorderLineContext = square_border_line_context::SquareBorderLineContext;
+pub use TextAlign = text_layout::TextAlign;
+pub use TextColorContext = text_color_context::TextColorContext;
+pub use TextContext = text_context::TextContext;
+pub use Texture = texture::Texture;
+pub use Textures = texture::Textures;
+pub use Tile = slice_mode::Tile;
+pub use Tolerance = resolution::Tolerance;
+pub use TriFan = triangle_kind::TriFan;
+pub use TriFanXyF32RgbaF32 = recording_back_end::TriFanXyF32RgbaF32;
+pub use TriList = triangle_kind::TriList;
+pub use TriListIndexedXyF32RgbaF32 = recording_back_end::TriListIndexedXyF32RgbaF32;
+pub use TriListIndexedXyF32RgbaF32U16 = recording_back_end::TriListIndexedXyF32RgbaF32U16;
+pub use TriListIndexedXyF32RgbaF32UvF32 = recording_back_end::TriListIndexedXyF32RgbaF32UvF32;
+pub use TriListIndexedXyF32RgbaF32UvF32U16 = recording_back_end::TriListIndexedXyF32RgbaF32UvF32U16;
+pub use TriListXyF32RgbaF32 = recording_back_end::TriListXyF32RgbaF32;
+pub use TriListXyF32RgbaF32UvF32 = recording_back_end::TriListXyF32RgbaF32UvF32;
+pub use TriListXyF32UniformRgbaF32 = recording_back_end::TriListXyF32UniformRgbaF32;
+pub use TriListXyF64RgbaF32 = recording_back_end::TriListXyF64RgbaF32;
+pub use TriStrip = triangle_kind::TriStrip;
+pub use TriStripXyF32RgbaF32 = recording_back_end::TriStripXyF32RgbaF32;
+pub use TriangleKind = triangle_kind::TriangleKind;
+pub use TriangularCap = line_cap::TriangularCap;
 pub use TweenContext = tween_context::TweenContext;
 pub use TweenColorContext = tween_color_context::TweenColorContext;
 pub use TweenPolygonsContext = tween_polygons_context::TweenPolygonsContext;
 pub use TweenPolygonsColorContext = tween_polygons_color_context::TweenPolygonsColorContext;
+pub use UnsupportedClear = render_error::UnsupportedClear;
+pub use UnsupportedTexture = render_error::UnsupportedTexture;
+pub use UnsupportedTriangles = render_error::UnsupportedTriangles;
+pub use UnsupportedVertexColors = render_error::UnsupportedVertexColors;
 pub use View = view::View;
 
 mod add_bevel;
 mod add_bevel_border;
+mod add_border;
+mod add_clip;
 mod add_color;
+mod add_dash;
 mod add_ellipse;
+mod add_fill_rule;
+mod add_gradient;
 mod add_image;
 mod add_line;
+mod add_line_cap;
+mod add_line_join;
+mod add_nine_slice;
+mod add_path;
 mod add_polygon;
 mod add_polygons;
+mod add_polyline;
 mod add_rectangle;
+mod add_resolution;
 mod add_round;
 mod add_round_border;
+mod add_shape;
+mod add_slice_mode;
 mod add_square_border;
+mod add_text;
+mod add_text_layout;
 mod add_tween;
 mod back_end;
+mod batch_back_end;
 mod bevel_border_line_color_context;
 mod bevel_border_line_context;
 mod bevel_rectangle_color_context;
 mod bevel_rectangle_context;
+mod border_bevel_rectangle_color_context;
+mod border_ellipse_color_context;
+mod border_path_color_context;
+mod border_polygon_color_context;
+mod border_polyline_color_context;
+mod border_rectangle_color_context;
+mod border_round_rectangle_color_context;
 mod clear;
+mod clip_back_end;
 mod color_context;
 mod context;
+mod dash;
 mod draw;
 mod ellipse_color_context;
 mod ellipse_context;
+mod fallback_back_end;
 mod fill;
+mod fill_rule;
+mod font;
+mod glyph_atlas;
+mod gradient;
+mod image_ellipse_color_context;
+mod image_ellipse_context;
+mod image_polygon_color_context;
+mod image_polygon_context;
 mod image_rectangle_color_context;
 mod image_rectangle_context;
+mod image_round_rectangle_color_context;
+mod image_round_rectangle_context;
+mod line_cap;
 mod line_color_context;
 mod line_context;
+mod line_join;
+mod mask;
+mod nine_slice_color_context;
+mod nine_slice_context;
+mod path;
+mod path_color_context;
+mod path_context;
 mod polygon_color_context;
 mod polygon_context;
+mod polyline_color_context;
+mod polyline_context;
+mod recording_back_end;
 mod rectangle_color_context;
 mod rectangle_context;
 mod relative_color;
+mod relative_image;
 mod relative_rectangle;
 mod relative_transform2d;
+mod render_error;
+mod resolution;
 mod round_border_line_color_context;
 mod round_border_line_context;
 mod round_rectangle_color_context;
 mod round_rectangle_context;
+mod shape_color_context;
+mod shape_context;
+mod slice_mode;
+mod software_back_end;
+mod sprite_sheet;
 mod square_border_line_color_context;
 mod square_border_line_context;
 mod stroke;
+mod text_color_context;
+mod text_context;
+mod text_layout;
+mod texture;
+mod triangle_kind;
 mod tween_color_context;
 mod tween_context;
 mod tween_polygons_color_context;
@@ -185,8 +375,9 @@ impl<'a, T> Field<'a, T> {
 /// An image contains the size of a texture to be able to
 /// compute normalized coordinates.
 ///
-/// There is no garbage collection of textures,
-/// this responsibility is given to the back-end.
+/// An image does not keep its texture alive.
+/// Textures created through `Textures` are deleted when their last
+/// `Texture` handle is dropped, other textures are managed by the back-end.
 pub struct Image {
     /// A unique identifier of the texture, recognizable by back-end.
     pub texture_id: uint,
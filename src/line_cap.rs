@@ -0,0 +1,13 @@
+
+/// The shape at the ends of stroked lines.
+#[deriving(Clone, PartialEq, Show)]
+pub enum LineCap {
+    /// Ends the line straight at the end point.
+    ButtCap,
+    /// Extends the line straight by half the border width.
+    SquareCap,
+    /// Ends the line with a half circle around the end point.
+    RoundCap,
+    /// Ends the line with a point half the border width from the end point.
+    TriangularCap,
+}
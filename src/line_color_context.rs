@@ -7,7 +7,9 @@ use {
     BevelBorderLineColorContext,
     Borrowed,
     Clear,
+    ClipBackEnd,
     Field,
+    RenderResult,
     RoundBorderLineColorContext,
     SquareBorderLineColorContext,
     Value,
@@ -23,6 +25,7 @@ use internal::{
     Line,
     Matrix2d,
     Radius,
+    Rectangle,
 };
 
 /// A line context.
@@ -31,6 +34,8 @@ pub struct LineColorContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transform.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current line.
     pub line: Field<'a, Line>,
     /// Current color.
@@ -43,6 +48,7 @@ impl<'a> Clone for LineColorContext<'a> {
         LineColorContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             line: Value(*self.line.get()),
             color: Value(*self.color.get()),
         }
@@ -62,6 +68,7 @@ impl<'a> CanTransform<'a, LineColorContext<'a>, Matrix2d> for LineColorContext<'
         LineColorContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             color: Borrowed(self.color.get()),
         }
@@ -82,6 +89,7 @@ for LineColorContext<'a> {
         LineColorContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             color: Borrowed(self.color.get()),
         }
@@ -101,6 +109,7 @@ impl<'a> CanColor<'a, LineColorContext<'a>, Color> for LineColorContext<'a> {
         LineColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             color: Value(value),
         }
@@ -113,8 +122,10 @@ impl<'a> AddRoundBorder<'a, RoundBorderLineColorContext<'a>> for LineColorContex
         RoundBorderLineColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             round_border_radius: Value(radius),
+            dash: Value(None),
             color: Borrowed(self.color.get()),
         }
     }
@@ -126,8 +137,10 @@ impl<'a> AddBevelBorder<'a, BevelBorderLineColorContext<'a>> for LineColorContex
         BevelBorderLineColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             bevel_border_radius: Value(radius),
+            dash: Value(None),
             color: Borrowed(self.color.get()),
         }
     }
@@ -139,19 +152,18 @@ impl<'a> AddSquareBorder<'a, SquareBorderLineColorContext<'a>> for LineColorCont
         SquareBorderLineColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             square_border_radius: Value(radius),
+            dash: Value(None),
             color: Borrowed(self.color.get()),
         }
     }
 }
 
 impl<'a> Clear for LineColorContext<'a> {
-    fn clear<B: BackEnd>(&self, back_end: &mut B) {
-        if back_end.supports_clear_rgba() {
-            let color = self.color.get();
-            back_end.clear_rgba(color[0], color[1], color[2], color[3]);
-        }
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
     }
 }
 
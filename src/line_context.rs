@@ -21,6 +21,7 @@ use internal::{
     Line,
     Matrix2d,
     Radius,
+    Rectangle,
 };
 
 /// A line context.
@@ -29,6 +30,8 @@ pub struct LineContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transform.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current line.
     pub line: Field<'a, Line>,
 }
@@ -39,6 +42,7 @@ impl<'a> Clone for LineContext<'a> {
         LineContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             line: Value(*self.line.get()),
         }
     }
@@ -57,6 +61,7 @@ impl<'a> CanTransform<'a, LineContext<'a>, Matrix2d> for LineContext<'a> {
         LineContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
         }
     }
@@ -76,6 +81,7 @@ for LineContext<'a> {
         LineContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
         }
     }
@@ -93,6 +99,7 @@ impl<'a> AddColor<'a, LineColorContext<'a>> for LineContext<'a> {
         LineColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             color: Value([r, g, b, a]),
         }
@@ -105,6 +112,7 @@ impl<'a> AddRoundBorder<'a, RoundBorderLineContext<'a>> for LineContext<'a> {
         RoundBorderLineContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             round_border_radius: Value(radius),
         }
@@ -117,6 +125,7 @@ impl<'a> AddBevelBorder<'a, BevelBorderLineContext<'a>> for LineContext<'a> {
         BevelBorderLineContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             bevel_border_radius: Value(radius),
         }
@@ -129,6 +138,7 @@ impl<'a> AddSquareBorder<'a, SquareBorderLineContext<'a>> for LineContext<'a> {
         SquareBorderLineContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             square_border_radius: Value(radius),
         }
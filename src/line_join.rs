@@ -0,0 +1,13 @@
+
+/// The shape at the corners where stroked lines meet.
+#[deriving(Clone, PartialEq, Show)]
+pub enum LineJoin {
+    /// Extends the outer edges until they meet in a sharp corner.
+    ///
+    /// Falls back to a bevel join when the corner is longer than the miter limit.
+    MiterJoin,
+    /// Rounds the corner with a circle arc.
+    RoundJoin,
+    /// Cuts off the corner with a straight line.
+    BevelJoin,
+}
@@ -0,0 +1,645 @@
+//! Restricts drawing to the shape of a mask.
+
+use {
+    BackEnd,
+    Context,
+};
+use triangulation::{
+    mask_tri_list_xy_f32_rgba_f32,
+    mask_tri_list_xy_f32_rgba_f32_uv_f32,
+    merge_mask_tri_list,
+    rect_tri_list_rgba_f32,
+    rect_tri_list_xy_f32,
+};
+
+/// A mask drawn with `Context::mask`.
+///
+/// Call `draw_inside` to draw restricted to the mask.
+/// A mask dropped without drawing inside turns off the stencil.
+#[must_use]
+pub struct Mask<'a, 'b, B> {
+    context: &'a Context<'a>,
+    back_end: &'b mut B,
+    /// The triangles of the mask when the back-end has no stencil.
+    triangles: Option<Vec<f32>>,
+    /// Whether the back-end is still writing to the stencil.
+    stencil_write: bool,
+}
+
+impl<'a, 'b, B: BackEnd> Mask<'a, 'b, B> {
+    /// Creates a mask from the shapes drawn by a closure.
+    ///
+    /// Uses the stencil of the back-end when it is supported.
+    /// Otherwise the triangles are stored and the drawing inside is clipped on the CPU.
+    /// The stored triangles are merged first, so overlapping shapes draw the inside once.
+    pub fn new(
+        context: &'a Context<'a>,
+        back_end: &'b mut B,
+        f: |c: &'a Context<'a>, mask: &mut MaskBackEnd<B>|
+    ) -> Mask<'a, 'b, B> {
+        if back_end.supports_stencil() {
+            back_end.begin_stencil_write();
+            f(context, &mut MaskBackEnd { back_end: &mut *back_end, triangles: None });
+            Mask { context: context, back_end: back_end, triangles: None, stencil_write: true }
+        } else {
+            let mut triangles = Vec::new();
+            f(context, &mut MaskBackEnd {
+                back_end: &mut *back_end,
+                triangles: Some(&mut triangles)
+            });
+            let triangles = merge_mask_tri_list(triangles.as_slice());
+            Mask {
+                context: context,
+                back_end: back_end,
+                triangles: Some(triangles),
+                stencil_write: false
+            }
+        }
+    }
+
+    /// Draws with everything restricted to the inside of the mask.
+    pub fn draw_inside(mut self, f: |c: &'a Context<'a>, back_end: &mut MaskedBackEnd<B>|) {
+        let context = self.context;
+        match self.triangles {
+            None => {
+                self.stencil_write = false;
+                self.back_end.begin_stencil_test();
+                f(context, &mut MaskedBackEnd {
+                    back_end: &mut *self.back_end,
+                    mask: None,
+                    alpha_blend: false
+                });
+                self.back_end.end_stencil();
+            },
+            Some(ref triangles) => {
+                f(context, &mut MaskedBackEnd {
+                    back_end: &mut *self.back_end,
+                    mask: Some(triangles.as_slice()),
+                    alpha_blend: false
+                });
+            }
+        }
+    }
+}
+
+#[unsafe_destructor]
+impl<'a, 'b, B: BackEnd> Drop for Mask<'a, 'b, B> {
+    fn drop(&mut self) {
+        // Later drawing would otherwise only mark the stencil.
+        if self.stencil_write { self.back_end.end_stencil(); }
+    }
+}
+
+/// A back-end that draws the shape of a mask.
+///
+/// Only the covered area matters, the colors and textures are ignored.
+/// Clearing does not change the mask.
+pub struct MaskBackEnd<'b, B> {
+    back_end: &'b mut B,
+    /// Collects the triangles when the back-end has no stencil.
+    triangles: Option<&'b mut Vec<f32>>,
+}
+
+impl<'b, B: BackEnd> BackEnd for MaskBackEnd<'b, B> {
+    #[inline(always)]
+    fn supports_clear_rgba(&self) -> bool { true }
+
+    fn clear_rgba(&mut self, _r: f32, _g: f32, _b: f32, _a: f32) {}
+
+    fn enable_alpha_blend(&mut self) {}
+
+    fn disable_alpha_blend(&mut self) {}
+
+    #[inline(always)]
+    fn supports_scissor(&self) -> bool {
+        self.triangles.is_none() && self.back_end.supports_scissor()
+    }
+
+    fn set_scissor(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        self.back_end.set_scissor(x, y, w, h);
+    }
+
+    fn clear_scissor(&mut self) {
+        self.back_end.clear_scissor();
+    }
+
+    #[inline(always)]
+    fn supports_single_texture(&self) -> bool {
+        self.triangles.is_some() || self.back_end.supports_single_texture()
+    }
+
+    fn enable_single_texture(&mut self, texture_id: uint) {
+        if self.triangles.is_none() { self.back_end.enable_single_texture(texture_id); }
+    }
+
+    fn disable_single_texture(&mut self) {
+        if self.triangles.is_none() { self.back_end.disable_single_texture(); }
+    }
+
+    // Textures are created on the real back-end, also while recording the mask.
+    #[inline(always)]
+    fn supports_texture_upload(&self) -> bool {
+        self.back_end.supports_texture_upload()
+    }
+
+    fn create_texture(&mut self, width: u32, height: u32, pixels: &[u8]) -> Option<uint> {
+        self.back_end.create_texture(width, height, pixels)
+    }
+
+    fn update_texture(
+        &mut self,
+        texture_id: uint,
+        x: u32, y: u32, w: u32, h: u32,
+        pixels: &[u8]
+    ) {
+        self.back_end.update_texture(texture_id, x, y, w, h, pixels);
+    }
+
+    fn delete_texture(&mut self, texture_id: uint) {
+        self.back_end.delete_texture(texture_id);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f64_rgba_f32(&self) -> bool {
+        self.triangles.is_none() && self.back_end.supports_tri_list_xy_f64_rgba_f32()
+    }
+
+    fn tri_list_xy_f64_rgba_f32(
+        &mut self,
+        vertices: &[f64],
+        colors: &[f32]
+    ) {
+        self.back_end.tri_list_xy_f64_rgba_f32(vertices, colors);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_rgba_f32(&self) -> bool {
+        self.triangles.is_some() || self.back_end.supports_tri_list_xy_f32_rgba_f32()
+    }
+
+    fn tri_list_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        match self.triangles {
+            Some(ref mut triangles) => triangles.push_all(vertices),
+            None => self.back_end.tri_list_xy_f32_rgba_f32(vertices, colors),
+        }
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_rgba_f32_uv_f32(&self) -> bool {
+        self.triangles.is_some() || self.back_end.supports_tri_list_xy_f32_rgba_f32_uv_f32()
+    }
+
+    fn tri_list_xy_f32_rgba_f32_uv_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32]
+    ) {
+        match self.triangles {
+            Some(ref mut triangles) => triangles.push_all(vertices),
+            None => self.back_end.tri_list_xy_f32_rgba_f32_uv_f32(
+                vertices, colors, texture_coords),
+        }
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32(&self) -> bool {
+        // Only triangle lists without indices are recorded in the mask.
+        self.triangles.is_none() && self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        indices: &[u32]
+    ) {
+        self.back_end.tri_list_indexed_xy_f32_rgba_f32(vertices, colors, indices);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32(&self) -> bool {
+        self.triangles.is_none() && self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32],
+        indices: &[u32]
+    ) {
+        self.back_end.tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+            vertices, colors, texture_coords, indices);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_u16(&self) -> bool {
+        self.triangles.is_none()
+        && self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_u16()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_u16(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        indices: &[u16]
+    ) {
+        self.back_end.tri_list_indexed_xy_f32_rgba_f32_u16(vertices, colors, indices);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(&self) -> bool {
+        self.triangles.is_none()
+        && self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32],
+        indices: &[u16]
+    ) {
+        self.back_end.tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(
+            vertices, colors, texture_coords, indices);
+    }
+
+    #[inline(always)]
+    fn supports_tri_strip_xy_f32_rgba_f32(&self) -> bool {
+        self.triangles.is_none() && self.back_end.supports_tri_strip_xy_f32_rgba_f32()
+    }
+
+    fn tri_strip_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        self.back_end.tri_strip_xy_f32_rgba_f32(vertices, colors);
+    }
+
+    #[inline(always)]
+    fn supports_tri_fan_xy_f32_rgba_f32(&self) -> bool {
+        self.triangles.is_none() && self.back_end.supports_tri_fan_xy_f32_rgba_f32()
+    }
+
+    fn tri_fan_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        self.back_end.tri_fan_xy_f32_rgba_f32(vertices, colors);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_uniform_rgba_f32(&self) -> bool {
+        self.triangles.is_none() && self.back_end.supports_tri_list_xy_f32_uniform_rgba_f32()
+    }
+
+    fn tri_list_xy_f32_uniform_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        r: f32, g: f32, b: f32, a: f32
+    ) {
+        self.back_end.tri_list_xy_f32_uniform_rgba_f32(vertices, r, g, b, a);
+    }
+}
+
+/// A back-end that restricts drawing to the inside of a mask.
+///
+/// Clearing fills the inside of the mask.
+pub struct MaskedBackEnd<'b, B> {
+    back_end: &'b mut B,
+    /// The triangles of the mask when the back-end has no stencil.
+    mask: Option<&'b [f32]>,
+    /// Whether alpha blending is enabled.
+    alpha_blend: bool,
+}
+
+impl<'b, B: BackEnd> BackEnd for MaskedBackEnd<'b, B> {
+    #[inline(always)]
+    fn supports_clear_rgba(&self) -> bool {
+        self.back_end.supports_tri_list_xy_f32_rgba_f32()
+    }
+
+    fn clear_rgba(&mut self, r: f32, g: f32, b: f32, a: f32) {
+        // Alpha blending is turned off, so the color replaces the background.
+        let alpha_blend = self.alpha_blend;
+        if alpha_blend { self.back_end.disable_alpha_blend(); }
+        let vertices = rect_tri_list_xy_f32([1.0, 0.0, 0.0, 0.0, 1.0, 0.0], [-1.0, -1.0, 2.0, 2.0]);
+        let colors = rect_tri_list_rgba_f32([r, g, b, a]);
+        self.tri_list_xy_f32_rgba_f32(vertices, colors);
+        if alpha_blend { self.back_end.enable_alpha_blend(); }
+    }
+
+    fn enable_alpha_blend(&mut self) {
+        self.alpha_blend = true;
+        self.back_end.enable_alpha_blend();
+    }
+
+    fn disable_alpha_blend(&mut self) {
+        self.alpha_blend = false;
+        self.back_end.disable_alpha_blend();
+    }
+
+    #[inline(always)]
+    fn supports_scissor(&self) -> bool {
+        self.back_end.supports_scissor()
+    }
+
+    fn set_scissor(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        self.back_end.set_scissor(x, y, w, h);
+    }
+
+    fn clear_scissor(&mut self) {
+        self.back_end.clear_scissor();
+    }
+
+    #[inline(always)]
+    fn supports_single_texture(&self) -> bool {
+        self.back_end.supports_single_texture()
+    }
+
+    fn enable_single_texture(&mut self, texture_id: uint) {
+        self.back_end.enable_single_texture(texture_id);
+    }
+
+    fn disable_single_texture(&mut self) {
+        self.back_end.disable_single_texture();
+    }
+
+    #[inline(always)]
+    fn has_texture_alpha(&self, texture_id: uint) -> bool {
+        self.back_end.has_texture_alpha(texture_id)
+    }
+
+    #[inline(always)]
+    fn supports_texture_upload(&self) -> bool {
+        self.back_end.supports_texture_upload()
+    }
+
+    fn create_texture(&mut self, width: u32, height: u32, pixels: &[u8]) -> Option<uint> {
+        self.back_end.create_texture(width, height, pixels)
+    }
+
+    fn update_texture(
+        &mut self,
+        texture_id: uint,
+        x: u32, y: u32, w: u32, h: u32,
+        pixels: &[u8]
+    ) {
+        self.back_end.update_texture(texture_id, x, y, w, h, pixels);
+    }
+
+    fn delete_texture(&mut self, texture_id: uint) {
+        self.back_end.delete_texture(texture_id);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f64_rgba_f32(&self) -> bool {
+        // Only triangles with f32 vertices are clipped.
+        self.mask.is_none() && self.back_end.supports_tri_list_xy_f64_rgba_f32()
+    }
+
+    fn tri_list_xy_f64_rgba_f32(
+        &mut self,
+        vertices: &[f64],
+        colors: &[f32]
+    ) {
+        self.back_end.tri_list_xy_f64_rgba_f32(vertices, colors);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_rgba_f32(&self) -> bool {
+        self.back_end.supports_tri_list_xy_f32_rgba_f32()
+    }
+
+    fn tri_list_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        match self.mask {
+            None => self.back_end.tri_list_xy_f32_rgba_f32(vertices, colors),
+            Some(mask) => {
+                let back_end = &mut self.back_end;
+                mask_tri_list_xy_f32_rgba_f32(mask, vertices, colors,
+                    |vertices, colors| back_end.tri_list_xy_f32_rgba_f32(vertices, colors));
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_rgba_f32_uv_f32(&self) -> bool {
+        self.back_end.supports_tri_list_xy_f32_rgba_f32_uv_f32()
+    }
+
+    fn tri_list_xy_f32_rgba_f32_uv_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32]
+    ) {
+        match self.mask {
+            None => self.back_end.tri_list_xy_f32_rgba_f32_uv_f32(
+                vertices, colors, texture_coords),
+            Some(mask) => {
+                let back_end = &mut self.back_end;
+                mask_tri_list_xy_f32_rgba_f32_uv_f32(mask, vertices, colors, texture_coords,
+                    |vertices, colors, texture_coords| {
+                        back_end.tri_list_xy_f32_rgba_f32_uv_f32(
+                            vertices, colors, texture_coords)
+                    });
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32(&self) -> bool {
+        // Only triangle lists without indices are clipped.
+        self.mask.is_none() && self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        indices: &[u32]
+    ) {
+        self.back_end.tri_list_indexed_xy_f32_rgba_f32(vertices, colors, indices);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32(&self) -> bool {
+        self.mask.is_none() && self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32],
+        indices: &[u32]
+    ) {
+        self.back_end.tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+            vertices, colors, texture_coords, indices);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_u16(&self) -> bool {
+        self.mask.is_none() && self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_u16()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_u16(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        indices: &[u16]
+    ) {
+        self.back_end.tri_list_indexed_xy_f32_rgba_f32_u16(vertices, colors, indices);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(&self) -> bool {
+        self.mask.is_none() && self.back_end.supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16()
+    }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32],
+        indices: &[u16]
+    ) {
+        self.back_end.tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(
+            vertices, colors, texture_coords, indices);
+    }
+
+    #[inline(always)]
+    fn supports_tri_strip_xy_f32_rgba_f32(&self) -> bool {
+        self.mask.is_none() && self.back_end.supports_tri_strip_xy_f32_rgba_f32()
+    }
+
+    fn tri_strip_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        self.back_end.tri_strip_xy_f32_rgba_f32(vertices, colors);
+    }
+
+    #[inline(always)]
+    fn supports_tri_fan_xy_f32_rgba_f32(&self) -> bool {
+        self.mask.is_none() && self.back_end.supports_tri_fan_xy_f32_rgba_f32()
+    }
+
+    fn tri_fan_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        self.back_end.tri_fan_xy_f32_rgba_f32(vertices, colors);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_uniform_rgba_f32(&self) -> bool {
+        self.mask.is_none() && self.back_end.supports_tri_list_xy_f32_uniform_rgba_f32()
+    }
+
+    fn tri_list_xy_f32_uniform_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        r: f32, g: f32, b: f32, a: f32
+    ) {
+        self.back_end.tri_list_xy_f32_uniform_rgba_f32(vertices, r, g, b, a);
+    }
+}
+
+#[test]
+fn test_mask() {
+    use {AddColor, AddEllipse, AddRectangle, Clear, Fill, SoftwareBackEnd};
+    use software_back_end::{MinimalBackEnd};
+
+    let c = Context::new();
+    let mut stencil = SoftwareBackEnd::new(8, 8);
+    c.mask(&mut stencil, |c, mask| {
+        c.ellipse(-1.0, -1.0, 2.0, 2.0).rgba(1.0, 1.0, 1.0, 1.0).fill(mask).unwrap();
+    }).draw_inside(|c, back_end| {
+        c.rgba(0.0, 0.0, 1.0, 1.0).clear(back_end).unwrap();
+        c.rect(-1.0, -1.0, 1.0, 2.0).rgba(1.0, 0.0, 0.0, 0.5).fill(back_end).unwrap();
+    });
+    let mut minimal = MinimalBackEnd { software: SoftwareBackEnd::new(8, 8) };
+    c.mask(&mut minimal, |c, mask| {
+        c.ellipse(-1.0, -1.0, 2.0, 2.0).rgba(1.0, 1.0, 1.0, 1.0).fill(mask).unwrap();
+    }).draw_inside(|c, back_end| {
+        c.rgba(0.0, 0.0, 1.0, 1.0).clear(back_end).unwrap();
+        c.rect(-1.0, -1.0, 1.0, 2.0).rgba(1.0, 0.0, 0.0, 0.5).fill(back_end).unwrap();
+    });
+    // The colors are interpolated differently across the cut triangles.
+    let near = |a: &[u8], b: &[u8]| {
+        a.iter().zip(b.iter()).all(|(&a, &b)| (a as int - b as int).abs() <= 1)
+    };
+    assert!(near(stencil.pixels(), minimal.software.pixels()));
+    // The corners are outside the ellipse.
+    assert!(stencil.pixel(0, 0) == [0, 0, 0, 0]);
+    assert!(stencil.pixel(7, 7) == [0, 0, 0, 0]);
+    assert!(near(stencil.pixel(2, 4), [128, 0, 128, 255]));
+    assert!(stencil.pixel(5, 4) == [0, 0, 255, 255]);
+}
+
+#[test]
+fn test_mask_overlap() {
+    use {AddColor, AddRectangle, Fill, SoftwareBackEnd};
+    use software_back_end::{MinimalBackEnd};
+
+    let c = Context::new();
+    let mut minimal = MinimalBackEnd { software: SoftwareBackEnd::new(8, 8) };
+    c.mask(&mut minimal, |c, mask| {
+        c.rect(-1.0, -1.0, 1.5, 2.0).rgba(1.0, 1.0, 1.0, 1.0).fill(mask).unwrap();
+        c.rect(-0.5, -1.0, 1.5, 2.0).rgba(1.0, 1.0, 1.0, 1.0).fill(mask).unwrap();
+    }).draw_inside(|c, back_end| {
+        c.rect(-1.0, -1.0, 2.0, 2.0).rgba(1.0, 0.0, 0.0, 0.5).fill(back_end).unwrap();
+    });
+    // The overlap of the mask shapes is drawn once.
+    let single = minimal.software.pixel(1, 4);
+    assert!(single[3] > 0 && single[3] < 255);
+    assert!(minimal.software.pixel(4, 4) == single);
+    assert!(minimal.software.pixel(7, 4) == single);
+}
+
+#[test]
+fn test_mask_clear_alpha_blend() {
+    use {AddColor, AddRectangle, Clear, Fill, SoftwareBackEnd};
+    use software_back_end::{MinimalBackEnd};
+
+    let c = Context::new();
+    let mut minimal = MinimalBackEnd { software: SoftwareBackEnd::new(8, 8) };
+    c.mask(&mut minimal, |c, mask| {
+        c.rect(-1.0, -1.0, 2.0, 2.0).rgba(1.0, 1.0, 1.0, 1.0).fill(mask).unwrap();
+    }).draw_inside(|c, back_end| {
+        c.rgba(1.0, 0.0, 0.0, 1.0).clear(back_end).unwrap();
+        back_end.enable_alpha_blend();
+        c.rgba(0.0, 0.0, 1.0, 0.5).clear(back_end).unwrap();
+        back_end.disable_alpha_blend();
+    });
+    // The clear color replaces the background while blending is on.
+    assert!(minimal.software.pixel(4, 4) == [0, 0, 255, 128]);
+}
+
+#[test]
+fn test_mask_drop() {
+    use {AddColor, AddRectangle, Fill, SoftwareBackEnd};
+
+    let c = Context::new();
+    let mut stencil = SoftwareBackEnd::new(8, 8);
+    let _ = c.mask(&mut stencil, |c, mask| {
+        c.rect(-1.0, -1.0, 1.0, 2.0).rgba(1.0, 1.0, 1.0, 1.0).fill(mask).unwrap();
+    });
+    // Dropping the mask turns off the stencil.
+    c.rect(-1.0, -1.0, 2.0, 2.0).rgba(1.0, 0.0, 0.0, 1.0).fill(&mut stencil).unwrap();
+    assert!(stencil.pixel(6, 4) == [255, 0, 0, 255]);
+}
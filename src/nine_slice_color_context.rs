@@ -0,0 +1,247 @@
+use {
+    AddSliceMode,
+    BackEnd,
+    Borrowed,
+    Clear,
+    ClipBackEnd,
+    Draw,
+    FallbackBackEnd,
+    Field,
+    Image,
+    RenderResult,
+    SliceMode,
+    Value,
+};
+use triangulation::{
+    nine_slice_tri_list_xy_f32_uv_f32,
+};
+use internal::{
+    CanColor,
+    CanRectangle,
+    CanTransform,
+    CanViewTransform,
+    Color,
+    HasColor,
+    HasRectangle,
+    HasTransform,
+    HasViewTransform,
+    Matrix2d,
+    Rectangle,
+    Scalar,
+};
+
+/// A nine-slice image context.
+pub struct NineSliceColorContext<'a> {
+    /// Base/original transformation.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transformation.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current rectangle.
+    pub rect: Field<'a, Rectangle>,
+    /// Current image.
+    pub image: Field<'a, Image>,
+    /// Current left, top, right and bottom border in source pixels.
+    pub insets: Field<'a, [Scalar, ..4]>,
+    /// Current slice mode.
+    pub slice_mode: Field<'a, SliceMode>,
+    /// Current color.
+    pub color: Field<'a, Color>,
+}
+
+impl<'a> Clone for NineSliceColorContext<'a> {
+    #[inline(always)]
+    fn clone(&self) -> NineSliceColorContext<'static> {
+        NineSliceColorContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            rect: Value(*self.rect.get()),
+            image: Value(*self.image.get()),
+            insets: Value(*self.insets.get()),
+            slice_mode: Value(*self.slice_mode.get()),
+            color: Value(*self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasTransform<'a, Matrix2d> for NineSliceColorContext<'a> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a> CanTransform<'a, NineSliceColorContext<'a>, Matrix2d> for NineSliceColorContext<'a> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> NineSliceColorContext<'a> {
+        NineSliceColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            image: Borrowed(self.image.get()),
+            insets: Borrowed(self.insets.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasViewTransform<'a, Matrix2d> for NineSliceColorContext<'a> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a> CanViewTransform<'a, NineSliceColorContext<'a>, Matrix2d> 
+for NineSliceColorContext<'a> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> NineSliceColorContext<'a> {
+        NineSliceColorContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            image: Borrowed(self.image.get()),
+            insets: Borrowed(self.insets.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> HasColor<'a, Color> for NineSliceColorContext<'a> {
+    #[inline(always)]
+    fn get_color(&'a self) -> &'a Color {
+        self.color.get()
+    }
+}
+
+impl<'a> CanColor<'a, NineSliceColorContext<'a>, Color> for NineSliceColorContext<'a> {
+    #[inline(always)]
+    fn color(&'a self, value: Color) -> NineSliceColorContext<'a> {
+        NineSliceColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            color: Value(value),
+            rect: Borrowed(self.rect.get()),
+            image: Borrowed(self.image.get()),
+            insets: Borrowed(self.insets.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
+        }
+    }
+}
+
+impl<'a> HasRectangle<'a, Rectangle> for NineSliceColorContext<'a> {
+    #[inline(always)]
+    fn get_rectangle(&'a self) -> &'a Rectangle {
+        self.rect.get()
+    }
+}
+
+impl<'a> CanRectangle<'a, NineSliceColorContext<'a>, Rectangle> for NineSliceColorContext<'a> {
+    #[inline(always)]
+    fn rectangle(&'a self, rect: Rectangle) -> NineSliceColorContext<'a> {
+        NineSliceColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Value(rect),
+            image: Borrowed(self.image.get()),
+            insets: Borrowed(self.insets.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a> Draw<'a> for NineSliceColorContext<'a> {
+    #[inline(always)]
+    fn draw<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_texture_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let rect = self.rect.get();
+                let color = self.color.get();
+                let texture_id = self.image.get().texture_id;
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                // Turn on alpha blending if not completely opaque or if the texture has alpha channel.
+                let needs_alpha = color[3] != 1.0 || back_end.has_texture_alpha(texture_id);
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                back_end.enable_single_texture(texture_id);
+                let (vertices, uvs) = nine_slice_tri_list_xy_f32_uv_f32(
+                    *self.transform.get(),
+                    *rect,
+                    self.image.get(),
+                    *self.insets.get(),
+                    *self.slice_mode.get()
+                );
+                let colors = Vec::from_fn(vertices.len() * 2, |i| color[i % 4]);
+                back_end.tri_list_xy_f32_rgba_f32_uv_f32(
+                    vertices.as_slice(),
+                    colors.as_slice(),
+                    uvs.as_slice()
+                );
+                back_end.disable_single_texture();
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+impl<'a> Clear for NineSliceColorContext<'a> {
+    #[inline(always)]
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
+
+impl<'a> AddSliceMode<'a, NineSliceColorContext<'a>> for NineSliceColorContext<'a> {
+    #[inline(always)]
+    fn slice_mode(&'a self, mode: SliceMode) -> NineSliceColorContext<'a> {
+        NineSliceColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            image: Borrowed(self.image.get()),
+            insets: Borrowed(self.insets.get()),
+            slice_mode: Value(mode),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+#[test]
+fn test_nine_slice() {
+    use {AddColor, AddImage, AddNineSlice, AddRectangle, Context, RelativeTransform2d};
+    use {SoftwareBackEnd};
+
+    // A 3x3 texture with a red center and blue borders.
+    let mut pixels = Vec::new();
+    for i in range(0u, 9) {
+        pixels.push_all(if i == 4 { [255u8, 0, 0, 255] } else { [0u8, 0, 255, 255] });
+    }
+    let mut back_end = SoftwareBackEnd::new(8, 8);
+    let texture_id = back_end.add_texture(3, 3, pixels.as_slice());
+    let image = Image {
+        texture_id: texture_id,
+        texture_width: 3,
+        texture_height: 3,
+        source_rect: [0, 0, 3, 3],
+    };
+    let c = Context::new();
+    let c = c.scale(2.0 / 8.0, -2.0 / 8.0);
+    let d = c.trans(-1.0, 1.0);
+    d.rect(0.0, 0.0, 8.0, 8.0).rgba(1.0, 1.0, 1.0, 1.0).image(image)
+        .nine_slice(1.0, 1.0, 1.0, 1.0).tile().draw(&mut back_end).unwrap();
+    // The borders stay one pixel wide.
+    assert!(back_end.pixel(0, 4) == [0, 0, 255, 255]);
+    assert!(back_end.pixel(7, 7) == [0, 0, 255, 255]);
+    assert!(back_end.pixel(1, 1) == [255, 0, 0, 255]);
+    assert!(back_end.pixel(6, 6) == [255, 0, 0, 255]);
+}
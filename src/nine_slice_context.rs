@@ -0,0 +1,229 @@
+use {
+    AddColor,
+    AddSliceMode,
+    BackEnd,
+    Borrowed,
+    ClipBackEnd,
+    Draw,
+    FallbackBackEnd,
+    Field,
+    Image,
+    NineSliceColorContext,
+    RenderResult,
+    SliceMode,
+    Value,
+};
+use triangulation::{
+    nine_slice_tri_list_xy_f32_uv_f32,
+};
+use internal::{
+    CanColor,
+    CanRectangle,
+    CanTransform,
+    CanViewTransform,
+    Color,
+    ColorComponent,
+    HasColor,
+    HasRectangle,
+    HasTransform,
+    HasViewTransform,
+    Matrix2d,
+    Rectangle,
+    Scalar,
+};
+
+/// A nine-slice image context.
+pub struct NineSliceContext<'a> {
+    /// Base/original transformation.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transformation.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current rectangle.
+    pub rect: Field<'a, Rectangle>,
+    /// Current image.
+    pub image: Field<'a, Image>,
+    /// Current left, top, right and bottom border in source pixels.
+    pub insets: Field<'a, [Scalar, ..4]>,
+    /// Current slice mode.
+    pub slice_mode: Field<'a, SliceMode>,
+}
+
+impl<'a> Clone for NineSliceContext<'a> {
+    #[inline(always)]
+    fn clone(&self) -> NineSliceContext<'static> {
+        NineSliceContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            rect: Value(*self.rect.get()),
+            image: Value(*self.image.get()),
+            insets: Value(*self.insets.get()),
+            slice_mode: Value(*self.slice_mode.get()),
+        }
+    }
+}
+
+impl<'a> HasTransform<'a, Matrix2d> for NineSliceContext<'a> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a> CanTransform<'a, NineSliceContext<'a>, Matrix2d> for NineSliceContext<'a> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> NineSliceContext<'a> {
+        NineSliceContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            image: Borrowed(self.image.get()),
+            insets: Borrowed(self.insets.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
+        }
+    }
+}
+
+impl<'a> HasViewTransform<'a, Matrix2d> for NineSliceContext<'a> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a> CanViewTransform<'a, NineSliceContext<'a>, Matrix2d> 
+for NineSliceContext<'a> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> NineSliceContext<'a> {
+        NineSliceContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            image: Borrowed(self.image.get()),
+            insets: Borrowed(self.insets.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
+        }
+    }
+}
+
+static WHITE: &'static Color = &[1.0, ..4];
+
+impl<'a> HasColor<'a, Color> for NineSliceContext<'a> {
+    #[inline(always)]
+    fn get_color(&'a self) -> &'a Color {
+        WHITE
+    }
+}
+
+impl<'a> CanColor<'a, NineSliceColorContext<'a>, Color> for NineSliceContext<'a> {
+    #[inline(always)]
+    fn color(&'a self, value: Color) -> NineSliceColorContext<'a> {
+        NineSliceColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            color: Value(value),
+            rect: Borrowed(self.rect.get()),
+            image: Borrowed(self.image.get()),
+            insets: Borrowed(self.insets.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
+        }
+    }
+}
+
+impl<'a> HasRectangle<'a, Rectangle> for NineSliceContext<'a> {
+    #[inline(always)]
+    fn get_rectangle(&'a self) -> &'a Rectangle {
+        self.rect.get()
+    }
+}
+
+impl<'a> CanRectangle<'a, NineSliceContext<'a>, Rectangle> for NineSliceContext<'a> {
+    #[inline(always)]
+    fn rectangle(&'a self, rect: Rectangle) -> NineSliceContext<'a> {
+        NineSliceContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Value(rect),
+            image: Borrowed(self.image.get()),
+            insets: Borrowed(self.insets.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
+        }
+    }
+}
+
+impl<'a> Draw<'a> for NineSliceContext<'a> {
+    #[inline(always)]
+    fn draw<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_texture_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let rect = self.rect.get();
+                let color: [f32, ..4] = [1.0, 1.0, 1.0, 1.0];
+                let texture_id = self.image.get().texture_id;
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                // Turn on alpha blending if not completely opaque or if the texture has alpha channel.
+                let needs_alpha = color[3] != 1.0 || back_end.has_texture_alpha(texture_id);
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                back_end.enable_single_texture(texture_id);
+                let (vertices, uvs) = nine_slice_tri_list_xy_f32_uv_f32(
+                    *self.transform.get(),
+                    *rect,
+                    self.image.get(),
+                    *self.insets.get(),
+                    *self.slice_mode.get()
+                );
+                let colors = Vec::from_fn(vertices.len() * 2, |i| color[i % 4]);
+                back_end.tri_list_xy_f32_rgba_f32_uv_f32(
+                    vertices.as_slice(),
+                    colors.as_slice(),
+                    uvs.as_slice()
+                );
+                back_end.disable_single_texture();
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+impl<'a> AddColor<'a, NineSliceColorContext<'a>> for NineSliceContext<'a> {
+    #[inline(always)]
+    fn rgba(
+        &'a self, 
+        r: ColorComponent, 
+        g: ColorComponent, 
+        b: ColorComponent, 
+        a: ColorComponent
+    ) -> NineSliceColorContext<'a> {
+        NineSliceColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            image: Borrowed(self.image.get()),
+            insets: Borrowed(self.insets.get()),
+            slice_mode: Borrowed(self.slice_mode.get()),
+            color: Value([r, g, b, a]),
+        }
+    }
+}
+
+impl<'a> AddSliceMode<'a, NineSliceContext<'a>> for NineSliceContext<'a> {
+    #[inline(always)]
+    fn slice_mode(&'a self, mode: SliceMode) -> NineSliceContext<'a> {
+        NineSliceContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            image: Borrowed(self.image.get()),
+            insets: Borrowed(self.insets.get()),
+            slice_mode: Value(mode),
+        }
+    }
+}
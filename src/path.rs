@@ -0,0 +1,279 @@
+
+use std::cmp::{min};
+use std::f64::consts::{PI_2};
+use interpolation::{
+    cub_bez,
+    quad_bez,
+};
+use internal::{
+    Radius,
+    Scalar,
+    Vec2d,
+};
+use triangulation::{
+    arc_resolution,
+};
+
+/// A segment in a path.
+enum Segment {
+    MoveTo(Vec2d),
+    LineTo(Vec2d),
+    QuadTo(Vec2d, Vec2d),
+    CubicTo(Vec2d, Vec2d, Vec2d),
+    ArcTo(Radius, Radius, Scalar, bool, bool, Vec2d),
+    Close,
+}
+
+/// A vector path built from lines and curves.
+///
+/// The commands work like in SVG.
+/// A path can contain several sub-paths, each starting with `move_to`.
+///
+/// ```
+/// let path = Path::new()
+///     .move_to(0.0, 0.0)
+///     .line_to(100.0, 0.0)
+///     .quad_to(100.0, 100.0, 0.0, 100.0)
+///     .close();
+/// ```
+pub struct Path {
+    segments: Vec<Segment>,
+}
+
+impl Path {
+    /// Creates a new empty path.
+    pub fn new() -> Path {
+        Path {
+            segments: Vec::new(),
+        }
+    }
+
+    /// Starts a new sub-path at a point.
+    pub fn move_to(mut self, x: Scalar, y: Scalar) -> Path {
+        self.segments.push(MoveTo([x, y]));
+        self
+    }
+
+    /// Adds a straight line to a point.
+    pub fn line_to(mut self, x: Scalar, y: Scalar) -> Path {
+        self.segments.push(LineTo([x, y]));
+        self
+    }
+
+    /// Adds a quadratic beziér curve with a control point.
+    pub fn quad_to(mut self, cx: Scalar, cy: Scalar, x: Scalar, y: Scalar) -> Path {
+        self.segments.push(QuadTo([cx, cy], [x, y]));
+        self
+    }
+
+    /// Adds a cubic beziér curve with two control points.
+    pub fn cubic_to(
+        mut self,
+        cx1: Scalar, cy1: Scalar,
+        cx2: Scalar, cy2: Scalar,
+        x: Scalar, y: Scalar
+    ) -> Path {
+        self.segments.push(CubicTo([cx1, cy1], [cx2, cy2], [x, y]));
+        self
+    }
+
+    /// Adds an elliptical arc to a point.
+    ///
+    /// The ellipse has radii `rx` and `ry` and is rotated by `rotation` radians.
+    /// Of the four possible arcs, `large_arc` picks the one larger than half a turn
+    /// and `sweep` picks the one going in positive angle direction.
+    pub fn arc_to(
+        mut self,
+        rx: Radius, ry: Radius,
+        rotation: Scalar,
+        large_arc: bool,
+        sweep: bool,
+        x: Scalar, y: Scalar
+    ) -> Path {
+        self.segments.push(ArcTo(rx, ry, rotation, large_arc, sweep, [x, y]));
+        self
+    }
+
+    /// Closes the current sub-path with a line back to its start.
+    pub fn close(mut self) -> Path {
+        self.segments.push(Close);
+        self
+    }
+
+    /// Converts curves into lines and streams each sub-path as a polyline.
+    ///
+    /// The tolerance is the maximum distance between the curves and the lines.
+    /// The closure is called with [x0, y0, x1, y1, ...] and whether the sub-path is closed.
+    pub fn flatten(&self, tolerance: Scalar, f: |polyline: &[Scalar], closed: bool|) {
+        let mut polyline: Vec<Scalar> = Vec::new();
+        let mut start = [0.0, 0.0];
+        let mut current = [0.0, 0.0];
+        for segment in self.segments.iter() {
+            match *segment {
+                MoveTo(p) => {
+                    if polyline.len() >= 4 { f(polyline.as_slice(), false); }
+                    polyline.clear();
+                    polyline.push_all(p);
+                    start = p;
+                    current = p;
+                    continue;
+                },
+                Close => {
+                    if polyline.len() >= 4 { f(polyline.as_slice(), true); }
+                    polyline.clear();
+                    current = start;
+                    continue;
+                },
+                _ => {},
+            }
+
+            // Drawing after closing continues from the start point.
+            if polyline.len() == 0 { polyline.push_all(current); }
+            let p0 = current;
+            match *segment {
+                LineTo(p) => {
+                    polyline.push_all(p);
+                    current = p;
+                },
+                QuadTo(p1, p2) => {
+                    // The second derivative bounds the error of the lines.
+                    let (ddx, ddy) = (p0[0] - 2.0 * p1[0] + p2[0], p0[1] - 2.0 * p1[1] + p2[1]);
+                    let dd = (ddx * ddx + ddy * ddy).sqrt();
+                    let n = curve_resolution(dd * 0.25, tolerance);
+                    for i in range(1, n + 1) {
+                        let t = i as Scalar / n as Scalar;
+                        polyline.push(quad_bez(&p0[0], &p1[0], &p2[0], &t));
+                        polyline.push(quad_bez(&p0[1], &p1[1], &p2[1], &t));
+                    }
+                    current = p2;
+                },
+                CubicTo(p1, p2, p3) => {
+                    let (ax, ay) = (p0[0] - 2.0 * p1[0] + p2[0], p0[1] - 2.0 * p1[1] + p2[1]);
+                    let (bx, by) = (p1[0] - 2.0 * p2[0] + p3[0], p1[1] - 2.0 * p2[1] + p3[1]);
+                    let dd = (ax * ax + ay * ay).sqrt().max((bx * bx + by * by).sqrt());
+                    let n = curve_resolution(dd * 0.75, tolerance);
+                    for i in range(1, n + 1) {
+                        let t = i as Scalar / n as Scalar;
+                        polyline.push(cub_bez(&p0[0], &p1[0], &p2[0], &p3[0], &t));
+                        polyline.push(cub_bez(&p0[1], &p1[1], &p2[1], &p3[1], &t));
+                    }
+                    current = p3;
+                },
+                ArcTo(rx, ry, rotation, large_arc, sweep, p) => {
+                    flatten_arc(p0, rx, ry, rotation, large_arc, sweep, p, tolerance,
+                        |q| polyline.push_all(q));
+                    current = p;
+                },
+                MoveTo(_) | Close => {},
+            }
+        }
+        if polyline.len() >= 4 { f(polyline.as_slice(), false); }
+    }
+}
+
+/// Computes the number of lines needed for a curve.
+///
+/// The error of `n` lines is `error_factor / n^2`.
+#[inline(always)]
+fn curve_resolution(error_factor: Scalar, tolerance: Scalar) -> uint {
+    let n = (error_factor / tolerance).sqrt().ceil();
+    if n >= 1.0 { min(n as uint, 1024) } else { 1 }
+}
+
+/// Converts an SVG arc to center parameterization and streams the points after the first.
+///
+/// Source: http://www.w3.org/TR/SVG/implnote.html#ArcImplementationNotes
+fn flatten_arc(
+    p0: Vec2d,
+    rx: Radius,
+    ry: Radius,
+    rotation: Scalar,
+    large_arc: bool,
+    sweep: bool,
+    p1: Vec2d,
+    tolerance: Scalar,
+    f: |Vec2d|
+) {
+    let (mut rx, mut ry) = (rx.abs(), ry.abs());
+    if rx == 0.0 || ry == 0.0 || p0 == p1 {
+        f(p1);
+        return;
+    }
+
+    let (cos, sin) = (rotation.cos(), rotation.sin());
+    let (dx, dy) = (0.5 * (p0[0] - p1[0]), 0.5 * (p0[1] - p1[1]));
+    let x = cos * dx + sin * dy;
+    let y = -sin * dx + cos * dy;
+    // Scale up radii that are too small to reach the end point.
+    let lambda = (x * x) / (rx * rx) + (y * y) / (ry * ry);
+    if lambda > 1.0 {
+        let s = lambda.sqrt();
+        rx *= s;
+        ry *= s;
+    }
+    let num = rx * rx * ry * ry - rx * rx * y * y - ry * ry * x * x;
+    let den = rx * rx * y * y + ry * ry * x * x;
+    let coef = (num / den).max(0.0).sqrt();
+    let coef = if large_arc == sweep { -coef } else { coef };
+    let (cx, cy) = (coef * rx * y / ry, -coef * ry * x / rx);
+    let center = [
+        cos * cx - sin * cy + 0.5 * (p0[0] + p1[0]),
+        sin * cx + cos * cy + 0.5 * (p0[1] + p1[1])
+    ];
+    let start_angle = ((y - cy) / ry).atan2((x - cx) / rx);
+    let end_angle = ((-y - cy) / ry).atan2((-x - cx) / rx);
+    let mut delta = end_angle - start_angle;
+    if sweep && delta < 0.0 { delta += PI_2; }
+    if !sweep && delta > 0.0 { delta -= PI_2; }
+
+    let n = arc_resolution(rx.max(ry), delta.abs(), tolerance);
+    for i in range(1, n) {
+        let angle = start_angle + delta * i as Scalar / n as Scalar;
+        let (ex, ey) = (rx * angle.cos(), ry * angle.sin());
+        f([center[0] + cos * ex - sin * ey, center[1] + sin * ex + cos * ey]);
+    }
+    // Use the exact end point.
+    f(p1);
+}
+
+#[test]
+fn test_flatten_lines() {
+    let path = Path::new()
+        .move_to(0.0, 0.0)
+        .line_to(1.0, 0.0)
+        .line_to(1.0, 1.0)
+        .close()
+        .line_to(0.0, 1.0);
+    let mut polylines = Vec::new();
+    path.flatten(0.01, |polyline, closed| {
+        polylines.push((Vec::from_slice(polyline), closed));
+    });
+    assert_eq!(polylines.len(), 2);
+    let (ref first, first_closed) = *polylines.get(0);
+    assert_eq!(first.as_slice(), &[0.0, 0.0, 1.0, 0.0, 1.0, 1.0]);
+    assert!(first_closed);
+    let (ref second, second_closed) = *polylines.get(1);
+    assert_eq!(second.as_slice(), &[0.0, 0.0, 0.0, 1.0]);
+    assert!(!second_closed);
+}
+
+#[test]
+fn test_flatten_curves() {
+    let tolerance = 0.001;
+    let path = Path::new()
+        .move_to(1.0, 0.0)
+        .arc_to(1.0, 1.0, 0.0, false, true, -1.0, 0.0)
+        .quad_to(0.0, -2.0, 1.0, 0.0);
+    path.flatten(tolerance, |polyline, _| {
+        let n = polyline.len() / 2;
+        assert!(n > 10);
+        // The arc is a half circle around the origin.
+        for i in range(0, n) {
+            let (x, y) = (polyline[i * 2], polyline[i * 2 + 1]);
+            if y < 0.0 { continue; }
+            assert!(((x * x + y * y).sqrt() - 1.0).abs() < tolerance);
+        }
+        assert_eq!(polyline[n * 2 - 2], 1.0);
+        assert_eq!(polyline[n * 2 - 1], 0.0);
+    });
+}
@@ -0,0 +1,227 @@
+use {
+    AddBorder,
+    AddFillRule,
+    BackEnd,
+    BorderPathColorContext,
+    Borrowed,
+    ButtCap,
+    Clear,
+    ClipBackEnd,
+    FallbackBackEnd,
+    Field,
+    Fill,
+    FillRule,
+    MiterJoin,
+    Path,
+    RenderResult,
+    Value,
+};
+use triangulation::{
+    Coordinate,
+    DEFAULT_MITER_LIMIT,
+    DEFAULT_TOLERANCE,
+    with_path_tri_list_xy_rgba_f32
+};
+use internal::{
+    CanColor,
+    CanTransform,
+    CanViewTransform,
+    Color,
+    HasColor,
+    HasTransform,
+    HasViewTransform,
+    Matrix2d,
+    Radius,
+    Rectangle,
+};
+
+/// A path color context.
+pub struct PathColorContext<'a, 'b> {
+    /// Base/original transformation.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transformation.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current path.
+    pub path: Field<'a, &'b Path>,
+    /// Current fill rule.
+    pub fill_rule: Field<'a, FillRule>,
+    /// Current color.
+    pub color: Field<'a, Color>,
+}
+
+impl<'a, 'b> Clone for PathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn clone(&self) -> PathColorContext<'static, 'b> {
+        PathColorContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            path: Value(*self.path.get()),
+            fill_rule: Value(*self.fill_rule.get()),
+            color: Value(*self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasTransform<'a, Matrix2d> for PathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a, 'b> CanTransform<'a, PathColorContext<'a, 'b>, Matrix2d> for PathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> PathColorContext<'a, 'b> {
+        PathColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            fill_rule: Borrowed(self.fill_rule.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasViewTransform<'a, Matrix2d> for PathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a, 'b> CanViewTransform<'a, PathColorContext<'a, 'b>, Matrix2d> for PathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> PathColorContext<'a, 'b> {
+        PathColorContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            fill_rule: Borrowed(self.fill_rule.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasColor<'a, Color> for PathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_color(&'a self) -> &'a Color {
+        self.color.get()
+    }
+}
+
+impl<'a, 'b> CanColor<'a, PathColorContext<'a, 'b>, Color> for PathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn color(&'a self, value: Color) -> PathColorContext<'a, 'b> {
+        PathColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            fill_rule: Borrowed(self.fill_rule.get()),
+            color: Value(value),
+        }
+    }
+}
+
+impl<'a, 'b> AddFillRule<'a, PathColorContext<'a, 'b>> for PathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn fill_rule(&'a self, fill_rule: FillRule) -> PathColorContext<'a, 'b> {
+        PathColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            fill_rule: Value(fill_rule),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> AddBorder<'a, BorderPathColorContext<'a, 'b>> for PathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn border_radius(&'a self, radius: Radius) -> BorderPathColorContext<'a, 'b> {
+        BorderPathColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            border_radius: Value(radius),
+            join: Value(MiterJoin),
+            miter_limit: Value(DEFAULT_MITER_LIMIT),
+            start_cap: Value(ButtCap),
+            end_cap: Value(ButtCap),
+            dash: Value(None),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> PathColorContext<'a, 'b> {
+    /// Streams the filled path with f32 or f64 vertices.
+    fn stream_tri_list<V: Coordinate>(&self, f: |vertices: &[V], colors: &[f32]|) {
+        with_path_tri_list_xy_rgba_f32(
+            DEFAULT_TOLERANCE,
+            *self.transform.get(),
+            *self.path.get(),
+            *self.fill_rule.get(),
+            *self.color.get(),
+            f
+        );
+    }
+}
+
+impl<'a, 'b> Fill<'a> for PathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn fill<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let color = self.color.get();
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                // Turn on alpha blending if not completely opaque.
+                let needs_alpha = color[3] != 1.0;
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                if back_end.supports_tri_list_xy_f64_rgba_f32() {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f64_rgba_f32(vertices, colors)
+                    });
+                } else {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+                    });
+                }
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+impl<'a, 'b> Clear for PathColorContext<'a, 'b> {
+    #[inline(always)]
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
+
+#[test]
+fn test_fill_path() {
+    use {AddColor, AddPath, Context, SoftwareBackEnd};
+
+    // A half circle covering the upper half of the framebuffer.
+    let path = Path::new()
+        .move_to(1.0, 0.0)
+        .arc_to(1.0, 1.0, 0.0, false, true, -1.0, 0.0)
+        .close();
+    let mut back_end = SoftwareBackEnd::new(8, 8);
+    let c = Context::new();
+    c.path(&path).rgba(1.0, 1.0, 1.0, 1.0).fill(&mut back_end).unwrap();
+    assert!(back_end.pixel(4, 1) == [255, 255, 255, 255]);
+    assert!(back_end.pixel(4, 6) == [0, 0, 0, 0]);
+    // The corners are outside the circle.
+    assert!(back_end.pixel(0, 0) == [0, 0, 0, 0]);
+}
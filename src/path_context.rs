@@ -0,0 +1,119 @@
+use {
+    AddColor,
+    AddFillRule,
+    Borrowed,
+    Field,
+    FillRule,
+    Path,
+    PathColorContext,
+    Value,
+};
+use internal::{
+    CanTransform,
+    CanViewTransform,
+    ColorComponent,
+    HasTransform,
+    HasViewTransform,
+    Matrix2d,
+    Rectangle,
+};
+
+/// A path context.
+pub struct PathContext<'a, 'b> {
+    /// Base/original transformation.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transformation.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current path.
+    pub path: Field<'a, &'b Path>,
+    /// Current fill rule.
+    pub fill_rule: Field<'a, FillRule>,
+}
+
+impl<'a, 'b> Clone for PathContext<'a, 'b> {
+    #[inline(always)]
+    fn clone(&self) -> PathContext<'static, 'b> {
+        PathContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            path: Value(*self.path.get()),
+            fill_rule: Value(*self.fill_rule.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasTransform<'a, Matrix2d> for PathContext<'a, 'b> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a, 'b> CanTransform<'a, PathContext<'a, 'b>, Matrix2d> for PathContext<'a, 'b> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> PathContext<'a, 'b> {
+        PathContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            fill_rule: Borrowed(self.fill_rule.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasViewTransform<'a, Matrix2d> for PathContext<'a, 'b> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a, 'b> CanViewTransform<'a, PathContext<'a, 'b>, Matrix2d> for PathContext<'a, 'b> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> PathContext<'a, 'b> {
+        PathContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            fill_rule: Borrowed(self.fill_rule.get()),
+        }
+    }
+}
+
+impl<'a, 'b> AddFillRule<'a, PathContext<'a, 'b>> for PathContext<'a, 'b> {
+    #[inline(always)]
+    fn fill_rule(&'a self, fill_rule: FillRule) -> PathContext<'a, 'b> {
+        PathContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            fill_rule: Value(fill_rule),
+        }
+    }
+}
+
+impl<'a, 'b> AddColor<'a, PathColorContext<'a, 'b>> for PathContext<'a, 'b> {
+    #[inline(always)]
+    fn rgba(
+        &'a self, 
+        r: ColorComponent, 
+        g: ColorComponent, 
+        b: ColorComponent, 
+        a: ColorComponent
+    ) -> PathColorContext<'a, 'b> {
+        PathColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            path: Borrowed(self.path.get()),
+            fill_rule: Borrowed(self.fill_rule.get()),
+            color: Value([r, g, b, a]),
+        }
+    }
+}
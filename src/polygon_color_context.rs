@@ -1,13 +1,31 @@
 use {
+    AddBorder,
+    AddGradient,
+    AddImage,
     BackEnd,
+    BorderPolygonColorContext,
     Borrowed,
     Clear,
+    ClipBackEnd,
+    FallbackBackEnd,
     Field,
     Fill,
+    Gradient,
+    Image,
+    ImagePolygonColorContext,
+    MiterJoin,
+    RenderResult,
     Value,
 };
 use triangulation::{
-    with_polygon_tri_list_xy_f32_rgba_f32
+    Coordinate,
+    DEFAULT_MITER_LIMIT,
+    polygon_tri_list_indexed_xy_f32_rgba_f32,
+    with_polygon_gradient_tri_list_xy_rgba_f32,
+    with_polygon_tri_list_xy_rgba_f32
+};
+use vecmath::{
+    identity,
 };
 use internal::{
     CanColor,
@@ -19,6 +37,8 @@ use internal::{
     HasViewTransform,
     Matrix2d,
     Polygon,
+    Radius,
+    Rectangle,
 };
 
 /// A polygon color context.
@@ -27,8 +47,12 @@ pub struct PolygonColorContext<'a, 'b> {
     pub base: Field<'a, Matrix2d>,
     /// Current transform.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current color.
     pub color: Field<'a, Color>,
+    /// Current gradient.
+    pub gradient: Field<'a, Option<Gradient>>,
     /// Current polygon.
     pub polygon: Field<'a, Polygon<'b>>,
 }
@@ -39,8 +63,10 @@ impl<'a, 'b> Clone for PolygonColorContext<'a, 'b> {
         PolygonColorContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             polygon: Value(*self.polygon.get()),
             color: Value(*self.color.get()),
+            gradient: Value(*self.gradient.get()),
         }
     }
 }
@@ -58,8 +84,10 @@ impl<'a, 'b> CanTransform<'a, PolygonColorContext<'a, 'b>, Matrix2d> for Polygon
         PolygonColorContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             polygon: Borrowed(self.polygon.get()),
             color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
         }
     }
 }
@@ -78,8 +106,10 @@ for PolygonColorContext<'a, 'b> {
         PolygonColorContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             polygon: Borrowed(self.polygon.get()),
             color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
         }
     }
 }
@@ -97,47 +127,118 @@ impl<'a, 'b> CanColor<'a, PolygonColorContext<'a, 'b>, Color> for PolygonColorCo
         PolygonColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Value(value),
+            gradient: Borrowed(self.gradient.get()),
             polygon: Borrowed(self.polygon.get()),
         }
     }
 }
 
+impl<'a, 'b> PolygonColorContext<'a, 'b> {
+    /// Streams the filled polygon with f32 or f64 vertices.
+    fn stream_tri_list<V: Coordinate>(&self, f: |vertices: &[V], colors: &[f32]|) {
+        match *self.gradient.get() {
+            Some(ref gradient) => with_polygon_gradient_tri_list_xy_rgba_f32(
+                *self.transform.get(), *self.polygon.get(), gradient, f),
+            None => with_polygon_tri_list_xy_rgba_f32(
+                *self.transform.get(), *self.polygon.get(), *self.color.get(), f),
+        }
+    }
+}
+
 impl<'a, 'b> Fill<'a> for PolygonColorContext<'a, 'b> {
     #[inline(always)]
-    fn fill<B: BackEnd>(&'a self, back_end: &mut B) {
-        if back_end.supports_tri_list_xy_f32_rgba_f32() {
-            let polygon = self.polygon.get();
-            let color = self.color.get();
-            // Complete transparency does not need to be rendered.
-            if color[3] == 0.0 { return; }
-            // Turn on alpha blending if not completely opaque.
-            let needs_alpha = color[3] != 1.0;
-            if needs_alpha { back_end.enable_alpha_blend(); }
-            with_polygon_tri_list_xy_f32_rgba_f32(
-                *self.transform.get(),
-                *polygon,
-                *color,
-                |vertices, colors| {
-                    back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+    fn fill<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let needs_alpha = match *self.gradient.get() {
+                    Some(ref gradient) => {
+                        // Complete transparency does not need to be rendered.
+                        if gradient.is_invisible() { return; }
+                        // Turn on alpha blending if any stop is not completely opaque.
+                        gradient.has_alpha()
+                    },
+                    None => {
+                        let color = self.color.get();
+                        // Complete transparency does not need to be rendered.
+                        if color[3] == 0.0 { return; }
+                        // Turn on alpha blending if not completely opaque.
+                        color[3] != 1.0
+                    },
+                };
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                // Gradients are only streamed as triangle lists.
+                let solid = self.gradient.get().is_none();
+                if back_end.supports_tri_list_xy_f64_rgba_f32() {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f64_rgba_f32(vertices, colors)
+                    });
+                } else if solid && back_end.supports_tri_list_indexed_xy_f32_rgba_f32() {
+                    let (vertices, colors, indices) = polygon_tri_list_indexed_xy_f32_rgba_f32(
+                        *self.transform.get(), *self.polygon.get(), *self.color.get());
+                    back_end.tri_list_indexed_xy_f32_rgba_f32(
+                        vertices.as_slice(), colors.as_slice(), indices.as_slice());
+                } else {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+                    });
                 }
-            );
-            if needs_alpha { back_end.disable_alpha_blend(); }
-        } else {
-            unimplemented!();
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+impl<'a, 'b> AddGradient<'a, PolygonColorContext<'a, 'b>> for PolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn gradient(&'a self, gradient: Gradient) -> PolygonColorContext<'a, 'b> {
+        PolygonColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            color: Borrowed(self.color.get()),
+            gradient: Value(Some(gradient)),
+            polygon: Borrowed(self.polygon.get()),
         }
     }
 }
 
 impl<'a, 'b> Clear for PolygonColorContext<'a, 'b> {
     #[inline(always)]
-    fn clear<B: BackEnd>(&self, back_end: &mut B) {
-        if back_end.supports_clear_rgba() {
-            let color = self.color.get();
-            back_end.clear_rgba(color[0], color[1], color[2], color[3]);
-        } else {
-            unimplemented!();
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
+
+impl<'a, 'b> AddBorder<'a, BorderPolygonColorContext<'a, 'b>> for PolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn border_radius(&'a self, radius: Radius) -> BorderPolygonColorContext<'a, 'b> {
+        BorderPolygonColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            border_radius: Value(radius),
+            join: Value(MiterJoin),
+            miter_limit: Value(DEFAULT_MITER_LIMIT),
+            dash: Value(None),
+            color: Borrowed(self.color.get()),
         }
     }
 }
 
+impl<'a, 'b> AddImage<'a, ImagePolygonColorContext<'a, 'b>> for PolygonColorContext<'a, 'b> {
+    #[inline(always)]
+    fn image(&'a self, image: Image) -> ImagePolygonColorContext<'a, 'b> {
+        ImagePolygonColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            image: Value(image),
+            uv_transform: Value(identity()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
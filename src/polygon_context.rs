@@ -1,10 +1,18 @@
 use {
     AddColor,
+    AddGradient,
+    AddImage,
     Borrowed,
     Field,
+    Gradient,
+    Image,
+    ImagePolygonContext,
     PolygonColorContext,
     Value,
 };
+use vecmath::{
+    identity,
+};
 use internal::{
     CanTransform,
     CanViewTransform,
@@ -13,6 +21,7 @@ use internal::{
     HasViewTransform,
     Matrix2d,
     Polygon,
+    Rectangle,
 };
 
 /// A polygon context.
@@ -21,6 +30,8 @@ pub struct PolygonContext<'a, 'b> {
     pub base: Field<'a, Matrix2d>,
     /// Current transform.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current polygon.
     pub polygon: Field<'a, Polygon<'b>>
 }
@@ -31,6 +42,7 @@ impl<'a, 'b> Clone for PolygonContext<'a, 'b> {
         PolygonContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             polygon: Value(*self.polygon.get()),
         }
     }
@@ -49,6 +61,7 @@ impl<'a, 'b> CanTransform<'a, PolygonContext<'a, 'b>, Matrix2d> for PolygonConte
         PolygonContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             polygon: Borrowed(self.polygon.get()),
         }
     }
@@ -68,6 +81,7 @@ for PolygonContext<'a, 'b> {
         PolygonContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             polygon: Borrowed(self.polygon.get()),
         }
     }
@@ -85,9 +99,38 @@ impl<'a, 'b> AddColor<'a, PolygonColorContext<'a, 'b>> for PolygonContext<'a, 'b
         PolygonColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Value([r, g, b, a]),
+            gradient: Value(None),
             polygon: Borrowed(self.polygon.get()),
         }
     }
 }
 
+impl<'a, 'b> AddGradient<'a, PolygonColorContext<'a, 'b>> for PolygonContext<'a, 'b> {
+    #[inline(always)]
+    fn gradient(&'a self, gradient: Gradient) -> PolygonColorContext<'a, 'b> {
+        PolygonColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            color: Value(gradient.stops()[0].val1()),
+            gradient: Value(Some(gradient)),
+            polygon: Borrowed(self.polygon.get()),
+        }
+    }
+}
+
+impl<'a, 'b> AddImage<'a, ImagePolygonContext<'a, 'b>> for PolygonContext<'a, 'b> {
+    #[inline(always)]
+    fn image(&'a self, image: Image) -> ImagePolygonContext<'a, 'b> {
+        ImagePolygonContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polygon: Borrowed(self.polygon.get()),
+            image: Value(image),
+            uv_transform: Value(identity()),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use {
+    AddBorder,
+    BackEnd,
+    BorderPolylineColorContext,
+    Borrowed,
+    ButtCap,
+    Clear,
+    ClipBackEnd,
+    Field,
+    MiterJoin,
+    RenderResult,
+    Value,
+};
+use triangulation::{
+    DEFAULT_MITER_LIMIT,
+};
+use internal::{
+    CanColor,
+    CanTransform,
+    CanViewTransform,
+    Color,
+    HasColor,
+    HasTransform,
+    HasViewTransform,
+    Matrix2d,
+    Polyline,
+    Radius,
+    Rectangle,
+};
+
+/// A polyline color context.
+pub struct PolylineColorContext<'a, 'b> {
+    /// Base/original transformation.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transformation.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current polyline.
+    pub polyline: Field<'a, Polyline<'b>>,
+    /// Whether the last point connects to the first.
+    pub closed: Field<'a, bool>,
+    /// Current color.
+    pub color: Field<'a, Color>,
+}
+
+impl<'a, 'b> Clone for PolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn clone(&self) -> PolylineColorContext<'static, 'b> {
+        PolylineColorContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            polyline: Value(*self.polyline.get()),
+            closed: Value(*self.closed.get()),
+            color: Value(*self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasTransform<'a, Matrix2d> for PolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a, 'b> CanTransform<'a, PolylineColorContext<'a, 'b>, Matrix2d> for PolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> PolylineColorContext<'a, 'b> {
+        PolylineColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            polyline: Borrowed(self.polyline.get()),
+            closed: Borrowed(self.closed.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasViewTransform<'a, Matrix2d> for PolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a, 'b> CanViewTransform<'a, PolylineColorContext<'a, 'b>, Matrix2d> for PolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> PolylineColorContext<'a, 'b> {
+        PolylineColorContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polyline: Borrowed(self.polyline.get()),
+            closed: Borrowed(self.closed.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasColor<'a, Color> for PolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_color(&'a self) -> &'a Color {
+        self.color.get()
+    }
+}
+
+impl<'a, 'b> CanColor<'a, PolylineColorContext<'a, 'b>, Color> for PolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn color(&'a self, value: Color) -> PolylineColorContext<'a, 'b> {
+        PolylineColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polyline: Borrowed(self.polyline.get()),
+            closed: Borrowed(self.closed.get()),
+            color: Value(value),
+        }
+    }
+}
+
+impl<'a, 'b> AddBorder<'a, BorderPolylineColorContext<'a, 'b>> for PolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn border_radius(&'a self, radius: Radius) -> BorderPolylineColorContext<'a, 'b> {
+        BorderPolylineColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polyline: Borrowed(self.polyline.get()),
+            closed: Borrowed(self.closed.get()),
+            border_radius: Value(radius),
+            join: Value(MiterJoin),
+            miter_limit: Value(DEFAULT_MITER_LIMIT),
+            start_cap: Value(ButtCap),
+            end_cap: Value(ButtCap),
+            dash: Value(None),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> Clear for PolylineColorContext<'a, 'b> {
+    #[inline(always)]
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
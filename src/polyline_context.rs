@@ -0,0 +1,104 @@
+use {
+    AddColor,
+    Borrowed,
+    Field,
+    PolylineColorContext,
+    Value,
+};
+use internal::{
+    CanTransform,
+    CanViewTransform,
+    ColorComponent,
+    HasTransform,
+    HasViewTransform,
+    Matrix2d,
+    Polyline,
+    Rectangle,
+};
+
+/// A polyline context.
+pub struct PolylineContext<'a, 'b> {
+    /// Base/original transformation.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transformation.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current polyline.
+    pub polyline: Field<'a, Polyline<'b>>,
+    /// Whether the last point connects to the first.
+    pub closed: Field<'a, bool>,
+}
+
+impl<'a, 'b> Clone for PolylineContext<'a, 'b> {
+    #[inline(always)]
+    fn clone(&self) -> PolylineContext<'static, 'b> {
+        PolylineContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            polyline: Value(*self.polyline.get()),
+            closed: Value(*self.closed.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasTransform<'a, Matrix2d> for PolylineContext<'a, 'b> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a, 'b> CanTransform<'a, PolylineContext<'a, 'b>, Matrix2d> for PolylineContext<'a, 'b> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> PolylineContext<'a, 'b> {
+        PolylineContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            polyline: Borrowed(self.polyline.get()),
+            closed: Borrowed(self.closed.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasViewTransform<'a, Matrix2d> for PolylineContext<'a, 'b> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a, 'b> CanViewTransform<'a, PolylineContext<'a, 'b>, Matrix2d> for PolylineContext<'a, 'b> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> PolylineContext<'a, 'b> {
+        PolylineContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polyline: Borrowed(self.polyline.get()),
+            closed: Borrowed(self.closed.get()),
+        }
+    }
+}
+
+impl<'a, 'b> AddColor<'a, PolylineColorContext<'a, 'b>> for PolylineContext<'a, 'b> {
+    #[inline(always)]
+    fn rgba(
+        &'a self, 
+        r: ColorComponent, 
+        g: ColorComponent, 
+        b: ColorComponent, 
+        a: ColorComponent
+    ) -> PolylineColorContext<'a, 'b> {
+        PolylineColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            polyline: Borrowed(self.polyline.get()),
+            closed: Borrowed(self.closed.get()),
+            color: Value([r, g, b, a]),
+        }
+    }
+}
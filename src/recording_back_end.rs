@@ -0,0 +1,517 @@
+//! A back-end that records draw calls.
+
+use {BackEnd};
+
+/// A draw call recorded by `RecordingBackEnd`.
+#[deriving(Clone, PartialEq, Show)]
+pub enum Command {
+    /// Clears background with a color.
+    ClearRgba(f32, f32, f32, f32),
+    /// Turns on alpha blending.
+    EnableAlphaBlend,
+    /// Turns off alpha blending.
+    DisableAlphaBlend,
+    /// Restricts drawing to a rectangle.
+    SetScissor(f32, f32, f32, f32),
+    /// Turns off the scissor.
+    ClearScissor,
+    /// Clears the stencil and starts marking it.
+    BeginStencilWrite,
+    /// Restricts drawing to the marked parts of the stencil.
+    BeginStencilTest,
+    /// Turns off the stencil.
+    EndStencil,
+    /// Sets the current single-texture.
+    EnableSingleTexture(uint),
+    /// Disables single-texture.
+    DisableSingleTexture,
+    /// Renders list of 2d triangles with f64 vertices and color assigned per vertex.
+    TriListXyF64RgbaF32(Vec<f64>, Vec<f32>),
+    /// Renders list of 2d triangles with color assigned per vertex.
+    TriListXyF32RgbaF32(Vec<f32>, Vec<f32>),
+    /// Renders list of 2d triangles with color and texture coordinate per vertex.
+    TriListXyF32RgbaF32UvF32(Vec<f32>, Vec<f32>, Vec<f32>),
+    /// Renders indexed list of 2d triangles with color assigned per vertex.
+    TriListIndexedXyF32RgbaF32(Vec<f32>, Vec<f32>, Vec<u32>),
+    /// Renders indexed list of 2d triangles with color and texture coordinate per vertex.
+    TriListIndexedXyF32RgbaF32UvF32(Vec<f32>, Vec<f32>, Vec<f32>, Vec<u32>),
+    /// Renders indexed list of 2d triangles with color assigned per vertex and `u16` indices.
+    TriListIndexedXyF32RgbaF32U16(Vec<f32>, Vec<f32>, Vec<u16>),
+    /// Renders indexed list of 2d triangles with color and texture coordinate per vertex
+    /// and `u16` indices.
+    TriListIndexedXyF32RgbaF32UvF32U16(Vec<f32>, Vec<f32>, Vec<f32>, Vec<u16>),
+    /// Renders strip of 2d triangles with color assigned per vertex.
+    TriStripXyF32RgbaF32(Vec<f32>, Vec<f32>),
+    /// Renders fan of 2d triangles with color assigned per vertex.
+    TriFanXyF32RgbaF32(Vec<f32>, Vec<f32>),
+    /// Renders list of 2d triangles with the same color for all vertices.
+    TriListXyF32UniformRgbaF32(Vec<f32>, f32, f32, f32, f32),
+}
+
+impl Command {
+    /// Sends the command to a back-end.
+    pub fn replay<B: BackEnd>(&self, back_end: &mut B) {
+        match *self {
+            ClearRgba(r, g, b, a) => back_end.clear_rgba(r, g, b, a),
+            EnableAlphaBlend => back_end.enable_alpha_blend(),
+            DisableAlphaBlend => back_end.disable_alpha_blend(),
+            SetScissor(x, y, w, h) => back_end.set_scissor(x, y, w, h),
+            ClearScissor => back_end.clear_scissor(),
+            BeginStencilWrite => back_end.begin_stencil_write(),
+            BeginStencilTest => back_end.begin_stencil_test(),
+            EndStencil => back_end.end_stencil(),
+            EnableSingleTexture(texture_id) => back_end.enable_single_texture(texture_id),
+            DisableSingleTexture => back_end.disable_single_texture(),
+            TriListXyF64RgbaF32(ref vertices, ref colors) => {
+                back_end.tri_list_xy_f64_rgba_f32(vertices.as_slice(), colors.as_slice())
+            },
+            TriListXyF32RgbaF32(ref vertices, ref colors) => {
+                back_end.tri_list_xy_f32_rgba_f32(vertices.as_slice(), colors.as_slice())
+            },
+            TriListXyF32RgbaF32UvF32(ref vertices, ref colors, ref texture_coords) => {
+                back_end.tri_list_xy_f32_rgba_f32_uv_f32(
+                    vertices.as_slice(),
+                    colors.as_slice(),
+                    texture_coords.as_slice()
+                )
+            },
+            TriListIndexedXyF32RgbaF32(ref vertices, ref colors, ref indices) => {
+                back_end.tri_list_indexed_xy_f32_rgba_f32(
+                    vertices.as_slice(),
+                    colors.as_slice(),
+                    indices.as_slice()
+                )
+            },
+            TriListIndexedXyF32RgbaF32UvF32(ref vertices, ref colors, ref texture_coords, ref indices) => {
+                back_end.tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+                    vertices.as_slice(),
+                    colors.as_slice(),
+                    texture_coords.as_slice(),
+                    indices.as_slice()
+                )
+            },
+            TriListIndexedXyF32RgbaF32U16(ref vertices, ref colors, ref indices) => {
+                back_end.tri_list_indexed_xy_f32_rgba_f32_u16(
+                    vertices.as_slice(),
+                    colors.as_slice(),
+                    indices.as_slice()
+                )
+            },
+            TriListIndexedXyF32RgbaF32UvF32U16(ref vertices, ref colors, ref texture_coords, ref indices) => {
+                back_end.tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(
+                    vertices.as_slice(),
+                    colors.as_slice(),
+                    texture_coords.as_slice(),
+                    indices.as_slice()
+                )
+            },
+            TriStripXyF32RgbaF32(ref vertices, ref colors) => {
+                back_end.tri_strip_xy_f32_rgba_f32(vertices.as_slice(), colors.as_slice())
+            },
+            TriFanXyF32RgbaF32(ref vertices, ref colors) => {
+                back_end.tri_fan_xy_f32_rgba_f32(vertices.as_slice(), colors.as_slice())
+            },
+            TriListXyF32UniformRgbaF32(ref vertices, r, g, b, a) => {
+                back_end.tri_list_xy_f32_uniform_rgba_f32(vertices.as_slice(), r, g, b, a)
+            },
+        }
+    }
+}
+
+/// Records every call it receives as a list of commands.
+///
+/// The commands can be inspected to check what a context emits,
+/// or replayed to one or more back-ends.
+///
+/// Because the recorder does not know the textures,
+/// the texture ids with alpha channel must be registered
+/// with `set_texture_alpha` to make images enable alpha blending.
+///
+/// Indexed triangle lists are not supported unless turned on with `set_indexed`,
+/// or with `set_indexed_u16` for `u16` indices,
+/// triangle strips and fans unless turned on with `set_strips_and_fans`,
+/// and f64 vertices unless turned on with `set_f64_vertices`.
+pub struct RecordingBackEnd {
+    /// The recorded commands.
+    pub commands: Vec<Command>,
+    textures_with_alpha: Vec<uint>,
+    indexed: bool,
+    indexed_u16: bool,
+    strips_and_fans: bool,
+    f64_vertices: bool,
+}
+
+impl RecordingBackEnd {
+    /// Creates a new empty recording.
+    pub fn new() -> RecordingBackEnd {
+        RecordingBackEnd {
+            commands: Vec::new(),
+            textures_with_alpha: Vec::new(),
+            indexed: false,
+            indexed_u16: false,
+            strips_and_fans: false,
+            f64_vertices: false,
+        }
+    }
+
+    /// Sets whether a texture should be treated as having alpha channel.
+    pub fn set_texture_alpha(&mut self, texture_id: uint, has_alpha: bool) {
+        self.textures_with_alpha.retain(|&id| id != texture_id);
+        if has_alpha { self.textures_with_alpha.push(texture_id); }
+    }
+
+    /// Sets whether indexed triangle lists are supported.
+    pub fn set_indexed(&mut self, indexed: bool) {
+        self.indexed = indexed;
+    }
+
+    /// Sets whether indexed triangle lists with `u16` indices are supported.
+    pub fn set_indexed_u16(&mut self, indexed_u16: bool) {
+        self.indexed_u16 = indexed_u16;
+    }
+
+    /// Sets whether triangle strips and fans are supported.
+    pub fn set_strips_and_fans(&mut self, strips_and_fans: bool) {
+        self.strips_and_fans = strips_and_fans;
+    }
+
+    /// Sets whether triangle lists with f64 vertices are supported.
+    pub fn set_f64_vertices(&mut self, f64_vertices: bool) {
+        self.f64_vertices = f64_vertices;
+    }
+
+    /// Removes all recorded commands.
+    pub fn clear_commands(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Sends the recorded commands to a back-end in the order they were recorded.
+    pub fn replay<B: BackEnd>(&self, back_end: &mut B) {
+        for command in self.commands.iter() {
+            command.replay(back_end);
+        }
+    }
+}
+
+impl BackEnd for RecordingBackEnd {
+    #[inline(always)]
+    fn supports_clear_rgba(&self) -> bool { true }
+
+    fn clear_rgba(&mut self, r: f32, g: f32, b: f32, a: f32) {
+        self.commands.push(ClearRgba(r, g, b, a));
+    }
+
+    fn enable_alpha_blend(&mut self) {
+        self.commands.push(EnableAlphaBlend);
+    }
+
+    fn disable_alpha_blend(&mut self) {
+        self.commands.push(DisableAlphaBlend);
+    }
+
+    #[inline(always)]
+    fn supports_scissor(&self) -> bool { true }
+
+    fn set_scissor(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        self.commands.push(SetScissor(x, y, w, h));
+    }
+
+    fn clear_scissor(&mut self) {
+        self.commands.push(ClearScissor);
+    }
+
+    #[inline(always)]
+    fn supports_stencil(&self) -> bool { true }
+
+    fn begin_stencil_write(&mut self) {
+        self.commands.push(BeginStencilWrite);
+    }
+
+    fn begin_stencil_test(&mut self) {
+        self.commands.push(BeginStencilTest);
+    }
+
+    fn end_stencil(&mut self) {
+        self.commands.push(EndStencil);
+    }
+
+    #[inline(always)]
+    fn supports_single_texture(&self) -> bool { true }
+
+    fn enable_single_texture(&mut self, texture_id: uint) {
+        self.commands.push(EnableSingleTexture(texture_id));
+    }
+
+    fn disable_single_texture(&mut self) {
+        self.commands.push(DisableSingleTexture);
+    }
+
+    #[inline(always)]
+    fn has_texture_alpha(&self, texture_id: uint) -> bool {
+        self.textures_with_alpha.contains(&texture_id)
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f64_rgba_f32(&self) -> bool { self.f64_vertices }
+
+    fn tri_list_xy_f64_rgba_f32(
+        &mut self,
+        vertices: &[f64],
+        colors: &[f32]
+    ) {
+        self.commands.push(TriListXyF64RgbaF32(
+            Vec::from_slice(vertices),
+            Vec::from_slice(colors)
+        ));
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_rgba_f32(&self) -> bool { true }
+
+    fn tri_list_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        self.commands.push(TriListXyF32RgbaF32(
+            Vec::from_slice(vertices),
+            Vec::from_slice(colors)
+        ));
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_rgba_f32_uv_f32(&self) -> bool { true }
+
+    fn tri_list_xy_f32_rgba_f32_uv_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32]
+    ) {
+        self.commands.push(TriListXyF32RgbaF32UvF32(
+            Vec::from_slice(vertices),
+            Vec::from_slice(colors),
+            Vec::from_slice(texture_coords)
+        ));
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32(&self) -> bool { self.indexed }
+
+    fn tri_list_indexed_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        indices: &[u32]
+    ) {
+        self.commands.push(TriListIndexedXyF32RgbaF32(
+            Vec::from_slice(vertices),
+            Vec::from_slice(colors),
+            Vec::from_slice(indices)
+        ));
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32(&self) -> bool { self.indexed }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32],
+        indices: &[u32]
+    ) {
+        self.commands.push(TriListIndexedXyF32RgbaF32UvF32(
+            Vec::from_slice(vertices),
+            Vec::from_slice(colors),
+            Vec::from_slice(texture_coords),
+            Vec::from_slice(indices)
+        ));
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_u16(&self) -> bool { self.indexed_u16 }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_u16(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        indices: &[u16]
+    ) {
+        self.commands.push(TriListIndexedXyF32RgbaF32U16(
+            Vec::from_slice(vertices),
+            Vec::from_slice(colors),
+            Vec::from_slice(indices)
+        ));
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(&self) -> bool { self.indexed_u16 }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32],
+        indices: &[u16]
+    ) {
+        self.commands.push(TriListIndexedXyF32RgbaF32UvF32U16(
+            Vec::from_slice(vertices),
+            Vec::from_slice(colors),
+            Vec::from_slice(texture_coords),
+            Vec::from_slice(indices)
+        ));
+    }
+
+    #[inline(always)]
+    fn supports_tri_strip_xy_f32_rgba_f32(&self) -> bool { self.strips_and_fans }
+
+    fn tri_strip_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        self.commands.push(TriStripXyF32RgbaF32(
+            Vec::from_slice(vertices),
+            Vec::from_slice(colors)
+        ));
+    }
+
+    #[inline(always)]
+    fn supports_tri_fan_xy_f32_rgba_f32(&self) -> bool { self.strips_and_fans }
+
+    fn tri_fan_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        self.commands.push(TriFanXyF32RgbaF32(
+            Vec::from_slice(vertices),
+            Vec::from_slice(colors)
+        ));
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_uniform_rgba_f32(&self) -> bool { true }
+
+    fn tri_list_xy_f32_uniform_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        r: f32, g: f32, b: f32, a: f32
+    ) {
+        self.commands.push(TriListXyF32UniformRgbaF32(Vec::from_slice(vertices), r, g, b, a));
+    }
+}
+
+#[test]
+fn test_record_rect() {
+    use {AddColor, AddRectangle, Context, Fill};
+
+    let mut back_end = RecordingBackEnd::new();
+    let c = Context::new();
+    c.rect(0.0, 0.0, 1.0, 1.0).rgba(1.0, 0.0, 0.0, 0.5).fill(&mut back_end).unwrap();
+    assert_eq!(back_end.commands.len(), 3);
+    assert_eq!(*back_end.commands.get(0), EnableAlphaBlend);
+    match *back_end.commands.get(1) {
+        TriListXyF32RgbaF32(ref vertices, ref colors) => {
+            assert_eq!(vertices.as_slice(), &[
+                0.0, 0.0, 1.0, 0.0, 0.0, 1.0,
+                1.0, 0.0, 1.0, 1.0, 0.0, 1.0
+            ]);
+            assert_eq!(colors.as_slice().slice(0, 4), &[1.0, 0.0, 0.0, 0.5]);
+        },
+        _ => fail!("Expected triangle list"),
+    }
+    assert_eq!(*back_end.commands.get(2), DisableAlphaBlend);
+}
+
+#[test]
+fn test_replay() {
+    use {AddColor, AddRectangle, Context, Fill, SoftwareBackEnd};
+
+    let mut recording = RecordingBackEnd::new();
+    let c = Context::new();
+    c.rgba(0.0, 0.0, 1.0, 1.0).rect(-1.0, -1.0, 1.0, 1.0).fill(&mut recording).unwrap();
+
+    let mut direct = SoftwareBackEnd::new(4, 4);
+    c.rgba(0.0, 0.0, 1.0, 1.0).rect(-1.0, -1.0, 1.0, 1.0).fill(&mut direct).unwrap();
+    let mut replayed = SoftwareBackEnd::new(4, 4);
+    recording.replay(&mut replayed);
+    assert!(direct.pixels() == replayed.pixels());
+}
+
+#[test]
+fn test_record_indexed_rect() {
+    use {AddColor, AddRectangle, Context, Fill, SoftwareBackEnd};
+
+    let mut recording = RecordingBackEnd::new();
+    recording.set_indexed(true);
+    let c = Context::new();
+    c.rgba(0.0, 1.0, 0.0, 1.0).rect(-1.0, -1.0, 1.0, 1.0).fill(&mut recording).unwrap();
+    assert_eq!(recording.commands.len(), 1);
+    match *recording.commands.get(0) {
+        TriListIndexedXyF32RgbaF32(ref vertices, ref colors, ref indices) => {
+            assert_eq!(vertices.len(), 8);
+            assert_eq!(colors.len(), 16);
+            assert_eq!(indices.as_slice(), &[0, 1, 2, 1, 3, 2]);
+        },
+        _ => fail!("Expected indexed triangle list"),
+    }
+
+    let mut direct = SoftwareBackEnd::new(4, 4);
+    c.rgba(0.0, 1.0, 0.0, 1.0).rect(-1.0, -1.0, 1.0, 1.0).fill(&mut direct).unwrap();
+    let mut replayed = SoftwareBackEnd::new(4, 4);
+    recording.replay(&mut replayed);
+    assert!(direct.pixels() == replayed.pixels());
+}
+
+#[test]
+fn test_record_strips_and_fans() {
+    use {AddBorder, AddColor, AddEllipse, AddRectangle, Context, Fill, Stroke};
+
+    let mut recording = RecordingBackEnd::new();
+    recording.set_strips_and_fans(true);
+    let c = Context::new();
+    let c = c.rgba(1.0, 0.0, 0.0, 1.0);
+    c.ellipse(-1.0, -1.0, 2.0, 2.0).fill(&mut recording).unwrap();
+    c.rect(-0.5, -0.5, 1.0, 1.0).border_radius(0.1).stroke(&mut recording).unwrap();
+    assert_eq!(recording.commands.len(), 2);
+    match *recording.commands.get(0) {
+        TriFanXyF32RgbaF32(ref vertices, ref colors) => {
+            assert_eq!(vertices.len() * 2, colors.len());
+        },
+        _ => fail!("Expected triangle fan"),
+    }
+    match *recording.commands.get(1) {
+        // The corners and the first corner repeated, 2 points each.
+        TriStripXyF32RgbaF32(ref vertices, _) => assert_eq!(vertices.len(), 5 * 2 * 2),
+        _ => fail!("Expected triangle strip"),
+    }
+}
+
+#[test]
+fn test_record_f64_vertices() {
+    use {AddBorder, AddColor, AddEllipse, AddRectangle, Context, Fill, Stroke};
+
+    let mut recording = RecordingBackEnd::new();
+    recording.set_f64_vertices(true);
+    recording.set_strips_and_fans(true);
+    let c = Context::new();
+    let c = c.rgba(1.0, 0.0, 0.0, 1.0);
+    c.rect(0.1, 0.1, 0.2, 0.2).fill(&mut recording).unwrap();
+    c.ellipse(-1.0, -1.0, 2.0, 2.0).fill(&mut recording).unwrap();
+    c.rect(-0.5, -0.5, 1.0, 1.0).border_radius(0.1).stroke(&mut recording).unwrap();
+    assert_eq!(recording.commands.len(), 3);
+    // The f64 path is preferred over strips and fans.
+    for command in recording.commands.iter() {
+        match *command {
+            TriListXyF64RgbaF32(..) => {},
+            _ => fail!("Expected triangle list with f64 vertices"),
+        }
+    }
+    // The vertices are not rounded to f32.
+    match *recording.commands.get(0) {
+        TriListXyF64RgbaF32(ref vertices, _) => assert_eq!(*vertices.get(0), 0.1),
+        _ => {}
+    }
+}
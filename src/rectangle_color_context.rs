@@ -1,22 +1,42 @@
 
 use {
     AddBevel,
+    AddBorder,
+    AddGradient,
     AddImage,
     AddRound,
     BackEnd,
     BevelRectangleColorContext,
+    BorderRectangleColorContext,
     Borrowed,
     Clear,
+    ClipBackEnd,
+    FallbackBackEnd,
     Field,
     Fill,
+    Gradient,
     Image,
     ImageRectangleColorContext,
+    MiterJoin,
+    RenderResult,
     RoundRectangleColorContext,
+    Stretch,
+    Tolerance,
     Value,
 };
 use triangulation::{
+    Coordinate,
+    DEFAULT_MITER_LIMIT,
+    DEFAULT_TOLERANCE,
+    RECT_TRI_LIST_INDICES,
+    rect_tri_list_indexed_rgba_f32,
+    rect_tri_list_indexed_xy_f32,
     rect_tri_list_xy_f32,
     rect_tri_list_rgba_f32,
+    with_rectangle_gradient_tri_list_xy_rgba_f32,
+};
+use vecmath::{
+    identity,
 };
 use internal::{
     CanColor,
@@ -29,6 +49,7 @@ use internal::{
     HasTransform,
     HasViewTransform,
     Matrix2d,
+    Radius,
     Rectangle,
 };
 
@@ -38,10 +59,14 @@ pub struct RectangleColorContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transformation.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current rectangle.
     pub rect: Field<'a, Rectangle>,
     /// Current color.
     pub color: Field<'a, Color>,
+    /// Current gradient.
+    pub gradient: Field<'a, Option<Gradient>>,
 }
 
 impl<'a> Clone for RectangleColorContext<'a> {
@@ -50,8 +75,10 @@ impl<'a> Clone for RectangleColorContext<'a> {
         RectangleColorContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             rect: Value(*self.rect.get()),
             color: Value(*self.color.get()),
+            gradient: Value(*self.gradient.get()),
         }
     }
 }
@@ -69,8 +96,10 @@ impl<'a> CanTransform<'a, RectangleColorContext<'a>, Matrix2d> for RectangleColo
         RectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
         }
     }
 }
@@ -89,8 +118,10 @@ for RectangleColorContext<'a> {
         RectangleColorContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
         }
     }
 }
@@ -108,7 +139,9 @@ impl<'a> CanColor<'a, RectangleColorContext<'a>, Color> for RectangleColorContex
         RectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Value(value),
+            gradient: Borrowed(self.gradient.get()),
             rect: Borrowed(self.rect.get()),
         }
     }
@@ -127,42 +160,89 @@ impl<'a> CanRectangle<'a, RectangleColorContext<'a>, Rectangle> for RectangleCol
         RectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Value(rect),
             color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
+        }
+    }
+}
+
+impl<'a> RectangleColorContext<'a> {
+    /// Streams the filled rectangle with f32 or f64 vertices.
+    fn stream_tri_list<V: Coordinate>(&self, f: |vertices: &[V], colors: &[f32]|) {
+        match *self.gradient.get() {
+            Some(ref gradient) => with_rectangle_gradient_tri_list_xy_rgba_f32(
+                *self.transform.get(), *self.rect.get(), gradient, f),
+            None => f(
+                rect_tri_list_xy_f32(*self.transform.get(), *self.rect.get()),
+                rect_tri_list_rgba_f32(*self.color.get())
+            ),
         }
     }
 }
 
 impl<'a> Fill<'a> for RectangleColorContext<'a> {
     #[inline(always)]
-    fn fill<B: BackEnd>(&'a self, back_end: &mut B) {
-        if back_end.supports_tri_list_xy_f32_rgba_f32() {
-            let rect = self.rect.get();
-            let color = self.color.get();
-            // Complete transparency does not need to be rendered.
-            if color[3] == 0.0 { return; }
-            // Turn on alpha blending if not completely opaque.
-            let needs_alpha = color[3] != 1.0;
-            if needs_alpha { back_end.enable_alpha_blend(); }
-            back_end.tri_list_xy_f32_rgba_f32(
-                rect_tri_list_xy_f32(*self.transform.get(), *rect),
-                rect_tri_list_rgba_f32(*color)
-            );
-            if needs_alpha { back_end.disable_alpha_blend(); }
-        } else {
-            unimplemented!();
+    fn fill<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let needs_alpha = match *self.gradient.get() {
+                    Some(ref gradient) => {
+                        // Complete transparency does not need to be rendered.
+                        if gradient.is_invisible() { return; }
+                        // Turn on alpha blending if any stop is not completely opaque.
+                        gradient.has_alpha()
+                    },
+                    None => {
+                        let color = self.color.get();
+                        // Complete transparency does not need to be rendered.
+                        if color[3] == 0.0 { return; }
+                        // Turn on alpha blending if not completely opaque.
+                        color[3] != 1.0
+                    },
+                };
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                // Gradients are only streamed as triangle lists.
+                let solid = self.gradient.get().is_none();
+                if back_end.supports_tri_list_xy_f64_rgba_f32() {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f64_rgba_f32(vertices, colors)
+                    });
+                } else if solid && back_end.supports_tri_list_indexed_xy_f32_rgba_f32() {
+                    back_end.tri_list_indexed_xy_f32_rgba_f32(
+                        rect_tri_list_indexed_xy_f32(*self.transform.get(), *self.rect.get()),
+                        rect_tri_list_indexed_rgba_f32(*self.color.get()),
+                        RECT_TRI_LIST_INDICES
+                    );
+                } else {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+                    });
+                }
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+impl<'a> AddGradient<'a, RectangleColorContext<'a>> for RectangleColorContext<'a> {
+    #[inline(always)]
+    fn gradient(&'a self, gradient: Gradient) -> RectangleColorContext<'a> {
+        RectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            color: Borrowed(self.color.get()),
+            gradient: Value(Some(gradient)),
         }
     }
 }
 
 impl<'a> Clear for RectangleColorContext<'a> {
-    fn clear<B: BackEnd>(&self, back_end: &mut B) {
-        if back_end.supports_clear_rgba() {
-            let color = self.color.get();
-            back_end.clear_rgba(color[0], color[1], color[2], color[3]);
-        } else {
-            unimplemented!();
-        }
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
     }
 }
 
@@ -172,9 +252,12 @@ impl<'a> AddRound<'a, RoundRectangleColorContext<'a>> for RectangleColorContext<
         RoundRectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
             rect: Borrowed(self.rect.get()),
             round_radius: Value(radius),
+            resolution: Value(Tolerance(DEFAULT_TOLERANCE)),
         }
     }
 }
@@ -185,7 +268,9 @@ impl<'a> AddBevel<'a, BevelRectangleColorContext<'a>> for RectangleColorContext<
         BevelRectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
             rect: Borrowed(self.rect.get()),
             bevel_radius: Value(radius),
         }
@@ -197,10 +282,45 @@ impl<'a> AddImage<'a, ImageRectangleColorContext<'a>> for RectangleColorContext<
         ImageRectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             image: Value(image),
+            uv_transform: Value(identity()),
+            slice_mode: Value(Stretch),
             color: Borrowed(self.color.get()),
         }
     }
 }
 
+impl<'a> AddBorder<'a, BorderRectangleColorContext<'a>> for RectangleColorContext<'a> {
+    #[inline(always)]
+    fn border_radius(&'a self, radius: Radius) -> BorderRectangleColorContext<'a> {
+        BorderRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            border_radius: Value(radius),
+            join: Value(MiterJoin),
+            miter_limit: Value(DEFAULT_MITER_LIMIT),
+            dash: Value(None),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+#[test]
+fn test_fill_gradient() {
+    use {AddGradient, AddRectangle, Context, SoftwareBackEnd};
+
+    let mut back_end = SoftwareBackEnd::new(4, 1);
+    let c = Context::new();
+    let (black, white) = ([0.0, 0.0, 0.0, 1.0], [1.0, 1.0, 1.0, 1.0]);
+    c.rect(-1.0, -1.0, 2.0, 2.0)
+        .linear_gradient([-1.0, 0.0], [1.0, 0.0], [(0.0, black), (1.0, white)])
+        .fill(&mut back_end).unwrap();
+    // The pixel centers are at 1/8, 3/8, 5/8 and 7/8 along the gradient.
+    assert!(back_end.pixel(0, 0) == [32, 32, 32, 255]);
+    assert!(back_end.pixel(1, 0) == [96, 96, 96, 255]);
+    assert!(back_end.pixel(3, 0) == [223, 223, 223, 255]);
+}
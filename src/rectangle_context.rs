@@ -2,17 +2,23 @@
 use {
     AddBevel,
     AddColor,
+    AddGradient,
     AddImage,
     AddRound,
     BevelRectangleContext,
     Borrowed,
     Field,
+    Gradient,
     Image,
     ImageRectangleContext,
     RectangleColorContext,
     RoundRectangleContext,
+    Stretch,
     Value,
 };
+use vecmath::{
+    identity,
+};
 use internal::{
     CanRectangle,
     CanTransform,
@@ -32,6 +38,8 @@ pub struct RectangleContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transformation.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current rectangle.
     pub rect: Field<'a, Rectangle>,
 }
@@ -42,6 +50,7 @@ impl<'a> Clone for RectangleContext<'a> {
         RectangleContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             rect: Value(*self.rect.get()),
         }
     }
@@ -60,6 +69,7 @@ impl<'a> CanTransform<'a, RectangleContext<'a>, Matrix2d> for RectangleContext<'
         RectangleContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
         }
     }
@@ -79,6 +89,7 @@ for RectangleContext<'a> {
         RectangleContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
         }
     }
@@ -97,6 +108,7 @@ impl<'a> CanRectangle<'a, RectangleContext<'a>, Rectangle> for RectangleContext<
         RectangleContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Value(rect),
         }
     }
@@ -115,7 +127,23 @@ impl<'a> AddColor<'a, RectangleColorContext<'a>> for RectangleContext<'a> {
         RectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Value([r, g, b, a]),
+            gradient: Value(None),
+            rect: Borrowed(self.rect.get()),
+        }
+    }
+}
+
+impl<'a> AddGradient<'a, RectangleColorContext<'a>> for RectangleContext<'a> {
+    #[inline(always)]
+    fn gradient(&'a self, gradient: Gradient) -> RectangleColorContext<'a> {
+        RectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            color: Value(gradient.stops()[0].val1()),
+            gradient: Value(Some(gradient)),
             rect: Borrowed(self.rect.get()),
         }
     }
@@ -138,6 +166,7 @@ impl<'a> AddRound<'a, RoundRectangleContext<'a>> for RectangleContext<'a> {
         RoundRectangleContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             round_radius: Value(radius),
         }
@@ -150,6 +179,7 @@ impl<'a> AddBevel<'a, BevelRectangleContext<'a>> for RectangleContext<'a> {
         BevelRectangleContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             bevel_radius: Value(radius),
         }
@@ -161,8 +191,11 @@ impl<'a> AddImage<'a, ImageRectangleContext<'a>> for RectangleContext<'a> {
         ImageRectangleContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             image: Value(image),
+            uv_transform: Value(identity()),
+            slice_mode: Value(Stretch),
         }
     }
 }
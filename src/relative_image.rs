@@ -0,0 +1,106 @@
+use std::cmp::{min};
+use vecmath::{
+    multiply,
+};
+use internal::{
+    CanSourceRectangle,
+    CanUvTransform,
+    HasSourceRectangle,
+    HasUvTransform,
+    Matrix2d,
+    PixelRectangle,
+};
+
+/// Should be implemented by contexts that draw images.
+///
+/// Flipping and rotating change which part of the image
+/// is shown in each corner of the destination rectangle.
+pub trait RelativeImage<'a, T> {
+    /// Selects a rectangle within the current source rectangle.
+    ///
+    /// The rectangle is clamped to the current source rectangle.
+    fn region(&'a self, x: u32, y: u32, w: u32, h: u32) -> T;
+
+    /// Transforms the texture coordinates before the current transform.
+    ///
+    /// The transform maps the destination, as a unit square,
+    /// to the source rectangle, as a unit square.
+    fn map_uv(&'a self, m: Matrix2d) -> T;
+
+    /// Mirrors the image horizontally.
+    fn flip_u(&'a self) -> T;
+
+    /// Mirrors the image vertically.
+    fn flip_v(&'a self) -> T;
+
+    /// Rotates the image 90 degrees clockwise.
+    ///
+    /// Use this for images packed in an atlas rotated counter-clockwise.
+    fn rot90(&'a self) -> T;
+
+    /// Rotates the image 180 degrees.
+    fn rot180(&'a self) -> T;
+
+    /// Rotates the image 90 degrees counter-clockwise.
+    fn rot270(&'a self) -> T;
+}
+
+impl<
+    'a,
+    T: HasSourceRectangle<'a, PixelRectangle> + CanSourceRectangle<'a, U, PixelRectangle>
+     + HasUvTransform<'a, Matrix2d> + CanUvTransform<'a, U, Matrix2d>,
+    U
+> RelativeImage<'a, U> for T {
+    #[inline(always)]
+    fn region(&'a self, x: u32, y: u32, w: u32, h: u32) -> U {
+        let source_rect = *self.get_source_rectangle();
+        let x = min(x, source_rect[2]);
+        let y = min(y, source_rect[3]);
+        self.source_rectangle([
+            source_rect[0] + x,
+            source_rect[1] + y,
+            min(w, source_rect[2] - x),
+            min(h, source_rect[3] - y)
+        ])
+    }
+
+    #[inline(always)]
+    fn map_uv(&'a self, m: Matrix2d) -> U {
+        self.uv_transform(multiply(*self.get_uv_transform(), m))
+    }
+
+    #[inline(always)]
+    fn flip_u(&'a self) -> U {
+        self.uv_transform(multiply(*self.get_uv_transform(),
+            [-1.0, 0.0, 1.0,
+              0.0, 1.0, 0.0]))
+    }
+
+    #[inline(always)]
+    fn flip_v(&'a self) -> U {
+        self.uv_transform(multiply(*self.get_uv_transform(),
+            [1.0,  0.0, 0.0,
+             0.0, -1.0, 1.0]))
+    }
+
+    #[inline(always)]
+    fn rot90(&'a self) -> U {
+        self.uv_transform(multiply(*self.get_uv_transform(),
+            [ 0.0, 1.0, 0.0,
+             -1.0, 0.0, 1.0]))
+    }
+
+    #[inline(always)]
+    fn rot180(&'a self) -> U {
+        self.uv_transform(multiply(*self.get_uv_transform(),
+            [-1.0,  0.0, 1.0,
+              0.0, -1.0, 1.0]))
+    }
+
+    #[inline(always)]
+    fn rot270(&'a self) -> U {
+        self.uv_transform(multiply(*self.get_uv_transform(),
+            [0.0, -1.0, 1.0,
+             1.0,  0.0, 0.0]))
+    }
+}
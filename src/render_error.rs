@@ -0,0 +1,20 @@
+//! Errors for contexts that a back-end can not render.
+
+/// The reason a back-end could not render a context.
+#[deriving(Clone, PartialEq, Show)]
+pub enum RenderError {
+    /// The back-end supports none of the formats for colored triangles.
+    UnsupportedTriangles,
+    /// The back-end supports a single color per triangle list only,
+    /// but the vertices have different colors.
+    UnsupportedVertexColors,
+    /// The back-end does not support textured triangles.
+    UnsupportedTexture,
+    /// The back-end can not clear the background.
+    UnsupportedClear,
+    /// The glyphs of a text do not fit in the glyph atlas together.
+    GlyphAtlasFull,
+}
+
+/// The result of rendering a context with a back-end.
+pub type RenderResult = Result<(), RenderError>;
@@ -0,0 +1,14 @@
+use internal::{Scalar};
+
+/// How many segments are used to approximate curved outlines.
+pub enum Resolution {
+    /// Picks the number of segments from the size after transformation,
+    /// such that the distance between the curve and the segments is within tolerance.
+    ///
+    /// The tolerance is measured after transformation.
+    Tolerance(Scalar),
+    /// Uses a fixed number of segments.
+    ///
+    /// For round rectangles this is the number of points at each corner.
+    Segments(uint),
+}
@@ -15,6 +15,7 @@ use internal::{
     Line,
     Matrix2d,
     Radius,
+    Rectangle,
 };
 
 /// A line context with round border information.
@@ -23,6 +24,8 @@ pub struct RoundBorderLineContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transform.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current line.
     pub line: Field<'a, Line>,
     /// Current round border.
@@ -35,6 +38,7 @@ impl<'a> Clone for RoundBorderLineContext<'a> {
         RoundBorderLineContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             line: Value(*self.line.get()),
             round_border_radius: Value(*self.round_border_radius.get()),
         }
@@ -54,6 +58,7 @@ impl<'a> CanTransform<'a, RoundBorderLineContext<'a>, Matrix2d> for RoundBorderL
         RoundBorderLineContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             round_border_radius: Borrowed(self.round_border_radius.get()),
         }
@@ -74,6 +79,7 @@ for RoundBorderLineContext<'a> {
         RoundBorderLineContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             round_border_radius: Borrowed(self.round_border_radius.get()),
         }
@@ -92,9 +98,11 @@ impl<'a> AddColor<'a, RoundBorderLineColorContext<'a>> for RoundBorderLineContex
         RoundBorderLineColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             color: Value([r, g, b, a]),
             round_border_radius: Borrowed(self.round_border_radius.get()),
+            dash: Value(None),
         }
     }
 }
@@ -1,14 +1,37 @@
 
 use {
+    AddBorder,
+    AddGradient,
+    AddImage,
+    AddResolution,
     BackEnd,
+    BorderRoundRectangleColorContext,
     Borrowed,
     Clear,
+    ClipBackEnd,
+    FallbackBackEnd,
     Field,
     Fill,
+    Gradient,
+    Image,
+    ImageRoundRectangleColorContext,
+    MiterJoin,
+    RenderResult,
+    Resolution,
+    TriFan,
+    TriList,
     Value,
 };
 use triangulation::{
-    with_round_rectangle_tri_list_xy_f32_rgba_f32
+    Coordinate,
+    DEFAULT_MITER_LIMIT,
+    round_rectangle_resolution,
+    round_rectangle_tri_list_indexed_xy_f32_rgba_f32,
+    with_round_rectangle_gradient_tri_list_xy_rgba_f32,
+    with_round_rectangle_xy_rgba_f32,
+};
+use vecmath::{
+    identity,
 };
 use internal::{
     CanColor,
@@ -31,12 +54,18 @@ pub struct RoundRectangleColorContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transformation.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current rectangle.
     pub rect: Field<'a, Rectangle>,
     /// Current roundness radius.
     pub round_radius: Field<'a, Radius>,
+    /// Current resolution of the curves.
+    pub resolution: Field<'a, Resolution>,
     /// Current color.
     pub color: Field<'a, Color>,
+    /// Current gradient.
+    pub gradient: Field<'a, Option<Gradient>>,
 }
 
 impl<'a> Clone for RoundRectangleColorContext<'a> {
@@ -45,9 +74,12 @@ impl<'a> Clone for RoundRectangleColorContext<'a> {
         RoundRectangleColorContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             rect: Value(*self.rect.get()),
             round_radius: Value(*self.round_radius.get()),
+            resolution: Value(*self.resolution.get()),
             color: Value(*self.color.get()),
+            gradient: Value(*self.gradient.get()),
         }
     }
 }
@@ -65,9 +97,12 @@ impl<'a> CanTransform<'a, RoundRectangleColorContext<'a>, Matrix2d> for RoundRec
         RoundRectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
             color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
         }
     }
 }
@@ -86,9 +121,12 @@ for RoundRectangleColorContext<'a> {
         RoundRectangleColorContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
             color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
         }
     }
 }
@@ -106,9 +144,12 @@ impl<'a> CanColor<'a, RoundRectangleColorContext<'a>, Color> for RoundRectangleC
         RoundRectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Value(value),
+            gradient: Borrowed(self.gradient.get()),
             rect: Borrowed(self.rect.get()),
             round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
         }
     }
 }
@@ -126,50 +167,165 @@ impl<'a> CanRectangle<'a, RoundRectangleColorContext<'a>, Rectangle> for RoundRe
         RoundRectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Value(rect),
             round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
             color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
         }
     }
 }
 
 impl<'a> Clear for RoundRectangleColorContext<'a> {
-    fn clear<B: BackEnd>(&self, back_end: &mut B) {
-        if back_end.supports_clear_rgba() {
-            let color = self.color.get();
-            back_end.clear_rgba(color[0], color[1], color[2], color[3]);
-        } else {
-            unimplemented!();
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
+
+impl<'a> RoundRectangleColorContext<'a> {
+    /// Streams the filled round rectangle with f32 or f64 vertices.
+    fn stream_tri_list<V: Coordinate>(
+        &self,
+        resolution: uint,
+        f: |vertices: &[V], colors: &[f32]|
+    ) {
+        let m = *self.transform.get();
+        let (rect, round_radius) = (*self.rect.get(), *self.round_radius.get());
+        match *self.gradient.get() {
+            Some(ref gradient) => with_round_rectangle_gradient_tri_list_xy_rgba_f32(
+                resolution, m, rect, round_radius, gradient, f),
+            None => {
+                with_round_rectangle_xy_rgba_f32(
+                    TriList, resolution, m, rect, round_radius, *self.color.get(), f);
+            },
         }
     }
 }
 
 impl<'a> Fill<'a> for RoundRectangleColorContext<'a> {
     #[inline(always)]
-    fn fill<B: BackEnd>(&'a self, back_end: &mut B) {
-        if back_end.supports_tri_list_xy_f32_rgba_f32() {
-            let rect = self.rect.get();
-            let round_radius = self.round_radius.get();
-            let color = self.color.get();
-            // Complete transparency does not need to be rendered.
-            if color[3] == 0.0 { return; }
-            // Turn on alpha blending if not completely opaque.
-            let needs_alpha = color[3] != 1.0;
-            if needs_alpha { back_end.enable_alpha_blend(); }
-            with_round_rectangle_tri_list_xy_f32_rgba_f32(
-                32,
-                *self.transform.get(),
-                *rect,
-                *round_radius,
-                *color,
-                |vertices, colors| {
-                    back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+    fn fill<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let rect = self.rect.get();
+                let round_radius = self.round_radius.get();
+                let color = self.color.get();
+                let needs_alpha = match *self.gradient.get() {
+                    Some(ref gradient) => {
+                        // Complete transparency does not need to be rendered.
+                        if gradient.is_invisible() { return; }
+                        // Turn on alpha blending if any stop is not completely opaque.
+                        gradient.has_alpha()
+                    },
+                    None => {
+                        // Complete transparency does not need to be rendered.
+                        if color[3] == 0.0 { return; }
+                        // Turn on alpha blending if not completely opaque.
+                        color[3] != 1.0
+                    },
+                };
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                let resolution = round_rectangle_resolution(
+                    *self.transform.get(), *round_radius, *self.resolution.get());
+                // Gradients are only streamed as triangle lists.
+                let solid = self.gradient.get().is_none();
+                if back_end.supports_tri_list_xy_f64_rgba_f32() {
+                    self.stream_tri_list(resolution, |vertices, colors| {
+                        back_end.tri_list_xy_f64_rgba_f32(vertices, colors)
+                    });
+                } else if solid && back_end.supports_tri_fan_xy_f32_rgba_f32() {
+                    with_round_rectangle_xy_rgba_f32(
+                        TriFan,
+                        resolution,
+                        *self.transform.get(),
+                        *rect,
+                        *round_radius,
+                        *color,
+                        |vertices, colors| {
+                            back_end.tri_fan_xy_f32_rgba_f32(vertices, colors)
+                        }
+                    );
+                } else if solid && back_end.supports_tri_list_indexed_xy_f32_rgba_f32() {
+                    let (vertices, colors, indices) = round_rectangle_tri_list_indexed_xy_f32_rgba_f32(
+                        resolution, *self.transform.get(), *rect, *round_radius, *color);
+                    back_end.tri_list_indexed_xy_f32_rgba_f32(
+                        vertices.as_slice(), colors.as_slice(), indices.as_slice());
+                } else {
+                    self.stream_tri_list(resolution, |vertices, colors| {
+                        back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+                    });
                 }
-            );
-            if needs_alpha { back_end.disable_alpha_blend(); }
-        } else {
-            unimplemented!();
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+impl<'a> AddResolution<'a, RoundRectangleColorContext<'a>> for RoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn resolution(&'a self, resolution: Resolution) -> RoundRectangleColorContext<'a> {
+        RoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Value(resolution),
+            color: Borrowed(self.color.get()),
+            gradient: Borrowed(self.gradient.get()),
+        }
+    }
+}
+
+impl<'a> AddGradient<'a, RoundRectangleColorContext<'a>> for RoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn gradient(&'a self, gradient: Gradient) -> RoundRectangleColorContext<'a> {
+        RoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
+            color: Borrowed(self.color.get()),
+            gradient: Value(Some(gradient)),
+        }
+    }
+}
+
+impl<'a> AddBorder<'a, BorderRoundRectangleColorContext<'a>> for RoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn border_radius(&'a self, radius: Radius) -> BorderRoundRectangleColorContext<'a> {
+        BorderRoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
+            border_radius: Value(radius),
+            join: Value(MiterJoin),
+            miter_limit: Value(DEFAULT_MITER_LIMIT),
+            dash: Value(None),
+            color: Borrowed(self.color.get()),
         }
     }
 }
 
+impl<'a> AddImage<'a, ImageRoundRectangleColorContext<'a>> for RoundRectangleColorContext<'a> {
+    #[inline(always)]
+    fn image(&'a self, image: Image) -> ImageRoundRectangleColorContext<'a> {
+        ImageRoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Borrowed(self.resolution.get()),
+            image: Value(image),
+            uv_transform: Value(identity()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
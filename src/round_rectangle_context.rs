@@ -1,10 +1,22 @@
 use {
     AddColor,
+    AddGradient,
+    AddImage,
     Borrowed,
     Field,
+    Gradient,
+    Image,
+    ImageRoundRectangleContext,
     RoundRectangleColorContext,
+    Tolerance,
     Value,
 };
+use triangulation::{
+    DEFAULT_TOLERANCE,
+};
+use vecmath::{
+    identity,
+};
 use internal::{
     CanRectangle,
     CanTransform,
@@ -24,6 +36,8 @@ pub struct RoundRectangleContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transform.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current rectangle.
     pub rect: Field<'a, Rectangle>,
     /// Current roundness radius.
@@ -36,6 +50,7 @@ impl<'a> Clone for RoundRectangleContext<'a> {
         RoundRectangleContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             rect: Value(*self.rect.get()),
             round_radius: Value(*self.round_radius.get()),
         }
@@ -55,6 +70,7 @@ impl<'a> CanTransform<'a, RoundRectangleContext<'a>, Matrix2d> for RoundRectangl
         RoundRectangleContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             round_radius: Borrowed(self.round_radius.get()),
         }
@@ -74,6 +90,7 @@ impl<'a> CanViewTransform<'a, RoundRectangleContext<'a>, Matrix2d> for RoundRect
         RoundRectangleContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Borrowed(self.rect.get()),
             round_radius: Borrowed(self.round_radius.get()),
         }
@@ -93,6 +110,7 @@ impl<'a> CanRectangle<'a, RoundRectangleContext<'a>, Rectangle> for RoundRectang
         RoundRectangleContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             rect: Value(rect),
             round_radius: Borrowed(self.round_radius.get()),
         }
@@ -112,10 +130,44 @@ impl<'a> AddColor<'a, RoundRectangleColorContext<'a>> for RoundRectangleContext<
         RoundRectangleColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Value([r, g, b, a]),
+            gradient: Value(None),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Value(Tolerance(DEFAULT_TOLERANCE)),
+        }
+    }
+}
+
+impl<'a> AddGradient<'a, RoundRectangleColorContext<'a>> for RoundRectangleContext<'a> {
+    #[inline(always)]
+    fn gradient(&'a self, gradient: Gradient) -> RoundRectangleColorContext<'a> {
+        RoundRectangleColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            color: Value(gradient.stops()[0].val1()),
+            gradient: Value(Some(gradient)),
             rect: Borrowed(self.rect.get()),
             round_radius: Borrowed(self.round_radius.get()),
+            resolution: Value(Tolerance(DEFAULT_TOLERANCE)),
         }
     }
 }
 
+impl<'a> AddImage<'a, ImageRoundRectangleContext<'a>> for RoundRectangleContext<'a> {
+    #[inline(always)]
+    fn image(&'a self, image: Image) -> ImageRoundRectangleContext<'a> {
+        ImageRoundRectangleContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            rect: Borrowed(self.rect.get()),
+            round_radius: Borrowed(self.round_radius.get()),
+            resolution: Value(Tolerance(DEFAULT_TOLERANCE)),
+            image: Value(image),
+            uv_transform: Value(identity()),
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use {
+    AddFillRule,
+    BackEnd,
+    Borrowed,
+    Clear,
+    ClipBackEnd,
+    FallbackBackEnd,
+    Field,
+    Fill,
+    FillRule,
+    RenderResult,
+    Value,
+};
+use triangulation::{
+    Coordinate,
+    with_shape_tri_list_xy_rgba_f32
+};
+use internal::{
+    CanColor,
+    CanTransform,
+    CanViewTransform,
+    Color,
+    HasColor,
+    HasTransform,
+    HasViewTransform,
+    Matrix2d,
+    Rectangle,
+    Shape,
+};
+
+/// A shape color context.
+pub struct ShapeColorContext<'a, 'b> {
+    /// Base/original transformation.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transformation.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current shape.
+    pub shape: Field<'a, Shape<'b>>,
+    /// Current fill rule.
+    pub fill_rule: Field<'a, FillRule>,
+    /// Current color.
+    pub color: Field<'a, Color>,
+}
+
+impl<'a, 'b> Clone for ShapeColorContext<'a, 'b> {
+    #[inline(always)]
+    fn clone(&self) -> ShapeColorContext<'static, 'b> {
+        ShapeColorContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            shape: Value(*self.shape.get()),
+            fill_rule: Value(*self.fill_rule.get()),
+            color: Value(*self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasTransform<'a, Matrix2d> for ShapeColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a, 'b> CanTransform<'a, ShapeColorContext<'a, 'b>, Matrix2d> for ShapeColorContext<'a, 'b> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> ShapeColorContext<'a, 'b> {
+        ShapeColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            shape: Borrowed(self.shape.get()),
+            fill_rule: Borrowed(self.fill_rule.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasViewTransform<'a, Matrix2d> for ShapeColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a, 'b> CanViewTransform<'a, ShapeColorContext<'a, 'b>, Matrix2d> for ShapeColorContext<'a, 'b> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> ShapeColorContext<'a, 'b> {
+        ShapeColorContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            shape: Borrowed(self.shape.get()),
+            fill_rule: Borrowed(self.fill_rule.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasColor<'a, Color> for ShapeColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_color(&'a self) -> &'a Color {
+        self.color.get()
+    }
+}
+
+impl<'a, 'b> CanColor<'a, ShapeColorContext<'a, 'b>, Color> for ShapeColorContext<'a, 'b> {
+    #[inline(always)]
+    fn color(&'a self, value: Color) -> ShapeColorContext<'a, 'b> {
+        ShapeColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            shape: Borrowed(self.shape.get()),
+            fill_rule: Borrowed(self.fill_rule.get()),
+            color: Value(value),
+        }
+    }
+}
+
+impl<'a, 'b> AddFillRule<'a, ShapeColorContext<'a, 'b>> for ShapeColorContext<'a, 'b> {
+    #[inline(always)]
+    fn fill_rule(&'a self, fill_rule: FillRule) -> ShapeColorContext<'a, 'b> {
+        ShapeColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            shape: Borrowed(self.shape.get()),
+            fill_rule: Value(fill_rule),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> ShapeColorContext<'a, 'b> {
+    /// Streams the filled shape with f32 or f64 vertices.
+    fn stream_tri_list<V: Coordinate>(&self, f: |vertices: &[V], colors: &[f32]|) {
+        with_shape_tri_list_xy_rgba_f32(
+            *self.transform.get(),
+            *self.shape.get(),
+            *self.fill_rule.get(),
+            *self.color.get(),
+            f
+        );
+    }
+}
+
+impl<'a, 'b> Fill<'a> for ShapeColorContext<'a, 'b> {
+    #[inline(always)]
+    fn fill<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let color = self.color.get();
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                // Turn on alpha blending if not completely opaque.
+                let needs_alpha = color[3] != 1.0;
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                if back_end.supports_tri_list_xy_f64_rgba_f32() {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f64_rgba_f32(vertices, colors)
+                    });
+                } else {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+                    });
+                }
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
+    }
+}
+
+impl<'a, 'b> Clear for ShapeColorContext<'a, 'b> {
+    #[inline(always)]
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
+
+#[test]
+fn test_fill_hole() {
+    use {AddColor, AddShape, Context, RelativeTransform2d, SoftwareBackEnd};
+
+    let outer = [0.0, 0.0, 4.0, 0.0, 4.0, 4.0, 0.0, 4.0];
+    // The same winding as the outer ring.
+    let inner = [1.0, 1.0, 3.0, 1.0, 3.0, 3.0, 1.0, 3.0];
+    let rings = [outer.as_slice(), inner.as_slice()];
+    let c = Context::new();
+    let d = c.trans(-1.0, -1.0);
+    let d = d.scale_local(0.5, 0.5);
+    let e = d.shape(rings.as_slice());
+    let f = e.rgba(1.0, 1.0, 1.0, 1.0);
+
+    let mut back_end = SoftwareBackEnd::new(4, 4);
+    f.non_zero().fill(&mut back_end).unwrap();
+    assert!(back_end.pixel(0, 0) == [255, 255, 255, 255]);
+    assert!(back_end.pixel(1, 1) == [255, 255, 255, 255]);
+
+    let mut back_end = SoftwareBackEnd::new(4, 4);
+    f.even_odd().fill(&mut back_end).unwrap();
+    assert!(back_end.pixel(0, 0) == [255, 255, 255, 255]);
+    assert!(back_end.pixel(1, 1) == [0, 0, 0, 0]);
+}
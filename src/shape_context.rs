@@ -0,0 +1,119 @@
+use {
+    AddColor,
+    AddFillRule,
+    Borrowed,
+    Field,
+    FillRule,
+    ShapeColorContext,
+    Value,
+};
+use internal::{
+    CanTransform,
+    CanViewTransform,
+    ColorComponent,
+    HasTransform,
+    HasViewTransform,
+    Matrix2d,
+    Rectangle,
+    Shape,
+};
+
+/// A shape context.
+pub struct ShapeContext<'a, 'b> {
+    /// Base/original transformation.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transformation.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current shape.
+    pub shape: Field<'a, Shape<'b>>,
+    /// Current fill rule.
+    pub fill_rule: Field<'a, FillRule>,
+}
+
+impl<'a, 'b> Clone for ShapeContext<'a, 'b> {
+    #[inline(always)]
+    fn clone(&self) -> ShapeContext<'static, 'b> {
+        ShapeContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            shape: Value(*self.shape.get()),
+            fill_rule: Value(*self.fill_rule.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasTransform<'a, Matrix2d> for ShapeContext<'a, 'b> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a, 'b> CanTransform<'a, ShapeContext<'a, 'b>, Matrix2d> for ShapeContext<'a, 'b> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> ShapeContext<'a, 'b> {
+        ShapeContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            shape: Borrowed(self.shape.get()),
+            fill_rule: Borrowed(self.fill_rule.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasViewTransform<'a, Matrix2d> for ShapeContext<'a, 'b> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a, 'b> CanViewTransform<'a, ShapeContext<'a, 'b>, Matrix2d> for ShapeContext<'a, 'b> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> ShapeContext<'a, 'b> {
+        ShapeContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            shape: Borrowed(self.shape.get()),
+            fill_rule: Borrowed(self.fill_rule.get()),
+        }
+    }
+}
+
+impl<'a, 'b> AddFillRule<'a, ShapeContext<'a, 'b>> for ShapeContext<'a, 'b> {
+    #[inline(always)]
+    fn fill_rule(&'a self, fill_rule: FillRule) -> ShapeContext<'a, 'b> {
+        ShapeContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            shape: Borrowed(self.shape.get()),
+            fill_rule: Value(fill_rule),
+        }
+    }
+}
+
+impl<'a, 'b> AddColor<'a, ShapeColorContext<'a, 'b>> for ShapeContext<'a, 'b> {
+    #[inline(always)]
+    fn rgba(
+        &'a self, 
+        r: ColorComponent, 
+        g: ColorComponent, 
+        b: ColorComponent, 
+        a: ColorComponent
+    ) -> ShapeColorContext<'a, 'b> {
+        ShapeColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            shape: Borrowed(self.shape.get()),
+            fill_rule: Borrowed(self.fill_rule.get()),
+            color: Value([r, g, b, a]),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+/// Decides how an image, or the edges and the center of a nine-slice image, fill their area.
+pub enum SliceMode {
+    /// The image is stretched to fill the area.
+    Stretch,
+    /// The image is repeated at its source size, cutting the last copies.
+    ///
+    /// The number of copies is capped, see `triangulation::MAX_TILES`.
+    Tile,
+}
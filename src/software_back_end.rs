@@ -0,0 +1,664 @@
+//! A back-end that renders into an in-memory framebuffer.
+
+use std::cmp::{max, min};
+use {BackEnd};
+use triangulation::{expand_indexed_f32, tri_fan_indices, tri_strip_indices};
+
+/// A texture registered in the software back-end.
+struct Texture {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+    has_alpha: bool,
+}
+
+/// Whether triangles mark the stencil or are restricted by it.
+enum StencilMode {
+    StencilOff,
+    StencilWrite,
+    StencilTest,
+}
+
+/// Renders triangles on the CPU into an RGBA framebuffer.
+///
+/// The vertices are expected in normalized device coordinates,
+/// with `[-1.0, -1.0]` in the lower left corner
+/// and `[1.0, 1.0]` in the upper right corner.
+///
+/// The pixels are stored row by row starting at the top,
+/// using 4 bytes per pixel in the order red, green, blue, alpha.
+pub struct SoftwareBackEnd {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+    textures: Vec<Option<Texture>>,
+    alpha_blend: bool,
+    texture: Option<uint>,
+    scissor: Option<[uint, ..4]>,
+    stencil: Vec<bool>,
+    stencil_mode: StencilMode,
+}
+
+impl SoftwareBackEnd {
+    /// Creates a new back-end with a transparent black framebuffer.
+    pub fn new(width: u32, height: u32) -> SoftwareBackEnd {
+        SoftwareBackEnd {
+            width: width,
+            height: height,
+            pixels: Vec::from_elem(width as uint * height as uint * 4, 0u8),
+            textures: Vec::new(),
+            alpha_blend: false,
+            texture: None,
+            scissor: None,
+            stencil: Vec::new(),
+            stencil_mode: StencilOff,
+        }
+    }
+
+    /// Returns the width of the framebuffer in pixels.
+    #[inline(always)]
+    pub fn width(&self) -> u32 { self.width }
+
+    /// Returns the height of the framebuffer in pixels.
+    #[inline(always)]
+    pub fn height(&self) -> u32 { self.height }
+
+    /// Returns the pixels of the framebuffer.
+    #[inline(always)]
+    pub fn pixels<'a>(&'a self) -> &'a [u8] {
+        self.pixels.as_slice()
+    }
+
+    /// Returns the color of a pixel, counting from the upper left corner.
+    pub fn pixel(&self, x: u32, y: u32) -> [u8, ..4] {
+        let i = (y as uint * self.width as uint + x as uint) * 4;
+        let p = self.pixels.as_slice();
+        [p[i], p[i + 1], p[i + 2], p[i + 3]]
+    }
+
+    /// Registers a texture from RGBA pixels and returns the texture id.
+    ///
+    /// The pixels are stored row by row starting at the top.
+    /// The ids of deleted textures are reused.
+    /// Fails if the number of pixels does not match the size.
+    pub fn add_texture(&mut self, width: u32, height: u32, pixels: &[u8]) -> uint {
+        assert_eq!(pixels.len(), width as uint * height as uint * 4);
+        let has_alpha = pixels.chunks(4).any(|p| p[3] != 255);
+        let texture = Some(Texture {
+            width: width,
+            height: height,
+            pixels: Vec::from_slice(pixels),
+            has_alpha: has_alpha,
+        });
+        match self.textures.iter().position(|t| t.is_none()) {
+            Some(id) => {
+                *self.textures.get_mut(id) = texture;
+                id
+            },
+            None => {
+                self.textures.push(texture);
+                self.textures.len() - 1
+            }
+        }
+    }
+
+    /// Returns the number of textures that are not deleted.
+    pub fn texture_count(&self) -> uint {
+        self.textures.iter().filter(|t| t.is_some()).count()
+    }
+
+    /// Returns a texture, or `None` if the id is unknown or deleted.
+    fn find_texture<'a>(&'a self, texture_id: uint) -> Option<&'a Texture> {
+        match self.textures.as_slice().get(texture_id) {
+            Some(&Some(ref texture)) => Some(texture),
+            _ => None,
+        }
+    }
+
+    /// Samples the current texture with nearest neighbor filtering.
+    ///
+    /// Unknown or deleted textures are transparent.
+    fn sample(&self, u: f32, v: f32) -> [f32, ..4] {
+        let texture = match self.texture {
+            None => return [1.0, ..4],
+            Some(id) => match self.find_texture(id) {
+                Some(texture) => texture,
+                None => return [0.0, ..4],
+            },
+        };
+        let (w, h) = (texture.width as int, texture.height as int);
+        let x = min(max((u * w as f32).floor() as int, 0), w - 1);
+        let y = min(max((v * h as f32).floor() as int, 0), h - 1);
+        let i = ((y * w + x) * 4) as uint;
+        let p = texture.pixels.as_slice();
+        [p[i] as f32 / 255.0, p[i + 1] as f32 / 255.0,
+         p[i + 2] as f32 / 255.0, p[i + 3] as f32 / 255.0]
+    }
+
+    /// Writes a color to a pixel, blending if alpha blending is enabled.
+    fn write_pixel(&mut self, x: uint, y: uint, color: [f32, ..4]) {
+        let j = y * self.width as uint + x;
+        match self.stencil_mode {
+            StencilOff => {},
+            StencilWrite => {
+                *self.stencil.get_mut(j) = true;
+                return;
+            },
+            StencilTest => if !*self.stencil.get(j) { return; },
+        }
+        let i = j * 4;
+        let alpha_blend = self.alpha_blend;
+        let p = self.pixels.mut_slice(i, i + 4);
+        if alpha_blend {
+            let a = color[3];
+            for j in range(0u, 3) {
+                let dst = p[j] as f32 / 255.0;
+                p[j] = to_u8(color[j] * a + dst * (1.0 - a));
+            }
+            let dst_a = p[3] as f32 / 255.0;
+            p[3] = to_u8(a + dst_a * (1.0 - a));
+        } else {
+            for j in range(0u, 4) {
+                p[j] = to_u8(color[j]);
+            }
+        }
+    }
+
+    /// Returns the pixels that can be drawn to, [min_x, min_y, max_x, max_y].
+    #[inline(always)]
+    fn bounds(&self) -> [uint, ..4] {
+        match self.scissor {
+            Some(bounds) => bounds,
+            None => [0, 0, self.width as uint, self.height as uint],
+        }
+    }
+
+    /// Rasterizes a triangle in normalized device coordinates.
+    ///
+    /// Pixels are covered when their center is inside the triangle.
+    /// Pixel centers on an edge are covered by only one of the triangles sharing it.
+    fn fill_triangle(
+        &mut self,
+        xy: [[f32, ..2], ..3],
+        rgba: [[f32, ..4], ..3],
+        uv: Option<[[f32, ..2], ..3]>
+    ) {
+        let (w, h) = (self.width as f32, self.height as f32);
+        let mut p = [[0.0f32, ..2], ..3];
+        for i in range(0u, 3) {
+            p[i] = [(xy[i][0] + 1.0) * 0.5 * w, (1.0 - xy[i][1]) * 0.5 * h];
+        }
+        let mut area = edge(p[0], p[1], p[2]);
+        if area == 0.0 { return; }
+        // Make the order consistent so the edge rule works for both windings.
+        let (mut rgba, mut uv) = (rgba, uv);
+        if area < 0.0 {
+            p.swap(1, 2);
+            rgba.swap(1, 2);
+            uv = uv.map(|mut uv| { uv.swap(1, 2); uv });
+            area = -area;
+        }
+
+        let bounds = self.bounds();
+        let min_x = p[0][0].min(p[1][0]).min(p[2][0]).floor().max(bounds[0] as f32);
+        let max_x = p[0][0].max(p[1][0]).max(p[2][0]).ceil().min(bounds[2] as f32);
+        let min_y = p[0][1].min(p[1][1]).min(p[2][1]).floor().max(bounds[1] as f32);
+        let max_y = p[0][1].max(p[1][1]).max(p[2][1]).ceil().min(bounds[3] as f32);
+        if min_x >= max_x || min_y >= max_y { return; }
+
+        for y in range(min_y as uint, max_y as uint) {
+            for x in range(min_x as uint, max_x as uint) {
+                let q = [x as f32 + 0.5, y as f32 + 0.5];
+                let w0 = edge(p[1], p[2], q);
+                let w1 = edge(p[2], p[0], q);
+                let w2 = edge(p[0], p[1], q);
+                if !covers(w0, p[1], p[2])
+                || !covers(w1, p[2], p[0])
+                || !covers(w2, p[0], p[1]) { continue; }
+
+                let (b0, b1, b2) = (w0 / area, w1 / area, w2 / area);
+                let mut color = [0.0f32, ..4];
+                for j in range(0u, 4) {
+                    color[j] = b0 * rgba[0][j] + b1 * rgba[1][j] + b2 * rgba[2][j];
+                }
+                match uv {
+                    None => {},
+                    Some(uv) => {
+                        let u = b0 * uv[0][0] + b1 * uv[1][0] + b2 * uv[2][0];
+                        let v = b0 * uv[0][1] + b1 * uv[1][1] + b2 * uv[2][1];
+                        let texel = self.sample(u, v);
+                        for j in range(0u, 4) {
+                            color[j] *= texel[j];
+                        }
+                    }
+                }
+                self.write_pixel(x, y, color);
+            }
+        }
+    }
+
+    /// Rasterizes a triangle list with optional texture coordinates.
+    fn tri_list(&mut self, vertices: &[f32], colors: &[f32], texture_coords: Option<&[f32]>) {
+        let n = vertices.len() / 6;
+        for i in range(0, n) {
+            let mut xy = [[0.0f32, ..2], ..3];
+            let mut rgba = [[0.0f32, ..4], ..3];
+            let mut uv = [[0.0f32, ..2], ..3];
+            for k in range(0u, 3) {
+                let v = i * 3 + k;
+                xy[k] = [vertices[v * 2], vertices[v * 2 + 1]];
+                rgba[k] = [colors[v * 4], colors[v * 4 + 1],
+                           colors[v * 4 + 2], colors[v * 4 + 3]];
+                match texture_coords {
+                    None => {},
+                    Some(uvs) => uv[k] = [uvs[v * 2], uvs[v * 2 + 1]],
+                }
+            }
+            self.fill_triangle(xy, rgba, texture_coords.map(|_| uv));
+        }
+    }
+}
+
+/// Converts a color component to a byte.
+#[inline(always)]
+fn to_u8(c: f32) -> u8 {
+    (c.max(0.0).min(1.0) * 255.0 + 0.5) as u8
+}
+
+/// Computes the edge function of point `q` relative to the edge from `a` to `b`.
+#[inline(always)]
+fn edge(a: [f32, ..2], b: [f32, ..2], q: [f32, ..2]) -> f32 {
+    (b[0] - a[0]) * (q[1] - a[1]) - (b[1] - a[1]) * (q[0] - a[0])
+}
+
+/// Returns true if the edge function value covers the point.
+///
+/// A point exactly on the edge is covered from one side only,
+/// decided by the direction of the edge.
+#[inline(always)]
+fn covers(w: f32, a: [f32, ..2], b: [f32, ..2]) -> bool {
+    if w != 0.0 { return w > 0.0; }
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    dy > 0.0 || (dy == 0.0 && dx < 0.0)
+}
+
+impl BackEnd for SoftwareBackEnd {
+    #[inline(always)]
+    fn supports_clear_rgba(&self) -> bool { true }
+
+    fn clear_rgba(&mut self, r: f32, g: f32, b: f32, a: f32) {
+        let color = [to_u8(r), to_u8(g), to_u8(b), to_u8(a)];
+        let bounds = self.bounds();
+        let width = self.width as uint;
+        for y in range(bounds[1], bounds[3]) {
+            let row = self.pixels.mut_slice((y * width + bounds[0]) * 4, (y * width + bounds[2]) * 4);
+            for (i, p) in row.mut_iter().enumerate() {
+                *p = color[i % 4];
+            }
+        }
+    }
+
+    fn enable_alpha_blend(&mut self) {
+        self.alpha_blend = true;
+    }
+
+    fn disable_alpha_blend(&mut self) {
+        self.alpha_blend = false;
+    }
+
+    #[inline(always)]
+    fn supports_scissor(&self) -> bool { true }
+
+    fn set_scissor(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        // Covers the pixels with center inside the rectangle.
+        let (fw, fh) = (self.width as f32, self.height as f32);
+        let to_pixel = |p: f32, size: f32| (p - 0.5).ceil().max(0.0).min(size) as uint;
+        self.scissor = Some([
+            to_pixel((x + 1.0) * 0.5 * fw, fw),
+            to_pixel((1.0 - (y + h)) * 0.5 * fh, fh),
+            to_pixel((x + w + 1.0) * 0.5 * fw, fw),
+            to_pixel((1.0 - y) * 0.5 * fh, fh)
+        ]);
+    }
+
+    fn clear_scissor(&mut self) {
+        self.scissor = None;
+    }
+
+    #[inline(always)]
+    fn supports_stencil(&self) -> bool { true }
+
+    fn begin_stencil_write(&mut self) {
+        self.stencil = Vec::from_elem(self.width as uint * self.height as uint, false);
+        self.stencil_mode = StencilWrite;
+    }
+
+    fn begin_stencil_test(&mut self) {
+        self.stencil_mode = StencilTest;
+    }
+
+    fn end_stencil(&mut self) {
+        self.stencil_mode = StencilOff;
+    }
+
+    #[inline(always)]
+    fn supports_single_texture(&self) -> bool { true }
+
+    fn enable_single_texture(&mut self, texture_id: uint) {
+        self.texture = Some(texture_id);
+    }
+
+    fn disable_single_texture(&mut self) {
+        self.texture = None;
+    }
+
+    #[inline(always)]
+    fn has_texture_alpha(&self, texture_id: uint) -> bool {
+        match self.find_texture(texture_id) {
+            Some(texture) => texture.has_alpha,
+            None => false,
+        }
+    }
+
+    #[inline(always)]
+    fn supports_texture_upload(&self) -> bool { true }
+
+    fn create_texture(&mut self, width: u32, height: u32, pixels: &[u8]) -> Option<uint> {
+        if pixels.len() != width as uint * height as uint * 4 { return None; }
+        Some(self.add_texture(width, height, pixels))
+    }
+
+    fn update_texture(
+        &mut self,
+        texture_id: uint,
+        x: u32, y: u32, w: u32, h: u32,
+        pixels: &[u8]
+    ) {
+        let texture = match self.textures.as_mut_slice().get_mut(texture_id) {
+            Some(&Some(ref mut texture)) => texture,
+            _ => return,
+        };
+        // Rectangles outside the texture or with the wrong number of pixels are ignored.
+        let (x, y, w, h) = (x as uint, y as uint, w as uint, h as uint);
+        if x + w > texture.width as uint || y + h > texture.height as uint
+        || pixels.len() != w * h * 4 { return; }
+        let row = w * 4;
+        for j in range(0, h) {
+            let i = ((y + j) * texture.width as uint + x) * 4;
+            texture.pixels.mut_slice(i, i + row).copy_from(pixels.slice(j * row, j * row + row));
+        }
+        texture.has_alpha = texture.pixels.as_slice().chunks(4).any(|p| p[3] != 255);
+    }
+
+    fn delete_texture(&mut self, texture_id: uint) {
+        if texture_id < self.textures.len() {
+            *self.textures.get_mut(texture_id) = None;
+        }
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f64_rgba_f32(&self) -> bool { true }
+
+    fn tri_list_xy_f64_rgba_f32(
+        &mut self,
+        vertices: &[f64],
+        colors: &[f32]
+    ) {
+        // The rasterizer works with f32 pixel coordinates.
+        let vertices: Vec<f32> = vertices.iter().map(|&x| x as f32).collect();
+        self.tri_list_xy_f32_rgba_f32(vertices.as_slice(), colors);
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_rgba_f32(&self) -> bool { true }
+
+    fn tri_list_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        // Ignore the current texture when drawing without texture coordinates.
+        let texture = self.texture.take();
+        self.tri_list(vertices, colors, None);
+        self.texture = texture;
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_rgba_f32_uv_f32(&self) -> bool { true }
+
+    fn tri_list_xy_f32_rgba_f32_uv_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32]
+    ) {
+        // Triangles with an unknown or deleted texture are ignored.
+        match self.texture {
+            Some(id) if self.find_texture(id).is_none() => return,
+            _ => {}
+        }
+        self.tri_list(vertices, colors, Some(texture_coords));
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32(&self) -> bool { true }
+
+    fn tri_list_indexed_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        indices: &[u32]
+    ) {
+        self.tri_list_xy_f32_rgba_f32(
+            expand_indexed_f32(vertices, 2, indices).as_slice(),
+            expand_indexed_f32(colors, 4, indices).as_slice()
+        );
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32(&self) -> bool { true }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32],
+        indices: &[u32]
+    ) {
+        // Triangles with an unknown or deleted texture are ignored.
+        match self.texture {
+            Some(id) if self.find_texture(id).is_none() => return,
+            _ => {}
+        }
+        self.tri_list(
+            expand_indexed_f32(vertices, 2, indices).as_slice(),
+            expand_indexed_f32(colors, 4, indices).as_slice(),
+            Some(expand_indexed_f32(texture_coords, 2, indices).as_slice())
+        );
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_u16(&self) -> bool { true }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_u16(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        indices: &[u16]
+    ) {
+        let indices: Vec<u32> = indices.iter().map(|&i| i as u32).collect();
+        self.tri_list_indexed_xy_f32_rgba_f32(vertices, colors, indices.as_slice());
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(&self) -> bool { true }
+
+    fn tri_list_indexed_xy_f32_rgba_f32_uv_f32_u16(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32],
+        texture_coords: &[f32],
+        indices: &[u16]
+    ) {
+        let indices: Vec<u32> = indices.iter().map(|&i| i as u32).collect();
+        self.tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+            vertices, colors, texture_coords, indices.as_slice());
+    }
+
+    #[inline(always)]
+    fn supports_tri_strip_xy_f32_rgba_f32(&self) -> bool { true }
+
+    fn tri_strip_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        let indices = tri_strip_indices(vertices.len() / 2);
+        self.tri_list_indexed_xy_f32_rgba_f32(vertices, colors, indices.as_slice());
+    }
+
+    #[inline(always)]
+    fn supports_tri_fan_xy_f32_rgba_f32(&self) -> bool { true }
+
+    fn tri_fan_xy_f32_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        colors: &[f32]
+    ) {
+        let indices = tri_fan_indices(vertices.len() / 2);
+        self.tri_list_indexed_xy_f32_rgba_f32(vertices, colors, indices.as_slice());
+    }
+
+    #[inline(always)]
+    fn supports_tri_list_xy_f32_uniform_rgba_f32(&self) -> bool { true }
+
+    fn tri_list_xy_f32_uniform_rgba_f32(
+        &mut self,
+        vertices: &[f32],
+        r: f32, g: f32, b: f32, a: f32
+    ) {
+        let color = [r, g, b, a];
+        let colors = Vec::from_fn(vertices.len() * 2, |i| color[i % 4]);
+        self.tri_list_xy_f32_rgba_f32(vertices, colors.as_slice());
+    }
+}
+
+/// Forwards to a software back-end, but hides the scissor and stencil.
+///
+/// Used to test the fallbacks for back-ends without these features.
+#[cfg(test)]
+pub struct MinimalBackEnd {
+    /// The back-end that renders.
+    pub software: SoftwareBackEnd,
+}
+
+#[cfg(test)]
+impl BackEnd for MinimalBackEnd {
+    fn supports_clear_rgba(&self) -> bool { true }
+
+    fn clear_rgba(&mut self, r: f32, g: f32, b: f32, a: f32) {
+        self.software.clear_rgba(r, g, b, a);
+    }
+
+    fn enable_alpha_blend(&mut self) { self.software.enable_alpha_blend(); }
+
+    fn disable_alpha_blend(&mut self) { self.software.disable_alpha_blend(); }
+
+    fn supports_tri_list_xy_f32_rgba_f32(&self) -> bool { true }
+
+    fn tri_list_xy_f32_rgba_f32(&mut self, vertices: &[f32], colors: &[f32]) {
+        self.software.tri_list_xy_f32_rgba_f32(vertices, colors);
+    }
+}
+
+#[test]
+fn test_fill_rect() {
+    use {AddColor, AddRectangle, Context, Fill};
+
+    let mut back_end = SoftwareBackEnd::new(4, 4);
+    let c = Context::new();
+    // Fill the lower left quarter of the framebuffer.
+    c.rect(-1.0, -1.0, 1.0, 1.0).rgba(1.0, 0.0, 0.0, 1.0).fill(&mut back_end).unwrap();
+    assert!(back_end.pixel(0, 3) == [255, 0, 0, 255]);
+    assert!(back_end.pixel(1, 2) == [255, 0, 0, 255]);
+    assert!(back_end.pixel(2, 2) == [0, 0, 0, 0]);
+    assert!(back_end.pixel(1, 1) == [0, 0, 0, 0]);
+}
+
+#[test]
+fn test_alpha_blend() {
+    use {AddColor, AddRectangle, Clear, Context, Fill};
+
+    let mut back_end = SoftwareBackEnd::new(2, 2);
+    let c = Context::new();
+    c.rgba(0.0, 0.0, 1.0, 1.0).clear(&mut back_end).unwrap();
+    c.rect(-1.0, -1.0, 2.0, 2.0).rgba(1.0, 0.0, 0.0, 0.5).fill(&mut back_end).unwrap();
+    assert!(back_end.pixel(1, 1) == [128, 0, 128, 255]);
+}
+
+#[test]
+fn test_draw_image() {
+    use {AddImage, AddRectangle, Context, Draw, Image};
+
+    let mut back_end = SoftwareBackEnd::new(2, 2);
+    let texture_id = back_end.add_texture(2, 1, [
+        255, 0, 0, 255,     0, 255, 0, 255
+    ]);
+    let image = Image {
+        texture_id: texture_id,
+        texture_width: 2,
+        texture_height: 1,
+        source_rect: [0, 0, 2, 1],
+    };
+    let c = Context::new();
+    c.rect(-1.0, -1.0, 2.0, 2.0).image(image).draw(&mut back_end).unwrap();
+    assert!(back_end.pixel(0, 0) == [255, 0, 0, 255]);
+    assert!(back_end.pixel(1, 1) == [0, 255, 0, 255]);
+}
+
+#[test]
+fn test_draw_unknown_texture() {
+    use {AddColor, AddImage, AddRectangle, Clear, Context, Draw, Image};
+
+    let mut back_end = SoftwareBackEnd::new(2, 2);
+    let texture_id = back_end.add_texture(1, 1, [255, 0, 0, 255]);
+    back_end.delete_texture(texture_id);
+    let c = Context::new();
+    c.rgba(0.0, 0.0, 1.0, 1.0).clear(&mut back_end).unwrap();
+    for &id in [texture_id, 7].iter() {
+        let image = Image {
+            texture_id: id,
+            texture_width: 1,
+            texture_height: 1,
+            source_rect: [0, 0, 1, 1],
+        };
+        // Unknown and deleted textures draw nothing instead of failing.
+        c.rect(-1.0, -1.0, 2.0, 2.0).image(image).draw(&mut back_end).unwrap();
+        assert!(back_end.pixel(0, 0) == [0, 0, 255, 255]);
+        back_end.enable_single_texture(id);
+        back_end.tri_list_indexed_xy_f32_rgba_f32_uv_f32(
+            [-1.0, -1.0, 1.0, -1.0, -1.0, 1.0, 1.0, 1.0],
+            [1.0, ..16],
+            [0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+            [0, 1, 2, 1, 3, 2]
+        );
+        back_end.disable_single_texture();
+        assert!(back_end.pixel(0, 0) == [0, 0, 255, 255]);
+    }
+    back_end.delete_texture(7);
+}
+
+#[test]
+fn test_invalid_texture_upload() {
+    let mut back_end = SoftwareBackEnd::new(2, 2);
+    assert_eq!(back_end.create_texture(2, 2, [255, ..12]), None);
+    assert_eq!(back_end.create_texture(0x10000, 0x10000, [255, ..4]), None);
+    let texture_id = back_end.create_texture(1, 1, [255, 0, 0, 255]).unwrap();
+    assert!(!back_end.has_texture_alpha(texture_id));
+    // Rectangles outside the texture and wrong pixel counts are ignored.
+    back_end.update_texture(texture_id, 1, 0, 1, 1, [0, 0, 0, 0]);
+    back_end.update_texture(texture_id, 0, 0, 1, 1, [0, 0, 0]);
+    assert!(!back_end.has_texture_alpha(texture_id));
+    back_end.update_texture(texture_id, 0, 0, 1, 1, [0, 0, 0, 0]);
+    assert!(back_end.has_texture_alpha(texture_id));
+}
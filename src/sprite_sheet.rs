@@ -0,0 +1,372 @@
+//! Packs many images into a few textures.
+
+use std::cmp::{max, min};
+use {BackEnd, Image, Texture, Textures};
+use internal::{
+    PixelRectangle,
+};
+
+/// Collects RGBA images to be packed into sprite sheet pages.
+///
+/// The images are placed with a skyline packer,
+/// tallest images first, starting a new page when one is full.
+pub struct SpriteSheetBuilder {
+    page_width: u32,
+    page_height: u32,
+    padding: u32,
+    extrude: u32,
+    sprites: Vec<(u32, u32, Vec<u8>)>,
+}
+
+impl SpriteSheetBuilder {
+    /// Creates a new builder for pages of a given size in pixels.
+    pub fn new(page_width: u32, page_height: u32) -> SpriteSheetBuilder {
+        SpriteSheetBuilder {
+            page_width: page_width,
+            page_height: page_height,
+            padding: 1,
+            extrude: 0,
+            sprites: Vec::new(),
+        }
+    }
+
+    /// Sets the number of empty pixels between sprites.
+    ///
+    /// The default is 1.
+    pub fn padding(&mut self, padding: u32) {
+        self.padding = padding;
+    }
+
+    /// Sets the number of times the edge pixels of a sprite are repeated around it.
+    ///
+    /// This prevents filtering from sampling neighbouring sprites.
+    /// The default is 0.
+    pub fn extrude(&mut self, extrude: u32) {
+        self.extrude = extrude;
+    }
+
+    /// Adds an image from RGBA pixels stored row by row starting at the top.
+    ///
+    /// Returns the index of the image in the packed sprite sheet,
+    /// or `None` if the number of pixels does not match the size.
+    pub fn add(&mut self, width: u32, height: u32, pixels: &[u8]) -> Option<uint> {
+        if pixels.len() != width as uint * height as uint * 4 { return None; }
+        self.sprites.push((width, height, Vec::from_slice(pixels)));
+        Some(self.sprites.len() - 1)
+    }
+
+    /// Packs the images.
+    ///
+    /// Returns `None` if an image with its extrusion does not fit in a page.
+    pub fn pack(&self) -> Option<SpriteSheet> {
+        let e = self.extrude;
+        // Padding is added to the right and bottom side of each sprite,
+        // so the page is extended to let sprites touch the edges.
+        let (page_w, page_h) = (self.page_width + self.padding, self.page_height + self.padding);
+        let mut order: Vec<uint> = range(0, self.sprites.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (_, ha, _) = *self.sprites.get(a);
+            let (_, hb, _) = *self.sprites.get(b);
+            hb.cmp(&ha)
+        });
+
+        let mut skylines: Vec<Vec<SkylineNode>> = Vec::new();
+        let mut placements: Vec<(uint, PixelRectangle)> =
+            range(0, self.sprites.len()).map(|_| (0, [0, 0, 0, 0])).collect();
+        let mut pages: Vec<Vec<u8>> = Vec::new();
+        for &i in order.iter() {
+            let &(w, h, ref pixels) = self.sprites.get(i);
+            if w == 0 || h == 0 { continue; }
+            let (cell_w, cell_h) = (w + 2 * e, h + 2 * e);
+            if cell_w > self.page_width || cell_h > self.page_height { return None; }
+            let (aw, ah) = (cell_w + self.padding, cell_h + self.padding);
+            let mut found = None;
+            for (page, skyline) in skylines.mut_iter().enumerate() {
+                match skyline_insert(skyline, page_w, page_h, aw, ah) {
+                    Some((x, y)) => { found = Some((page, x, y)); break; },
+                    None => {},
+                }
+            }
+            let (page, x, y) = match found {
+                Some(found) => found,
+                None => {
+                    let mut skyline = vec![SkylineNode { x: 0, y: 0, width: page_w }];
+                    let (x, y) = skyline_insert(&mut skyline, page_w, page_h, aw, ah).unwrap();
+                    skylines.push(skyline);
+                    pages.push(Vec::from_elem(self.page_size(), 0u8));
+                    (skylines.len() - 1, x, y)
+                }
+            };
+            copy_extruded(pages.get_mut(page).as_mut_slice(), self.page_width,
+                pixels.as_slice(), w, h, x, y, e);
+            *placements.get_mut(i) = (page, [x + e, y + e, w, h]);
+        }
+        // Images without pixels refer to the first page, so it must exist.
+        if pages.is_empty() && !placements.is_empty() {
+            pages.push(Vec::from_elem(self.page_size(), 0u8));
+        }
+        Some(SpriteSheet {
+            page_width: self.page_width,
+            page_height: self.page_height,
+            pages: pages,
+            placements: placements,
+        })
+    }
+
+    /// Returns the number of bytes in a page.
+    fn page_size(&self) -> uint {
+        self.page_width as uint * self.page_height as uint * 4
+    }
+}
+
+/// A horizontal segment of the top of the occupied area in a page.
+struct SkylineNode {
+    x: u32,
+    y: u32,
+    width: u32,
+}
+
+/// Places a rectangle as low as possible, then as far left as possible.
+///
+/// Returns the top left corner, or `None` if the rectangle does not fit.
+fn skyline_insert(
+    skyline: &mut Vec<SkylineNode>,
+    page_w: u32,
+    page_h: u32,
+    w: u32,
+    h: u32
+) -> Option<(u32, u32)> {
+    let mut best: Option<(uint, u32, u32)> = None;
+    for i in range(0, skyline.len()) {
+        let x = skyline.get(i).x;
+        if x + w > page_w { break; }
+        // The rectangle rests on the highest node it spans.
+        let mut y = 0;
+        let mut j = i;
+        while j < skyline.len() && skyline.get(j).x < x + w {
+            y = max(y, skyline.get(j).y);
+            j += 1;
+        }
+        if y + h > page_h { continue; }
+        match best {
+            Some((_, _, best_y)) if best_y <= y => {},
+            _ => best = Some((i, x, y)),
+        }
+    }
+    let (i, x, y) = match best {
+        Some(best) => best,
+        None => return None,
+    };
+    skyline.insert(i, SkylineNode { x: x, y: y + h, width: w });
+    // Shrink or remove the nodes now below the rectangle.
+    let right = x + w;
+    while i + 1 < skyline.len() && skyline.get(i + 1).x < right {
+        let node_right = skyline.get(i + 1).x + skyline.get(i + 1).width;
+        if node_right <= right {
+            skyline.remove(i + 1);
+        } else {
+            let node = skyline.get_mut(i + 1);
+            node.width = node_right - right;
+            node.x = right;
+            break;
+        }
+    }
+    // Merge neighbours at the same height.
+    let mut k = 0;
+    while k + 1 < skyline.len() {
+        if skyline.get(k).y == skyline.get(k + 1).y {
+            let width = skyline.get(k + 1).width;
+            skyline.get_mut(k).width += width;
+            skyline.remove(k + 1);
+        } else {
+            k += 1;
+        }
+    }
+    Some((x, y))
+}
+
+/// Copies a sprite into a page, repeating its edge pixels `e` times around it.
+fn copy_extruded(
+    page: &mut [u8],
+    page_width: u32,
+    pixels: &[u8],
+    w: u32,
+    h: u32,
+    x: u32,
+    y: u32,
+    e: u32
+) {
+    for row in range(0, h + 2 * e) {
+        let src_row = min(max(row as int - e as int, 0), h as int - 1) as u32;
+        for col in range(0, w + 2 * e) {
+            let src_col = min(max(col as int - e as int, 0), w as int - 1) as u32;
+            let i = (src_row as uint * w as uint + src_col as uint) * 4;
+            let j = ((y + row) as uint * page_width as uint + (x + col) as uint) * 4;
+            page.mut_slice(j, j + 4).copy_from(pixels.slice(i, i + 4));
+        }
+    }
+}
+
+/// Images packed into pages of RGBA pixels.
+pub struct SpriteSheet {
+    page_width: u32,
+    page_height: u32,
+    pages: Vec<Vec<u8>>,
+    placements: Vec<(uint, PixelRectangle)>,
+}
+
+impl SpriteSheet {
+    /// Returns the width of the pages in pixels.
+    #[inline(always)]
+    pub fn page_width(&self) -> u32 { self.page_width }
+
+    /// Returns the height of the pages in pixels.
+    #[inline(always)]
+    pub fn page_height(&self) -> u32 { self.page_height }
+
+    /// Returns the number of pages.
+    #[inline(always)]
+    pub fn page_count(&self) -> uint { self.pages.len() }
+
+    /// Returns the RGBA pixels of a page stored row by row starting at the top.
+    #[inline(always)]
+    pub fn page_pixels<'a>(&'a self, page: uint) -> &'a [u8] {
+        self.pages.get(page).as_slice()
+    }
+
+    /// Returns the page and the source rectangle of an image.
+    ///
+    /// Images without pixels have an empty rectangle in the first page.
+    #[inline(always)]
+    pub fn placement(&self, index: uint) -> (uint, PixelRectangle) {
+        *self.placements.get(index)
+    }
+
+    /// Returns one image per input, given the texture id of each page.
+    ///
+    /// Use this when the pages are uploaded to the back-end manually.
+    pub fn images(&self, texture_ids: &[uint]) -> Vec<Image> {
+        self.placements.iter().map(|&(page, source_rect)| Image {
+            texture_id: texture_ids[page],
+            texture_width: self.page_width,
+            texture_height: self.page_height,
+            source_rect: source_rect,
+        }).collect()
+    }
+
+    /// Creates one texture per page.
+    ///
+    /// Returns `None` if the back-end does not support texture upload.
+    pub fn upload<B: BackEnd>(&self, textures: &Textures, back_end: &mut B) -> Option<Sprites> {
+        let mut page_textures = Vec::new();
+        for pixels in self.pages.iter() {
+            match textures.create(back_end, self.page_width, self.page_height, pixels.as_slice()) {
+                Some(texture) => page_textures.push(texture),
+                None => return None,
+            }
+        }
+        let ids: Vec<uint> = page_textures.iter().map(|t| t.id()).collect();
+        Some(Sprites {
+            images: self.images(ids.as_slice()),
+            textures: page_textures,
+        })
+    }
+}
+
+/// Images packed into textures.
+pub struct Sprites {
+    /// The textures of the pages, keeping them alive.
+    pub textures: Vec<Texture>,
+    /// One image per input, in the order they were added.
+    pub images: Vec<Image>,
+}
+
+#[cfg(test)]
+fn solid(n: uint, color: [u8, ..4]) -> Vec<u8> {
+    Vec::from_fn(n * 4, |i| color[i % 4])
+}
+
+#[test]
+fn test_skyline() {
+    let mut skyline = vec![SkylineNode { x: 0, y: 0, width: 8 }];
+    assert_eq!(skyline_insert(&mut skyline, 8, 8, 4, 4), Some((0, 0)));
+    assert_eq!(skyline_insert(&mut skyline, 8, 8, 4, 2), Some((4, 0)));
+    assert_eq!(skyline_insert(&mut skyline, 8, 8, 4, 2), Some((4, 2)));
+    // Both sides are now at the same height and merged.
+    assert_eq!(skyline.len(), 1);
+    assert_eq!(skyline_insert(&mut skyline, 8, 8, 8, 4), Some((0, 4)));
+    assert_eq!(skyline_insert(&mut skyline, 8, 8, 1, 1), None);
+}
+
+#[test]
+fn test_sprite_sheet() {
+    let mut builder = SpriteSheetBuilder::new(8, 8);
+    builder.padding(0);
+    builder.extrude(1);
+    let red = builder.add(2, 2, solid(4, [255, 0, 0, 255]).as_slice()).unwrap();
+    let green = builder.add(6, 2, solid(6 * 2, [0, 255, 0, 255]).as_slice()).unwrap();
+    let blue = builder.add(6, 6, solid(6 * 6, [0, 0, 255, 255]).as_slice()).unwrap();
+    let empty = builder.add(0, 0, []).unwrap();
+    // The pixels must match the size.
+    assert_eq!(builder.add(2, 2, [0u8, ..4]), None);
+    let sheet = builder.pack().unwrap();
+    assert_eq!(sheet.page_count(), 2);
+    // The tallest image is placed first, images of equal height in order.
+    assert_eq!(sheet.placement(blue).val0(), 0);
+    assert!(sheet.placement(blue).val1() == [1, 1, 6, 6]);
+    assert_eq!(sheet.placement(green).val0(), 1);
+    assert!(sheet.placement(green).val1() == [1, 5, 6, 2]);
+    assert_eq!(sheet.placement(red).val0(), 1);
+    assert!(sheet.placement(red).val1() == [1, 1, 2, 2]);
+    assert_eq!(sheet.placement(empty).val0(), 0);
+    assert!(sheet.placement(empty).val1() == [0, 0, 0, 0]);
+    // Edge pixels are extruded.
+    let page = sheet.page_pixels(0);
+    assert!(page.slice(0, 4) == [0, 0, 255, 255]);
+    assert!(page.slice((7 * 8 + 7) * 4, (7 * 8 + 8) * 4) == [0, 0, 255, 255]);
+
+    let mut big = SpriteSheetBuilder::new(4, 4);
+    big.extrude(1);
+    big.add(3, 3, [0u8, ..3 * 3 * 4]).unwrap();
+    assert!(big.pack().is_none());
+}
+
+#[test]
+fn test_sprite_sheet_empty_images() {
+    let mut builder = SpriteSheetBuilder::new(4, 4);
+    builder.add(0, 0, []).unwrap();
+    builder.add(0, 2, []).unwrap();
+    let sheet = builder.pack().unwrap();
+    // The first page is created even though nothing is drawn to it.
+    assert_eq!(sheet.page_count(), 1);
+    let images = sheet.images([7]);
+    assert_eq!(images.get(1).texture_id, 7);
+    assert!(images.get(1).source_rect == [0, 0, 0, 0]);
+
+    let none = SpriteSheetBuilder::new(4, 4).pack().unwrap();
+    assert_eq!(none.page_count(), 0);
+}
+
+#[test]
+fn test_sprite_sheet_upload() {
+    use {AddImage, AddRectangle, Context, Draw, RelativeTransform2d, SoftwareBackEnd};
+
+    let mut builder = SpriteSheetBuilder::new(4, 4);
+    builder.add(2, 2, solid(4, [255, 0, 0, 255]).as_slice()).unwrap();
+    builder.add(1, 2, solid(2, [0, 255, 0, 255]).as_slice()).unwrap();
+    let sheet = builder.pack().unwrap();
+    let mut back_end = SoftwareBackEnd::new(4, 4);
+    let textures = Textures::new();
+    let sprites = sheet.upload(&textures, &mut back_end).unwrap();
+    assert_eq!(sprites.textures.len(), 1);
+    let image = sprites.images.get(1).clone();
+    assert_eq!(image.texture_id, sprites.textures.get(0).id());
+    assert_eq!(image.texture_width, 4);
+    assert!(image.source_rect == [3, 0, 1, 2]);
+
+    let c = Context::new();
+    let c = c.scale(2.0 / 4.0, -2.0 / 4.0);
+    let d = c.trans(-1.0, 1.0);
+    d.rect(0.0, 0.0, 4.0, 4.0).image(image).draw(&mut back_end).unwrap();
+    assert!(back_end.pixel(1, 1) == [0, 255, 0, 255]);
+}
@@ -14,6 +14,7 @@ use internal::{
     Line,
     Matrix2d,
     Radius,
+    Rectangle,
 };
 
 /// A line context with square border information.
@@ -22,6 +23,8 @@ pub struct SquareBorderLineContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transform.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current line.
     pub line: Field<'a, Line>,
     /// Current square border.
@@ -34,6 +37,7 @@ impl<'a> Clone for SquareBorderLineContext<'a> {
         SquareBorderLineContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             line: Value(*self.line.get()),
             square_border_radius: Value(*self.square_border_radius.get()),
         }
@@ -53,6 +57,7 @@ impl<'a> CanTransform<'a, SquareBorderLineContext<'a>, Matrix2d> for SquareBorde
         SquareBorderLineContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             square_border_radius: Borrowed(self.square_border_radius.get()),
         }
@@ -72,6 +77,7 @@ impl<'a> CanViewTransform<'a, SquareBorderLineContext<'a>, Matrix2d> for SquareB
         SquareBorderLineContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             square_border_radius: Borrowed(self.square_border_radius.get()),
         }
@@ -91,9 +97,11 @@ impl<'a> AddColor<'a, SquareBorderLineColorContext<'a>> for SquareBorderLineCont
         SquareBorderLineColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             line: Borrowed(self.line.get()),
             color: Value([r, g, b, a]),
             square_border_radius: Borrowed(self.square_border_radius.get()),
+            dash: Value(None),
         }
     }
 }
@@ -1,8 +1,10 @@
-use {BackEnd};
+use {BackEnd, RenderResult};
 
 /// Strokes a shape using a back-end.
 pub trait Stroke<'a> {
     /// Stroke shape using back-end.
-    fn stroke<B: BackEnd>(&'a self, back_end: &mut B);
+    ///
+    /// Returns an error if the back-end can not render it.
+    fn stroke<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult;
 }
 
@@ -0,0 +1,300 @@
+use {
+    AddTextLayout,
+    BackEnd,
+    Borrowed,
+    Clear,
+    ClipBackEnd,
+    Draw,
+    FallbackBackEnd,
+    Field,
+    Font,
+    GlyphAtlasFull,
+    Image,
+    RenderResult,
+    TextAlign,
+    UnsupportedTexture,
+    Value,
+};
+use triangulation::{
+    rect_tri_list_xy_f32,
+    rect_tri_list_uv_f32,
+};
+use internal::{
+    CanColor,
+    CanTransform,
+    CanViewTransform,
+    Color,
+    HasColor,
+    HasTransform,
+    HasViewTransform,
+    Matrix2d,
+    Rectangle,
+    Scalar,
+};
+
+/// A text color context.
+pub struct TextColorContext<'a, 'b> {
+    /// Base/original transformation.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transformation.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current font.
+    pub font: Field<'a, &'b Font>,
+    /// Current font size.
+    pub size: Field<'a, Scalar>,
+    /// Current text.
+    pub text: Field<'a, &'b str>,
+    /// Current maximum line width.
+    pub max_width: Field<'a, Option<Scalar>>,
+    /// Current alignment of lines.
+    pub align: Field<'a, TextAlign>,
+    /// Current color.
+    pub color: Field<'a, Color>,
+}
+
+impl<'a, 'b> Clone for TextColorContext<'a, 'b> {
+    #[inline(always)]
+    fn clone(&self) -> TextColorContext<'static, 'b> {
+        TextColorContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            font: Value(*self.font.get()),
+            size: Value(*self.size.get()),
+            text: Value(*self.text.get()),
+            max_width: Value(*self.max_width.get()),
+            align: Value(*self.align.get()),
+            color: Value(*self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasTransform<'a, Matrix2d> for TextColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a, 'b> CanTransform<'a, TextColorContext<'a, 'b>, Matrix2d> for TextColorContext<'a, 'b> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> TextColorContext<'a, 'b> {
+        TextColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            font: Borrowed(self.font.get()),
+            size: Borrowed(self.size.get()),
+            text: Borrowed(self.text.get()),
+            max_width: Borrowed(self.max_width.get()),
+            align: Borrowed(self.align.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasViewTransform<'a, Matrix2d> for TextColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a, 'b> CanViewTransform<'a, TextColorContext<'a, 'b>, Matrix2d> for TextColorContext<'a, 'b> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> TextColorContext<'a, 'b> {
+        TextColorContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            font: Borrowed(self.font.get()),
+            size: Borrowed(self.size.get()),
+            text: Borrowed(self.text.get()),
+            max_width: Borrowed(self.max_width.get()),
+            align: Borrowed(self.align.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasColor<'a, Color> for TextColorContext<'a, 'b> {
+    #[inline(always)]
+    fn get_color(&'a self) -> &'a Color {
+        self.color.get()
+    }
+}
+
+impl<'a, 'b> CanColor<'a, TextColorContext<'a, 'b>, Color> for TextColorContext<'a, 'b> {
+    #[inline(always)]
+    fn color(&'a self, value: Color) -> TextColorContext<'a, 'b> {
+        TextColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            font: Borrowed(self.font.get()),
+            size: Borrowed(self.size.get()),
+            text: Borrowed(self.text.get()),
+            max_width: Borrowed(self.max_width.get()),
+            align: Borrowed(self.align.get()),
+            color: Value(value),
+        }
+    }
+}
+
+impl<'a, 'b> AddTextLayout<'a, TextColorContext<'a, 'b>> for TextColorContext<'a, 'b> {
+    #[inline(always)]
+    fn align(&'a self, align: TextAlign) -> TextColorContext<'a, 'b> {
+        TextColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            font: Borrowed(self.font.get()),
+            size: Borrowed(self.size.get()),
+            text: Borrowed(self.text.get()),
+            max_width: Borrowed(self.max_width.get()),
+            align: Value(align),
+            color: Borrowed(self.color.get()),
+        }
+    }
+
+    #[inline(always)]
+    fn max_width(&'a self, width: Scalar) -> TextColorContext<'a, 'b> {
+        TextColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            font: Borrowed(self.font.get()),
+            size: Borrowed(self.size.get()),
+            text: Borrowed(self.text.get()),
+            max_width: Value(Some(width)),
+            align: Borrowed(self.align.get()),
+            color: Borrowed(self.color.get()),
+        }
+    }
+}
+
+impl<'a, 'b> Draw<'a> for TextColorContext<'a, 'b> {
+    fn draw<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        let font = *self.font.get();
+        let size = *self.size.get();
+        let color = self.color.get();
+        // Complete transparency does not need to be rendered.
+        if color[3] == 0.0 { return Ok(()); }
+        let glyphs = font.layout(size, *self.text.get(), *self.max_width.get(),
+            *self.align.get());
+        let glyph_ids: Vec<u16> = glyphs.iter().map(|g| g.glyph).collect();
+        let atlas_glyphs = match font.cache_glyphs(glyph_ids.as_slice(), size) {
+            Some(atlas_glyphs) => atlas_glyphs,
+            None => return Err(GlyphAtlasFull),
+        };
+        font.upload(back_end);
+        // Nothing can be drawn before the atlas is uploaded.
+        let texture_id = match font.texture_id() {
+            Some(texture_id) => texture_id,
+            None => return Err(UnsupportedTexture),
+        };
+        FallbackBackEnd::with_texture_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let atlas = font.atlas();
+                let m = *self.transform.get();
+                let mut vertices: Vec<f32> = Vec::new();
+                let mut colors: Vec<f32> = Vec::new();
+                let mut texture_coords: Vec<f32> = Vec::new();
+                for (g, atlas_glyph) in glyphs.iter().zip(atlas_glyphs.iter()) {
+                    let source_rect = atlas_glyph.source_rect;
+                    if source_rect[2] == 0 || source_rect[3] == 0 { continue; }
+                    let rect = [
+                        g.x + atlas_glyph.left,
+                        g.y + atlas_glyph.top,
+                        source_rect[2] as Scalar,
+                        source_rect[3] as Scalar
+                    ];
+                    let image = Image {
+                        texture_id: texture_id,
+                        texture_width: atlas.width(),
+                        texture_height: atlas.height(),
+                        source_rect: source_rect,
+                    };
+                    vertices.push_all(rect_tri_list_xy_f32(m, rect));
+                    for _ in range(0u, 6) { colors.push_all(*color); }
+                    texture_coords.push_all(rect_tri_list_uv_f32(&image));
+                }
+                if vertices.len() == 0 { return; }
+                // The glyph coverage is stored in the alpha channel.
+                back_end.enable_alpha_blend();
+                back_end.enable_single_texture(texture_id);
+                back_end.tri_list_xy_f32_rgba_f32_uv_f32(
+                    vertices.as_slice(),
+                    colors.as_slice(),
+                    texture_coords.as_slice()
+                );
+                back_end.disable_single_texture();
+                back_end.disable_alpha_blend();
+            })
+        })
+    }
+}
+
+impl<'a, 'b> Clear for TextColorContext<'a, 'b> {
+    #[inline(always)]
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
+    }
+}
+
+#[test]
+fn test_draw_text() {
+    use {AddColor, AddText, Context, RelativeTransform2d, SoftwareBackEnd};
+    use font::{test_font_data};
+
+    let font = Font::from_bytes(test_font_data()).unwrap();
+    let mut back_end = SoftwareBackEnd::new(32, 16);
+    // Use pixel coordinates with the y axis pointing down.
+    let c = Context::new();
+    let c = c.scale(2.0 / 32.0, -2.0 / 16.0);
+    let d = c.trans(-1.0, 1.0);
+    d.text(&font, 10.0, "AV").rgba(1.0, 0.0, 0.0, 1.0).draw(&mut back_end).unwrap();
+    // The square 'A' covers [1, 9] horizontally and [0, 8] vertically.
+    assert!(back_end.pixel(4, 4) == [255, 0, 0, 255]);
+    assert_eq!(back_end.pixel(0, 4)[3], 0);
+    assert_eq!(back_end.pixel(4, 9)[3], 0);
+    // The 'V' is kerned to start at 9, and its top row is full.
+    assert!(back_end.pixel(12, 0) == [255, 0, 0, 255]);
+    // The atlas is uploaded once and updated when new glyphs are added.
+    assert_eq!(back_end.texture_count(), 1);
+    assert!(!font.cache_text(10.0, "AV"));
+    d.text(&font, 10.0, "A").rgba(1.0, 0.0, 0.0, 1.0).draw(&mut back_end).unwrap();
+    assert_eq!(back_end.texture_count(), 1);
+    // A glyph larger than the largest atlas can not be drawn.
+    let result = d.text(&font, 10000.0, "A").rgba(1.0, 0.0, 0.0, 1.0).draw(&mut back_end);
+    assert_eq!(result, Err(GlyphAtlasFull));
+}
+
+#[test]
+fn test_draw_text_manual_upload() {
+    use {AddColor, AddText, Context, RelativeTransform2d, SoftwareBackEnd};
+    use font::{test_font_data};
+    use software_back_end::{MinimalBackEnd};
+
+    let font = Font::from_bytes(test_font_data()).unwrap();
+    let mut back_end = MinimalBackEnd { software: SoftwareBackEnd::new(32, 16) };
+    assert!(font.cache_text(10.0, "A"));
+    let texture_id = {
+        let atlas = font.atlas();
+        back_end.software.add_texture(atlas.width(), atlas.height(), atlas.pixels())
+    };
+    font.set_texture_id(texture_id);
+    assert!(!font.cache_text(10.0, "A"));
+    assert!(!font.upload(&mut back_end));
+    let c = Context::new();
+    let c = c.scale(2.0 / 32.0, -2.0 / 16.0);
+    let d = c.trans(-1.0, 1.0);
+    d.text(&font, 10.0, "A").rgba(1.0, 0.0, 0.0, 1.0).draw(&mut back_end.software).unwrap();
+    assert!(back_end.software.pixel(4, 4) == [255, 0, 0, 255]);
+    // Without texture upload, the atlas of another font has no texture.
+    let other = Font::from_bytes(test_font_data()).unwrap();
+    let result = d.text(&other, 10.0, "A").rgba(1.0, 0.0, 0.0, 1.0).draw(&mut back_end);
+    assert_eq!(result, Err(UnsupportedTexture));
+}
@@ -0,0 +1,155 @@
+use {
+    AddColor,
+    AddTextLayout,
+    Borrowed,
+    Field,
+    Font,
+    TextAlign,
+    TextColorContext,
+    Value,
+};
+use internal::{
+    CanTransform,
+    CanViewTransform,
+    ColorComponent,
+    HasTransform,
+    HasViewTransform,
+    Matrix2d,
+    Rectangle,
+    Scalar,
+};
+
+/// A text context.
+pub struct TextContext<'a, 'b> {
+    /// Base/original transformation.
+    pub base: Field<'a, Matrix2d>,
+    /// Current transformation.
+    pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
+    /// Current font.
+    pub font: Field<'a, &'b Font>,
+    /// Current font size.
+    pub size: Field<'a, Scalar>,
+    /// Current text.
+    pub text: Field<'a, &'b str>,
+    /// Current maximum line width.
+    pub max_width: Field<'a, Option<Scalar>>,
+    /// Current alignment of lines.
+    pub align: Field<'a, TextAlign>,
+}
+
+impl<'a, 'b> Clone for TextContext<'a, 'b> {
+    #[inline(always)]
+    fn clone(&self) -> TextContext<'static, 'b> {
+        TextContext {
+            base: Value(*self.base.get()),
+            transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
+            font: Value(*self.font.get()),
+            size: Value(*self.size.get()),
+            text: Value(*self.text.get()),
+            max_width: Value(*self.max_width.get()),
+            align: Value(*self.align.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasTransform<'a, Matrix2d> for TextContext<'a, 'b> {
+    #[inline(always)]
+    fn get_transform(&'a self) -> &'a Matrix2d {
+        self.transform.get()
+    }
+}
+
+impl<'a, 'b> CanTransform<'a, TextContext<'a, 'b>, Matrix2d> for TextContext<'a, 'b> {
+    #[inline(always)]
+    fn transform(&'a self, value: Matrix2d) -> TextContext<'a, 'b> {
+        TextContext {
+            base: Borrowed(self.base.get()),
+            transform: Value(value),
+            clip: Borrowed(self.clip.get()),
+            font: Borrowed(self.font.get()),
+            size: Borrowed(self.size.get()),
+            text: Borrowed(self.text.get()),
+            max_width: Borrowed(self.max_width.get()),
+            align: Borrowed(self.align.get()),
+        }
+    }
+}
+
+impl<'a, 'b> HasViewTransform<'a, Matrix2d> for TextContext<'a, 'b> {
+    #[inline(always)]
+    fn get_view_transform(&'a self) -> &'a Matrix2d {
+        self.base.get()
+    }
+}
+
+impl<'a, 'b> CanViewTransform<'a, TextContext<'a, 'b>, Matrix2d> for TextContext<'a, 'b> {
+    #[inline(always)]
+    fn view_transform(&'a self, value: Matrix2d) -> TextContext<'a, 'b> {
+        TextContext {
+            base: Value(value),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            font: Borrowed(self.font.get()),
+            size: Borrowed(self.size.get()),
+            text: Borrowed(self.text.get()),
+            max_width: Borrowed(self.max_width.get()),
+            align: Borrowed(self.align.get()),
+        }
+    }
+}
+
+impl<'a, 'b> AddTextLayout<'a, TextContext<'a, 'b>> for TextContext<'a, 'b> {
+    #[inline(always)]
+    fn align(&'a self, align: TextAlign) -> TextContext<'a, 'b> {
+        TextContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            font: Borrowed(self.font.get()),
+            size: Borrowed(self.size.get()),
+            text: Borrowed(self.text.get()),
+            max_width: Borrowed(self.max_width.get()),
+            align: Value(align),
+        }
+    }
+
+    #[inline(always)]
+    fn max_width(&'a self, width: Scalar) -> TextContext<'a, 'b> {
+        TextContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            font: Borrowed(self.font.get()),
+            size: Borrowed(self.size.get()),
+            text: Borrowed(self.text.get()),
+            max_width: Value(Some(width)),
+            align: Borrowed(self.align.get()),
+        }
+    }
+}
+
+impl<'a, 'b> AddColor<'a, TextColorContext<'a, 'b>> for TextContext<'a, 'b> {
+    #[inline(always)]
+    fn rgba(
+        &'a self,
+        r: ColorComponent,
+        g: ColorComponent,
+        b: ColorComponent,
+        a: ColorComponent
+    ) -> TextColorContext<'a, 'b> {
+        TextColorContext {
+            base: Borrowed(self.base.get()),
+            transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
+            font: Borrowed(self.font.get()),
+            size: Borrowed(self.size.get()),
+            text: Borrowed(self.text.get()),
+            max_width: Borrowed(self.max_width.get()),
+            align: Borrowed(self.align.get()),
+            color: Value([r, g, b, a]),
+        }
+    }
+}
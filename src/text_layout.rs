@@ -0,0 +1,25 @@
+//! Types used when placing glyphs of a text in lines.
+
+use internal::{
+    Scalar,
+};
+
+/// Decides where lines are placed horizontally.
+pub enum TextAlign {
+    /// Lines start at the left side.
+    AlignLeft,
+    /// Lines are centered.
+    AlignCenter,
+    /// Lines end at the right side.
+    AlignRight,
+}
+
+/// A glyph placed by the text layout.
+pub struct PositionedGlyph {
+    /// The glyph in the font.
+    pub glyph: u16,
+    /// The pen position.
+    pub x: Scalar,
+    /// The baseline, with the y axis pointing down.
+    pub y: Scalar,
+}
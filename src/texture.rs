@@ -0,0 +1,151 @@
+//! Reference counted textures.
+
+use std::cell::{RefCell};
+use std::rc::{Rc, Weak};
+use {BackEnd, Image};
+use internal::{
+    PixelRectangle,
+};
+
+/// The shared part of a texture handle.
+struct TextureData {
+    id: uint,
+    width: u32,
+    height: u32,
+}
+
+/// A handle to a texture created by `Textures`.
+///
+/// Cloning the handle shares the texture.
+/// When the last handle is dropped, the texture is deleted
+/// the next time `Textures::delete_unused` is called.
+pub struct Texture {
+    data: Rc<TextureData>,
+}
+
+impl Clone for Texture {
+    #[inline(always)]
+    fn clone(&self) -> Texture {
+        Texture { data: self.data.clone() }
+    }
+}
+
+impl Texture {
+    /// Returns the id recognized by the back-end.
+    #[inline(always)]
+    pub fn id(&self) -> uint { self.data.id }
+
+    /// Returns the width in pixels.
+    #[inline(always)]
+    pub fn width(&self) -> u32 { self.data.width }
+
+    /// Returns the height in pixels.
+    #[inline(always)]
+    pub fn height(&self) -> u32 { self.data.height }
+
+    /// Returns an image showing the whole texture.
+    #[inline(always)]
+    pub fn image(&self) -> Image {
+        self.sub_image([0, 0, self.data.width, self.data.height])
+    }
+
+    /// Returns an image showing a rectangle within the texture.
+    #[inline(always)]
+    pub fn sub_image(&self, source_rect: PixelRectangle) -> Image {
+        Image {
+            texture_id: self.data.id,
+            texture_width: self.data.width,
+            texture_height: self.data.height,
+            source_rect: source_rect,
+        }
+    }
+
+    /// Replaces the pixels of a rectangle within the texture.
+    #[inline(always)]
+    pub fn update<B: BackEnd>(
+        &self,
+        back_end: &mut B,
+        x: u32, y: u32, w: u32, h: u32,
+        pixels: &[u8]
+    ) {
+        back_end.update_texture(self.data.id, x, y, w, h, pixels);
+    }
+}
+
+/// Creates textures and deletes them when they are no longer used.
+///
+/// Call `delete_unused` regularly, for example once per frame,
+/// with the back-end that created the textures.
+pub struct Textures {
+    // The back-end is not available when a handle is dropped,
+    // so the textures are checked for handles when deleting.
+    textures: RefCell<Vec<(uint, Weak<TextureData>)>>,
+}
+
+impl Textures {
+    /// Creates a new texture manager.
+    pub fn new() -> Textures {
+        Textures {
+            textures: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Creates a texture from RGBA pixels stored row by row starting at the top.
+    ///
+    /// Returns `None` if the back-end does not support texture upload.
+    pub fn create<B: BackEnd>(
+        &self,
+        back_end: &mut B,
+        width: u32,
+        height: u32,
+        pixels: &[u8]
+    ) -> Option<Texture> {
+        if !back_end.supports_texture_upload() { return None; }
+        back_end.create_texture(width, height, pixels).map(|id| {
+            let data = Rc::new(TextureData {
+                id: id,
+                width: width,
+                height: height,
+            });
+            self.textures.borrow_mut().push((id, data.downgrade()));
+            Texture { data: data }
+        })
+    }
+
+    /// Deletes the textures without handles.
+    ///
+    /// Returns the number of deleted textures.
+    pub fn delete_unused<B: BackEnd>(&self, back_end: &mut B) -> uint {
+        let mut textures = self.textures.borrow_mut();
+        let n = textures.len();
+        textures.retain(|&(id, ref data)| {
+            let used = data.upgrade().is_some();
+            if !used { back_end.delete_texture(id); }
+            used
+        });
+        n - textures.len()
+    }
+}
+
+#[test]
+fn test_textures() {
+    use {SoftwareBackEnd};
+
+    let mut back_end = SoftwareBackEnd::new(1, 1);
+    let textures = Textures::new();
+    let texture = textures.create(&mut back_end, 2, 1, [255u8, ..8]).unwrap();
+    let image = texture.image();
+    assert_eq!(image.texture_id, texture.id());
+    assert!(image.source_rect == [0, 0, 2, 1]);
+    assert!(!back_end.has_texture_alpha(texture.id()));
+    texture.update(&mut back_end, 1, 0, 1, 1, [255, 255, 255, 0]);
+    assert!(back_end.has_texture_alpha(texture.id()));
+
+    let shared = texture.clone();
+    drop(texture);
+    assert_eq!(textures.delete_unused(&mut back_end), 0);
+    assert_eq!(back_end.texture_count(), 1);
+    drop(shared);
+    assert_eq!(textures.delete_unused(&mut back_end), 1);
+    assert_eq!(back_end.texture_count(), 0);
+}
@@ -0,0 +1,14 @@
+
+/// The kind of triangles a shape is streamed as.
+///
+/// Not every shape can be streamed as every kind,
+/// see the streamers in `triangulation`.
+#[deriving(Clone, PartialEq, Show)]
+pub enum TriangleKind {
+    /// Every 3 points make a triangle.
+    TriList,
+    /// Every point after the second makes a triangle with the two previous points.
+    TriStrip,
+    /// Every point after the second makes a triangle with the first and the previous point.
+    TriFan,
+}
@@ -1,51 +1,183 @@
 //! Methods for converting shapes into triangles.
 
+use std::cmp::{Equal, Greater, Less, Ordering, max, min};
+use std::mem::{replace};
 use std::f64::consts::{
     PI,
     PI_2,
     FRAC_PI_2,
 };
 use {
+    BevelJoin,
+    ButtCap,
+    Dash,
+    EvenOdd,
+    FillRule,
+    Gradient,
     Image,
+    LineCap,
+    LineJoin,
+    LinearGradient,
+    MAX_DASHES,
+    MiterJoin,
+    NonZero,
+    Path,
+    RadialGradient,
+    Resolution,
+    RoundCap,
+    RoundJoin,
+    Segments,
+    SliceMode,
+    SquareCap,
+    Stretch,
+    Tile,
+    Tolerance,
+    TriFan,
+    TriList,
+    TriStrip,
+    TriangleKind,
+    TriangularCap,
 };
-use interpolation::{lerp};
+use interpolation::{lerp, lerp_2, lerp_4};
 use internal::{
     Color,
     Line,
     Matrix2d,
     Polygon,
     Polygons,
+    Polyline,
     Radius,
     Rectangle,
     Scalar,
+    Shape,
     Vec2d,
 };
 use vecmath::{
+    area,
+    get_scale,
+    inside_triangle,
+    line_side,
     multiply,
     orient,
+    polygon_bounds,
     translate,
+    triangle_face,
 };
+use modular_index::{next, previous};
 
-/// Transformed x coordinate as f32.
+/// The default maximum distance between curves and their lines.
+///
+/// The distance is measured after transformation.
+pub static DEFAULT_TOLERANCE: Scalar = 0.001;
+
+/// The default maximum ratio between the length of a miter join and the border width.
+///
+/// Corners sharper than about 29 degrees get a bevel join instead.
+pub static DEFAULT_MITER_LIMIT: Scalar = 4.0;
+
+/// The maximum number of copies along each side of a tiled image.
+///
+/// Every copy takes 2 triangles, so a tiled image takes at most
+/// `2 * MAX_TILES * MAX_TILES` triangles and a nine-slice image 4 times that.
+/// When more copies are needed, the copies are stretched to fill the area.
+pub static MAX_TILES: uint = 256;
+
+/// A vertex coordinate type, f32 or f64.
+///
+/// The streamers are generic over the vertex type,
+/// such that back-ends with f64 vertices get the full precision.
+pub trait Coordinate: Copy {
+    /// Converts from a scalar, rounding if needed.
+    fn from_scalar(x: Scalar) -> Self;
+}
+
+impl Coordinate for f32 {
+    #[inline(always)]
+    fn from_scalar(x: Scalar) -> f32 { x as f32 }
+}
+
+impl Coordinate for f64 {
+    #[inline(always)]
+    fn from_scalar(x: Scalar) -> f64 { x }
+}
+
+/// Transformed x coordinate.
 #[inline(always)]
-fn tx(m: Matrix2d, x: Scalar, y: Scalar) -> f32 {
-    (m[0] * x + m[1] * y + m[2]) as f32
+fn tx<V: Coordinate>(m: Matrix2d, x: Scalar, y: Scalar) -> V {
+    Coordinate::from_scalar(m[0] * x + m[1] * y + m[2])
 }
 
-/// Transformed y coordinate as f32.
+/// Transformed y coordinate.
 #[inline(always)]
-fn ty(m: Matrix2d, x: Scalar, y: Scalar) -> f32 {
-    (m[3] * x + m[4] * y + m[5]) as f32
+fn ty<V: Coordinate>(m: Matrix2d, x: Scalar, y: Scalar) -> V {
+    Coordinate::from_scalar(m[3] * x + m[4] * y + m[5])
 }
 
-/// Streams tweened polygons using linear interpolation.
+/// Converts a tolerance after transformation to local coordinates.
+///
+/// Uses the largest scale of the transform,
+/// such that curves are smooth in all directions.
 #[inline(always)]
-pub fn with_lerp_polygons_tri_list_xy_f32_rgba_f32(
+pub fn local_tolerance(m: Matrix2d, tolerance: Scalar) -> Scalar {
+    let scale = get_scale(m);
+    let scale = scale[0].max(scale[1]);
+    if scale == 0.0 { tolerance } else { tolerance / scale }
+}
+
+/// Computes the number of segments needed to approximate an arc.
+///
+/// The maximum distance between the arc and the segments is within tolerance.
+pub fn arc_resolution(radius: Radius, angle: Scalar, tolerance: Scalar) -> uint {
+    if radius <= tolerance { return 1; }
+    // The distance is largest in the middle of each segment.
+    let step = 2.0 * (1.0 - tolerance / radius).acos();
+    let n = (angle / step).ceil();
+    if n < 1.0 { 1 } else if n > 1024.0 { 1024 } else { n as uint }
+}
+
+/// Computes the number of points on the outline of an ellipse.
+///
+/// With a tolerance, the radii after transformation decide the number,
+/// so small ellipses use few triangles and large ones stay smooth.
+pub fn ellipse_resolution(m: Matrix2d, rect: Rectangle, resolution: Resolution) -> uint {
+    let n = match resolution {
+        Segments(n) => n,
+        Tolerance(tolerance) => {
+            let scale = get_scale(m);
+            let radius = (0.5 * rect[2] * scale[0]).abs().max((0.5 * rect[3] * scale[1]).abs());
+            arc_resolution(radius, PI_2, tolerance)
+        },
+    };
+    if n < 3 { 3 } else { n }
+}
+
+/// Computes the number of points at each corner of a round rectangle.
+///
+/// With a tolerance, the corner radius after transformation decides the number.
+pub fn round_rectangle_resolution(
+    m: Matrix2d,
+    round_radius: Radius,
+    resolution: Resolution
+) -> uint {
+    let n = match resolution {
+        Segments(n) => n,
+        Tolerance(tolerance) => {
+            let scale = get_scale(m);
+            let radius = round_radius.abs() * scale[0].max(scale[1]);
+            // There is one more point than segments at each corner.
+            arc_resolution(radius, FRAC_PI_2, tolerance) + 1
+        },
+    };
+    if n < 2 { 2 } else { n }
+}
+
+/// Streams tweened polygons using linear interpolation.
+pub fn with_lerp_polygons_tri_list_xy_rgba_f32<V: Coordinate>(
     m: Matrix2d,
     polygons: Polygons,
     tween_factor: Scalar,
     color: Color,
-    f: |vertices: &[f32], colors: &[f32]|) {
+    f: |vertices: &[V], colors: &[f32]|) {
 
     let poly_len = polygons.len() as f64;
     // Map to interval between 0 and 1.
@@ -64,7 +196,7 @@ pub fn with_lerp_polygons_tri_list_xy_f32_rgba_f32(
     let tw = tw - frame as f64;
     let n = polygons[0].len();
     let mut i = 0u;
-    stream_polygon_tri_list_xy_f32_rgba_f32(m, || {
+    stream_polygon_tri_list_xy_rgba_f32(m, || {
         if i >= n { return None; }
 
         let j = i;
@@ -76,26 +208,28 @@ pub fn with_lerp_polygons_tri_list_xy_f32_rgba_f32(
 }
 
 /// Streams an ellipse specified by a resolution.
+///
+/// Returns false without streaming if the kind is a triangle strip.
 #[inline(always)]
-pub fn with_ellipse_tri_list_xy_f32_rgba_f32(
+pub fn with_ellipse_xy_rgba_f32<V: Coordinate>(
+    kind: TriangleKind,
     resolution: uint,
     m: Matrix2d,
     rect: Rectangle,
     color: Color,
-    f: |vertices: &[f32], colors: &[f32]|) {
+    f: |vertices: &[V], colors: &[f32]|) -> bool {
+
+    stream_convex_polygon(kind, m, resolution, |i| ellipse_point(resolution, rect, i), color, f)
+}
 
+/// Computes a point on the outline of an ellipse.
+#[inline(always)]
+fn ellipse_point(resolution: uint, rect: Rectangle, i: uint) -> Vec2d {
     let (x, y, w, h) = (rect[0], rect[1], rect[2], rect[3]);
     let (cw, ch) = (0.5 * w, 0.5 * h);
     let (cx, cy) = (x + cw, y + ch);
-    let n = resolution;
-    let mut i = 0u;
-    stream_polygon_tri_list_xy_f32_rgba_f32(m, || {
-        if i >= n { return None; }
-
-        let angle = i as f64 / n as f64 * PI_2;
-        i += 1;
-        Some([cx + angle.cos() * cw, cy + angle.sin() * ch])
-    }, color, f);
+    let angle = i as f64 / resolution as f64 * PI_2;
+    [cx + angle.cos() * cw, cy + angle.sin() * ch]
 }
 
 /// Streams a round border line.
@@ -116,7 +250,7 @@ pub fn with_round_border_line_tri_list_xy_f32_rgba_f32(
     let m = multiply(m, orient(dx, dy));
     let n = resolution_cap * 2;
     let mut i = 0u;
-    stream_polygon_tri_list_xy_f32_rgba_f32(m, || {
+    stream_polygon_tri_list_xy_rgba_f32(m, || {
         if i >= n { return None; }
 
         let j = i;
@@ -145,75 +279,108 @@ pub fn with_round_border_line_tri_list_xy_f32_rgba_f32(
 }
 
 /// Streams a round rectangle.
+///
+/// Returns false without streaming if the kind is a triangle strip.
 #[inline(always)]
-pub fn with_round_rectangle_tri_list_xy_f32_rgba_f32(
+pub fn with_round_rectangle_xy_rgba_f32<V: Coordinate>(
+    kind: TriangleKind,
     resolution_corner: uint,
     m: Matrix2d,
     rect: Rectangle,
     round_radius: Radius,
     color: Color,
-    f: |vertices: &[f32], colors: &[f32]|) {
+    f: |vertices: &[V], colors: &[f32]|) -> bool {
+
+    stream_convex_polygon(kind, m, resolution_corner * 4,
+        |i| round_rectangle_point(resolution_corner, rect, round_radius, i), color, f)
+}
+
+/// Streams a convex polygon of `n` points as a triangle list or a triangle fan.
+///
+/// Returns false without streaming if the kind is a triangle strip.
+fn stream_convex_polygon<V: Coordinate>(
+    kind: TriangleKind,
+    m: Matrix2d,
+    n: uint,
+    point: |uint| -> Vec2d,
+    color: Color,
+    f: |vertices: &[V], colors: &[f32]|) -> bool {
 
-    let (x, y, w, h) = (rect[0], rect[1], rect[2], rect[3]);
-    let radius = round_radius;
-    let n = resolution_corner * 4;
     let mut i = 0u;
-    stream_polygon_tri_list_xy_f32_rgba_f32(m, || {
+    let polygon = || {
         if i >= n { return None; }
 
         let j = i;
         i += 1;
-        // Detect quarter circle from index.
-        // There is one quarter circle at each corner.
-        // Together they form a full circle if each side of rectangle is 2 times the radius.
-        match j {
-            j if j >= resolution_corner * 3 => {
-                // Compute the angle to match start and end point of quarter circle.
-                // This requires an angle offset since this is the last quarter.
-                let angle = (j - resolution_corner * 3) as f64 / (resolution_corner - 1) as f64 * FRAC_PI_2
-                    + 3.0 * FRAC_PI_2;
-                // Set center of the circle to the last corner.
-                let (cx, cy) = (x + w - radius, y + radius);
-                Some([cx + angle.cos() * radius, cy + angle.sin() * radius])
-            },
-            j if j >= resolution_corner * 2 => {
-                // Compute the angle to match start and end point of quarter circle.
-                // This requires an angle offset since this is the second last quarter.
-                let angle = (j - resolution_corner * 2) as f64 / (resolution_corner - 1) as f64 * FRAC_PI_2
-                    + PI;
-                // Set center of the circle to the second last corner.
-                let (cx, cy) = (x + radius, y + radius);
-                Some([cx + angle.cos() * radius, cy + angle.sin() * radius])
-            },
-            j if j >= resolution_corner * 1 => {
-                // Compute the angle to match start and end point of quarter circle.
-                // This requires an angle offset since this is the second quarter.
-                let angle = (j - resolution_corner) as f64 / (resolution_corner - 1) as f64 * FRAC_PI_2
-                    + FRAC_PI_2;
-                // Set center of the circle to the second corner.
-                let (cx, cy) = (x + radius, y + h - radius);
-                Some([cx + angle.cos() * radius, cy + angle.sin() * radius])
-            },
-            j => {
-                // Compute the angle to match start and end point of quarter circle.
-                let angle = j as f64 / (resolution_corner - 1) as f64 * FRAC_PI_2;
-                // Set center of the circle to the first corner.
-                let (cx, cy) = (x + w - radius, y + h - radius);
-                Some([cx + angle.cos() * radius, cy + angle.sin() * radius])
-            },
-        }
-    }, color, f);
+        Some(point(j))
+    };
+    match kind {
+        TriList => stream_polygon_tri_list_xy_rgba_f32(m, polygon, color, f),
+        TriFan => stream_polygon_tri_fan_xy_rgba_f32(m, polygon, color, f),
+        TriStrip => return false,
+    }
+    true
+}
+
+/// Computes a point on the outline of a round rectangle.
+fn round_rectangle_point(
+    resolution_corner: uint,
+    rect: Rectangle,
+    round_radius: Radius,
+    j: uint
+) -> Vec2d {
+    let (x, y, w, h) = (rect[0], rect[1], rect[2], rect[3]);
+    let radius = round_radius;
+    // Detect quarter circle from index.
+    // There is one quarter circle at each corner.
+    // Together they form a full circle if each side of rectangle is 2 times the radius.
+    match j {
+        j if j >= resolution_corner * 3 => {
+            // Compute the angle to match start and end point of quarter circle.
+            // This requires an angle offset since this is the last quarter.
+            let angle = (j - resolution_corner * 3) as f64 / (resolution_corner - 1) as f64 * FRAC_PI_2
+                + 3.0 * FRAC_PI_2;
+            // Set center of the circle to the last corner.
+            let (cx, cy) = (x + w - radius, y + radius);
+            [cx + angle.cos() * radius, cy + angle.sin() * radius]
+        },
+        j if j >= resolution_corner * 2 => {
+            // Compute the angle to match start and end point of quarter circle.
+            // This requires an angle offset since this is the second last quarter.
+            let angle = (j - resolution_corner * 2) as f64 / (resolution_corner - 1) as f64 * FRAC_PI_2
+                + PI;
+            // Set center of the circle to the second last corner.
+            let (cx, cy) = (x + radius, y + radius);
+            [cx + angle.cos() * radius, cy + angle.sin() * radius]
+        },
+        j if j >= resolution_corner * 1 => {
+            // Compute the angle to match start and end point of quarter circle.
+            // This requires an angle offset since this is the second quarter.
+            let angle = (j - resolution_corner) as f64 / (resolution_corner - 1) as f64 * FRAC_PI_2
+                + FRAC_PI_2;
+            // Set center of the circle to the second corner.
+            let (cx, cy) = (x + radius, y + h - radius);
+            [cx + angle.cos() * radius, cy + angle.sin() * radius]
+        },
+        j => {
+            // Compute the angle to match start and end point of quarter circle.
+            let angle = j as f64 / (resolution_corner - 1) as f64 * FRAC_PI_2;
+            // Set center of the circle to the first corner.
+            let (cx, cy) = (x + w - radius, y + h - radius);
+            [cx + angle.cos() * radius, cy + angle.sin() * radius]
+        },
+    }
 }
 
 /// Streams a polygon into tri list with color per vertex.
 /// Uses buffers that fit inside L1 cache.
-pub fn stream_polygon_tri_list_xy_f32_rgba_f32(
+pub fn stream_polygon_tri_list_xy_rgba_f32<V: Coordinate>(
     m: Matrix2d,
     polygon: || -> Option<Vec2d>,
     color: Color,
-    f: |vertices: &[f32], colors: &[f32]|) {
+    f: |vertices: &[V], colors: &[f32]|) {
 
-    let mut vertices: [f32, ..740] = [0.0, ..740];
+    let mut vertices: [V, ..740] = [Coordinate::from_scalar(0.0), ..740];
     let mut colors: [f32, ..1480] = [0.0, ..1480];
     // Get the first point which will be used a lot.
     let fp = match polygon() { None => return, Some(val) => val };
@@ -275,66 +442,2618 @@ pub fn stream_polygon_tri_list_xy_f32_rgba_f32(
     }
 }
 
-/// Splits polygon into convex segments with one color per vertex.
+/// Streams a list of triangles with color per vertex.
+/// Every 3 points make a triangle.
+/// Uses buffers that fit inside L1 cache.
+pub fn stream_tri_list_xy_rgba_f32<V: Coordinate>(
+    m: Matrix2d,
+    points: || -> Option<Vec2d>,
+    color: Color,
+    f: |vertices: &[V], colors: &[f32]|) {
+
+    let mut vertices: [V, ..738] = [Coordinate::from_scalar(0.0), ..738];
+    let mut colors: [f32, ..1476] = [0.0, ..1476];
+    let mut i = 0;
+    loop {
+        let p = match points() { None => break, Some(val) => val };
+        vertices[i * 2 + 0] = tx(m, p[0], p[1]);
+        vertices[i * 2 + 1] = ty(m, p[0], p[1]);
+        colors[i * 4 + 0] = color[0];
+        colors[i * 4 + 1] = color[1];
+        colors[i * 4 + 2] = color[2];
+        colors[i * 4 + 3] = color[3];
+
+        i += 1;
+        // Buffer is full.
+        if i * 2 == vertices.len() {
+            // Send chunk and start over.
+            f(vertices.as_slice(), colors.as_slice());
+            i = 0;
+        }
+    }
+
+    // Send only complete triangles.
+    let i = i - i % 3;
+    if i > 0 {
+        f(vertices.slice(0, i * 2),
+            colors.slice(0, i * 4));
+    }
+}
+
+/// Streams a convex polygon into triangle fans with color per vertex.
+///
+/// Every point after the second makes a triangle with the first and the previous point.
+/// Uses buffers that fit inside L1 cache.
+pub fn stream_polygon_tri_fan_xy_rgba_f32<V: Coordinate>(
+    m: Matrix2d,
+    polygon: || -> Option<Vec2d>,
+    color: Color,
+    f: |vertices: &[V], colors: &[f32]|) {
+
+    let mut vertices: [V, ..740] = [Coordinate::from_scalar(0.0), ..740];
+    let mut colors: [f32, ..1480] = [0.0, ..1480];
+    for i in range(0u, colors.len()) {
+        colors[i] = color[i % 4];
+    }
+    let mut i = 0;
+    loop {
+        let p = match polygon() { None => break, Some(val) => val };
+        vertices[i * 2 + 0] = tx(m, p[0], p[1]);
+        vertices[i * 2 + 1] = ty(m, p[0], p[1]);
+
+        i += 1;
+        // Buffer is full.
+        if i * 2 == vertices.len() {
+            // Send chunk and continue the fan from the first and the last point.
+            f(vertices.as_slice(), colors.slice(0, i * 4));
+            vertices[2] = vertices[(i - 1) * 2];
+            vertices[3] = vertices[(i - 1) * 2 + 1];
+            i = 2;
+        }
+    }
+
+    if i > 2 {
+        f(vertices.slice(0, i * 2), colors.slice(0, i * 4));
+    }
+}
+
+/// Streams a triangle strip [x0, y0, x1, y1, ...] with color per vertex.
+///
+/// Every point after the second makes a triangle with the two previous points.
+/// Uses buffers that fit inside L1 cache.
+pub fn stream_tri_strip_xy_rgba_f32<V: Coordinate>(
+    m: Matrix2d,
+    strip: &[Scalar],
+    color: Color,
+    f: |vertices: &[V], colors: &[f32]|) {
+
+    let mut vertices: [V, ..740] = [Coordinate::from_scalar(0.0), ..740];
+    let mut colors: [f32, ..1480] = [0.0, ..1480];
+    for i in range(0u, colors.len()) {
+        colors[i] = color[i % 4];
+    }
+    let n = strip.len() / 2;
+    let chunk = vertices.len() / 2;
+    let mut start = 0;
+    while start + 2 < n {
+        let end = min(start + chunk, n);
+        for i in range(start, end) {
+            let (x, y) = (strip[i * 2], strip[i * 2 + 1]);
+            vertices[(i - start) * 2 + 0] = tx(m, x, y);
+            vertices[(i - start) * 2 + 1] = ty(m, x, y);
+        }
+        f(vertices.slice(0, (end - start) * 2), colors.slice(0, (end - start) * 4));
+        // The next chunk repeats the last two points.
+        // The chunk size is even, so the triangles keep their winding.
+        start = end - 2;
+    }
+}
+
+/// Computes the indices that turn a triangle strip into a triangle list.
+///
+/// The odd triangles are flipped to keep the winding of the strip.
+pub fn tri_strip_indices(n: uint) -> Vec<u32> {
+    let mut indices = Vec::with_capacity(if n < 2 { 0 } else { (n - 2) * 3 });
+    for i in range(2, n) {
+        let i = i as u32;
+        if i % 2 == 0 { indices.push_all([i - 2, i - 1, i]); }
+        else { indices.push_all([i - 1, i - 2, i]); }
+    }
+    indices
+}
+
+/// Computes the indices that turn a triangle fan into a triangle list.
+pub fn tri_fan_indices(n: uint) -> Vec<u32> {
+    let mut indices = Vec::with_capacity(if n < 2 { 0 } else { (n - 2) * 3 });
+    for i in range(2, n) {
+        indices.push_all([0, i as u32 - 1, i as u32]);
+    }
+    indices
+}
+
+/// Splits a polygon into triangles using ear clipping.
+///
+/// Returns indices to the vertices, 3 per triangle,
+/// with the same winding as the polygon.
+/// Handles both convex and concave simple polygons.
+/// Collinear and duplicated vertices do not produce triangles.
+///
+/// A self-intersecting polygon is still split into triangles,
+/// but the result does not follow any particular fill rule.
+pub fn ear_clip_polygon(polygon: Polygon) -> Vec<uint> {
+    let n = polygon.len() / 2;
+    let mut res = Vec::new();
+    if n < 3 { return res; }
+
+    // The area is positive when the polygon is counter clock-wise.
+    let ccw = area(polygon) > 0.0;
+    let mut remaining: Vec<uint> = range(0, n).collect();
+    let point = |i: uint| -> Vec2d { [polygon[i * 2], polygon[i * 2 + 1]] };
+    // Counts the vertices visited since the last ear was clipped.
+    let mut visited = 0;
+    let mut i = 0;
+    while remaining.len() >= 3 {
+        let len = remaining.len();
+        let ia = *remaining.get(previous(len, i));
+        let ib = *remaining.get(i);
+        let ic = *remaining.get(next(len, i));
+        let (a, b, c) = (point(ia), point(ib), point(ic));
+        let triangle = [a[0], a[1], b[0], b[1], c[0], c[1]];
+
+        // Remove collinear vertices without adding a triangle.
+        if line_side([a[0], a[1], b[0], b[1]], c[0], c[1]) == 0.0 {
+            remaining.remove(i);
+            if i >= remaining.len() { i = 0; }
+            visited = 0;
+            continue;
+        }
+
+        // The last triangle does not need to be checked.
+        if len == 3 {
+            res.push(ia);
+            res.push(ib);
+            res.push(ic);
+            break;
+        }
+
+        // A convex corner has the same winding as the polygon.
+        let convex = triangle_face(triangle) != ccw;
+        let is_ear = convex && remaining.iter().all(|&j| {
+            if j == ia || j == ib || j == ic { return true; }
+            let p = point(j);
+            // Bridged vertices might share position with the corner.
+            if p == a || p == b || p == c { return true; }
+            !inside_triangle(triangle, p[0], p[1])
+        });
+
+        // Clip the vertex anyway when no ear is left,
+        // which happens for self-intersecting polygons.
+        if is_ear || visited > len {
+            res.push(ia);
+            res.push(ib);
+            res.push(ic);
+            remaining.remove(i);
+            if i >= remaining.len() { i = 0; }
+            visited = 0;
+        } else {
+            i = next(len, i);
+            visited += 1;
+        }
+    }
+    res
+}
+
+/// Splits polygon into triangles with one color per vertex.
+/// Concave polygons are triangulated with ear clipping.
 /// Create a buffer that fits into L1 cache with 1KB overhead.
-pub fn with_polygon_tri_list_xy_f32_rgba_f32(
+pub fn with_polygon_tri_list_xy_rgba_f32<V: Coordinate>(
     m: Matrix2d,
     polygon: Polygon,
     color: Color,
-    f: |vertices: &[f32], colors: &[f32]|) {
+    f: |vertices: &[V], colors: &[f32]|) {
 
-    let n = polygon.len();
+    let indices = ear_clip_polygon(polygon);
+    let n = indices.len();
+    let mut i = 0;
+    stream_tri_list_xy_rgba_f32(
+        m, || {
+            if i >= n { return None; }
+
+            let j = *indices.get(i);
+            i += 1;
+            Some([polygon[j * 2], polygon[j * 2 + 1]])
+        }, color, f);
+}
+
+/// Splits a shape into triangles using a fill rule.
+///
+/// Returns the triangle vertices, [x0, y0, x1, y1, ...].
+///
+/// The shape is cut into horizontal slabs at every vertex and edge crossing.
+/// Within a slab the edges do not cross, so the spans between them
+/// that are inside by the fill rule become trapezoids.
+/// This handles holes and self-intersecting rings.
+///
+/// The slabs are visited from top to bottom while keeping the edges
+/// that cross the current slab sorted by x.
+/// The first crossing in a slab is always between neighbours,
+/// so only those are tested.
+pub fn triangulate_shape(shape: Shape, fill_rule: FillRule) -> Vec<Scalar> {
+    // Store edges pointing downwards with the winding direction.
+    let mut edges: Vec<(Line, int)> = Vec::new();
+    let mut ys: Vec<Scalar> = Vec::new();
+    for ring in shape.iter() {
+        let n = ring.len() / 2;
+        for i in range(0, n) {
+            let j = next(n, i);
+            let (x0, y0) = (ring[i * 2], ring[i * 2 + 1]);
+            let (x1, y1) = (ring[j * 2], ring[j * 2 + 1]);
+            ys.push(y0);
+            // Horizontal edges do not change winding inside slabs.
+            if y0 < y1 { edges.push(([x0, y0, x1, y1], 1)); }
+            else if y0 > y1 { edges.push(([x1, y1, x0, y0], -1)); }
+        }
+    }
+    edges.sort_by(|&(a, _), &(b, _)| compare_scalar(&a[1], &b[1]));
+    ys.sort_by(compare_scalar);
+    ys.dedup();
+
+    let inside = |winding: int| -> bool {
+        match fill_rule {
+            EvenOdd => winding % 2 != 0,
+            NonZero => winding != 0,
+        }
+    };
+    let mut res = Vec::new();
+    if ys.len() < 2 { return res; }
+    // The edges crossing the current slab, sorted by x in the middle of it.
+    let mut active: Vec<uint> = Vec::new();
+    let mut next_edge = 0;
+    let mut next_y = 1;
+    let mut ya = *ys.get(0);
+    while next_y < ys.len() {
+        let mut yb = *ys.get(next_y);
+        while next_edge < edges.len() && edges.get(next_edge).ref0()[1] <= ya {
+            active.push(next_edge);
+            next_edge += 1;
+        }
+        active.retain(|&i| edges.get(i).ref0()[3] > ya);
+
+        // Stop the slab at the first crossing until no neighbours cross.
+        loop {
+            let ym = 0.5 * (ya + yb);
+            // The order changes little between slabs, so insertion sort is fast.
+            for k in range(1, active.len()) {
+                let mut j = k;
+                while j > 0 {
+                    let a = edge_x_at(*edges.get(*active.get(j - 1)).ref0(), ym);
+                    let b = edge_x_at(*edges.get(*active.get(j)).ref0(), ym);
+                    if a <= b { break; }
+                    active.as_mut_slice().swap(j - 1, j);
+                    j -= 1;
+                }
+            }
+            let mut cut = yb;
+            for k in range(1, active.len()) {
+                // Use the same order of edges to get the same crossing every time.
+                let (i, j) = (*active.get(k - 1), *active.get(k));
+                let (i, j) = (min(i, j), max(i, j));
+                match edge_crossing_y(*edges.get(i).ref0(), *edges.get(j).ref0()) {
+                    Some(y) if y > ya && y < cut => cut = y,
+                    _ => {}
+                }
+            }
+            if cut < yb { yb = cut; } else { break; }
+        }
+
+        let mut winding = 0;
+        let (mut left_a, mut left_b) = (0.0, 0.0);
+        for &i in active.iter() {
+            let (e, w) = *edges.get(i);
+            let (xa, xb) = (edge_x_at(e, ya), edge_x_at(e, yb));
+            let was_inside = inside(winding);
+            winding += w;
+            let is_inside = inside(winding);
+            if !was_inside && is_inside {
+                left_a = xa;
+                left_b = xb;
+            } else if was_inside && !is_inside {
+                res.push_all([left_a, ya, xa, ya, xb, yb]);
+                res.push_all([left_a, ya, xb, yb, left_b, yb]);
+            }
+        }
+        if yb >= *ys.get(next_y) { next_y += 1; }
+        ya = yb;
+    }
+    res
+}
+
+/// Computes the x coordinate of an edge pointing downwards at a given y.
+#[inline(always)]
+fn edge_x_at(e: Line, y: Scalar) -> Scalar {
+    e[0] + (y - e[1]) / (e[3] - e[1]) * (e[2] - e[0])
+}
+
+/// Compares two scalars, treating NaN as equal.
+fn compare_scalar(a: &Scalar, b: &Scalar) -> Ordering {
+    if *a < *b { Less } else if *a > *b { Greater } else { Equal }
+}
+
+/// Computes the y coordinate where two edges cross.
+///
+/// Returns `None` if the edges are parallel or do not cross.
+fn edge_crossing_y(a: Line, b: Line) -> Option<Scalar> {
+    let (dax, day) = (a[2] - a[0], a[3] - a[1]);
+    let (dbx, dby) = (b[2] - b[0], b[3] - b[1]);
+    let d = dax * dby - day * dbx;
+    if d == 0.0 { return None; }
+    let (ox, oy) = (b[0] - a[0], b[1] - a[1]);
+    let t = (ox * dby - oy * dbx) / d;
+    let u = (ox * day - oy * dax) / d;
+    if t < 0.0 || t > 1.0 || u < 0.0 || u > 1.0 { return None; }
+    Some(a[1] + t * day)
+}
+
+/// Streams a shape using a fill rule with one color per vertex.
+pub fn with_shape_tri_list_xy_rgba_f32<V: Coordinate>(
+    m: Matrix2d,
+    shape: Shape,
+    fill_rule: FillRule,
+    color: Color,
+    f: |vertices: &[V], colors: &[f32]|) {
+
+    let triangles = triangulate_shape(shape, fill_rule);
+    stream_triangles_tri_list(m, triangles.as_slice(), color, f);
+}
+
+/// Streams a filled path using a fill rule with one color per vertex.
+///
+/// All sub-paths are closed when filling.
+pub fn with_path_tri_list_xy_rgba_f32<V: Coordinate>(
+    tolerance: Scalar,
+    m: Matrix2d,
+    path: &Path,
+    fill_rule: FillRule,
+    color: Color,
+    f: |vertices: &[V], colors: &[f32]|) {
+
+    let mut rings: Vec<Vec<Scalar>> = Vec::new();
+    path.flatten(local_tolerance(m, tolerance), |polyline, _| {
+        rings.push(Vec::from_slice(polyline));
+    });
+    let shape: Vec<Polygon> = rings.iter().map(|ring| ring.as_slice()).collect();
+    with_shape_tri_list_xy_rgba_f32(m, shape.as_slice(), fill_rule, color, f);
+}
+
+/// Streams the outline of a path.
+///
+/// Closed sub-paths are joined at the start point,
+/// while open sub-paths get the caps at the ends.
+pub fn with_path_border_tri_list_xy_rgba_f32<V: Coordinate>(
+    tolerance: Scalar,
+    m: Matrix2d,
+    path: &Path,
+    border_radius: Radius,
+    join: LineJoin,
+    miter_limit: Scalar,
+    start_cap: LineCap,
+    end_cap: LineCap,
+    dash: Option<Dash>,
+    color: Color,
+    f: |vertices: &[V], colors: &[f32]|) {
+
+    let tolerance = local_tolerance(m, tolerance);
+    let mut triangles = Vec::new();
+    path.flatten(tolerance, |polyline, closed| {
+        triangles.push_all(stroke_dashed_polyline(
+            polyline, closed, border_radius, join, miter_limit, start_cap, end_cap, dash, tolerance
+        ).as_slice());
+    });
+    stream_triangles_tri_list(m, triangles.as_slice(), color, f);
+}
+
+/// Streams the outline of a polyline.
+///
+/// If `closed` is true the last point connects to the first.
+/// Returns false without streaming if the border can not be streamed as the kind,
+/// see `with_stroke_xy_rgba_f32`.
+pub fn with_polyline_border_xy_rgba_f32<V: Coordinate>(
+    kind: TriangleKind,
+    tolerance: Scalar,
+    m: Matrix2d,
+    polyline: Polyline,
+    closed: bool,
+    border_radius: Radius,
+    join: LineJoin,
+    miter_limit: Scalar,
+    start_cap: LineCap,
+    end_cap: LineCap,
+    dash: Option<Dash>,
+    color: Color,
+    f: |vertices: &[V], colors: &[f32]|) -> bool {
+
+    with_stroke_xy_rgba_f32(kind, tolerance, m, polyline, closed, border_radius, join,
+        miter_limit, start_cap, end_cap, dash, color, f)
+}
+
+/// Streams the outline of a line with caps at the ends.
+///
+/// Returns false without streaming if the border can not be streamed as the kind.
+pub fn with_line_border_xy_rgba_f32<V: Coordinate>(
+    kind: TriangleKind,
+    tolerance: Scalar,
+    m: Matrix2d,
+    line: Line,
+    border_radius: Radius,
+    start_cap: LineCap,
+    end_cap: LineCap,
+    dash: Option<Dash>,
+    color: Color,
+    f: |vertices: &[V], colors: &[f32]|) -> bool {
+
+    // A single segment has no joins.
+    with_stroke_xy_rgba_f32(kind, tolerance, m, line.as_slice(), false, border_radius,
+        MiterJoin, DEFAULT_MITER_LIMIT, start_cap, end_cap, dash, color, f)
+}
+
+/// Streams a closed border centered on the outline of a polygon.
+///
+/// Returns false without streaming if the border can not be streamed as the kind.
+pub fn with_polygon_border_xy_rgba_f32<V: Coordinate>(
+    kind: TriangleKind,
+    m: Matrix2d,
+    polygon: Polygon,
+    border_radius: Radius,
+    join: LineJoin,
+    miter_limit: Scalar,
+    dash: Option<Dash>,
+    color: Color,
+    f: |vertices: &[V], colors: &[f32]|) -> bool {
+
+    // Closed polylines have no caps, but the dashes have.
+    with_stroke_xy_rgba_f32(kind, DEFAULT_TOLERANCE, m, polygon, true, border_radius, join,
+        miter_limit, ButtCap, ButtCap, dash, color, f)
+}
+
+/// Streams a closed border centered on the outline of a rectangle.
+///
+/// Returns false without streaming if the border can not be streamed as the kind.
+pub fn with_rectangle_border_xy_rgba_f32<V: Coordinate>(
+    kind: TriangleKind,
+    m: Matrix2d,
+    rect: Rectangle,
+    border_radius: Radius,
+    join: LineJoin,
+    miter_limit: Scalar,
+    dash: Option<Dash>,
+    color: Color,
+    f: |vertices: &[V], colors: &[f32]|) -> bool {
+
+    let (x, y, w, h) = (rect[0], rect[1], rect[2], rect[3]);
+    let polygon = [x, y, x + w, y, x + w, y + h, x, y + h];
+    with_polygon_border_xy_rgba_f32(kind, m, polygon, border_radius, join, miter_limit,
+        dash, color, f)
+}
+
+/// Streams a closed border centered on the outline of an ellipse.
+///
+/// Returns false without streaming if the border can not be streamed as the kind.
+pub fn with_ellipse_border_xy_rgba_f32<V: Coordinate>(
+    kind: TriangleKind,
+    resolution: uint,
+    m: Matrix2d,
+    rect: Rectangle,
+    border_radius: Radius,
+    join: LineJoin,
+    miter_limit: Scalar,
+    dash: Option<Dash>,
+    color: Color,
+    f: |vertices: &[V], colors: &[f32]|) -> bool {
+
+    let polygon = ellipse_polygon(resolution, rect);
+    with_polygon_border_xy_rgba_f32(kind, m, polygon.as_slice(), border_radius, join,
+        miter_limit, dash, color, f)
+}
+
+/// Streams a closed border centered on the outline of a round rectangle.
+///
+/// Returns false without streaming if the border can not be streamed as the kind.
+pub fn with_round_rectangle_border_xy_rgba_f32<V: Coordinate>(
+    kind: TriangleKind,
+    resolution_corner: uint,
+    m: Matrix2d,
+    rect: Rectangle,
+    round_radius: Radius,
+    border_radius: Radius,
+    join: LineJoin,
+    miter_limit: Scalar,
+    dash: Option<Dash>,
+    color: Color,
+    f: |vertices: &[V], colors: &[f32]|) -> bool {
+
+    let polygon = round_rectangle_polygon(resolution_corner, rect, round_radius);
+    with_polygon_border_xy_rgba_f32(kind, m, polygon.as_slice(), border_radius, join,
+        miter_limit, dash, color, f)
+}
+
+/// Computes the outline of an ellipse, [x0, y0, x1, y1, ...].
+fn ellipse_polygon(resolution: uint, rect: Rectangle) -> Vec<Scalar> {
+    let mut polygon = Vec::with_capacity(resolution * 2);
+    for i in range(0, resolution) {
+        polygon.push_all(ellipse_point(resolution, rect, i));
+    }
+    polygon
+}
+
+/// Computes the outline of a round rectangle, [x0, y0, x1, y1, ...].
+fn round_rectangle_polygon(
+    resolution_corner: uint,
+    rect: Rectangle,
+    round_radius: Radius
+) -> Vec<Scalar> {
+    let n = resolution_corner * 4;
+    let mut polygon = Vec::with_capacity(n * 2);
+    for i in range(0, n) {
+        polygon.push_all(round_rectangle_point(resolution_corner, rect, round_radius, i));
+    }
+    polygon
+}
+
+/// Streams the border of a polyline as a triangle list or a triangle strip.
+///
+/// Returns false without streaming if the border is not a single strip,
+/// see `stroke_polyline_tri_strip`, if it is dashed and the kind is a triangle strip,
+/// or if the kind is a triangle fan.
+pub fn with_stroke_xy_rgba_f32<V: Coordinate>(
+    kind: TriangleKind,
+    tolerance: Scalar,
+    m: Matrix2d,
+    polyline: Polyline,
+    closed: bool,
+    radius: Radius,
+    join: LineJoin,
+    miter_limit: Scalar,
+    start_cap: LineCap,
+    end_cap: LineCap,
+    dash: Option<Dash>,
+    color: Color,
+    f: |vertices: &[V], colors: &[f32]|) -> bool {
+
+    match kind {
+        TriList => {
+            let triangles = stroke_dashed_polyline(polyline, closed, radius, join, miter_limit,
+                start_cap, end_cap, dash, local_tolerance(m, tolerance));
+            stream_triangles_tri_list(m, triangles.as_slice(), color, f);
+            true
+        },
+        TriStrip => {
+            match dash {
+                Some(ref dash) if dash.period() > 0.0 => return false,
+                _ => {}
+            }
+            match stroke_polyline_tri_strip(polyline, closed, radius, join, miter_limit,
+                start_cap, end_cap) {
+                Some(strip) => {
+                    stream_tri_strip_xy_rgba_f32(m, strip.as_slice(), color, f);
+                    true
+                },
+                None => false,
+            }
+        },
+        TriFan => false,
+    }
+}
+
+/// Streams a list of triangles [x0, y0, x1, y1, x2, y2, ...].
+fn stream_triangles_tri_list<V: Coordinate>(
+    m: Matrix2d,
+    triangles: &[Scalar],
+    color: Color,
+    f: |vertices: &[V], colors: &[f32]|) {
+
+    let n = triangles.len();
     let mut i = 0;
-    stream_polygon_tri_list_xy_f32_rgba_f32(
+    stream_tri_list_xy_rgba_f32(
         m, || {
             if i >= n { return None; }
 
             let j = i;
             i += 2;
-            Some([polygon[j], polygon[j+1]])
+            Some([triangles[j], triangles[j + 1]])
         }, color, f);
 }
 
-/// Creates triangle list vertices from rectangle.
-#[inline(always)]
-pub fn rect_tri_list_xy_f32(
+/// Streams a rectangle filled with a gradient.
+pub fn with_rectangle_gradient_tri_list_xy_rgba_f32<V: Coordinate>(
     m: Matrix2d,
-    rect: Rectangle
-) -> [f32, ..12] {
+    rect: Rectangle,
+    gradient: &Gradient,
+    f: |vertices: &[V], colors: &[f32]|) {
+
     let (x, y, w, h) = (rect[0], rect[1], rect[2], rect[3]);
     let (x2, y2) = (x + w, y + h);
-    [tx(m,x,y), ty(m,x,y), tx(m,x2,y), ty(m,x2,y), tx(m,x,y2), ty(m,x,y2),
-     tx(m,x2,y), ty(m,x2,y), tx(m,x2,y2), ty(m,x2,y2), tx(m,x,y2), ty(m,x,y2)]
+    let triangles = [x, y, x2, y, x, y2, x2, y, x2, y2, x, y2];
+    with_gradient_tri_list_xy_rgba_f32(m, triangles, gradient, f);
 }
 
-/// Creates triangle list colors from rectangle.
-#[inline(always)]
-pub fn rect_tri_list_rgba_f32(
-    color: Color
-) -> [f32, ..48] {
-    let (r, g, b, a) = (color[0], color[1], color[2], color[3]);
-    [r, g, b, a, // 0
-     r, g, b, a, // 1
-     r, g, b, a, // 2
-     r, g, b, a, // 3
-     r, g, b, a, // 4
-     r, g, b, a, // 5
-     r, g, b, a, // 6
-     r, g, b, a, // 7
-     r, g, b, a, // 8
-     r, g, b, a, // 9
-     r, g, b, a, // 10
-     r, g, b, a]
+/// Streams an ellipse filled with a gradient.
+pub fn with_ellipse_gradient_tri_list_xy_rgba_f32<V: Coordinate>(
+    resolution: uint,
+    m: Matrix2d,
+    rect: Rectangle,
+    gradient: &Gradient,
+    f: |vertices: &[V], colors: &[f32]|) {
+
+    let center = [rect[0] + 0.5 * rect[2], rect[1] + 0.5 * rect[3]];
+    let triangles = fan_tri_list(center, resolution,
+        |i| ellipse_point(resolution, rect, i));
+    with_gradient_tri_list_xy_rgba_f32(m, triangles.as_slice(), gradient, f);
 }
 
-/// Creates triangle list texture coords from image.
-#[inline(always)]
-pub fn rect_tri_list_uv_f32(image: &Image) -> [f32, ..12] {
-    let source_rect = image.source_rect;
-    let x1 = source_rect[0] as f32 / image.texture_width as f32;
-    let y1 = source_rect[1] as f32 / image.texture_height as f32;
-    let x2 = (source_rect[0] + source_rect[2]) as f32 / image.texture_width as f32;
-    let y2 = (source_rect[1] + source_rect[3]) as f32 / image.texture_height as f32;
-    [x1, y1, x2, y1, x1, y2,
-     x2, y1, x2, y2, x1, y2]
+/// Streams a round rectangle filled with a gradient.
+pub fn with_round_rectangle_gradient_tri_list_xy_rgba_f32<V: Coordinate>(
+    resolution_corner: uint,
+    m: Matrix2d,
+    rect: Rectangle,
+    round_radius: Radius,
+    gradient: &Gradient,
+    f: |vertices: &[V], colors: &[f32]|) {
+
+    let center = [rect[0] + 0.5 * rect[2], rect[1] + 0.5 * rect[3]];
+    let triangles = fan_tri_list(center, resolution_corner * 4,
+        |i| round_rectangle_point(resolution_corner, rect, round_radius, i));
+    with_gradient_tri_list_xy_rgba_f32(m, triangles.as_slice(), gradient, f);
+}
+
+/// Streams a polygon filled with a gradient.
+/// Concave polygons are triangulated with ear clipping.
+pub fn with_polygon_gradient_tri_list_xy_rgba_f32<V: Coordinate>(
+    m: Matrix2d,
+    polygon: Polygon,
+    gradient: &Gradient,
+    f: |vertices: &[V], colors: &[f32]|) {
+
+    let mut triangles = Vec::new();
+    for &i in ear_clip_polygon(polygon).iter() {
+        triangles.push_all([polygon[i * 2], polygon[i * 2 + 1]]);
+    }
+    with_gradient_tri_list_xy_rgba_f32(m, triangles.as_slice(), gradient, f);
+}
+
+/// Creates a triangle list connecting a center point with a closed outline.
+fn fan_tri_list(center: Vec2d, n: uint, point: |uint| -> Vec2d) -> Vec<Scalar> {
+    let mut triangles = Vec::with_capacity(n * 6);
+    if n < 2 { return triangles; }
+    let first = point(0);
+    let mut prev = first;
+    for i in range(1, n + 1) {
+        let p = if i == n { first } else { point(i) };
+        triangles.push_all(center);
+        triangles.push_all(prev);
+        triangles.push_all(p);
+        prev = p;
+    }
+    triangles
+}
+
+/// Streams a list of triangles [x0, y0, x1, y1, x2, y2, ...] filled with a gradient.
+/// Uses buffers that fit inside L1 cache.
+pub fn with_gradient_tri_list_xy_rgba_f32<V: Coordinate>(
+    m: Matrix2d,
+    triangles: &[Scalar],
+    gradient: &Gradient,
+    f: |vertices: &[V], colors: &[f32]|) {
+
+    let tolerance = local_tolerance(m, DEFAULT_TOLERANCE);
+    let (points, point_colors) = gradient_tri_list(triangles, gradient, tolerance);
+    let mut vertices: [V, ..738] = [Coordinate::from_scalar(0.0), ..738];
+    let mut colors: [f32, ..1476] = [0.0, ..1476];
+    let mut i = 0;
+    for j in range(0, points.len() / 2) {
+        let (x, y) = (*points.get(j * 2), *points.get(j * 2 + 1));
+        vertices[i * 2 + 0] = tx(m, x, y);
+        vertices[i * 2 + 1] = ty(m, x, y);
+        let color = point_colors.get(j);
+        colors[i * 4 + 0] = color[0];
+        colors[i * 4 + 1] = color[1];
+        colors[i * 4 + 2] = color[2];
+        colors[i * 4 + 3] = color[3];
+
+        i += 1;
+        // Buffer is full.
+        if i * 2 == vertices.len() {
+            // Send chunk and start over.
+            f(vertices.as_slice(), colors.as_slice());
+            i = 0;
+        }
+    }
+
+    if i > 0 {
+        f(vertices.slice(0, i * 2),
+            colors.slice(0, i * 4));
+    }
+}
+
+/// Splits triangles so a gradient can be interpolated linearly between vertices.
+///
+/// Every triangle is cut at the offsets of the color stops,
+/// so the color within each piece changes linearly.
+/// For radial gradients the triangles are subdivided first,
+/// because the offset is not linear in the position.
+/// The pieces are small enough to follow the circles within tolerance,
+/// and only triangles reaching inside the outermost stop are subdivided.
+/// Returns the triangle list [x0, y0, x1, y1, ...] and a color per vertex.
+pub fn gradient_tri_list(
+    triangles: &[Scalar],
+    gradient: &Gradient,
+    tolerance: Scalar
+) -> (Vec<Scalar>, Vec<Color>) {
+    let mut points = Vec::new();
+    let mut colors = Vec::new();
+    // The color changes within the circle around the center.
+    let (center, outer, max_edge) = match gradient.kind {
+        LinearGradient(_, _) => ([0.0, 0.0], 0.0, 0.0),
+        RadialGradient(center, radius) => {
+            let stops = gradient.stops();
+            let (first, last) = (stops[0].val0(), stops[stops.len() - 1].val0());
+            let outer = radius.abs() * last.max(-first).max(0.0);
+            // Use the length of the segments approximating the circle within tolerance.
+            let segments = arc_resolution(radius.abs(), PI_2, tolerance);
+            (center, outer, radius.abs() * PI_2 / segments as Scalar)
+        },
+    };
+    for i in range(0, triangles.len() / 6) {
+        let a = [triangles[i * 6], triangles[i * 6 + 1]];
+        let b = [triangles[i * 6 + 2], triangles[i * 6 + 3]];
+        let c = [triangles[i * 6 + 4], triangles[i * 6 + 5]];
+        let n = if max_edge > 0.0 && triangle_distance([a, b, c], center) < outer {
+            let longest = distance(a, b).max(distance(b, c)).max(distance(c, a));
+            (longest / max_edge).ceil().max(1.0).min(64.0) as uint
+        } else {
+            1
+        };
+        // Splits the triangle into n * n smaller triangles.
+        let point = |u: uint, v: uint| -> Vec2d {
+            let (u, v) = (u as Scalar / n as Scalar, v as Scalar / n as Scalar);
+            [a[0] + (b[0] - a[0]) * u + (c[0] - a[0]) * v,
+             a[1] + (b[1] - a[1]) * u + (c[1] - a[1]) * v]
+        };
+        for u in range(0, n) {
+            for v in range(0, n - u) {
+                split_gradient_triangle(
+                    [point(u, v), point(u + 1, v), point(u, v + 1)],
+                    gradient, &mut points, &mut colors);
+                if u + v + 1 < n {
+                    split_gradient_triangle(
+                        [point(u + 1, v), point(u + 1, v + 1), point(u, v + 1)],
+                        gradient, &mut points, &mut colors);
+                }
+            }
+        }
+    }
+    (points, colors)
+}
+
+/// Computes the distance between two points.
+#[inline(always)]
+fn distance(a: Vec2d, b: Vec2d) -> Scalar {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// Computes the distance from a point to a triangle, which is zero inside.
+fn triangle_distance(triangle: [Vec2d, ..3], p: Vec2d) -> Scalar {
+    let (a, b, c) = (triangle[0], triangle[1], triangle[2]);
+    if inside_triangle([a[0], a[1], b[0], b[1], c[0], c[1]], p[0], p[1]) { return 0.0; }
+    let segment = |a: Vec2d, b: Vec2d| -> Scalar {
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        let len_sq = dx * dx + dy * dy;
+        let t = if len_sq == 0.0 { 0.0 } else {
+            (((p[0] - a[0]) * dx + (p[1] - a[1]) * dy) / len_sq).max(0.0).min(1.0)
+        };
+        distance(p, [a[0] + t * dx, a[1] + t * dy])
+    };
+    segment(a, b).min(segment(b, c)).min(segment(c, a))
+}
+
+/// Cuts a triangle into pieces between the color stops of a gradient.
+fn split_gradient_triangle(
+    triangle: [Vec2d, ..3],
+    gradient: &Gradient,
+    points: &mut Vec<Scalar>,
+    colors: &mut Vec<Color>
+) {
+    let offsets = [
+        gradient.offset_at(triangle[0]),
+        gradient.offset_at(triangle[1]),
+        gradient.offset_at(triangle[2])
+    ];
+    let min_offset = offsets[0].min(offsets[1]).min(offsets[2]);
+    let max_offset = offsets[0].max(offsets[1]).max(offsets[2]);
+    if min_offset == max_offset {
+        for &p in triangle.iter() {
+            points.push_all(p);
+            colors.push(gradient.color_at(min_offset));
+        }
+        return;
+    }
+
+    let stops = gradient.stops();
+    let k = stops.len();
+    // There is a band before the first stop, between each pair and after the last stop.
+    for band in range(0, k + 1) {
+        let lo = if band == 0 { None } else { Some(stops[band - 1].val0()) };
+        let hi = if band == k { None } else { Some(stops[band].val0()) };
+        match (lo, hi) {
+            (Some(lo), Some(hi)) if lo >= hi => continue,
+            _ => {}
+        }
+        match lo { Some(lo) if max_offset <= lo => continue, _ => {} }
+        match hi { Some(hi) if min_offset >= hi => continue, _ => {} }
+
+        let mut piece: Vec<(Vec2d, Scalar)> = Vec::new();
+        for i in range(0u, 3) {
+            piece.push((triangle[i], offsets[i]));
+        }
+        match lo { Some(lo) => piece = clip_offset(piece, lo, true), None => {} }
+        match hi { Some(hi) => piece = clip_offset(piece, hi, false), None => {} }
+        if piece.len() < 3 { continue; }
+
+        let color = |t: Scalar| -> Color {
+            match (lo, hi) {
+                (Some(lo), Some(hi)) => {
+                    let (a, b) = (stops[band - 1].val1(), stops[band].val1());
+                    let s = ((t - lo) / (hi - lo)).max(0.0).min(1.0) as f32;
+                    lerp_4(&a, &b, &s)
+                },
+                (None, _) => stops[0].val1(),
+                (_, None) => stops[k - 1].val1(),
+            }
+        };
+        // The clipped piece is convex.
+        let (p0, t0) = *piece.get(0);
+        for i in range(1, piece.len() - 1) {
+            let (p1, t1) = *piece.get(i);
+            let (p2, t2) = *piece.get(i + 1);
+            points.push_all(p0);
+            colors.push(color(t0));
+            points.push_all(p1);
+            colors.push(color(t1));
+            points.push_all(p2);
+            colors.push(color(t2));
+        }
+    }
+}
+
+/// Clips a convex polygon of points with offsets against an offset.
+///
+/// Keeps the part above the offset when `above` is `true`, otherwise the part below.
+fn clip_offset(
+    polygon: Vec<(Vec2d, Scalar)>,
+    offset: Scalar,
+    above: bool
+) -> Vec<(Vec2d, Scalar)> {
+    let inside = |t: Scalar| if above { t >= offset } else { t <= offset };
+    let n = polygon.len();
+    let mut res = Vec::new();
+    for i in range(0, n) {
+        let (p, tp) = *polygon.get(i);
+        let (q, tq) = *polygon.get((i + 1) % n);
+        if inside(tp) { res.push((p, tp)); }
+        if inside(tp) != inside(tq) {
+            let s = (offset - tp) / (tq - tp);
+            res.push((lerp_2(&p, &q, &s), offset));
+        }
+    }
+    res
+}
+
+/// Removes repeated points from a polyline and computes its segments.
+///
+/// A closed polyline drops its last point when it repeats the first one,
+/// and is only kept closed with more than 2 points.
+/// Returns the points, whether the polyline is closed,
+/// and the unit normal and the length of each segment.
+/// The normals point to the left of the segments.
+fn polyline_segments(
+    polyline: Polyline,
+    closed: bool
+) -> (Vec<Vec2d>, bool, Vec<Vec2d>, Vec<Scalar>) {
+    // Repeated points have no direction.
+    let mut points: Vec<Vec2d> = Vec::new();
+    for i in range(0, polyline.len() / 2) {
+        let p = [polyline[i * 2], polyline[i * 2 + 1]];
+        if points.last().map_or(true, |q| *q != p) { points.push(p); }
+    }
+    if closed && points.len() > 1 && *points.get(0) == *points.last().unwrap() {
+        points.pop();
+    }
+
+    let n = points.len();
+    let closed = closed && n > 2;
+    let segments = if closed { n } else if n > 0 { n - 1 } else { 0 };
+    let mut normals: Vec<Vec2d> = Vec::with_capacity(segments);
+    let mut lengths: Vec<Scalar> = Vec::with_capacity(segments);
+    for i in range(0, segments) {
+        let (a, b) = (*points.get(i), *points.get(next(n, i)));
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        let len = (dx * dx + dy * dy).sqrt();
+        // The unit normal points to the left of the segment.
+        normals.push([-dy / len, dx / len]);
+        lengths.push(len);
+    }
+    (points, closed, normals, lengths)
+}
+
+/// Converts a polyline into triangles covering a border around it.
+///
+/// Each segment becomes a quad, and the gaps on the outer side
+/// of the corners are filled with the line join.
+/// The triangles do not overlap unless the segments are shorter than the border.
+/// Closed polylines are also joined between the last and the first point,
+/// while open polylines get the caps at the ends.
+/// Returns a triangle list [x0, y0, x1, y1, x2, y2, ...].
+pub fn stroke_polyline(
+    polyline: Polyline,
+    closed: bool,
+    radius: Radius,
+    join: LineJoin,
+    miter_limit: Scalar,
+    start_cap: LineCap,
+    end_cap: LineCap,
+    tolerance: Scalar
+) -> Vec<Scalar> {
+    let (points, closed, normals, lengths) = polyline_segments(polyline, closed);
+    let mut triangles = Vec::new();
+    let n = points.len();
+    if n == 0 || radius <= 0.0 { return triangles; }
+    if n == 1 {
+        // A single point has only caps, pointing along the x axis.
+        if !closed {
+            triangles = stroke_dot(*points.get(0), [1.0, 0.0], radius, start_cap, end_cap,
+                tolerance);
+        }
+        return triangles;
+    }
+
+    let segments = normals.len();
+    let offset = |p: Vec2d, n: Vec2d, s: Scalar| -> Vec2d {
+        [p[0] + s * radius * n[0], p[1] + s * radius * n[1]]
+    };
+    // Returns the cross and dot product of the directions at a corner.
+    // The directions are the normals rotated a quarter turn clockwise.
+    let turn = |i: uint| -> (Scalar, Scalar) {
+        let (n0, n1) = (*normals.get(previous(n, i)), *normals.get(i));
+        (n0[1] * -n1[0] + n0[0] * n1[1], n0[0] * n1[0] + n0[1] * n1[1])
+    };
+
+    let corners: Vec<uint> = if closed { range(0, n).collect() } else { range(1, n - 1).collect() };
+    // The inner edges of the segments meet at the inner side of the corners.
+    // Ending the segments there avoids overlapping triangles,
+    // unless the segments are too short.
+    let mut inner: Vec<Option<(Scalar, Vec2d)>> = range(0, n).map(|_| None).collect();
+    for &i in corners.iter() {
+        let (cross, dot) = turn(i);
+        if cross == 0.0 { continue; }
+
+        let prev = previous(n, i);
+        // The distance along the segments from the corner to where the edges meet.
+        let along = radius * cross.abs() / (1.0 + dot);
+        if along > 0.5 * (*lengths.get(prev)).min(*lengths.get(i)) { continue; }
+
+        // The inner side is to the left when turning left.
+        let side = if cross > 0.0 { 1.0 } else { -1.0 };
+        let (n0, n1) = (*normals.get(prev), *normals.get(i));
+        let (mx, my) = (n0[0] + n1[0], n0[1] + n1[1]);
+        let len_sq = mx * mx + my * my;
+        let meet = offset(*points.get(i), [2.0 * mx / len_sq, 2.0 * my / len_sq], side);
+        *inner.get_mut(i) = Some((side, meet));
+    }
+
+    for i in range(0, segments) {
+        let j = next(n, i);
+        let normal = *normals.get(i);
+        let corner = |k: uint, s: Scalar| -> Vec2d {
+            match *inner.get(k) {
+                Some((side, q)) if side == s => q,
+                _ => offset(*points.get(k), normal, s),
+            }
+        };
+        let (a0, a1) = (corner(i, 1.0), corner(i, -1.0));
+        let (b0, b1) = (corner(j, 1.0), corner(j, -1.0));
+        triangles.push_all(a0);
+        triangles.push_all(a1);
+        triangles.push_all(b0);
+        triangles.push_all(a1);
+        triangles.push_all(b1);
+        triangles.push_all(b0);
+    }
+
+    if !closed {
+        // The directions are the normals rotated a quarter turn clockwise.
+        let (first, last) = (*normals.get(0), *normals.get(segments - 1));
+        triangles.push_all(stroke_cap(start_cap, *points.get(0),
+            [-first[1], first[0]], radius, tolerance).as_slice());
+        triangles.push_all(stroke_cap(end_cap, *points.get(n - 1),
+            [last[1], -last[0]], radius, tolerance).as_slice());
+    }
+
+    for &i in corners.iter() {
+        let p = *points.get(i);
+        let (n0, n1) = (*normals.get(previous(n, i)), *normals.get(i));
+        let (cross, dot) = turn(i);
+        if cross == 0.0 && dot > 0.0 { continue; }
+
+        // The join fills the gap between the segments on the outer side.
+        let side = if cross > 0.0 { -1.0 } else { 1.0 };
+        let (a, b) = (offset(p, n0, side), offset(p, n1, side));
+        let center = match *inner.get(i) {
+            Some((_, q)) => q,
+            None => p,
+        };
+        let bevel = |triangles: &mut Vec<Scalar>| {
+            triangles.push_all(center);
+            triangles.push_all(a);
+            triangles.push_all(b);
+        };
+        match join {
+            BevelJoin => bevel(&mut triangles),
+            MiterJoin => {
+                // The length of the sum of the normals is `2 * sin(angle / 2)`,
+                // where `angle` is the angle between the segments.
+                let (mx, my) = (n0[0] + n1[0], n0[1] + n1[1]);
+                let len_sq = mx * mx + my * my;
+                if len_sq == 0.0 || 2.0 / len_sq.sqrt() > miter_limit {
+                    bevel(&mut triangles);
+                    continue;
+                }
+                let tip = offset(p, [2.0 * mx / len_sq, 2.0 * my / len_sq], side);
+                triangles.push_all(center);
+                triangles.push_all(a);
+                triangles.push_all(tip);
+                triangles.push_all(center);
+                triangles.push_all(tip);
+                triangles.push_all(b);
+            },
+            RoundJoin => {
+                let angle = if cross == 0.0 { PI } else { dot.max(-1.0).min(1.0).acos() };
+                let step = if cross > 0.0 { angle } else { -angle };
+                let start = (side * n0[1]).atan2(side * n0[0]);
+                let k = arc_resolution(radius, angle, tolerance);
+                let mut prev = a;
+                for j in range(1, k + 1) {
+                    let point = if j == k { b } else {
+                        let t = start + step * j as Scalar / k as Scalar;
+                        [p[0] + radius * t.cos(), p[1] + radius * t.sin()]
+                    };
+                    triangles.push_all(center);
+                    triangles.push_all(prev);
+                    triangles.push_all(point);
+                    prev = point;
+                }
+            },
+        }
+    }
+    triangles
+}
+
+/// Converts a polyline into a triangle strip covering a border around it.
+///
+/// The strip goes along the polyline with one point on each side per corner,
+/// so it is the same border as `stroke_polyline` with miter joins.
+/// Returns `None` if the border is not a single strip:
+/// when the polyline has less than 2 points,
+/// a corner needs a join other than a miter within the limit,
+/// a segment is shorter than the border at a corner,
+/// or an open polyline has caps other than butt or square.
+/// Returns a triangle strip [x0, y0, x1, y1, ...].
+pub fn stroke_polyline_tri_strip(
+    polyline: Polyline,
+    closed: bool,
+    radius: Radius,
+    join: LineJoin,
+    miter_limit: Scalar,
+    start_cap: LineCap,
+    end_cap: LineCap
+) -> Option<Vec<Scalar>> {
+    let (points, closed, normals, lengths) = polyline_segments(polyline, closed);
+    let n = points.len();
+    if n < 2 || radius <= 0.0 { return None; }
+    // The distance the caps extend the ends of an open polyline.
+    let extend = |cap: LineCap| -> Option<Scalar> {
+        match cap {
+            ButtCap => Some(0.0),
+            SquareCap => Some(radius),
+            _ => None,
+        }
+    };
+    let (start, end) = if closed { (0.0, 0.0) } else {
+        match (extend(start_cap), extend(end_cap)) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return None,
+        }
+    };
+
+    let segments = normals.len();
+    let mut strip = Vec::with_capacity((n + 1) * 4);
+    for i in range(0, n) {
+        let p = *points.get(i);
+        let (p, miter) = if !closed && (i == 0 || i == n - 1) {
+            let (normal, extend) = if i == 0 {
+                (*normals.get(0), -start)
+            } else {
+                (*normals.get(segments - 1), end)
+            };
+            // The direction is the normal rotated a quarter turn clockwise.
+            ([p[0] + extend * normal[1], p[1] - extend * normal[0]], normal)
+        } else {
+            let prev = previous(n, i);
+            let (n0, n1) = (*normals.get(prev), *normals.get(i));
+            let (mx, my) = (n0[0] + n1[0], n0[1] + n1[1]);
+            let len_sq = mx * mx + my * my;
+            if len_sq == 0.0 { return None; }
+            let cross = n0[1] * -n1[0] + n0[0] * n1[1];
+            if cross != 0.0 {
+                match join { MiterJoin => {}, _ => return None }
+                if 2.0 / len_sq.sqrt() > miter_limit { return None; }
+                // The inner edges must meet before the middle of the segments.
+                let dot = n0[0] * n1[0] + n0[1] * n1[1];
+                let along = radius * cross.abs() / (1.0 + dot);
+                if along > 0.5 * (*lengths.get(prev)).min(*lengths.get(i)) { return None; }
+            }
+            (p, [2.0 * mx / len_sq, 2.0 * my / len_sq])
+        };
+        strip.push_all([p[0] + radius * miter[0], p[1] + radius * miter[1]]);
+        strip.push_all([p[0] - radius * miter[0], p[1] - radius * miter[1]]);
+    }
+    if closed {
+        // Repeat the first points to close the strip.
+        let first = Vec::from_slice(strip.slice(0, 4));
+        strip.push_all(first.as_slice());
+    }
+    Some(strip)
+}
+
+/// Converts a polyline into triangles covering a dashed border around it.
+///
+/// Each dash is stroked as an open polyline with the caps at its ends.
+/// Without a dash pattern this is the same as `stroke_polyline`.
+pub fn stroke_dashed_polyline(
+    polyline: Polyline,
+    closed: bool,
+    radius: Radius,
+    join: LineJoin,
+    miter_limit: Scalar,
+    start_cap: LineCap,
+    end_cap: LineCap,
+    dash: Option<Dash>,
+    tolerance: Scalar
+) -> Vec<Scalar> {
+    match dash {
+        Some(ref dash) if dash.period() > 0.0 => {
+            let mut triangles = Vec::new();
+            for &(ref part, direction) in dash_polyline_directions(polyline, closed, dash).iter() {
+                let p = [*part.get(0), *part.get(1)];
+                if part.as_slice().chunks(2).all(|q| q[0] == p[0] && q[1] == p[1]) {
+                    // Dots have no direction of their own, so they follow the line.
+                    triangles.push_all(stroke_dot(p, direction, radius, start_cap, end_cap,
+                        tolerance).as_slice());
+                } else {
+                    triangles.push_all(stroke_polyline(part.as_slice(), false, radius, join,
+                        miter_limit, start_cap, end_cap, tolerance).as_slice());
+                }
+            }
+            triangles
+        },
+        _ => stroke_polyline(polyline, closed, radius, join, miter_limit,
+            start_cap, end_cap, tolerance),
+    }
+}
+
+/// Splits a polyline into the drawn parts of a dash pattern.
+///
+/// Closed polylines are split along the way back to the first point.
+/// A drawn part with zero length becomes a single point.
+pub fn dash_polyline(polyline: Polyline, closed: bool, dash: &Dash) -> Vec<Vec<Scalar>> {
+    dash_polyline_directions(polyline, closed, dash).move_iter().map(|(part, _)| part).collect()
+}
+
+/// Splits a polyline into the drawn parts of a dash pattern,
+/// together with the unit direction of the line where each part ends.
+///
+/// The direction orients the caps of parts with zero length.
+fn dash_polyline_directions(
+    polyline: Polyline,
+    closed: bool,
+    dash: &Dash
+) -> Vec<(Vec<Scalar>, Vec2d)> {
+    let lengths = dash.lengths();
+    let period = dash.period();
+    let n = polyline.len() / 2;
+    let mut dashes = Vec::new();
+    if n == 0 { return dashes; }
+
+    let count = if closed { n + 1 } else { n };
+    let point = |i: uint| -> Vec2d {
+        let i = i % n;
+        [polyline[i * 2], polyline[i * 2 + 1]]
+    };
+    // Patterns too short for the length of the line would make too many dashes.
+    let mut total = 0.0;
+    for i in range(1, count) {
+        let (a, b) = (point(i - 1), point(i));
+        total += (b[0] - a[0]).hypot(b[1] - a[1]);
+    }
+    let per_period = if lengths.len() % 2 == 1 { lengths.len() } else { lengths.len() / 2 };
+    if period <= 0.0 || total / period * per_period as Scalar > MAX_DASHES as Scalar {
+        // Without any lengths the whole line is drawn.
+        let mut whole = Vec::with_capacity(count * 2);
+        for i in range(0, count) { whole.push_all(point(i)); }
+        dashes.push((whole, [1.0, 0.0]));
+        return dashes;
+    }
+
+    // Find where in the pattern the line starts.
+    let mut phase = dash.phase % period;
+    if phase < 0.0 { phase += period; }
+    let (mut k, mut on) = (0u, true);
+    while phase > 0.0 && phase >= lengths[k] {
+        phase -= lengths[k];
+        k = next(lengths.len(), k);
+        on = !on;
+    }
+    // The distance left of the current length.
+    let mut left = lengths[k] - phase;
+
+    let mut current: Vec<Scalar> = Vec::new();
+    let mut direction = [1.0, 0.0];
+    if on { current.push_all(point(0)); }
+    for i in range(1, count) {
+        let (a, b) = (point(i - 1), point(i));
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        let len = (dx * dx + dy * dy).sqrt();
+        if len > 0.0 { direction = [dx / len, dy / len]; }
+        let mut pos = 0.0;
+        while pos + left <= len {
+            pos += left;
+            let t = if len == 0.0 { 0.0 } else { pos / len };
+            current.push_all([a[0] + t * dx, a[1] + t * dy]);
+            if on {
+                dashes.push((current, direction));
+                current = Vec::new();
+            }
+            k = next(lengths.len(), k);
+            on = !on;
+            left = lengths[k];
+        }
+        left -= len - pos;
+        if on && pos < len { current.push_all(b); }
+    }
+    // A dash starting at the end point is not visible.
+    if on && current.len() >= 4 { dashes.push((current, direction)); }
+    dashes
+}
+
+/// Converts a point into the caps of a line with zero length going in the unit direction.
+///
+/// Returns a triangle list [x0, y0, x1, y1, x2, y2, ...].
+fn stroke_dot(
+    p: Vec2d,
+    direction: Vec2d,
+    radius: Radius,
+    start_cap: LineCap,
+    end_cap: LineCap,
+    tolerance: Scalar
+) -> Vec<Scalar> {
+    let mut triangles = stroke_cap(start_cap, p, [-direction[0], -direction[1]], radius,
+        tolerance);
+    triangles.push_all(stroke_cap(end_cap, p, direction, radius, tolerance).as_slice());
+    triangles
+}
+
+/// Converts the outline of a polygon into triangles covering a closed border.
+///
+/// Returns a triangle list [x0, y0, x1, y1, x2, y2, ...].
+#[inline(always)]
+pub fn stroke_polygon(
+    polygon: Polygon,
+    radius: Radius,
+    join: LineJoin,
+    miter_limit: Scalar,
+    tolerance: Scalar
+) -> Vec<Scalar> {
+    // Closed polylines have no caps.
+    stroke_polyline(polygon, true, radius, join, miter_limit, ButtCap, ButtCap, tolerance)
+}
+
+/// Converts a line cap into triangles.
+///
+/// The cap is added at the end point of a line going in the unit direction.
+/// Returns a triangle list [x0, y0, x1, y1, x2, y2, ...].
+pub fn stroke_cap(
+    cap: LineCap,
+    end: Vec2d,
+    direction: Vec2d,
+    radius: Radius,
+    tolerance: Scalar
+) -> Vec<Scalar> {
+    let (dx, dy) = (direction[0] * radius, direction[1] * radius);
+    // The left and right corners of the line end.
+    let left = [end[0] - dy, end[1] + dx];
+    let right = [end[0] + dy, end[1] - dx];
+    let mut triangles = Vec::new();
+    match cap {
+        ButtCap => {},
+        SquareCap => {
+            let left_out = [left[0] + dx, left[1] + dy];
+            let right_out = [right[0] + dx, right[1] + dy];
+            triangles.push_all(left);
+            triangles.push_all(right);
+            triangles.push_all(right_out);
+            triangles.push_all(left);
+            triangles.push_all(right_out);
+            triangles.push_all(left_out);
+        },
+        TriangularCap => {
+            triangles.push_all(left);
+            triangles.push_all(right);
+            triangles.push_all([end[0] + dx, end[1] + dy]);
+        },
+        RoundCap => {
+            // Goes clockwise from the left corner around the tip to the right corner.
+            let start = direction[0].atan2(-direction[1]);
+            let k = arc_resolution(radius, PI, tolerance);
+            let mut prev = left;
+            for j in range(1, k + 1) {
+                let point = if j == k { right } else {
+                    let angle = start - PI * j as Scalar / k as Scalar;
+                    [end[0] + radius * angle.cos(), end[1] + radius * angle.sin()]
+                };
+                triangles.push_all(end);
+                triangles.push_all(prev);
+                triangles.push_all(point);
+                prev = point;
+            }
+        },
+    }
+    triangles
+}
+
+/// Clips a triangle list with color per vertex to a rectangle.
+///
+/// The rectangle is in the same coordinates as the vertices.
+/// Triangles crossing the edges are cut and the colors are interpolated.
+/// Uses buffers that fit inside L1 cache.
+pub fn clip_tri_list_xy_f32_rgba_f32(
+    clip: Rectangle,
+    vertices: &[f32],
+    colors: &[f32],
+    f: |vertices: &[f32], colors: &[f32]|) {
+
+    let regions = [rectangle_half_planes(clip)];
+    let clipped = clip_tri_list(regions, vertices, colors, None);
+    send_tri_list_xy_f32_rgba_f32(clipped.as_slice(), f);
+}
+
+/// Clips a triangle list with color and texture coordinate per vertex to a rectangle.
+///
+/// The rectangle is in the same coordinates as the vertices.
+/// Triangles crossing the edges are cut,
+/// and the colors and texture coordinates are interpolated.
+/// Uses buffers that fit inside L1 cache.
+pub fn clip_tri_list_xy_f32_rgba_f32_uv_f32(
+    clip: Rectangle,
+    vertices: &[f32],
+    colors: &[f32],
+    texture_coords: &[f32],
+    f: |vertices: &[f32], colors: &[f32], texture_coords: &[f32]|) {
+
+    let regions = [rectangle_half_planes(clip)];
+    let clipped = clip_tri_list(regions, vertices, colors, Some(texture_coords));
+    send_tri_list_xy_f32_rgba_f32_uv_f32(clipped.as_slice(), f);
+}
+
+/// Clips a triangle list with color per vertex to the inside of a mask.
+///
+/// The mask is a triangle list [x0, y0, x1, y1, ...] in the same coordinates as the vertices.
+/// The triangles of the mask should not overlap,
+/// because the parts inside several of them are drawn once for each.
+/// Use `merge_mask_tri_list` to remove the overlaps.
+/// Uses buffers that fit inside L1 cache.
+pub fn mask_tri_list_xy_f32_rgba_f32(
+    mask: &[f32],
+    vertices: &[f32],
+    colors: &[f32],
+    f: |vertices: &[f32], colors: &[f32]|) {
+
+    let regions = triangle_half_planes(mask);
+    let clipped = clip_tri_list(regions.as_slice(), vertices, colors, None);
+    send_tri_list_xy_f32_rgba_f32(clipped.as_slice(), f);
+}
+
+/// Clips a triangle list with color and texture coordinate per vertex
+/// to the inside of a mask.
+///
+/// The mask is a triangle list [x0, y0, x1, y1, ...] in the same coordinates as the vertices.
+/// The triangles of the mask should not overlap,
+/// because the parts inside several of them are drawn once for each.
+/// Use `merge_mask_tri_list` to remove the overlaps.
+/// Uses buffers that fit inside L1 cache.
+pub fn mask_tri_list_xy_f32_rgba_f32_uv_f32(
+    mask: &[f32],
+    vertices: &[f32],
+    colors: &[f32],
+    texture_coords: &[f32],
+    f: |vertices: &[f32], colors: &[f32], texture_coords: &[f32]|) {
+
+    let regions = triangle_half_planes(mask);
+    let clipped = clip_tri_list(regions.as_slice(), vertices, colors, Some(texture_coords));
+    send_tri_list_xy_f32_rgba_f32_uv_f32(clipped.as_slice(), f);
+}
+
+/// Merges the triangles of a mask into triangles that do not overlap.
+///
+/// The mask is a triangle list [x0, y0, x1, y1, ...].
+/// Returns a triangle list covering the union of the triangles.
+pub fn merge_mask_tri_list(mask: &[f32]) -> Vec<f32> {
+    // With the same orientation for all triangles,
+    // the union is where the winding number is not zero.
+    let triangles: Vec<[Scalar, ..6]> = mask.chunks(6).filter(|t| t.len() == 6).map(|t| {
+        let triangle = [
+            t[0] as Scalar, t[1] as Scalar,
+            t[2] as Scalar, t[3] as Scalar,
+            t[4] as Scalar, t[5] as Scalar
+        ];
+        if triangle_face(triangle) {
+            [triangle[0], triangle[1], triangle[4], triangle[5], triangle[2], triangle[3]]
+        } else {
+            triangle
+        }
+    }).collect();
+    let shape: Vec<&[Scalar]> = triangles.iter().map(|t| t.as_slice()).collect();
+    triangulate_shape(shape.as_slice(), NonZero).iter().map(|&x| x as f32).collect()
+}
+
+/// A half plane [a, b, c] containing the points where `a * x + b * y + c >= 0`.
+type HalfPlane = [f32, ..3];
+
+/// A vertex with all attributes, [x, y, r, g, b, a, u, v].
+type ClipVertex = [f32, ..8];
+
+/// Computes the half planes that intersect to a rectangle.
+fn rectangle_half_planes(rect: Rectangle) -> Vec<HalfPlane> {
+    let (x0, y0) = (rect[0] as f32, rect[1] as f32);
+    let (x1, y1) = ((rect[0] + rect[2]) as f32, (rect[1] + rect[3]) as f32);
+    vec![[1.0, 0.0, -x0], [-1.0, 0.0, x1], [0.0, 1.0, -y0], [0.0, -1.0, y1]]
+}
+
+/// Computes the half planes that intersect to each triangle in a triangle list.
+///
+/// Triangles without area are left out.
+fn triangle_half_planes(triangles: &[f32]) -> Vec<Vec<HalfPlane>> {
+    let mut res = Vec::new();
+    for t in triangles.chunks(6) {
+        if t.len() < 6 { break; }
+        let area = (t[2] - t[0]) * (t[5] - t[1]) - (t[4] - t[0]) * (t[3] - t[1]);
+        if area == 0.0 { continue; }
+        // Orient the edges such that the inside is on the positive side.
+        let sign = if area > 0.0 { 1.0 } else { -1.0 };
+        let mut planes = Vec::with_capacity(3);
+        for i in range(0u, 3) {
+            let (ax, ay) = (t[i * 2], t[i * 2 + 1]);
+            let (bx, by) = (t[(i * 2 + 2) % 6], t[(i * 2 + 3) % 6]);
+            let (a, b) = (-(by - ay) * sign, (bx - ax) * sign);
+            planes.push([a, b, -(a * ax + b * ay)]);
+        }
+        res.push(planes);
+    }
+    res
+}
+
+/// Clips every triangle to each convex region and returns the vertices of the pieces.
+///
+/// The colors and texture coordinates are interpolated.
+fn clip_tri_list<P: Vector<HalfPlane>>(
+    regions: &[P],
+    vertices: &[f32],
+    colors: &[f32],
+    texture_coords: Option<&[f32]>
+) -> Vec<ClipVertex> {
+    let vertex = |j: uint| -> ClipVertex {
+        let (u, v) = match texture_coords {
+            Some(uv) => (uv[j * 2], uv[j * 2 + 1]),
+            None => (0.0, 0.0),
+        };
+        [vertices[j * 2], vertices[j * 2 + 1],
+         colors[j * 4], colors[j * 4 + 1], colors[j * 4 + 2], colors[j * 4 + 3],
+         u, v]
+    };
+    let side = |plane: &HalfPlane, v: &ClipVertex| plane[0] * v[0] + plane[1] * v[1] + plane[2];
+    let mut res = Vec::new();
+    let mut polygon: Vec<ClipVertex> = Vec::with_capacity(8);
+    for i in range(0, vertices.len() / 6) {
+        let triangle = [vertex(i * 3), vertex(i * 3 + 1), vertex(i * 3 + 2)];
+        for region in regions.iter() {
+            let planes = region.as_slice();
+            let inside = triangle.iter().all(|v| planes.iter().all(|p| side(p, v) >= 0.0));
+            if inside {
+                for &v in triangle.iter() { res.push(v); }
+                continue;
+            }
+
+            polygon.clear();
+            for &v in triangle.iter() { polygon.push(v); }
+            for plane in planes.iter() {
+                let input = replace(&mut polygon, Vec::with_capacity(8));
+                let m = input.len();
+                for j in range(0, m) {
+                    let p = input.get(j);
+                    let q = input.get((j + 1) % m);
+                    let (dp, dq) = (side(plane, p), side(plane, q));
+                    if dp >= 0.0 { polygon.push(*p); }
+                    if (dp >= 0.0) != (dq >= 0.0) {
+                        let t = dp / (dp - dq);
+                        let mut v = *p;
+                        for k in range(0u, 8) { v[k] = p[k] + t * (q[k] - p[k]); }
+                        polygon.push(v);
+                    }
+                }
+            }
+
+            // The clipped polygon is convex.
+            if polygon.len() < 3 { continue; }
+            for j in range(1, polygon.len() - 1) {
+                res.push(*polygon.get(0));
+                res.push(*polygon.get(j));
+                res.push(*polygon.get(j + 1));
+            }
+        }
+    }
+    res
+}
+
+/// Sends clipped vertices as a triangle list with color per vertex.
+fn send_tri_list_xy_f32_rgba_f32(
+    clipped: &[ClipVertex],
+    f: |vertices: &[f32], colors: &[f32]|) {
+
+    let mut vertices: [f32, ..738] = [0.0, ..738];
+    let mut colors: [f32, ..1476] = [0.0, ..1476];
+    for chunk in clipped.chunks(vertices.len() / 2) {
+        for (i, v) in chunk.iter().enumerate() {
+            vertices[i * 2 + 0] = v[0];
+            vertices[i * 2 + 1] = v[1];
+            colors[i * 4 + 0] = v[2];
+            colors[i * 4 + 1] = v[3];
+            colors[i * 4 + 2] = v[4];
+            colors[i * 4 + 3] = v[5];
+        }
+        let n = chunk.len();
+        f(vertices.slice(0, n * 2), colors.slice(0, n * 4));
+    }
+}
+
+/// Sends clipped vertices as a triangle list with color and texture coordinate per vertex.
+fn send_tri_list_xy_f32_rgba_f32_uv_f32(
+    clipped: &[ClipVertex],
+    f: |vertices: &[f32], colors: &[f32], texture_coords: &[f32]|) {
+
+    let mut vertices: [f32, ..738] = [0.0, ..738];
+    let mut colors: [f32, ..1476] = [0.0, ..1476];
+    let mut texture_coords: [f32, ..738] = [0.0, ..738];
+    for chunk in clipped.chunks(vertices.len() / 2) {
+        for (i, v) in chunk.iter().enumerate() {
+            vertices[i * 2 + 0] = v[0];
+            vertices[i * 2 + 1] = v[1];
+            colors[i * 4 + 0] = v[2];
+            colors[i * 4 + 1] = v[3];
+            colors[i * 4 + 2] = v[4];
+            colors[i * 4 + 3] = v[5];
+            texture_coords[i * 2 + 0] = v[6];
+            texture_coords[i * 2 + 1] = v[7];
+        }
+        let n = chunk.len();
+        f(vertices.slice(0, n * 2), colors.slice(0, n * 4), texture_coords.slice(0, n * 2));
+    }
+}
+
+/// Creates triangle list vertices from rectangle.
+#[inline(always)]
+pub fn rect_tri_list_xy_f32<V: Coordinate>(
+    m: Matrix2d,
+    rect: Rectangle
+) -> [V, ..12] {
+    let (x, y, w, h) = (rect[0], rect[1], rect[2], rect[3]);
+    let (x2, y2) = (x + w, y + h);
+    [tx(m,x,y), ty(m,x,y), tx(m,x2,y), ty(m,x2,y), tx(m,x,y2), ty(m,x,y2),
+     tx(m,x2,y), ty(m,x2,y), tx(m,x2,y2), ty(m,x2,y2), tx(m,x,y2), ty(m,x,y2)]
+}
+
+/// Creates triangle list colors from rectangle.
+#[inline(always)]
+pub fn rect_tri_list_rgba_f32(
+    color: Color
+) -> [f32, ..24] {
+    let (r, g, b, a) = (color[0], color[1], color[2], color[3]);
+    [r, g, b, a, // 0
+     r, g, b, a, // 1
+     r, g, b, a, // 2
+     r, g, b, a, // 3
+     r, g, b, a, // 4
+     r, g, b, a]
+}
+
+/// Creates triangle list texture coords from image.
+#[inline(always)]
+pub fn rect_tri_list_uv_f32(image: &Image) -> [f32, ..12] {
+    let source_rect = image.source_rect;
+    let x1 = source_rect[0] as f32 / image.texture_width as f32;
+    let y1 = source_rect[1] as f32 / image.texture_height as f32;
+    let x2 = (source_rect[0] + source_rect[2]) as f32 / image.texture_width as f32;
+    let y2 = (source_rect[1] + source_rect[3]) as f32 / image.texture_height as f32;
+    [x1, y1, x2, y1, x1, y2,
+     x2, y1, x2, y2, x1, y2]
+}
+
+/// The indices of the two triangles of a rectangle,
+/// for use with `rect_tri_list_indexed_xy_f32`.
+pub static RECT_TRI_LIST_INDICES: [u32, ..6] = [0, 1, 2, 1, 3, 2];
+
+/// Creates indexed triangle list vertices from rectangle.
+///
+/// The corners are in the order top-left, top-right, bottom-left, bottom-right.
+#[inline(always)]
+pub fn rect_tri_list_indexed_xy_f32(
+    m: Matrix2d,
+    rect: Rectangle
+) -> [f32, ..8] {
+    let (x, y, w, h) = (rect[0], rect[1], rect[2], rect[3]);
+    let (x2, y2) = (x + w, y + h);
+    [tx(m,x,y), ty(m,x,y), tx(m,x2,y), ty(m,x2,y),
+     tx(m,x,y2), ty(m,x,y2), tx(m,x2,y2), ty(m,x2,y2)]
+}
+
+/// Creates indexed triangle list colors from rectangle.
+#[inline(always)]
+pub fn rect_tri_list_indexed_rgba_f32(
+    color: Color
+) -> [f32, ..16] {
+    let (r, g, b, a) = (color[0], color[1], color[2], color[3]);
+    [r, g, b, a, // 0
+     r, g, b, a, // 1
+     r, g, b, a, // 2
+     r, g, b, a]
+}
+
+/// Splits polygon into an indexed triangle list with one color per vertex.
+/// Concave polygons are triangulated with ear clipping.
+///
+/// Returns the vertices, colors and indices.
+pub fn polygon_tri_list_indexed_xy_f32_rgba_f32(
+    m: Matrix2d,
+    polygon: Polygon,
+    color: Color
+) -> (Vec<f32>, Vec<f32>, Vec<u32>) {
+    let n = polygon.len() / 2;
+    let vertices = indexed_vertices(m, n, |i| [polygon[i * 2], polygon[i * 2 + 1]]);
+    let indices = ear_clip_polygon(polygon).iter().map(|&i| i as u32).collect();
+    (vertices, indexed_colors(n, color), indices)
+}
+
+/// Splits an ellipse into an indexed triangle list with one color per vertex.
+///
+/// Returns the vertices, colors and indices.
+pub fn ellipse_tri_list_indexed_xy_f32_rgba_f32(
+    resolution: uint,
+    m: Matrix2d,
+    rect: Rectangle,
+    color: Color
+) -> (Vec<f32>, Vec<f32>, Vec<u32>) {
+    outline_tri_list_indexed_xy_f32_rgba_f32(m, resolution, color,
+        |i| ellipse_point(resolution, rect, i))
+}
+
+/// Splits a round rectangle into an indexed triangle list with one color per vertex.
+///
+/// Returns the vertices, colors and indices.
+pub fn round_rectangle_tri_list_indexed_xy_f32_rgba_f32(
+    resolution_corner: uint,
+    m: Matrix2d,
+    rect: Rectangle,
+    round_radius: Radius,
+    color: Color
+) -> (Vec<f32>, Vec<f32>, Vec<u32>) {
+    outline_tri_list_indexed_xy_f32_rgba_f32(m, resolution_corner * 4, color,
+        |i| round_rectangle_point(resolution_corner, rect, round_radius, i))
+}
+
+/// Creates an indexed triangle list of a convex outline,
+/// connecting the first point with the other points.
+fn outline_tri_list_indexed_xy_f32_rgba_f32(
+    m: Matrix2d,
+    n: uint,
+    color: Color,
+    point: |uint| -> Vec2d
+) -> (Vec<f32>, Vec<f32>, Vec<u32>) {
+    let vertices = indexed_vertices(m, n, point);
+    let mut indices = Vec::with_capacity(if n < 2 { 0 } else { (n - 2) * 3 });
+    for i in range(2, n) {
+        indices.push_all([0, i as u32 - 1, i as u32]);
+    }
+    (vertices, indexed_colors(n, color), indices)
+}
+
+/// Transforms the points of an indexed triangle list.
+fn indexed_vertices(m: Matrix2d, n: uint, point: |uint| -> Vec2d) -> Vec<f32> {
+    let mut vertices = Vec::with_capacity(n * 2);
+    for i in range(0, n) {
+        let p = point(i);
+        vertices.push_all([tx(m, p[0], p[1]), ty(m, p[0], p[1])]);
+    }
+    vertices
+}
+
+/// Creates the same color for every vertex.
+fn indexed_colors(n: uint, color: Color) -> Vec<f32> {
+    Vec::from_fn(n * 4, |i| color[i % 4])
+}
+
+/// Expands an indexed vertex attribute to a triangle list.
+///
+/// The size is the number of values per vertex,
+/// for example 2 for vertices and 4 for colors.
+pub fn expand_indexed_f32(values: &[f32], size: uint, indices: &[u32]) -> Vec<f32> {
+    let mut expanded = Vec::with_capacity(indices.len() * size);
+    for &i in indices.iter() {
+        let i = i as uint * size;
+        expanded.push_all(values.slice(i, i + size));
+    }
+    expanded
+}
+
+/// Streams textured triangles with color and texture coordinate per vertex.
+///
+/// Every 3 points make a triangle.
+/// The bounds are projected onto the source rectangle of the image
+/// through the texture coordinate transform, see `image_tri_list_xy_f32_uv_f32`.
+/// Uses buffers that fit inside L1 cache.
+pub fn stream_textured_tri_list_xy_f32_rgba_f32_uv_f32(
+    m: Matrix2d,
+    points: || -> Option<Vec2d>,
+    bounds: Rectangle,
+    image: &Image,
+    uv_transform: Matrix2d,
+    color: Color,
+    f: |vertices: &[f32], colors: &[f32], texture_coords: &[f32]|) {
+
+    let source_rect = image.source_rect;
+    let (sx, sy) = (source_rect[0] as Scalar, source_rect[1] as Scalar);
+    let (sw, sh) = (source_rect[2] as Scalar, source_rect[3] as Scalar);
+    let (tw, th) = (image.texture_width as Scalar, image.texture_height as Scalar);
+    let t = uv_transform;
+    let mut vertices: [f32, ..738] = [0.0, ..738];
+    let mut colors: [f32, ..1476] = [0.0, ..1476];
+    let mut uvs: [f32, ..738] = [0.0, ..738];
+    for i in range(0u, colors.len()) {
+        colors[i] = color[i % 4];
+    }
+    let mut i = 0;
+    loop {
+        let p = match points() { None => break, Some(val) => val };
+        let (x, y) = (p[0], p[1]);
+        vertices[i * 2 + 0] = tx(m, x, y);
+        vertices[i * 2 + 1] = ty(m, x, y);
+        let s = if bounds[2] == 0.0 { 0.0 } else { (x - bounds[0]) / bounds[2] };
+        let r = if bounds[3] == 0.0 { 0.0 } else { (y - bounds[1]) / bounds[3] };
+        let u = t[0] * s + t[1] * r + t[2];
+        let v = t[3] * s + t[4] * r + t[5];
+        uvs[i * 2 + 0] = ((sx + u * sw) / tw) as f32;
+        uvs[i * 2 + 1] = ((sy + v * sh) / th) as f32;
+
+        i += 1;
+        // Buffer is full.
+        if i * 2 == vertices.len() {
+            // Send chunk and start over.
+            f(vertices.as_slice(), colors.as_slice(), uvs.as_slice());
+            i = 0;
+        }
+    }
+
+    // Send only complete triangles.
+    let i = i - i % 3;
+    if i > 0 {
+        f(vertices.slice(0, i * 2),
+            colors.slice(0, i * 4),
+            uvs.slice(0, i * 2));
+    }
+}
+
+/// Streams a convex polygon as textured triangles sharing the first point.
+fn stream_convex_textured_tri_list(
+    m: Matrix2d,
+    n: uint,
+    point: |uint| -> Vec2d,
+    bounds: Rectangle,
+    image: &Image,
+    uv_transform: Matrix2d,
+    color: Color,
+    f: |vertices: &[f32], colors: &[f32], texture_coords: &[f32]|) {
+
+    if n < 3 { return; }
+    let first = point(0);
+    let mut triangle = [first, first, point(1)];
+    let mut i = 2;
+    let mut k = 0u;
+    stream_textured_tri_list_xy_f32_rgba_f32_uv_f32(m, || {
+        if k % 3 == 0 {
+            if i >= n { return None; }
+
+            // Continue the fan from the last point of the previous triangle.
+            triangle = [first, triangle[2], point(i)];
+            i += 1;
+        }
+        let j = k % 3;
+        k += 1;
+        Some(triangle[j])
+    }, bounds, image, uv_transform, color, f);
+}
+
+/// Streams a textured polygon with color per vertex.
+///
+/// Concave polygons are triangulated with ear clipping.
+/// The bounding box of the polygon is mapped to the image.
+pub fn with_polygon_textured_tri_list_xy_f32_rgba_f32_uv_f32(
+    m: Matrix2d,
+    polygon: Polygon,
+    image: &Image,
+    uv_transform: Matrix2d,
+    color: Color,
+    f: |vertices: &[f32], colors: &[f32], texture_coords: &[f32]|) {
+
+    let indices = ear_clip_polygon(polygon);
+    let n = indices.len();
+    let mut i = 0;
+    stream_textured_tri_list_xy_f32_rgba_f32_uv_f32(m, || {
+        if i >= n { return None; }
+
+        let j = *indices.get(i);
+        i += 1;
+        Some([polygon[j * 2], polygon[j * 2 + 1]])
+    }, polygon_bounds(polygon), image, uv_transform, color, f);
+}
+
+/// Streams a textured ellipse with color per vertex.
+///
+/// The rectangle of the ellipse is mapped to the image.
+pub fn with_ellipse_textured_tri_list_xy_f32_rgba_f32_uv_f32(
+    resolution: uint,
+    m: Matrix2d,
+    rect: Rectangle,
+    image: &Image,
+    uv_transform: Matrix2d,
+    color: Color,
+    f: |vertices: &[f32], colors: &[f32], texture_coords: &[f32]|) {
+
+    stream_convex_textured_tri_list(m, resolution, |i| ellipse_point(resolution, rect, i),
+        rect, image, uv_transform, color, f);
+}
+
+/// Streams a textured round rectangle with color per vertex.
+///
+/// The rectangle is mapped to the image.
+pub fn with_round_rectangle_textured_tri_list_xy_f32_rgba_f32_uv_f32(
+    resolution_corner: uint,
+    m: Matrix2d,
+    rect: Rectangle,
+    round_radius: Radius,
+    image: &Image,
+    uv_transform: Matrix2d,
+    color: Color,
+    f: |vertices: &[f32], colors: &[f32], texture_coords: &[f32]|) {
+
+    stream_convex_textured_tri_list(m, resolution_corner * 4,
+        |i| round_rectangle_point(resolution_corner, rect, round_radius, i),
+        rect, image, uv_transform, color, f);
+}
+
+/// Creates triangle list vertices and texture coordinates for an image.
+///
+/// The texture coordinate transform maps the rectangle, as a unit square,
+/// to the source rectangle, as a unit square.
+/// When tiling, each copy has the size of the transformed source rectangle.
+/// Returns the vertices and the texture coordinates.
+pub fn image_tri_list_xy_f32_uv_f32(
+    m: Matrix2d,
+    rect: Rectangle,
+    image: &Image,
+    uv_transform: Matrix2d,
+    mode: SliceMode
+) -> (Vec<f32>, Vec<f32>) {
+    let source_rect = image.source_rect;
+    let (sx, sy) = (source_rect[0] as Scalar, source_rect[1] as Scalar);
+    let (sw, sh) = (source_rect[2] as Scalar, source_rect[3] as Scalar);
+    let (tw, th) = (image.texture_width as Scalar, image.texture_height as Scalar);
+    let t = uv_transform;
+    let uv = |s: Scalar, r: Scalar| -> (f32, f32) {
+        let u = t[0] * s + t[1] * r + t[2];
+        let v = t[3] * s + t[4] * r + t[5];
+        (((sx + u * sw) / tw) as f32, ((sy + v * sh) / th) as f32)
+    };
+    let (cols, rows) = match mode {
+        Stretch => (
+            slice_pieces(rect[0], rect[0] + rect[2], 0.0, 1.0, false),
+            slice_pieces(rect[1], rect[1] + rect[3], 0.0, 1.0, false)
+        ),
+        Tile => {
+            // The size of a copy is the length of the source edges it shows.
+            let tile_w = (t[0] * sw).hypot(t[3] * sh);
+            let tile_h = (t[1] * sw).hypot(t[4] * sh);
+            // An empty source rectangle gives a single piece with an empty source span.
+            let to_unit = |pieces: Vec<(Scalar, Scalar, Scalar, Scalar)>, size: Scalar| {
+                if size == 0.0 { return pieces; }
+                pieces.iter().map(|&(d1, d2, s1, s2)| (d1, d2, s1 / size, s2 / size)).collect()
+            };
+            (
+                to_unit(slice_pieces(rect[0], rect[0] + rect[2], 0.0, tile_w, true), tile_w),
+                to_unit(slice_pieces(rect[1], rect[1] + rect[3], 0.0, tile_h, true), tile_h)
+            )
+        }
+    };
+
+    let mut vertices = Vec::new();
+    let mut uvs = Vec::new();
+    for &(y1, y2, r1, r2) in rows.iter() {
+        for &(x1, x2, s1, s2) in cols.iter() {
+            vertices.push_all(rect_tri_list_xy_f32(m, [x1, y1, x2 - x1, y2 - y1]));
+            let (u11, v11) = uv(s1, r1);
+            let (u21, v21) = uv(s2, r1);
+            let (u12, v12) = uv(s1, r2);
+            let (u22, v22) = uv(s2, r2);
+            uvs.push_all([u11, v11, u21, v21, u12, v12, u21, v21, u22, v22, u12, v12]);
+        }
+    }
+    (vertices, uvs)
+}
+
+/// Creates triangle list vertices and texture coordinates for a nine-slice image.
+///
+/// The insets are the left, top, right and bottom border in source pixels.
+/// Negative insets count as zero,
+/// and insets that do not fit the source rectangle are shrunk to share its size.
+/// Returns the vertices and the texture coordinates.
+pub fn nine_slice_tri_list_xy_f32_uv_f32(
+    m: Matrix2d,
+    rect: Rectangle,
+    image: &Image,
+    insets: [Scalar, ..4],
+    mode: SliceMode
+) -> (Vec<f32>, Vec<f32>) {
+    let source_rect = image.source_rect;
+    let (sx, sy) = (source_rect[0] as Scalar, source_rect[1] as Scalar);
+    let (sw, sh) = (source_rect[2] as Scalar, source_rect[3] as Scalar);
+    let (tw, th) = (image.texture_width as Scalar, image.texture_height as Scalar);
+    let (left, top, right, bottom) = (
+        insets[0].max(0.0), insets[1].max(0.0), insets[2].max(0.0), insets[3].max(0.0)
+    );
+    // Insets that do not fit are shrunk, such that no slice is dropped.
+    let (left, right) = if left + right > sw {
+            (left * sw / (left + right), right * sw / (left + right))
+        } else { (left, right) };
+    let (top, bottom) = if top + bottom > sh {
+            (top * sh / (top + bottom), bottom * sh / (top + bottom))
+        } else { (top, bottom) };
+    let src_x = [sx, sx + left, sx + sw - right, sx + sw];
+    let src_y = [sy, sy + top, sy + sh - bottom, sy + sh];
+    // Borders that do not fit are shrunk to share the available size.
+    let sx_scale = if left + right > rect[2] { rect[2] / (left + right) } else { 1.0 };
+    let sy_scale = if top + bottom > rect[3] { rect[3] / (top + bottom) } else { 1.0 };
+    let (x, y, x2, y2) = (rect[0], rect[1], rect[0] + rect[2], rect[1] + rect[3]);
+    let dst_x = [x, x + left * sx_scale, x2 - right * sx_scale, x2];
+    let dst_y = [y, y + top * sy_scale, y2 - bottom * sy_scale, y2];
+    let tile = match mode { Stretch => false, Tile => true };
+
+    let mut vertices = Vec::new();
+    let mut uvs = Vec::new();
+    for j in range(0u, 3) {
+        let rows = slice_pieces(dst_y[j], dst_y[j + 1], src_y[j], src_y[j + 1], tile && j == 1);
+        for i in range(0u, 3) {
+            let cols = slice_pieces(dst_x[i], dst_x[i + 1], src_x[i], src_x[i + 1], tile && i == 1);
+            for &(y1, y2, v1, v2) in rows.iter() {
+                for &(x1, x2, u1, u2) in cols.iter() {
+                    vertices.push_all(rect_tri_list_xy_f32(m, [x1, y1, x2 - x1, y2 - y1]));
+                    let (u1, u2) = ((u1 / tw) as f32, (u2 / tw) as f32);
+                    let (v1, v2) = ((v1 / th) as f32, (v2 / th) as f32);
+                    uvs.push_all([u1, v1, u2, v1, u1, v2, u2, v1, u2, v2, u1, v2]);
+                }
+            }
+        }
+    }
+    (vertices, uvs)
+}
+
+/// Splits a destination span into pieces mapped to a source span.
+///
+/// Returns `(d1, d2, s1, s2)` for each piece.
+/// When tiling, each piece has the source size and the last one is cut.
+/// There are at most `MAX_TILES` pieces, which are stretched when more are needed.
+/// A destination span going backwards is tiled from `d0` towards `d1`.
+fn slice_pieces(
+    d0: Scalar,
+    d1: Scalar,
+    s0: Scalar,
+    s1: Scalar,
+    tile: bool
+) -> Vec<(Scalar, Scalar, Scalar, Scalar)> {
+    if d1 == d0 || s1 < s0 { return Vec::new(); }
+    // An empty source span stretches the texels at its edge.
+    if !tile || s1 == s0 { return vec![(d0, d1, s0, s1)]; }
+    let size = s1 - s0;
+    let len = (d1 - d0).abs();
+    let dir = if d1 < d0 { -1.0 } else { 1.0 };
+    let step = size.max(len / MAX_TILES as Scalar);
+    let n = min((len / step).ceil() as uint, MAX_TILES);
+    let mut pieces = Vec::with_capacity(n);
+    for i in range(0, n) {
+        let d = d0 + dir * i as Scalar * step;
+        let end = if i + 1 == n { d1 } else { d + dir * step };
+        pieces.push((d, end, s0, s0 + (end - d).abs() * size / step));
+    }
+    pieces
+}
+
+#[cfg(test)]
+fn check_ear_clip(polygon: Polygon) {
+    let indices = ear_clip_polygon(polygon);
+    let n = polygon.len() / 2;
+    assert_eq!(indices.len(), (n - 2) * 3);
+    let polygon_area = area(polygon);
+    let mut sum = 0.0;
+    for triangle in indices.as_slice().chunks(3) {
+        let (a, b, c) = (triangle[0], triangle[1], triangle[2]);
+        let triangle_area = area([
+            polygon[a * 2], polygon[a * 2 + 1],
+            polygon[b * 2], polygon[b * 2 + 1],
+            polygon[c * 2], polygon[c * 2 + 1]
+        ]);
+        // Every triangle has the same winding as the polygon.
+        assert!(triangle_area * polygon_area > 0.0);
+        sum += triangle_area;
+    }
+    assert!((sum - polygon_area).abs() < 0.000001);
+}
+
+#[test]
+fn test_ear_clip_l_shape() {
+    check_ear_clip([2.0, 1.0, 1.0, 1.0, 1.0, 2.0, 0.0, 2.0, 0.0, 0.0, 2.0, 0.0]);
+    // Clock-wise.
+    check_ear_clip([2.0, 0.0, 0.0, 0.0, 0.0, 2.0, 1.0, 2.0, 1.0, 1.0, 2.0, 1.0]);
+}
+
+#[test]
+fn test_ear_clip_star() {
+    let mut star = Vec::new();
+    for i in range(0u, 10) {
+        let angle = i as f64 / 10.0 * PI_2;
+        let radius = if i % 2 == 0 { 1.0 } else { 0.4 };
+        star.push(angle.cos() * radius);
+        star.push(angle.sin() * radius);
+    }
+    check_ear_clip(star.as_slice());
+}
+
+#[test]
+fn test_ear_clip_spiral() {
+    // A band winding two times around the center.
+    let n = 40u;
+    let mut spiral = Vec::new();
+    for i in range(0, n) {
+        let angle = i as f64 / n as f64 * 2.0 * PI_2;
+        let radius = 1.0 + 0.5 * i as f64 / n as f64 * 2.0;
+        spiral.push(angle.cos() * (radius + 0.2));
+        spiral.push(angle.sin() * (radius + 0.2));
+    }
+    for i in range(0, n).rev() {
+        let angle = i as f64 / n as f64 * 2.0 * PI_2;
+        let radius = 1.0 + 0.5 * i as f64 / n as f64 * 2.0;
+        spiral.push(angle.cos() * radius);
+        spiral.push(angle.sin() * radius);
+    }
+    check_ear_clip(spiral.as_slice());
+}
+
+#[test]
+fn test_fill_concave_polygon() {
+    use {AddColor, AddPolygon, Context, Fill, RelativeTransform2d, SoftwareBackEnd};
+
+    let mut back_end = SoftwareBackEnd::new(4, 4);
+    let c = Context::new();
+    let d = c.trans(-1.0, -1.0);
+    // The triangle fan from the first vertex covers the upper right corner.
+    let l_shape = [2.0, 1.0, 1.0, 1.0, 1.0, 2.0, 0.0, 2.0, 0.0, 0.0, 2.0, 0.0];
+    d.polygon(l_shape).rgba(1.0, 1.0, 1.0, 1.0).fill(&mut back_end).unwrap();
+    assert!(back_end.pixel(0, 0) == [255, 255, 255, 255]);
+    assert!(back_end.pixel(3, 3) == [255, 255, 255, 255]);
+    assert!(back_end.pixel(3, 0) == [0, 0, 0, 0]);
+}
+
+#[cfg(test)]
+fn tri_list_area(triangles: &[Scalar]) -> Scalar {
+    triangles.chunks(6).map(|t| area(t).abs()).fold(0.0, |a, b| a + b)
+}
+
+#[test]
+fn test_triangulate_shape_hole() {
+    let outer = [0.0, 0.0, 4.0, 0.0, 4.0, 4.0, 0.0, 4.0];
+    let same = [1.0, 1.0, 3.0, 1.0, 3.0, 3.0, 1.0, 3.0];
+    let opposite = [1.0, 1.0, 1.0, 3.0, 3.0, 3.0, 3.0, 1.0];
+    let a = triangulate_shape([outer.as_slice(), same.as_slice()], EvenOdd);
+    assert!((tri_list_area(a.as_slice()) - 12.0).abs() < 0.000001);
+    let b = triangulate_shape([outer.as_slice(), same.as_slice()], NonZero);
+    assert!((tri_list_area(b.as_slice()) - 16.0).abs() < 0.000001);
+    let c = triangulate_shape([outer.as_slice(), opposite.as_slice()], NonZero);
+    assert!((tri_list_area(c.as_slice()) - 12.0).abs() < 0.000001);
+}
+
+#[test]
+fn test_triangulate_shape_self_intersecting() {
+    // A pentagram has a center with winding number 2.
+    let mut star = Vec::new();
+    for i in range(0u, 5) {
+        let angle = (i * 2) as f64 / 5.0 * PI_2 + FRAC_PI_2;
+        star.push(angle.cos());
+        star.push(angle.sin());
+    }
+    let star = star.as_slice();
+    let even_odd = tri_list_area(triangulate_shape([star], EvenOdd).as_slice());
+    let non_zero = tri_list_area(triangulate_shape([star], NonZero).as_slice());
+    // The center pentagon is only filled with the non-zero rule.
+    assert!(non_zero > even_odd);
+    // The non-zero area equals the area of the pentagon outlining the star.
+    let mut outline = Vec::new();
+    for i in range(0u, 10) {
+        let angle = i as f64 / 10.0 * PI_2 + FRAC_PI_2;
+        let radius = if i % 2 == 0 { 1.0 } else { (0.4 * PI).cos() / (0.2 * PI).cos() };
+        outline.push(angle.cos() * radius);
+        outline.push(angle.sin() * radius);
+    }
+    assert!((non_zero - area(outline.as_slice()).abs()).abs() < 0.000001);
+}
+
+#[test]
+fn test_triangulate_shape_many_edges() {
+    use std::f64::consts::{FRAC_1_SQRT2, FRAC_PI_4};
+
+    // Many overlapping copies of a square rotated around its center.
+    let mut squares = Vec::new();
+    for i in range(0u, 50) {
+        let angle = i as f64 / 50.0 * FRAC_PI_2;
+        let mut square = Vec::new();
+        for k in range(0u, 4) {
+            let corner = angle + k as f64 * FRAC_PI_2;
+            square.push(corner.cos());
+            square.push(corner.sin());
+        }
+        squares.push(square);
+    }
+    let shape: Vec<&[Scalar]> = squares.iter().map(|s| s.as_slice()).collect();
+    let non_zero = tri_list_area(triangulate_shape(shape.as_slice(), NonZero).as_slice());
+    // The union is a star with the corners on the unit circle,
+    // and the sides of neighbouring squares crossing halfway between them.
+    let half = PI / 200.0;
+    let inner = FRAC_1_SQRT2 / (FRAC_PI_4 - half).cos();
+    assert!((non_zero - 200.0 * inner * half.sin()).abs() < 0.000001);
+}
+
+#[test]
+fn test_stroke_polyline() {
+    let polyline = [0.0, 0.0, 1.0, 0.0, 1.0, 1.0];
+    let area_of = |join: LineJoin, miter_limit: Scalar| {
+        let triangles = stroke_polyline(
+            polyline, false, 0.1, join, miter_limit, ButtCap, ButtCap, 0.0001);
+        tri_list_area(triangles.as_slice())
+    };
+    // Two overlapping rectangles and the corner triangle of the bevel.
+    let bevel = area_of(BevelJoin, DEFAULT_MITER_LIMIT);
+    assert!((bevel - 0.395).abs() < 0.000001);
+    assert!((area_of(MiterJoin, DEFAULT_MITER_LIMIT) - 0.4).abs() < 0.000001);
+    assert!((area_of(RoundJoin, DEFAULT_MITER_LIMIT) - 0.39 - 0.0025 * PI).abs() < 0.0001);
+    // A right angle has a miter ratio of square root of two.
+    assert_eq!(area_of(MiterJoin, 1.4), bevel);
+    // Closed polylines are joined at the first point too.
+    let square = [0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0];
+    let closed = stroke_polyline(
+        square, true, 0.1, MiterJoin, DEFAULT_MITER_LIMIT, RoundCap, RoundCap, 0.0001);
+    assert!((tri_list_area(closed.as_slice()) - (1.44 - 0.64)).abs() < 0.000001);
+}
+
+#[test]
+fn test_stroke_cap() {
+    let (end, direction) = ([1.0, 0.0], [1.0, 0.0]);
+    assert_eq!(stroke_cap(ButtCap, end, direction, 1.0, 0.3).len(), 0);
+    assert_eq!(stroke_cap(SquareCap, end, direction, 1.0, 0.3).as_slice(), &[
+        1.0, 1.0, 1.0, -1.0, 2.0, -1.0,
+        1.0, 1.0, 2.0, -1.0, 2.0, 1.0
+    ]);
+    assert_eq!(stroke_cap(TriangularCap, end, direction, 1.0, 0.3).as_slice(), &[
+        1.0, 1.0, 1.0, -1.0, 2.0, 0.0
+    ]);
+    // The tolerance allows quarter circle segments.
+    assert_eq!(stroke_cap(RoundCap, end, direction, 1.0, 0.3).as_slice(), &[
+        1.0, 0.0, 1.0, 1.0, 2.0, 0.0,
+        1.0, 0.0, 2.0, 0.0, 1.0, -1.0
+    ]);
+    // Caps follow the direction of the line.
+    assert_eq!(stroke_cap(TriangularCap, [0.0, 0.0], [0.0, -1.0], 0.5, 0.3).as_slice(), &[
+        0.5, 0.0, -0.5, 0.0, 0.0, -0.5
+    ]);
+}
+
+#[test]
+fn test_stroke_polyline_caps() {
+    let line = [0.0, 0.0, 2.0, 0.0];
+    let area_of = |start_cap: LineCap, end_cap: LineCap| {
+        let triangles = stroke_polyline(
+            line, false, 0.5, MiterJoin, DEFAULT_MITER_LIMIT, start_cap, end_cap, 0.0001);
+        tri_list_area(triangles.as_slice())
+    };
+    assert!((area_of(ButtCap, ButtCap) - 2.0).abs() < 0.000001);
+    assert!((area_of(SquareCap, ButtCap) - 2.5).abs() < 0.000001);
+    assert!((area_of(TriangularCap, TriangularCap) - 2.5).abs() < 0.000001);
+    assert!((area_of(RoundCap, RoundCap) - 2.0 - 0.25 * PI).abs() < 0.001);
+    // A single point only has caps.
+    let dot = stroke_polyline([1.0, 1.0], false, 0.5, MiterJoin, DEFAULT_MITER_LIMIT,
+        SquareCap, SquareCap, 0.0001);
+    assert!((tri_list_area(dot.as_slice()) - 1.0).abs() < 0.000001);
+}
+
+#[test]
+fn test_stroke_polygon() {
+    // The ring of a square is the difference between the outer and inner square,
+    // where the outer corners depend on the join.
+    let square = [0.0, 0.0, 2.0, 0.0, 2.0, 2.0, 0.0, 2.0];
+    for &join in [MiterJoin, RoundJoin, BevelJoin].iter() {
+        let ring = stroke_polygon(square, 0.25, join, DEFAULT_MITER_LIMIT, 0.0001);
+        let corners = match join {
+            MiterJoin => 0.25,
+            RoundJoin => 0.0625 * PI,
+            BevelJoin => 0.125,
+        };
+        let expected = 2.5 * 2.5 - 1.5 * 1.5 - 4.0 * 0.25 * 0.25 + corners;
+        assert!((tri_list_area(ring.as_slice()) - expected).abs() < 0.001);
+    }
+}
+
+#[test]
+fn test_dash_polyline() {
+    let line = [0.0, 0.0, 10.0, 0.0];
+    let dashes = dash_polyline(line, false, &Dash::new([2.0, 1.0], 0.0));
+    let expected: &[&[Scalar]] = &[
+        &[0.0, 0.0, 2.0, 0.0],
+        &[3.0, 0.0, 5.0, 0.0],
+        &[6.0, 0.0, 8.0, 0.0],
+        &[9.0, 0.0, 10.0, 0.0],
+    ];
+    assert_eq!(dashes.iter().map(|d| d.as_slice()).collect::<Vec<&[Scalar]>>().as_slice(), expected);
+    // The phase moves the pattern backwards along the line.
+    let dashes = dash_polyline(line, false, &Dash::new([2.0, 1.0], 2.5));
+    assert_eq!(dashes.get(0).as_slice(), &[0.5, 0.0, 2.5, 0.0]);
+    let dashes = dash_polyline(line, false, &Dash::new([2.0, 1.0], -1.0));
+    assert_eq!(dashes.get(0).as_slice(), &[1.0, 0.0, 3.0, 0.0]);
+    // Dashes continue around corners and back to the start of closed polylines.
+    let square = [0.0, 0.0, 2.0, 0.0, 2.0, 2.0, 0.0, 2.0];
+    let dashes = dash_polyline(square, true, &Dash::new([3.0, 1.0], 0.0));
+    assert_eq!(dashes.len(), 2);
+    assert_eq!(dashes.get(0).as_slice(), &[0.0, 0.0, 2.0, 0.0, 2.0, 1.0]);
+    assert_eq!(dashes.get(1).as_slice(), &[2.0, 2.0, 0.0, 2.0, 0.0, 1.0]);
+    // Patterns making more than `MAX_DASHES` dashes draw the whole line.
+    let dashes = dash_polyline(line, false, &Dash::new([1e-9, 1e-9], 0.0));
+    assert_eq!(dashes.len(), 1);
+    assert_eq!(dashes.get(0).as_slice(), line.as_slice());
+}
+
+#[test]
+fn test_dash_dots() {
+    // Drawn parts with zero length become dots with round caps.
+    let line = [0.0, 0.0, 4.0, 0.0];
+    let dash = Dash::new([0.0, 1.0], 0.0);
+    assert_eq!(dash_polyline(line, false, &dash).len(), 5);
+    let triangles = stroke_dashed_polyline(line, false, 0.25, MiterJoin, DEFAULT_MITER_LIMIT,
+        RoundCap, RoundCap, Some(dash), 0.0001);
+    assert!((tri_list_area(triangles.as_slice()) - 5.0 * 0.0625 * PI).abs() < 0.001);
+    // Without caps the dots are not visible.
+    let triangles = stroke_dashed_polyline(line, false, 0.25, MiterJoin, DEFAULT_MITER_LIMIT,
+        ButtCap, ButtCap, Some(dash), 0.0001);
+    assert_eq!(triangles.len(), 0);
+    // Square dots on a diagonal line are turned along the line.
+    let diagonal = [0.0, 0.0, 2.5, 2.5];
+    let triangles = stroke_dashed_polyline(diagonal, false, 0.25, MiterJoin, DEFAULT_MITER_LIMIT,
+        SquareCap, SquareCap, Some(Dash::new([0.0, 2.0_f64.sqrt()], 0.0)), 0.0001);
+    assert!((tri_list_area(triangles.as_slice()) - 3.0 * 0.25).abs() < 0.000001);
+    for p in triangles.as_slice().chunks(2) {
+        // The distance to the line is at most the radius.
+        assert!((p[0] - p[1]).abs() / 2.0_f64.sqrt() <= 0.25 + 0.000001);
+    }
+}
+
+#[test]
+fn test_gradient_tri_list() {
+    let (red, blue) = ([1.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]);
+    let white = [1.0, 1.0, 1.0, 1.0];
+    // A hard edge from red to blue in the middle.
+    let gradient = Gradient::linear([0.0, 0.0], [1.0, 0.0], [(0.5, red), (0.5, blue), (1.0, white)]);
+    let rect = [0.0, 0.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+    let (points, colors) = gradient_tri_list(rect, &gradient, 0.001);
+    assert_eq!(points.len() / 2, colors.len());
+    assert!((tri_list_area(points.as_slice()) - 1.0).abs() < 0.000001);
+    for i in range(0, colors.len()) {
+        let (x, color) = (*points.get(i * 2), *colors.get(i));
+        if x < 0.5 { assert!(color == red); }
+        if x > 0.5 { assert!(color == gradient.color_at(x)); }
+    }
+    // Both sides of the edge have vertices on it.
+    assert!(range(0, colors.len()).any(|i| *points.get(i * 2) == 0.5 && *colors.get(i) == red));
+    assert!(range(0, colors.len()).any(|i| *points.get(i * 2) == 0.5 && *colors.get(i) == blue));
+
+    let gradient = Gradient::radial([0.0, 0.0], 1.0, [(0.0, red), (1.0, blue)]);
+    let (points, colors) = gradient_tri_list(rect, &gradient, 0.001);
+    assert!((tri_list_area(points.as_slice()) - 1.0).abs() < 0.000001);
+    for i in range(0, colors.len()) {
+        let p = [*points.get(i * 2), *points.get(i * 2 + 1)];
+        let expected = gradient.color_at(gradient.offset_at(p));
+        assert!((colors.get(i)[2] - expected[2]).abs() < 0.01);
+    }
+    // A larger tolerance needs fewer pieces.
+    let (coarse, _) = gradient_tri_list(rect, &gradient, 0.1);
+    assert!(coarse.len() < points.len());
+    // Outside the last stop the color does not change, so the triangle is kept.
+    let far = [2.0, 2.0, 3.0, 2.0, 2.0, 3.0];
+    let (points, colors) = gradient_tri_list(far, &gradient, 0.001);
+    assert_eq!(points.as_slice(), far.as_slice());
+    assert!(colors.iter().all(|&color| color == blue));
+}
+
+#[test]
+fn test_ellipse_resolution() {
+    use vecmath::{scale};
+
+    let rect = [0.0, 0.0, 1.0, 1.0];
+    let small = ellipse_resolution(scale(0.01, 0.01), rect, Tolerance(DEFAULT_TOLERANCE));
+    let large = ellipse_resolution(scale(10.0, 10.0), rect, Tolerance(DEFAULT_TOLERANCE));
+    assert!(small < 16);
+    assert!(large > 128);
+    assert_eq!(ellipse_resolution(scale(10.0, 10.0), rect, Segments(20)), 20);
+    assert_eq!(ellipse_resolution(scale(10.0, 10.0), rect, Segments(1)), 3);
+    // Each corner of the round rectangle gets at least one segment.
+    assert_eq!(round_rectangle_resolution(scale(0.01, 0.01), 0.1, Tolerance(DEFAULT_TOLERANCE)), 2);
+    assert!(round_rectangle_resolution(scale(10.0, 1.0), 0.1, Tolerance(DEFAULT_TOLERANCE)) > 16);
+}
+
+#[test]
+fn test_nine_slice() {
+    let image = Image {
+        texture_id: 0,
+        texture_width: 8,
+        texture_height: 8,
+        source_rect: [0, 0, 8, 8],
+    };
+    let m = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+    let (vertices, uvs) = nine_slice_tri_list_xy_f32_uv_f32(
+        m, [0.0, 0.0, 20.0, 10.0], &image, [2.0, 2.0, 2.0, 2.0], Stretch);
+    assert_eq!(vertices.len(), 9 * 12);
+    assert_eq!(uvs.len(), vertices.len());
+    // The center keeps the borders at their source size.
+    let center = vertices.slice(4 * 12, 5 * 12);
+    assert!(center.slice(0, 2) == [2.0, 2.0]);
+    assert!(center.slice(8, 10) == [18.0, 8.0]);
+    assert!(uvs.slice(4 * 12, 4 * 12 + 2) == [0.25, 0.25]);
+    assert!(uvs.slice(4 * 12 + 8, 4 * 12 + 10) == [0.75, 0.75]);
+
+    // The 4 pixel center is repeated 4 times horizontally, the last one cut.
+    let (vertices, uvs) = nine_slice_tri_list_xy_f32_uv_f32(
+        m, [0.0, 0.0, 18.0, 8.0], &image, [2.0, 2.0, 2.0, 2.0], Tile);
+    assert_eq!(vertices.len(), 3 * (1 + 4 + 1) * 12);
+    let last = (6 + 1 + 3) * 12;
+    assert!(vertices.slice(last, last + 2) == [14.0, 2.0]);
+    assert!(vertices.slice(last + 8, last + 10) == [16.0, 6.0]);
+    assert!(uvs.slice(last + 8, last + 10) == [0.5, 0.75]);
+
+    // Borders larger than the rectangle are shrunk.
+    let (vertices, _) = nine_slice_tri_list_xy_f32_uv_f32(
+        m, [0.0, 0.0, 2.0, 2.0], &image, [2.0, 2.0, 2.0, 2.0], Stretch);
+    assert_eq!(vertices.len(), 4 * 12);
+    assert!(vertices.slice(8, 10) == [1.0, 1.0]);
+
+    // Insets larger than the source are shrunk to fit it.
+    let (vertices, uvs) = nine_slice_tri_list_xy_f32_uv_f32(
+        m, [0.0, 0.0, 20.0, 20.0], &image, [12.0, 4.0, 4.0, -1.0], Stretch);
+    assert_eq!(vertices.len(), 6 * 12);
+    assert!(vertices.slice(8, 10) == [6.0, 4.0]);
+    assert!(uvs.slice(8, 10) == [0.75, 0.5]);
+
+    // Tiles are stretched when more than `MAX_TILES` copies are needed.
+    let (vertices, uvs) = nine_slice_tri_list_xy_f32_uv_f32(
+        m, [0.0, 0.0, 1000000.0, 8.0], &image, [2.0, 2.0, 2.0, 2.0], Tile);
+    assert_eq!(vertices.len(), 3 * (1 + MAX_TILES + 1) * 12);
+    let last = (MAX_TILES + 2 + MAX_TILES) * 12;
+    assert!(vertices.slice(last + 8, last + 10) == [999998.0, 6.0]);
+    assert!(uvs.slice(last + 8, last + 10) == [0.75, 0.75]);
+}
+
+#[test]
+fn test_image_uv() {
+    let image = Image {
+        texture_id: 0,
+        texture_width: 8,
+        texture_height: 8,
+        source_rect: [0, 0, 4, 2],
+    };
+    let m = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+    let identity = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+    let (vertices, uvs) = image_tri_list_xy_f32_uv_f32(
+        m, [0.0, 0.0, 8.0, 8.0], &image, identity, Stretch);
+    assert_eq!(vertices.len(), 12);
+    assert!(uvs.as_slice() == [0.0, 0.0, 0.5, 0.0, 0.0, 0.25,
+                               0.5, 0.0, 0.5, 0.25, 0.0, 0.25]);
+
+    // Flipping horizontally swaps the left and right texture coordinates.
+    let flip_u = [-1.0, 0.0, 1.0, 0.0, 1.0, 0.0];
+    let (_, uvs) = image_tri_list_xy_f32_uv_f32(
+        m, [0.0, 0.0, 8.0, 8.0], &image, flip_u, Stretch);
+    assert!(uvs.slice(0, 4) == [0.5, 0.0, 0.0, 0.0]);
+
+    // Tiles are 4x2, so a 6x3 rectangle has 2x2 tiles with partial ones.
+    let (vertices, uvs) = image_tri_list_xy_f32_uv_f32(
+        m, [0.0, 0.0, 6.0, 3.0], &image, identity, Tile);
+    assert_eq!(vertices.len(), 4 * 12);
+    assert!(vertices.slice(3 * 12 + 8, 3 * 12 + 10) == [6.0, 3.0]);
+    assert!(uvs.slice(3 * 12 + 8, 3 * 12 + 10) == [0.25, 0.125]);
+
+    // Rotated tiles are 2x4.
+    let rot90 = [0.0, 1.0, 0.0, -1.0, 0.0, 1.0];
+    let (vertices, _) = image_tri_list_xy_f32_uv_f32(
+        m, [0.0, 0.0, 4.0, 4.0], &image, rot90, Tile);
+    assert_eq!(vertices.len(), 2 * 12);
+
+    // A negative width draws the image mirrored.
+    let (vertices, uvs) = image_tri_list_xy_f32_uv_f32(
+        m, [8.0, 0.0, -8.0, 8.0], &image, identity, Stretch);
+    assert_eq!(vertices.len(), 12);
+    assert!(vertices.slice(0, 4) == [8.0, 0.0, 0.0, 0.0]);
+    assert!(uvs.slice(0, 4) == [0.0, 0.0, 0.5, 0.0]);
+    let (vertices, uvs) = image_tri_list_xy_f32_uv_f32(
+        m, [6.0, 0.0, -6.0, 2.0], &image, identity, Tile);
+    assert_eq!(vertices.len(), 2 * 12);
+    assert!(vertices.slice(12 + 2, 12 + 4) == [0.0, 0.0]);
+    assert!(uvs.slice(12 + 2, 12 + 4) == [0.25, 0.0]);
+
+    // An empty source rectangle stretches its edge instead of tiling.
+    let empty = Image { source_rect: [1, 1, 0, 0], ..image };
+    let (vertices, uvs) = image_tri_list_xy_f32_uv_f32(
+        m, [0.0, 0.0, 6.0, 3.0], &empty, identity, Tile);
+    assert_eq!(vertices.len(), 12);
+    assert!(uvs.iter().all(|&x| x == 0.125));
+}
+
+#[test]
+fn test_textured_tri_list() {
+    let image = Image {
+        texture_id: 0,
+        texture_width: 4,
+        texture_height: 4,
+        source_rect: [2, 0, 2, 4],
+    };
+    let m = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+    let identity = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+    let color = [1.0, 1.0, 1.0, 1.0];
+    let mut n = 0;
+    with_polygon_textured_tri_list_xy_f32_rgba_f32_uv_f32(
+        m, [0.0, 0.0, 10.0, 0.0, 10.0, 20.0], &image, identity, color,
+        |vertices, colors, uvs| {
+            n += vertices.len();
+            assert_eq!(colors.len(), vertices.len() * 2);
+            // The bounding box corners map to the source rectangle corners.
+            for (p, uv) in vertices.chunks(2).zip(uvs.chunks(2)) {
+                assert_eq!(uv[0], 0.5 + 0.5 * p[0] / 10.0);
+                assert_eq!(uv[1], p[1] / 20.0);
+            }
+        });
+    assert_eq!(n, 6);
+
+    // Large ellipses are sent in several chunks of complete triangles.
+    let mut n = 0;
+    with_ellipse_textured_tri_list_xy_f32_rgba_f32_uv_f32(
+        1000, m, [0.0, 0.0, 2.0, 2.0], &image, identity, color,
+        |vertices, _, uvs| {
+            n += vertices.len();
+            assert_eq!(vertices.len() % 6, 0);
+            assert_eq!(uvs.len(), vertices.len());
+            // The rectangle of the ellipse maps to the source rectangle.
+            for uv in uvs.chunks(2) {
+                assert!(uv[0] >= 0.5 && uv[0] <= 1.0);
+                assert!(uv[1] >= 0.0 && uv[1] <= 1.0);
+            }
+        });
+    assert_eq!(n, 998 * 6);
+}
+
+#[test]
+fn test_indexed_tri_list() {
+    let m = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+    let color = [1.0, 0.0, 0.0, 1.0];
+    // The expanded indexed rectangle matches the triangle list.
+    let vertices = rect_tri_list_indexed_xy_f32(m, [0.0, 0.0, 2.0, 1.0]);
+    let expanded = expand_indexed_f32(vertices, 2, RECT_TRI_LIST_INDICES);
+    assert!(expanded.as_slice() == rect_tri_list_xy_f32(m, [0.0, 0.0, 2.0, 1.0]).as_slice());
+
+    let l_shape = [0.0, 0.0, 2.0, 0.0, 2.0, 1.0, 1.0, 1.0, 1.0, 2.0, 0.0, 2.0];
+    let (vertices, colors, indices) = polygon_tri_list_indexed_xy_f32_rgba_f32(m, l_shape, color);
+    assert_eq!(vertices.len(), 12);
+    assert_eq!(colors.len(), 24);
+    assert_eq!(indices.len(), 4 * 3);
+    let expanded = expand_indexed_f32(vertices.as_slice(), 2, indices.as_slice());
+    assert_eq!(tri_list_area(expanded.iter().map(|&x| x as Scalar).collect::<Vec<Scalar>>().as_slice()), 3.0);
+
+    let (vertices, _, indices) = ellipse_tri_list_indexed_xy_f32_rgba_f32(
+        8, m, [0.0, 0.0, 2.0, 2.0], color);
+    assert_eq!(vertices.len(), 8 * 2);
+    assert_eq!(indices.len(), 6 * 3);
+}
+
+#[test]
+fn test_stroke_polyline_tri_strip() {
+    let polyline = [0.0, 0.0, 4.0, 0.0, 4.0, 4.0];
+    let strip = stroke_polyline_tri_strip(polyline, false, 0.5, MiterJoin, 4.0, ButtCap, ButtCap)
+        .expect("Expected strip");
+    assert_eq!(strip.len(), 3 * 4);
+    // The strip covers the same area as the list with miter joins.
+    let indices = tri_strip_indices(strip.len() / 2);
+    let strip_f32: Vec<f32> = strip.iter().map(|&x| x as f32).collect();
+    let expanded = expand_indexed_f32(strip_f32.as_slice(), 2, indices.as_slice());
+    let triangles = stroke_polyline(polyline, false, 0.5, MiterJoin, 4.0, ButtCap, ButtCap, 0.01);
+    let area = tri_list_area(expanded.iter().map(|&x| x as Scalar).collect::<Vec<Scalar>>().as_slice());
+    assert_eq!(area, tri_list_area(triangles.as_slice()));
+
+    // A closed polygon repeats the first points.
+    let square = [0.0, 0.0, 4.0, 0.0, 4.0, 4.0, 0.0, 4.0];
+    let strip = stroke_polyline_tri_strip(square, true, 0.5, MiterJoin, 4.0, ButtCap, ButtCap)
+        .expect("Expected strip");
+    assert_eq!(strip.len(), 5 * 4);
+    assert!(strip.slice(0, 4) == strip.slice(16, 20));
+
+    assert!(stroke_polyline_tri_strip(polyline, false, 0.5, RoundJoin, 4.0, ButtCap, ButtCap).is_none());
+    assert!(stroke_polyline_tri_strip(polyline, false, 0.5, MiterJoin, 4.0, RoundCap, ButtCap).is_none());
+    // The inner edges do not meet within short segments.
+    assert!(stroke_polyline_tri_strip(polyline, false, 5.0, MiterJoin, 4.0, ButtCap, ButtCap).is_none());
+
+    // Dashed borders are not streamed as strips.
+    let m = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+    let dash = Some(Dash::new([1.0], 0.0));
+    assert!(!with_polyline_border_xy_rgba_f32(TriStrip, 0.01, m, polyline, false, 0.5,
+        MiterJoin, 4.0, ButtCap, ButtCap, dash, [1.0, ..4],
+        |_: &[f32], _| fail!("Expected no triangles")));
+    assert!(with_polyline_border_xy_rgba_f32(TriStrip, 0.01, m, polyline, false, 0.5,
+        MiterJoin, 4.0, ButtCap, ButtCap, None, [1.0, ..4], |_: &[f32], _| {}));
+}
+
+#[test]
+fn test_tri_fan_chunks() {
+    let m = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+    let mut triangles = 0;
+    assert!(with_ellipse_xy_rgba_f32(TriFan, 1000, m, [0.0, 0.0, 1.0, 1.0], [1.0, ..4],
+        |vertices: &[f32], colors| {
+            assert_eq!(vertices.len() * 2, colors.len());
+            triangles += vertices.len() / 2 - 2;
+        }));
+    assert_eq!(triangles, 1000 - 2);
+    // An ellipse is not a triangle strip.
+    assert!(!with_ellipse_xy_rgba_f32(TriStrip, 8, m, [0.0, 0.0, 1.0, 1.0], [1.0, ..4],
+        |_: &[f32], _| fail!("Expected no triangles")));
+
+    let strip = Vec::from_fn(1000 * 2, |i| i as Scalar);
+    let mut triangles = 0;
+    stream_tri_strip_xy_rgba_f32(m, strip.as_slice(), [1.0, ..4], |vertices: &[f32], _| {
+        triangles += vertices.len() / 2 - 2;
+    });
+    assert_eq!(triangles, 1000 - 2);
+    assert!(tri_strip_indices(4).as_slice() == [0, 1, 2, 2, 1, 3]);
 }
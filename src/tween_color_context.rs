@@ -3,7 +3,9 @@ use {
     BackEnd,
     Borrowed,
     Clear,
+    ClipBackEnd,
     Field,
+    RenderResult,
     TweenPolygonsColorContext,
     Value,
 };
@@ -17,6 +19,7 @@ use internal::{
     HasViewTransform,
     Matrix2d,
     Polygons,
+    Rectangle,
 };
 
 /// An animation inbetweening context with color.
@@ -25,6 +28,8 @@ pub struct TweenColorContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transform.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current color.
     pub color: Field<'a, Color>,
     /// Animation inbetweening factor.
@@ -37,6 +42,7 @@ impl<'a> Clone for TweenColorContext<'a> {
         TweenColorContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             color: Value(*self.color.get()),
             tween_factor: Value(*self.tween_factor.get()),
         }
@@ -56,6 +62,7 @@ impl<'a> CanColor<'a, TweenColorContext<'a>, Color> for TweenColorContext<'a> {
         TweenColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Value(value),
             tween_factor: Borrowed(self.tween_factor.get()),
         }
@@ -75,6 +82,7 @@ impl<'a> CanTransform<'a, TweenColorContext<'a>, Matrix2d> for TweenColorContext
         TweenColorContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             color: Borrowed(self.color.get()),
             tween_factor: Borrowed(self.tween_factor.get()),
         }
@@ -94,6 +102,7 @@ impl<'a> CanViewTransform<'a, TweenColorContext<'a>, Matrix2d> for TweenColorCon
         TweenColorContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             tween_factor: Borrowed(self.tween_factor.get()),
             color: Borrowed(self.color.get()),
         }
@@ -107,6 +116,7 @@ impl<'a, 'b> AddPolygons<'a, TweenPolygonsColorContext<'a, 'b>> for TweenColorCo
         TweenPolygonsColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Borrowed(self.color.get()),
             tween_factor: Borrowed(self.tween_factor.get()),
             polygons: Value(polygons),
@@ -116,11 +126,8 @@ impl<'a, 'b> AddPolygons<'a, TweenPolygonsColorContext<'a, 'b>> for TweenColorCo
 
 impl<'a> Clear for TweenColorContext<'a> {
     #[inline(always)]
-    fn clear<B: BackEnd>(&self, back_end: &mut B) {
-        if back_end.supports_clear_rgba() {
-            let color = self.color.get();
-            back_end.clear_rgba(color[0], color[1], color[2], color[3]);
-        }
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
     }
 }
 
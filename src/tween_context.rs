@@ -15,6 +15,7 @@ use internal::{
     HasViewTransform,
     Matrix2d,
     Polygons,
+    Rectangle,
     Scalar,
 };
 
@@ -24,6 +25,8 @@ pub struct TweenContext<'a> {
     pub base: Field<'a, Matrix2d>,
     /// Current transform.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Animation inbetweening factor.
     pub tween_factor: Field<'a, Scalar>,
 }
@@ -34,6 +37,7 @@ impl<'a> Clone for TweenContext<'a> {
         TweenContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             tween_factor: Value(*self.tween_factor.get()),
         }
     }
@@ -51,6 +55,7 @@ impl<'a> AddColor<'a, TweenColorContext<'a>> for TweenContext<'a> {
         TweenColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             tween_factor: Borrowed(self.tween_factor.get()),
             color: Value([r, g, b, a]),
         }
@@ -63,6 +68,7 @@ impl<'a, 'b> AddPolygons<'a, TweenPolygonsContext<'a, 'b>> for TweenContext<'a>
         TweenPolygonsContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             tween_factor: Borrowed(self.tween_factor.get()),
             polygons: Value(polygons),
         }
@@ -82,6 +88,7 @@ impl<'a> CanTransform<'a, TweenContext<'a>, Matrix2d> for TweenContext<'a> {
         TweenContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             tween_factor: Borrowed(self.tween_factor.get()),
         }
     }
@@ -100,6 +107,7 @@ impl<'a> CanViewTransform<'a, TweenContext<'a>, Matrix2d> for TweenContext<'a> {
         TweenContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             tween_factor: Borrowed(self.tween_factor.get()),
         }
     }
@@ -2,12 +2,16 @@ use {
     BackEnd,
     Borrowed,
     Clear,
+    ClipBackEnd,
+    FallbackBackEnd,
     Field,
     Fill,
+    RenderResult,
     Value,
 };
 use triangulation::{
-    with_lerp_polygons_tri_list_xy_f32_rgba_f32
+    Coordinate,
+    with_lerp_polygons_tri_list_xy_rgba_f32
 };
 use internal::{
     CanColor,
@@ -19,6 +23,7 @@ use internal::{
     HasViewTransform,
     Matrix2d,
     Polygons,
+    Rectangle,
     Scalar,
 };
 
@@ -28,6 +33,8 @@ pub struct TweenPolygonsColorContext<'a, 'b> {
     pub base: Field<'a, Matrix2d>,
     /// Current transform.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Current color.
     pub color: Field<'a, Color>,
     /// Animation inbetweening factor.
@@ -42,6 +49,7 @@ impl<'a, 'b> Clone for TweenPolygonsColorContext<'a, 'b> {
         TweenPolygonsColorContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             color: Value(*self.color.get()),
             tween_factor: Value(*self.tween_factor.get()),
             polygons: Value(*self.polygons.get()),
@@ -62,6 +70,7 @@ impl<'a, 'b> CanColor<'a, TweenPolygonsColorContext<'a, 'b>, Color> for TweenPol
         TweenPolygonsColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Value(value),
             tween_factor: Borrowed(self.tween_factor.get()),
             polygons: Borrowed(self.polygons.get()),
@@ -82,6 +91,7 @@ impl<'a, 'b> CanTransform<'a, TweenPolygonsColorContext<'a, 'b>, Matrix2d> for T
         TweenPolygonsColorContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             color: Borrowed(self.color.get()),
             tween_factor: Borrowed(self.tween_factor.get()),
             polygons: Borrowed(self.polygons.get()),
@@ -103,6 +113,7 @@ for TweenPolygonsColorContext<'a, 'b> {
         TweenPolygonsColorContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             tween_factor: Borrowed(self.tween_factor.get()),
             polygons: Borrowed(self.polygons.get()),
             color: Borrowed(self.color.get()),
@@ -111,40 +122,49 @@ for TweenPolygonsColorContext<'a, 'b> {
 }
 
 
+impl<'a, 'b> TweenPolygonsColorContext<'a, 'b> {
+    /// Streams the tweened polygons with f32 or f64 vertices.
+    fn stream_tri_list<V: Coordinate>(&self, f: |vertices: &[V], colors: &[f32]|) {
+        with_lerp_polygons_tri_list_xy_rgba_f32(
+            *self.transform.get(),
+            *self.polygons.get(),
+            *self.tween_factor.get(),
+            *self.color.get(),
+            f
+        );
+    }
+}
+
 impl<'a, 'b> Fill<'a> for TweenPolygonsColorContext<'a, 'b> {
     #[inline(always)]
-    fn fill<B: BackEnd>(&'a self, back_end: &mut B) {
-        if back_end.supports_tri_list_xy_f32_rgba_f32() {
-            let polygons = self.polygons.get();
-            let color = self.color.get();
-            // Complete transparency does not need to be rendered.
-            if color[3] == 0.0 { return; }
-            // Turn on alpha blending if not completely opaque.
-            let needs_alpha = color[3] != 1.0;
-            if needs_alpha { back_end.enable_alpha_blend(); }
-            with_lerp_polygons_tri_list_xy_f32_rgba_f32(
-                *self.transform.get(),
-                *polygons,
-                *self.tween_factor.get(),
-                *color,
-                |vertices, colors| {
-                    back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+    fn fill<B: BackEnd>(&'a self, back_end: &mut B) -> RenderResult {
+        FallbackBackEnd::with_fallback(back_end, |back_end| {
+            ClipBackEnd::with_clip(*self.clip.get(), back_end, |back_end| {
+                let color = self.color.get();
+                // Complete transparency does not need to be rendered.
+                if color[3] == 0.0 { return; }
+                // Turn on alpha blending if not completely opaque.
+                let needs_alpha = color[3] != 1.0;
+                if needs_alpha { back_end.enable_alpha_blend(); }
+                if back_end.supports_tri_list_xy_f64_rgba_f32() {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f64_rgba_f32(vertices, colors)
+                    });
+                } else {
+                    self.stream_tri_list(|vertices, colors| {
+                        back_end.tri_list_xy_f32_rgba_f32(vertices, colors)
+                    });
                 }
-            );
-            if needs_alpha { back_end.disable_alpha_blend(); }
-        } else {
-            unimplemented!();
-        }
+                if needs_alpha { back_end.disable_alpha_blend(); }
+            })
+        })
     }
 }
 
 impl<'a, 'b> Clear for TweenPolygonsColorContext<'a, 'b> {
     #[inline(always)]
-    fn clear<B: BackEnd>(&self, back_end: &mut B) {
-        if back_end.supports_clear_rgba() {
-            let color = self.color.get();
-            back_end.clear_rgba(color[0], color[1], color[2], color[3]);
-        }
+    fn clear<B: BackEnd>(&self, back_end: &mut B) -> RenderResult {
+        ClipBackEnd::clear(*self.clip.get(), back_end, *self.color.get())
     }
 }
 
@@ -14,6 +14,7 @@ use internal::{
     HasViewTransform,
     Matrix2d,
     Polygons,
+    Rectangle,
     Scalar,
 };
 
@@ -23,6 +24,8 @@ pub struct TweenPolygonsContext<'a, 'b> {
     pub base: Field<'a, Matrix2d>,
     /// Current transform.
     pub transform: Field<'a, Matrix2d>,
+    /// Current clipping rectangle after transformation.
+    pub clip: Field<'a, Option<Rectangle>>,
     /// Animation inbetweening factor.
     pub tween_factor: Field<'a, Scalar>,
     /// The animated polygons.
@@ -35,6 +38,7 @@ impl<'a, 'b> Clone for TweenPolygonsContext<'a, 'b> {
         TweenPolygonsContext {
             base: Value(*self.base.get()),
             transform: Value(*self.transform.get()),
+            clip: Value(*self.clip.get()),
             tween_factor: Value(*self.tween_factor.get()),
             polygons: Value(*self.polygons.get()),
         }
@@ -54,6 +58,7 @@ impl<'a, 'b> AddColor<'a, TweenPolygonsColorContext<'a, 'b>> for TweenPolygonsCo
         TweenPolygonsColorContext {
             base: Borrowed(self.base.get()),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             color: Value([r, g, b, a]),
             tween_factor: Borrowed(self.tween_factor.get()),
             polygons: Borrowed(self.polygons.get()),
@@ -74,6 +79,7 @@ impl<'a, 'b> CanTransform<'a, TweenPolygonsContext<'a, 'b>, Matrix2d> for TweenP
         TweenPolygonsContext {
             base: Borrowed(self.base.get()),
             transform: Value(value),
+            clip: Borrowed(self.clip.get()),
             tween_factor: Borrowed(self.tween_factor.get()),
             polygons: Borrowed(self.polygons.get()),
         }
@@ -93,6 +99,7 @@ impl<'a, 'b> CanViewTransform<'a, TweenPolygonsContext<'a, 'b>, Matrix2d> for Tw
         TweenPolygonsContext {
             base: Value(value),
             transform: Borrowed(self.transform.get()),
+            clip: Borrowed(self.clip.get()),
             tween_factor: Borrowed(self.tween_factor.get()),
             polygons: Borrowed(self.polygons.get()),
         }
@@ -1,6 +1,8 @@
 
 //! Various methods for computing with vectors.
 
+use std::collections::{HashMap, HashSet};
+use std::f64::{INFINITY, NEG_INFINITY};
 use internal::{
     Area,
     Line,
@@ -8,6 +10,8 @@ use internal::{
     Polygon,
     Ray,
     Rectangle,
+    Scalar,
+    Shape,
     Vec2d,
     Triangle
 };
@@ -143,6 +147,52 @@ pub fn relative_rectangle(rect: Rectangle, x: f64, y: f64) -> Rectangle {
     [rect[0] + x * rect[2], rect[1] + y * rect[3], rect[2], rect[3]]
 }
 
+/// Computes the bounding rectangle of a transformed rectangle.
+#[inline(always)]
+pub fn transform_rectangle_bounds(m: Matrix2d, rect: Rectangle) -> Rectangle {
+    let (x, y, w, h) = (rect[0], rect[1], rect[2], rect[3]);
+    let corners = [[x, y], [x + w, y], [x, y + h], [x + w, y + h]];
+    let (mut min_x, mut min_y) = (INFINITY, INFINITY);
+    let (mut max_x, mut max_y) = (NEG_INFINITY, NEG_INFINITY);
+    for p in corners.iter() {
+        let tx = m[0] * p[0] + m[1] * p[1] + m[2];
+        let ty = m[3] * p[0] + m[4] * p[1] + m[5];
+        min_x = min_x.min(tx);
+        min_y = min_y.min(ty);
+        max_x = max_x.max(tx);
+        max_y = max_y.max(ty);
+    }
+    [min_x, min_y, max_x - min_x, max_y - min_y]
+}
+
+/// Computes the bounding rectangle of a polygon.
+///
+/// Returns an empty rectangle at the origin if the polygon has no points.
+pub fn polygon_bounds(polygon: Polygon) -> Rectangle {
+    if polygon.len() < 2 { return [0.0, 0.0, 0.0, 0.0]; }
+    let (mut min_x, mut min_y) = (polygon[0], polygon[1]);
+    let (mut max_x, mut max_y) = (min_x, min_y);
+    for p in polygon.chunks(2) {
+        min_x = min_x.min(p[0]);
+        min_y = min_y.min(p[1]);
+        max_x = max_x.max(p[0]);
+        max_y = max_y.max(p[1]);
+    }
+    [min_x, min_y, max_x - min_x, max_y - min_y]
+}
+
+/// Computes the overlap of two rectangles.
+///
+/// Returns a rectangle with zero size if they do not overlap.
+#[inline(always)]
+pub fn intersect_rectangles(a: Rectangle, b: Rectangle) -> Rectangle {
+    let x = a[0].max(b[0]);
+    let y = a[1].max(b[1]);
+    let w = ((a[0] + a[2]).min(b[0] + b[2]) - x).max(0.0);
+    let h = ((a[1] + a[3]).min(b[1] + b[3]) - y).max(0.0);
+    [x, y, w, h]
+}
+
 /// Computes modular offset safely for numbers.
 #[inline(always)]
 pub fn modular_offset<T: Add<T, T> + Rem<T, T>>(
@@ -259,6 +309,340 @@ pub fn triangle_face(
     ab_side.is_negative()
 }
 
+/// Computes the union of two shapes.
+///
+/// See `polygon_boolean` for how the shapes are interpreted.
+#[inline(always)]
+pub fn polygon_union(a: Shape, b: Shape) -> Vec<Vec<Vec<Scalar>>> {
+    polygon_boolean(a, b, |in_a, in_b| in_a || in_b)
+}
+
+/// Computes the intersection of two shapes.
+///
+/// See `polygon_boolean` for how the shapes are interpreted.
+#[inline(always)]
+pub fn polygon_intersection(a: Shape, b: Shape) -> Vec<Vec<Vec<Scalar>>> {
+    polygon_boolean(a, b, |in_a, in_b| in_a && in_b)
+}
+
+/// Computes the parts of the first shape not covered by the second.
+///
+/// See `polygon_boolean` for how the shapes are interpreted.
+#[inline(always)]
+pub fn polygon_difference(a: Shape, b: Shape) -> Vec<Vec<Vec<Scalar>>> {
+    polygon_boolean(a, b, |in_a, in_b| in_a && !in_b)
+}
+
+/// Computes the parts covered by exactly one of the shapes.
+///
+/// See `polygon_boolean` for how the shapes are interpreted.
+#[inline(always)]
+pub fn polygon_xor(a: Shape, b: Shape) -> Vec<Vec<Vec<Scalar>>> {
+    polygon_boolean(a, b, |in_a, in_b| in_a != in_b)
+}
+
+/// Combines two shapes with a boolean operation on their insides.
+///
+/// Each shape is a list of rings in any orientation, filled with the even-odd rule,
+/// so holes are added as extra rings. The rings may be concave.
+/// The operation is called with whether a point is inside the first and second shape.
+///
+/// Returns a list of polygons where each polygon is a list of rings.
+/// The first ring of a polygon is the outer ring in counter-clockwise order,
+/// followed by the holes in clockwise order.
+/// Each polygon can be drawn as a shape with either fill rule.
+///
+/// The edges of both shapes are split where they cross or touch,
+/// and the pieces with the result inside on one side only are linked into rings.
+pub fn polygon_boolean<'a>(
+    a: Shape<'a>,
+    b: Shape<'a>,
+    op: |in_a: bool, in_b: bool| -> bool
+) -> Vec<Vec<Vec<Scalar>>> {
+    // Collect the edges of both shapes.
+    let mut edges: Vec<(Vec2d, Vec2d)> = Vec::new();
+    let mut size = 1.0_f64;
+    for &shape in [a, b].iter() {
+        for ring in shape.iter() {
+            let n = ring.len() / 2;
+            if n < 3 { continue; }
+            for i in range(0, n) {
+                let p_i = previous(n, i);
+                let p = [ring[p_i * 2], ring[p_i * 2 + 1]];
+                let q = [ring[i * 2], ring[i * 2 + 1]];
+                size = size.max(q[0].abs()).max(q[1].abs());
+                if p != q { edges.push((p, q)); }
+            }
+        }
+    }
+    let eps = size * 1e-9;
+
+    // Find where each edge is split by the other edges.
+    let mut splits: Vec<Vec<Scalar>> = Vec::from_fn(edges.len(), |_| vec![0.0, 1.0]);
+    for i in range(0, edges.len()) {
+        for j in range(i + 1, edges.len()) {
+            let (p, p2) = *edges.get(i);
+            let (q, q2) = *edges.get(j);
+            let r = [p2[0] - p[0], p2[1] - p[1]];
+            let s = [q2[0] - q[0], q2[1] - q[1]];
+            let denom = r[0] * s[1] - r[1] * s[0];
+            let len_r = (r[0] * r[0] + r[1] * r[1]).sqrt();
+            let len_s = (s[0] * s[0] + s[1] * s[1]).sqrt();
+            if denom.abs() > 1e-12 * len_r * len_s {
+                let d = [q[0] - p[0], q[1] - p[1]];
+                let t = (d[0] * s[1] - d[1] * s[0]) / denom;
+                let u = (d[0] * r[1] - d[1] * r[0]) / denom;
+                let (t_eps, u_eps) = (eps / len_r, eps / len_s);
+                if t > -t_eps && t < 1.0 + t_eps && u > -u_eps && u < 1.0 + u_eps {
+                    splits.get_mut(i).push(t.max(0.0).min(1.0));
+                    splits.get_mut(j).push(u.max(0.0).min(1.0));
+                }
+            } else {
+                // Parallel edges only split each other where they overlap.
+                for &end in [q, q2].iter() {
+                    match segment_param(p, p2, end, eps) {
+                        Some(t) => splits.get_mut(i).push(t),
+                        None => {}
+                    }
+                }
+                for &end in [p, p2].iter() {
+                    match segment_param(q, q2, end, eps) {
+                        Some(u) => splits.get_mut(j).push(u),
+                        None => {}
+                    }
+                }
+            }
+        }
+    }
+
+    // Split the edges into pieces between shared points.
+    let mut grid = PointGrid::new(eps);
+    let mut pieces: Vec<(uint, uint)> = Vec::new();
+    let mut seen: HashSet<(uint, uint)> = HashSet::new();
+    for (i, ts) in splits.mut_iter().enumerate() {
+        let (p, p2) = *edges.get(i);
+        ts.sort_by(|a, b| if a < b { Less } else if a > b { Greater } else { Equal });
+        let mut prev: Option<uint> = None;
+        for &t in ts.iter() {
+            let x = [p[0] + t * (p2[0] - p[0]), p[1] + t * (p2[1] - p[1])];
+            let id = grid.snap(x);
+            match prev {
+                Some(prev_id) if prev_id != id => {
+                    let piece = if prev_id < id { (prev_id, id) } else { (id, prev_id) };
+                    if seen.insert(piece) { pieces.push(piece); }
+                },
+                _ => {}
+            }
+            prev = Some(id);
+        }
+    }
+
+    let points = grid.points;
+
+    // Keep the pieces that separate the inside of the result from the outside.
+    let in_result = |x: Vec2d| op(inside_shape(a, x), inside_shape(b, x));
+    let mut kept: Vec<(uint, uint)> = Vec::new();
+    for &(i, j) in pieces.iter() {
+        let (p, q) = (*points.get(i), *points.get(j));
+        let (dx, dy) = (q[0] - p[0], q[1] - p[1]);
+        let len = (dx * dx + dy * dy).sqrt();
+        let off = eps.max(len * 1e-6) / len;
+        let mid = [0.5 * (p[0] + q[0]), 0.5 * (p[1] + q[1])];
+        let left = in_result([mid[0] - dy * off, mid[1] + dx * off]);
+        let right = in_result([mid[0] + dy * off, mid[1] - dx * off]);
+        if left && !right { kept.push((i, j)); }
+        if right && !left { kept.push((j, i)); }
+    }
+
+    // Link the pieces into rings with the inside on the left.
+    let mut outgoing: Vec<Vec<uint>> = Vec::from_elem(points.len(), Vec::new());
+    for (k, &(from, _)) in kept.iter().enumerate() {
+        outgoing.get_mut(from).push(k);
+    }
+    let mut used = Vec::from_elem(kept.len(), false);
+    let mut rings: Vec<Vec<Scalar>> = Vec::new();
+    for first in range(0, kept.len()) {
+        if *used.get(first) { continue; }
+        *used.get_mut(first) = true;
+        let (start, _) = *kept.get(first);
+        let mut ring = vec![start];
+        let mut edge = first;
+        let mut closed = false;
+        loop {
+            let (from, to) = *kept.get(edge);
+            if to == start {
+                closed = true;
+                break;
+            }
+            ring.push(to);
+            // Turn as far left as possible to keep rings touching at a point apart.
+            let (p, q) = (*points.get(from), *points.get(to));
+            let d_in = [q[0] - p[0], q[1] - p[1]];
+            let mut best: Option<(uint, Scalar)> = None;
+            for &k in outgoing.get(to).iter() {
+                if *used.get(k) { continue; }
+                let (_, k_to) = *kept.get(k);
+                let r = *points.get(k_to);
+                let d_out = [r[0] - q[0], r[1] - q[1]];
+                let angle = (d_in[0] * d_out[1] - d_in[1] * d_out[0])
+                    .atan2(d_in[0] * d_out[0] + d_in[1] * d_out[1]);
+                match best {
+                    Some((_, best_angle)) if best_angle >= angle => {},
+                    _ => best = Some((k, angle)),
+                }
+            }
+            match best {
+                Some((k, _)) => {
+                    *used.get_mut(k) = true;
+                    edge = k;
+                },
+                None => break,
+            }
+        }
+        if !closed { continue; }
+        let ring = remove_collinear(ring.iter().map(|&id| *points.get(id)).collect(), eps);
+        if ring.len() >= 6 { rings.push(ring); }
+    }
+
+    // Put each hole in the smallest outer ring that contains it.
+    let areas: Vec<Scalar> = rings.iter().map(|ring| area(ring.as_slice())).collect();
+    let mut polygons: Vec<Vec<Vec<Scalar>>> = Vec::new();
+    let mut outer_ids: Vec<uint> = Vec::new();
+    for (i, ring) in rings.iter().enumerate() {
+        if *areas.get(i) > 0.0 {
+            polygons.push(vec![ring.clone()]);
+            outer_ids.push(i);
+        }
+    }
+    for (i, ring) in rings.iter().enumerate() {
+        if *areas.get(i) > 0.0 { continue; }
+        // Pick a point just inside the hole, to the right of its first edge.
+        let ring = ring.as_slice();
+        let (p, q) = ([ring[0], ring[1]], [ring[2], ring[3]]);
+        let (dx, dy) = (q[0] - p[0], q[1] - p[1]);
+        let len = (dx * dx + dy * dy).sqrt();
+        let off = eps / len;
+        let x = [0.5 * (p[0] + q[0]) + dy * off, 0.5 * (p[1] + q[1]) - dx * off];
+        let mut best: Option<(uint, Scalar)> = None;
+        for (k, &outer) in outer_ids.iter().enumerate() {
+            let outer_ring = rings.get(outer).as_slice();
+            if !inside_shape(&[outer_ring], x) { continue; }
+            let outer_area = *areas.get(outer);
+            match best {
+                Some((_, best_area)) if best_area <= outer_area => {},
+                _ => best = Some((k, outer_area)),
+            }
+        }
+        match best {
+            Some((k, _)) => polygons.get_mut(k).push(Vec::from_slice(ring)),
+            None => {}
+        }
+    }
+    polygons
+}
+
+/// Returns the parameter of a point on a segment,
+/// or `None` if it is not strictly between the end points.
+fn segment_param(p: Vec2d, q: Vec2d, x: Vec2d, eps: Scalar) -> Option<Scalar> {
+    let (dx, dy) = (q[0] - p[0], q[1] - p[1]);
+    let len_sq = dx * dx + dy * dy;
+    let t = ((x[0] - p[0]) * dx + (x[1] - p[1]) * dy) / len_sq;
+    if t <= 0.0 || t >= 1.0 { return None; }
+    let (ex, ey) = (p[0] + t * dx - x[0], p[1] + t * dy - x[1]);
+    if ex * ex + ey * ey <= eps * eps { Some(t) } else { None }
+}
+
+/// Shares points closer than `eps`, using a hashed grid of cells with that size.
+struct PointGrid {
+    points: Vec<Vec2d>,
+    cells: HashMap<(i64, i64), Vec<uint>>,
+    eps: Scalar,
+}
+
+impl PointGrid {
+    fn new(eps: Scalar) -> PointGrid {
+        PointGrid { points: Vec::new(), cells: HashMap::new(), eps: eps }
+    }
+
+    /// Returns the index of a point, adding it if no point is closer than `eps`.
+    fn snap(&mut self, x: Vec2d) -> uint {
+        let eps = self.eps;
+        let (cx, cy) = ((x[0] / eps).floor() as i64, (x[1] / eps).floor() as i64);
+        // Points closer than `eps` are in the same or a neighbouring cell.
+        let mut found: Option<uint> = None;
+        for dy in range(-1i64, 2) {
+            for dx in range(-1i64, 2) {
+                let ids = match self.cells.find(&(cx + dx, cy + dy)) {
+                    None => continue,
+                    Some(ids) => ids,
+                };
+                for &i in ids.iter() {
+                    let p = *self.points.get(i);
+                    if (p[0] - x[0]).abs() > eps || (p[1] - x[1]).abs() > eps { continue; }
+                    // Pick the lowest index, so the order of the cells does not matter.
+                    match found {
+                        Some(j) if j < i => {},
+                        _ => found = Some(i),
+                    }
+                }
+            }
+        }
+        match found {
+            Some(i) => return i,
+            None => {}
+        }
+        let id = self.points.len();
+        self.points.push(x);
+        self.cells.find_or_insert_with((cx, cy), |_| Vec::new()).push(id);
+        id
+    }
+}
+
+/// Returns true if a point is inside a shape using the even-odd rule.
+fn inside_shape(shape: Shape, x: Vec2d) -> bool {
+    let mut inside = false;
+    for ring in shape.iter() {
+        let n = ring.len() / 2;
+        if n < 3 { continue; }
+        for i in range(0, n) {
+            let p_i = previous(n, i);
+            let (px, py) = (ring[p_i * 2], ring[p_i * 2 + 1]);
+            let (qx, qy) = (ring[i * 2], ring[i * 2 + 1]);
+            if (py > x[1]) != (qy > x[1])
+            && x[0] < px + (x[1] - py) / (qy - py) * (qx - px) {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// Removes points lying on the line between their neighbours.
+fn remove_collinear(mut ring: Vec<Vec2d>, eps: Scalar) -> Vec<Scalar> {
+    let mut i = 0;
+    while ring.len() >= 3 && i < ring.len() {
+        let n = ring.len();
+        let p = *ring.get(previous(n, i));
+        let q = *ring.get(i);
+        let r = *ring.get((i + 1) % n);
+        let (ax, ay) = (q[0] - p[0], q[1] - p[1]);
+        let (bx, by) = (r[0] - q[0], r[1] - q[1]);
+        let len = (ax * ax + ay * ay).sqrt() + (bx * bx + by * by).sqrt();
+        if (ax * by - ay * bx).abs() <= eps * len && ax * bx + ay * by >= 0.0 {
+            ring.remove(i);
+            if i > 0 { i -= 1; }
+        } else {
+            i += 1;
+        }
+    }
+    let mut res = Vec::with_capacity(ring.len() * 2);
+    for p in ring.iter() {
+        res.push(p[0]);
+        res.push(p[1]);
+    }
+    res
+}
+
 #[test]
 fn test_triangle() {
     // Triangle counter clock-wise.
@@ -271,3 +655,75 @@ fn test_triangle() {
     assert_eq!(triangle_face(tri_1), false);
     assert_eq!(triangle_face(tri_2), true);
 }
+
+#[test]
+fn test_clip_rectangles() {
+    use std::f64::consts::{FRAC_PI_2};
+
+    let rect = transform_rectangle_bounds(rotate_radians(FRAC_PI_2), [0.0, 0.0, 2.0, 1.0]);
+    assert!((rect[0] + 1.0).abs() < 0.000001 && rect[1].abs() < 0.000001);
+    assert!((rect[2] - 1.0).abs() < 0.000001 && (rect[3] - 2.0).abs() < 0.000001);
+    assert!(intersect_rectangles([0.0, 0.0, 2.0, 2.0], [1.0, -1.0, 2.0, 2.0]) == [1.0, 0.0, 1.0, 1.0]);
+    assert_eq!(intersect_rectangles([0.0, 0.0, 1.0, 1.0], [2.0, 0.0, 1.0, 1.0])[2], 0.0);
+}
+
+#[test]
+fn test_polygon_boolean() {
+    let total_area = |polygons: &Vec<Vec<Vec<f64>>>| -> f64 {
+        polygons.iter().map(|rings| rings.iter()
+            .map(|ring| area(ring.as_slice())).fold(0.0, |a, b| a + b))
+            .fold(0.0, |a, b| a + b)
+    };
+    let a = [0.0, 0.0, 2.0, 0.0, 2.0, 2.0, 0.0, 2.0];
+    let b = [1.0, 1.0, 3.0, 1.0, 3.0, 3.0, 1.0, 3.0];
+    let (a, b) = (&[a.as_slice()], &[b.as_slice()]);
+    let union = polygon_union(a, b);
+    assert_eq!(union.len(), 1);
+    assert_eq!(union.get(0).get(0).len(), 16);
+    assert_eq!(total_area(&union), 7.0);
+    assert_eq!(total_area(&polygon_intersection(a, b)), 1.0);
+    assert_eq!(total_area(&polygon_difference(a, b)), 3.0);
+    let xor = polygon_xor(a, b);
+    assert_eq!(xor.len(), 2);
+    assert_eq!(total_area(&xor), 6.0);
+}
+
+#[test]
+fn test_polygon_boolean_holes() {
+    // A square with a hole and a concave L shape crossing it.
+    let outer = [0.0, 0.0, 4.0, 0.0, 4.0, 4.0, 0.0, 4.0];
+    let hole = [1.0, 1.0, 1.0, 3.0, 3.0, 3.0, 3.0, 1.0];
+    let l_shape = [2.0, 2.0, 3.5, 2.0, 3.5, 3.5, 2.5, 3.5, 2.5, 3.0, 2.0, 3.0];
+    let a = &[outer.as_slice(), hole.as_slice()];
+    let b = &[l_shape.as_slice()];
+    let difference = polygon_difference(a, b);
+    assert_eq!(difference.len(), 1);
+    assert_eq!(difference.get(0).len(), 2);
+    assert!(area(difference.get(0).get(0).as_slice()) > 0.0);
+    assert!(area(difference.get(0).get(1).as_slice()) < 0.0);
+    let sum = difference.get(0).iter().map(|ring| area(ring.as_slice())).fold(0.0, |a, b| a + b);
+    assert_eq!(sum, 11.0);
+    let union = polygon_union(a, b);
+    let sum = union.iter().map(|rings| rings.iter()
+        .map(|ring| area(ring.as_slice())).fold(0.0, |a, b| a + b)).fold(0.0, |a, b| a + b);
+    assert_eq!(union.len(), 1);
+    assert_eq!(sum, 13.0);
+}
+
+#[test]
+fn test_polygon_bounds() {
+    let rect = polygon_bounds([1.0, 2.0, 4.0, -1.0, 3.0, 5.0]);
+    assert!(rect == [1.0, -1.0, 3.0, 6.0]);
+}
+
+#[test]
+fn test_point_grid() {
+    let mut grid = PointGrid::new(0.1);
+    assert_eq!(grid.snap([0.0, 0.0]), 0);
+    assert_eq!(grid.snap([1.0, 0.0]), 1);
+    // Points in neighbouring cells are shared.
+    assert_eq!(grid.snap([0.95, -0.05]), 1);
+    assert_eq!(grid.snap([-0.09, 0.09]), 0);
+    assert_eq!(grid.snap([0.0, 0.2]), 2);
+    assert_eq!(grid.points.len(), 3);
+}